@@ -6,24 +6,39 @@ use crate::core::io::input::InputPlugin;
 use crate::core::io::pointer::PointerPlugin;
 use crate::core::settings::{BezySettings, DEFAULT_WINDOW_SIZE, WINDOW_TITLE};
 use crate::core::state::GlyphNavigation;
-use crate::editing::{FontEditorSystemSetsPlugin, SelectionPlugin, TextEditorPlugin, UndoPlugin};
+use crate::editing::{
+    AnchorPlugin, EditAllMastersPlugin, FontEditorSystemSetsPlugin,
+    GlyphComponentsPlugin, GuidelinePlugin, ImpliedOnCurvesPlugin,
+    SelectionPlugin, SnappingPlugin, TextEditorPlugin, UndoPlugin,
+};
 use crate::rendering::{
     camera_responsive::CameraResponsivePlugin, cameras::CameraPlugin,
     checkerboard::CheckerboardPlugin, EntityPoolingPlugin, MeshCachingPlugin,
-    MetricsRenderingPlugin,
+    MetricsRenderingPlugin, ReferenceImagePlugin,
     SortHandleRenderingPlugin, UnifiedGlyphEditingPlugin,
 };
 use crate::systems::{
     exit_on_esc, load_fontir_font, create_startup_layout, center_camera_on_startup_layout,
-    BezySystems, CommandsPlugin, InputConsumerPlugin, HarfBuzzShapingPlugin, 
+    BezySystems, ChecksPlugin, CommandsPlugin, InputConsumerPlugin, HarfBuzzShapingPlugin, 
     UiInteractionPlugin,
 };
 use crate::ui::hud::HudPlugin;
+use crate::ui::panes::axes_pane::AxesPanePlugin;
+use crate::ui::panes::compatibility_pane::CompatibilityPanePlugin;
 use crate::ui::panes::coord_pane::CoordinatePanePlugin;
 use crate::ui::panes::design_space::DesignSpacePlugin;
+use crate::ui::panes::designspace_pane::DesignspacePanePlugin;
 use crate::ui::panes::file_pane::FilePanePlugin;
+use crate::ui::panes::font_info_pane::FontInfoPanePlugin;
 use crate::ui::panes::glyph_pane::GlyphPanePlugin;
+use crate::ui::panes::language_support_pane::LanguageSupportPanePlugin;
+use crate::ui::panes::name_entry_pane::NameEntryPanePlugin;
+use crate::ui::panes::outline_check_pane::OutlineCheckPanePlugin;
+use crate::ui::panes::reference_image_pane::ReferenceImagePanePlugin;
+use crate::ui::panes::settings_pane::SettingsPanePlugin;
+use crate::ui::panes::variable_font_pane::VariableFontPanePlugin;
 use crate::ui::file_menu::FileMenuPlugin;
+use crate::ui::font_overview::FontOverviewPlugin;
 use crate::ui::theme::CurrentTheme;
 #[cfg(debug_assertions)]
 use crate::ui::themes::runtime_reload::RuntimeThemePlugin;
@@ -49,9 +64,16 @@ impl PluginGroup for CorePluginGroup {
             // RE-ENABLED: HarfBuzz text shaping for RTL support
             .add(HarfBuzzShapingPlugin)
             .add(SelectionPlugin)
+            .add(GlyphComponentsPlugin)
+            .add(AnchorPlugin)
+            .add(GuidelinePlugin)
+            .add(SnappingPlugin)
+            .add(EditAllMastersPlugin)
+            .add(ImpliedOnCurvesPlugin)
             .add(UndoPlugin)
             .add(UiInteractionPlugin)
             .add(CommandsPlugin)
+            .add(ChecksPlugin)
             .add(BezySystems)
     }
 }
@@ -71,6 +93,7 @@ impl PluginGroup for RenderingPluginGroup {
             .add(MetricsRenderingPlugin)
             .add(SortHandleRenderingPlugin)
             .add(UnifiedGlyphEditingPlugin)
+            .add(ReferenceImagePlugin)
     }
 }
 
@@ -85,6 +108,17 @@ impl PluginGroup for EditorPluginGroup {
             .add(FilePanePlugin)
             .add(GlyphPanePlugin)
             .add(CoordinatePanePlugin)
+            .add(FontInfoPanePlugin)
+            .add(LanguageSupportPanePlugin)
+            .add(OutlineCheckPanePlugin)
+            .add(CompatibilityPanePlugin)
+            .add(AxesPanePlugin)
+            .add(DesignspacePanePlugin)
+            .add(VariableFontPanePlugin)
+            .add(ReferenceImagePanePlugin)
+            .add(SettingsPanePlugin)
+            .add(NameEntryPanePlugin)
+            .add(FontOverviewPlugin)
             .add(EditModeToolbarPlugin) // ✅ Includes ConfigBasedToolbarPlugin - handles all tools automatically
             .add(FileMenuPlugin)
             .add(HudPlugin)
@@ -3,6 +3,10 @@
 //! This module contains all configurable settings for the Bezy font editor.
 //! For visual/UI settings, see ui/theme.rs
 
+use crate::geometry::autotrace::TraceParams;
+use crate::geometry::offset::{Cap, Join, OffsetParams};
+use crate::geometry::simplify::SimplifyParams;
+use crate::geometry::snapping::{default_snap_rules, SnapKind, SnapRule};
 use crate::ui::themes::ThemeVariant;
 use bevy::prelude::*;
 
@@ -31,6 +35,74 @@ impl Default for GridSettings {
     }
 }
 
+/// Configuration for snapping to points, extrema, metrics, guidelines and
+/// the alignment of other points
+#[derive(Debug, Clone)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    /// Snap target kinds in priority order, highest first, each with its
+    /// reach at default zoom; kinds that are left out are never snapped to
+    pub rules: Vec<SnapRule>,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: default_snap_rules(),
+        }
+    }
+}
+
+impl SnapSettings {
+    /// Reach of a kind of snap target, `None` if it is never snapped to
+    pub fn threshold(&self, kind: SnapKind) -> Option<f64> {
+        self.rules
+            .iter()
+            .find(|rule| rule.kind == kind)
+            .map(|rule| rule.threshold)
+    }
+
+    /// Set the reach of a kind of snap target
+    ///
+    /// A reach of zero or less stops snapping to that kind; kinds that
+    /// were left out are added with the lowest priority.
+    pub fn set_threshold(&mut self, kind: SnapKind, threshold: f64) {
+        let index = self.rules.iter().position(|rule| rule.kind == kind);
+        match index {
+            Some(index) if threshold <= 0.0 => {
+                self.rules.remove(index);
+            }
+            Some(index) => self.rules[index].threshold = threshold,
+            None if threshold > 0.0 => {
+                self.rules.push(SnapRule { kind, threshold })
+            }
+            None => {}
+        }
+    }
+
+    /// Rank of a kind of snap target, 1 being the highest priority
+    pub fn priority(&self, kind: SnapKind) -> Option<usize> {
+        self.rules
+            .iter()
+            .position(|rule| rule.kind == kind)
+            .map(|index| index + 1)
+    }
+
+    /// Move a kind of snap target up (positive) or down (negative) the
+    /// priority order
+    pub fn raise_priority(&mut self, kind: SnapKind, steps: i32) {
+        let Some(index) = self.rules.iter().position(|rule| rule.kind == kind)
+        else {
+            return;
+        };
+        let last = self.rules.len() as i32 - 1;
+        let target = (index as i32 - steps).clamp(0, last) as usize;
+        let rule = self.rules.remove(index);
+        self.rules.insert(target, rule);
+    }
+}
+
 /// Configuration for keyboard nudging behavior
 #[derive(Debug, Clone, Copy)]
 pub struct NudgeSettings {
@@ -52,6 +124,136 @@ impl Default for NudgeSettings {
     }
 }
 
+/// Configuration for curve simplification
+#[derive(Debug, Clone, Copy)]
+pub struct SimplifySettings {
+    /// Maximum distance between original and simplified outline, in font units
+    pub tolerance: f64,
+    /// Joins turning by more than this many degrees are kept as corners
+    pub corner_angle: f64,
+    /// Insert on-curve points at horizontal/vertical extrema
+    pub keep_extrema: bool,
+    /// Simplify outlines produced by the metaball and hyper tools
+    pub apply_to_tool_output: bool,
+}
+
+impl Default for SimplifySettings {
+    fn default() -> Self {
+        Self {
+            tolerance: 1.0,
+            corner_angle: 30.0,
+            keep_extrema: true,
+            apply_to_tool_output: false,
+        }
+    }
+}
+
+impl SimplifySettings {
+    /// Parameters for the simplification algorithm
+    pub fn params(&self) -> SimplifyParams {
+        SimplifyParams {
+            tolerance: self.tolerance,
+            corner_angle: self.corner_angle,
+            keep_extrema: self.keep_extrema,
+        }
+    }
+}
+
+/// Configuration for cubic/quadratic curve conversion
+#[derive(Debug, Clone, Copy)]
+pub struct CurveConversionSettings {
+    /// Maximum distance between cubic and quadratic outlines, in font units
+    pub tolerance: f64,
+}
+
+impl Default for CurveConversionSettings {
+    fn default() -> Self {
+        Self { tolerance: 1.0 }
+    }
+}
+
+/// Configuration for contour offsetting and stroke expansion
+#[derive(Debug, Clone, Copy)]
+pub struct OffsetSettings {
+    /// Distance contours are grown or shrunk by, in font units
+    pub distance: f64,
+    /// Width of outlines made by expanding strokes, in font units
+    pub stroke_width: f64,
+    /// How offset outlines are connected at corners
+    pub join: Join,
+    /// Shape of the ends of expanded open contours
+    pub cap: Cap,
+    /// Miter joins longer than this many times the offset width are beveled
+    pub miter_limit: f64,
+    /// Maximum deviation from the exact offset curve, in font units
+    pub tolerance: f64,
+    /// Refit the result with as few curves as possible
+    pub simplify: bool,
+}
+
+impl Default for OffsetSettings {
+    fn default() -> Self {
+        Self {
+            distance: 10.0,
+            stroke_width: 40.0,
+            join: Join::Miter,
+            cap: Cap::Butt,
+            miter_limit: 4.0,
+            tolerance: 0.5,
+            simplify: true,
+        }
+    }
+}
+
+impl OffsetSettings {
+    /// Parameters for the offset algorithm
+    pub fn params(&self) -> OffsetParams {
+        OffsetParams {
+            join: self.join,
+            miter_limit: self.miter_limit,
+            tolerance: self.tolerance,
+            simplify: self.simplify,
+        }
+    }
+}
+
+/// Configuration for tracing bitmaps into contours
+#[derive(Debug, Clone, Copy)]
+pub struct AutotraceSettings {
+    /// Darkness from 0 (white) to 1 (black) above which a pixel is ink
+    pub threshold: f64,
+    /// Maximum distance between the traced edges and the curves, in font
+    /// units
+    pub tolerance: f64,
+    /// Edges turning by more than this many degrees get a corner
+    pub corner_angle: f64,
+    /// Shapes smaller than this many square pixels are ignored
+    pub despeckle_area: f64,
+}
+
+impl Default for AutotraceSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            tolerance: 2.0,
+            corner_angle: 55.0,
+            despeckle_area: 4.0,
+        }
+    }
+}
+
+impl AutotraceSettings {
+    /// Parameters for the tracing algorithm
+    pub fn params(&self) -> TraceParams {
+        TraceParams {
+            threshold: self.threshold,
+            tolerance: self.tolerance,
+            corner_angle: self.corner_angle,
+            despeckle_area: self.despeckle_area,
+        }
+    }
+}
+
 /// Main settings resource containing all configuration
 ///
 /// This is a Bevy resource that can be accessed from any system.
@@ -59,7 +261,12 @@ impl Default for NudgeSettings {
 #[derive(Resource, Debug, Clone, Default)]
 pub struct BezySettings {
     pub grid: GridSettings,
+    pub snap: SnapSettings,
     pub nudge: NudgeSettings,
+    pub simplify: SimplifySettings,
+    pub curve_conversion: CurveConversionSettings,
+    pub offset: OffsetSettings,
+    pub autotrace: AutotraceSettings,
     pub theme: ThemeVariant,
 }
 
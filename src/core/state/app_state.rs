@@ -295,8 +295,8 @@ impl Workspace {
     pub fn get_font_name(&self) -> String {
         let parts: Vec<&str> = [&self.info.family_name, &self.info.style_name]
             .iter()
+            .filter_map(|s| s.as_deref())
             .filter(|s| !s.is_empty())
-            .map(|s| s.as_str())
             .collect();
 
         if parts.is_empty() {
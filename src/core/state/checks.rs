@@ -0,0 +1,143 @@
+//! Results of the outline and interpolation compatibility checks
+//!
+//! The checks run in `systems::checks`; the outline check and compatibility
+//! panes list what they find, and the glyph editing rendering marks it on
+//! the canvas.
+
+use crate::core::state::FontIRAppState;
+use crate::data::compatibility::{check_compatibility, CompatibilityIssue};
+use crate::data::designspace::locations_match;
+use crate::geometry::outline_check::{check_contours, OutlineProblem};
+use bevy::prelude::*;
+use fontdrasil::coords::NormalizedLocation;
+use kurbo::{BezPath, Point};
+use std::collections::HashSet;
+
+/// Which glyphs the outline check looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheckScope {
+    /// The glyph being edited
+    #[default]
+    Glyph,
+    /// Every glyph in the font
+    Font,
+}
+
+/// Problems found by the outline check, listed in the outline check pane
+/// and marked on the canvas while it is open
+#[derive(Resource, Default)]
+pub struct OutlineCheckState {
+    /// Whether the outline check pane is open; glyphs are only checked
+    /// meanwhile
+    pub visible: bool,
+    pub scope: CheckScope,
+    /// Problems found, with the glyph each one is in
+    pub problems: Vec<(String, OutlineProblem)>,
+    /// Glyphs to check again, after fixes made from the pane
+    pub stale: HashSet<String>,
+}
+
+impl OutlineCheckState {
+    /// Problems found in a glyph
+    pub fn glyph_problems<'a>(
+        &'a self,
+        glyph_name: &'a str,
+    ) -> impl Iterator<Item = &'a OutlineProblem> + 'a {
+        self.problems
+            .iter()
+            .filter(move |(name, _)| name == glyph_name)
+            .map(|(_, problem)| problem)
+    }
+
+    /// Replace the problems found in a glyph
+    pub fn set_glyph_problems(
+        &mut self,
+        glyph_name: &str,
+        contours: &[BezPath],
+    ) {
+        self.problems.retain(|(name, _)| name != glyph_name);
+        self.problems.extend(
+            check_contours(contours)
+                .into_iter()
+                .map(|problem| (glyph_name.to_string(), problem)),
+        );
+    }
+}
+
+/// A difference between a master of a glyph and its default master
+#[derive(Debug, Clone)]
+pub struct GlyphIssue {
+    pub glyph_name: String,
+    /// Display name of the master that differs
+    pub master: String,
+    pub location: NormalizedLocation,
+    /// Location of the master compared against
+    pub reference_location: NormalizedLocation,
+    pub issue: CompatibilityIssue,
+}
+
+/// Interpolation issues found by the compatibility check, listed in the
+/// compatibility pane and marked on the canvas
+#[derive(Resource, Default)]
+pub struct CompatibilityState {
+    /// Whether the compatibility pane is open; while it is closed only the
+    /// current glyph is checked, whatever the scope
+    pub visible: bool,
+    pub scope: CheckScope,
+    pub issues: Vec<GlyphIssue>,
+}
+
+impl CompatibilityState {
+    /// Points of a glyph to mark in the master at a location, with their
+    /// labels: the points that differ in that master and, in the master
+    /// compared against, the points they should match
+    pub fn glyph_markers(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Vec<(Point, String)> {
+        let mut markers = Vec::new();
+        for issue in &self.issues {
+            if issue.glyph_name != glyph_name {
+                continue;
+            }
+            if let Some(position) = issue.issue.position {
+                if locations_match(&issue.location, location) {
+                    markers.push((position, issue.issue.description()));
+                }
+            }
+            if let Some(position) = issue.issue.reference_position {
+                if locations_match(&issue.reference_location, location) {
+                    markers
+                        .push((position, format!("{} differs", issue.master)));
+                }
+            }
+        }
+        markers
+    }
+
+    /// Replace the issues found in a glyph
+    pub fn set_glyph_issues(
+        &mut self,
+        fontir_state: &FontIRAppState,
+        glyph_name: &str,
+    ) {
+        self.issues.retain(|issue| issue.glyph_name != glyph_name);
+        let masters = fontir_state.glyph_masters(glyph_name);
+        let Some((reference_location, _)) = masters.first() else {
+            return;
+        };
+        let glyphs: Vec<_> =
+            masters.iter().map(|(_, glyph)| glyph.clone()).collect();
+        for issue in check_compatibility(&glyphs, 0) {
+            let (location, glyph) = &masters[issue.master];
+            self.issues.push(GlyphIssue {
+                glyph_name: glyph_name.to_string(),
+                master: glyph.master.clone(),
+                location: location.clone(),
+                reference_location: reference_location.clone(),
+                issue,
+            });
+        }
+    }
+}
@@ -30,10 +30,14 @@ pub struct GlyphData {
     pub outline: Option<OutlineData>,
     /// Component references for composite glyphs
     pub components: Vec<ComponentData>,
+    /// Anchors for mark attachment and composites
+    pub anchors: Vec<AnchorData>,
+    /// Glyph-level guidelines
+    pub guidelines: Vec<GuidelineData>,
 }
 
 /// Thread-safe component data for composite glyphs
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ComponentData {
     /// Name of the base glyph being referenced
     pub base_glyph: String,
@@ -51,6 +55,281 @@ impl Default for ComponentData {
     }
 }
 
+impl ComponentData {
+    /// Create an untransformed reference to a base glyph
+    pub fn new(base_glyph: impl Into<String>) -> Self {
+        Self {
+            base_glyph: base_glyph.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The transformation as a kurbo affine
+    ///
+    /// The UFO matrix order (xx, xy, yx, yy, x, y) matches kurbo's
+    /// coefficient order, so no reordering is needed.
+    pub fn affine(&self) -> kurbo::Affine {
+        kurbo::Affine::new(self.transform)
+    }
+
+    /// Replace the transformation with a kurbo affine
+    pub fn set_affine(&mut self, affine: kurbo::Affine) {
+        self.transform = affine.as_coeffs();
+    }
+}
+
+/// Prefix that marks an anchor as the attaching side of a mark glyph
+pub const MARK_ANCHOR_PREFIX: char = '_';
+
+/// Thread-safe anchor data
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnchorData {
+    /// Anchor name, e.g. "top" or "_top" for marks
+    pub name: String,
+    /// X coordinate
+    pub x: f64,
+    /// Y coordinate
+    pub y: f64,
+    /// Unique identifier from the UFO, kept so it survives a save
+    pub identifier: Option<String>,
+    /// Color as red, green, blue and alpha from 0 to 1
+    pub color: Option<(f64, f64, f64, f64)>,
+}
+
+impl AnchorData {
+    /// Create a named anchor at a position
+    pub fn new(name: impl Into<String>, x: f64, y: f64) -> Self {
+        Self {
+            name: name.into(),
+            x,
+            y,
+            identifier: None,
+            color: None,
+        }
+    }
+
+    /// Whether this is the attaching anchor of a mark glyph, e.g. `_top`
+    pub fn is_mark(&self) -> bool {
+        self.name.starts_with(MARK_ANCHOR_PREFIX)
+    }
+
+    /// Name of the anchor a mark anchor attaches to, e.g. `top` for `_top`
+    pub fn attached_name(&self) -> Option<&str> {
+        self.name.strip_prefix(MARK_ANCHOR_PREFIX)
+    }
+}
+
+/// Opacity reference images are shown at when their UFO doesn't say
+pub const DEFAULT_IMAGE_OPACITY: f64 = 0.5;
+
+/// Thread-safe reference image data
+///
+/// An image placed in a glyph to trace from, such as a scanned sketch. It
+/// starts with its bottom left corner at the origin, one unit per pixel,
+/// is skewed horizontally, scaled, rotated counter-clockwise by `rotation`
+/// degrees about the origin and then moved to (`x`, `y`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageData {
+    /// PNG file in the UFO's `images` directory
+    pub file_name: String,
+    /// X coordinate of the image's origin
+    pub x: f64,
+    /// Y coordinate of the image's origin
+    pub y: f64,
+    /// Horizontal scale, in units per pixel
+    pub x_scale: f64,
+    /// Vertical scale, in units per pixel
+    pub y_scale: f64,
+    /// Rotation in degrees, counter-clockwise
+    pub rotation: f64,
+    /// Horizontal skew, in pixels moved right per pixel up
+    ///
+    /// Only images from other editors are skewed. It is kept so they save
+    /// as they were, but the canvas draws them without it.
+    pub skew: f64,
+    /// Opacity, from 0 (hidden) to 1
+    pub opacity: f64,
+    /// Red, green and blue of the image's color in its UFO, if it has one;
+    /// the alpha is the opacity
+    pub color: Option<(f64, f64, f64)>,
+}
+
+impl ImageData {
+    /// Place an image file unscaled at the origin
+    pub fn new(file_name: impl Into<String>) -> Self {
+        Self {
+            file_name: file_name.into(),
+            x: 0.0,
+            y: 0.0,
+            x_scale: 1.0,
+            y_scale: 1.0,
+            rotation: 0.0,
+            skew: 0.0,
+            opacity: DEFAULT_IMAGE_OPACITY,
+            color: None,
+        }
+    }
+
+    /// The placement as a kurbo affine, mapping pixels to font units
+    pub fn affine(&self) -> kurbo::Affine {
+        kurbo::Affine::translate((self.x, self.y))
+            * kurbo::Affine::rotate(self.rotation.to_radians())
+            * kurbo::Affine::scale_non_uniform(self.x_scale, self.y_scale)
+            * kurbo::Affine::new([1.0, 0.0, self.skew, 1.0, 0.0, 0.0])
+    }
+
+    /// Replace the placement with a kurbo affine
+    ///
+    /// A mirrored image gets a negative vertical scale.
+    pub fn set_affine(&mut self, affine: kurbo::Affine) {
+        let [a, b, c, d, x, y] = affine.as_coeffs();
+        let x_scale = a.hypot(b);
+        self.x = x;
+        self.y = y;
+        if x_scale > 0.0 {
+            let (sin, cos) = (b / x_scale, a / x_scale);
+            self.x_scale = x_scale;
+            self.y_scale = (a * d - b * c) / x_scale;
+            self.rotation = b.atan2(a).to_degrees();
+            self.skew = (cos * c + sin * d) / x_scale;
+        } else {
+            self.x_scale = 0.0;
+            self.y_scale = c.hypot(d);
+            self.rotation = 0.0;
+            self.skew = 0.0;
+        }
+    }
+
+    /// Scale the image by a factor, keeping a point where it is
+    pub fn scale_about(&mut self, factor: f64, center: kurbo::Point) {
+        self.x = center.x + (self.x - center.x) * factor;
+        self.y = center.y + (self.y - center.y) * factor;
+        self.x_scale *= factor;
+        self.y_scale *= factor;
+    }
+
+    /// Rotate the image counter-clockwise by some degrees, keeping a point
+    /// where it is
+    pub fn rotate_about(&mut self, degrees: f64, center: kurbo::Point) {
+        let rotation =
+            kurbo::Affine::rotate_about(degrees.to_radians(), center);
+        let origin = rotation * kurbo::Point::new(self.x, self.y);
+        self.x = origin.x;
+        self.y = origin.y;
+        self.rotation = normalize_image_rotation(self.rotation + degrees);
+    }
+}
+
+/// Bring a rotation in degrees into (-180, 180]
+fn normalize_image_rotation(degrees: f64) -> f64 {
+    let degrees = degrees.rem_euclid(360.0);
+    if degrees > 180.0 {
+        degrees - 360.0
+    } else {
+        degrees
+    }
+}
+
+/// Thread-safe guideline data
+///
+/// A guideline is an infinite line through a point at an angle, measured
+/// counter-clockwise from the x axis in degrees. Horizontal guidelines have
+/// an angle of 0, vertical ones an angle of 90.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuidelineData {
+    /// Optional guideline name
+    pub name: Option<String>,
+    /// X coordinate of a point on the line
+    pub x: f64,
+    /// Y coordinate of a point on the line
+    pub y: f64,
+    /// Angle in degrees, normalized to [0, 180)
+    pub angle: f64,
+    /// Locked guidelines can't be selected or moved
+    ///
+    /// The UFO has no lock state for guidelines, so this only lives in the
+    /// editor and every guideline is unlocked after loading.
+    pub locked: bool,
+}
+
+impl GuidelineData {
+    /// Create an unlocked, unnamed guideline
+    pub fn new(x: f64, y: f64, angle: f64) -> Self {
+        Self {
+            name: None,
+            x,
+            y,
+            angle: normalize_guideline_angle(angle),
+            locked: false,
+        }
+    }
+
+    /// Horizontal guideline at a height
+    pub fn horizontal(y: f64) -> Self {
+        Self::new(0.0, y, 0.0)
+    }
+
+    /// Vertical guideline at an x position
+    pub fn vertical(x: f64) -> Self {
+        Self::new(x, 0.0, 90.0)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.angle == 0.0
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.angle == 90.0
+    }
+
+    /// Rotate around the guideline's point
+    pub fn rotate(&mut self, degrees: f64) {
+        self.angle = normalize_guideline_angle(self.angle + degrees);
+    }
+
+    /// Unit vector along the guideline
+    pub fn direction(&self) -> kurbo::Vec2 {
+        let radians = self.angle.to_radians();
+        kurbo::Vec2::new(radians.cos(), radians.sin())
+    }
+
+    /// Closest point on the guideline to `point`
+    pub fn project(&self, point: kurbo::Point) -> kurbo::Point {
+        let origin = kurbo::Point::new(self.x, self.y);
+        let direction = self.direction();
+        origin + direction * (point - origin).dot(direction)
+    }
+
+    /// Distance from `point` to the guideline
+    pub fn distance(&self, point: kurbo::Point) -> f64 {
+        (point - self.project(point)).hypot()
+    }
+
+    /// Point where two guidelines cross, if they are not parallel
+    pub fn intersection(&self, other: &GuidelineData) -> Option<kurbo::Point> {
+        let (d1, d2) = (self.direction(), other.direction());
+        let denominator = d1.cross(d2);
+        if denominator.abs() < 1e-9 {
+            return None;
+        }
+        let origin = kurbo::Point::new(self.x, self.y);
+        let offset = kurbo::Point::new(other.x, other.y) - origin;
+        Some(origin + d1 * (offset.cross(d2) / denominator))
+    }
+}
+
+/// Bring an angle in degrees into [0, 180), since a line at 190° is the
+/// same line as one at 10°
+pub fn normalize_guideline_angle(degrees: f64) -> f64 {
+    let angle = degrees.rem_euclid(180.0);
+    // Rounding can land exactly on 180
+    if angle >= 180.0 {
+        0.0
+    } else {
+        angle
+    }
+}
+
 /// Thread-safe outline data
 #[derive(Clone, Debug)]
 pub struct OutlineData {
@@ -86,9 +365,108 @@ pub enum PointTypeData {
     QCurve,
 }
 
+impl PointTypeData {
+    /// Whether this point lies on the outline (cubic or quadratic)
+    pub fn is_on_curve(&self) -> bool {
+        !matches!(self, PointTypeData::OffCurve)
+    }
+}
+
 impl FontData {
     /// Get a glyph by name
     pub fn get_glyph(&self, name: &str) -> Option<&GlyphData> {
         self.glyphs.get(name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guideline_geometry() {
+        let horizontal = GuidelineData::horizontal(500.0);
+        let point = kurbo::Point::new(120.0, 510.0);
+        assert_eq!(horizontal.project(point), kurbo::Point::new(120.0, 500.0));
+        assert!((horizontal.distance(point) - 10.0).abs() < 1e-9);
+
+        let vertical = GuidelineData::vertical(300.0);
+        assert!((vertical.distance(point) - 180.0).abs() < 1e-9);
+
+        let diagonal = GuidelineData::new(0.0, 0.0, 45.0);
+        let projected = diagonal.project(kurbo::Point::new(100.0, 0.0));
+        assert!((projected.x - 50.0).abs() < 1e-9);
+        assert!((projected.y - 50.0).abs() < 1e-9);
+
+        let crossing = horizontal.intersection(&vertical).unwrap();
+        assert!((crossing.x - 300.0).abs() < 1e-9);
+        assert!((crossing.y - 500.0).abs() < 1e-9);
+        assert!(horizontal
+            .intersection(&GuidelineData::horizontal(0.0))
+            .is_none());
+    }
+
+    #[test]
+    fn test_image_placement() {
+        let mut image = ImageData::new("sketch.png");
+        image.x = 10.0;
+        image.y = -200.0;
+        image.x_scale = 0.5;
+        image.y_scale = 0.25;
+        image.rotation = 90.0;
+        let corner = image.affine() * kurbo::Point::new(100.0, 0.0);
+        assert!((corner.x - 10.0).abs() < 1e-9);
+        assert!((corner.y + 150.0).abs() < 1e-9);
+
+        let mut placed = ImageData::new("sketch.png");
+        placed.set_affine(image.affine());
+        assert!((placed.x - 10.0).abs() < 1e-9);
+        assert!((placed.y + 200.0).abs() < 1e-9);
+        assert!((placed.x_scale - 0.5).abs() < 1e-9);
+        assert!((placed.y_scale - 0.25).abs() < 1e-9);
+        assert!((placed.rotation - 90.0).abs() < 1e-9);
+
+        let mut mirrored = ImageData::new("sketch.png");
+        mirrored
+            .set_affine(kurbo::Affine::new([1.0, 0.0, 0.0, -1.0, 0.0, 0.0]));
+        assert_eq!(mirrored.y_scale, -1.0);
+
+        let skewed_affine = kurbo::Affine::new([0.0, 2.0, -1.0, 0.5, 3.0, 4.0]);
+        let mut skewed = ImageData::new("sketch.png");
+        skewed.set_affine(skewed_affine);
+        assert!((skewed.skew - 0.25).abs() < 1e-9);
+        for (coeff, expected) in skewed
+            .affine()
+            .as_coeffs()
+            .iter()
+            .zip(skewed_affine.as_coeffs())
+        {
+            assert!((coeff - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_image_scale_and_rotate_about() {
+        let mut image = ImageData::new("sketch.png");
+        image.x = 100.0;
+        image.scale_about(2.0, kurbo::Point::new(50.0, 0.0));
+        assert_eq!((image.x, image.y), (150.0, 0.0));
+        assert_eq!((image.x_scale, image.y_scale), (2.0, 2.0));
+
+        image.rotate_about(270.0, kurbo::Point::new(50.0, 0.0));
+        assert!((image.x - 50.0).abs() < 1e-9);
+        assert!((image.y + 100.0).abs() < 1e-9);
+        assert_eq!(image.rotation, -90.0);
+    }
+
+    #[test]
+    fn test_guideline_angle_normalization() {
+        assert_eq!(normalize_guideline_angle(190.0), 10.0);
+        assert_eq!(normalize_guideline_angle(-90.0), 90.0);
+        assert_eq!(normalize_guideline_angle(180.0), 0.0);
+
+        let mut guideline = GuidelineData::vertical(0.0);
+        guideline.rotate(-105.0);
+        assert_eq!(guideline.angle, 165.0);
+    }
+}
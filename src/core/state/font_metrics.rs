@@ -3,20 +3,70 @@
 //! This module contains structures for font metrics like ascender,
 //! descender, x-height, and other measurement information.
 
+use anyhow::{anyhow, Result};
 use bevy::prelude::*;
 use norad::Font;
+use std::str::FromStr;
 
 /// Font information
 #[derive(Clone, Default)]
 pub struct FontInfo {
-    pub family_name: String,
-    pub style_name: String,
-    pub units_per_em: f64,
+    pub family_name: Option<String>,
+    pub style_name: Option<String>,
+    pub units_per_em: Option<f64>,
     pub metrics: FontMetrics,
     pub ascender: Option<f64>,
     pub descender: Option<f64>,
     pub x_height: Option<f64>,
     pub cap_height: Option<f64>,
+    pub italic_angle: Option<f64>,
+    pub version_major: Option<i32>,
+    pub version_minor: Option<u32>,
+    pub os2_typo_ascender: Option<i32>,
+    pub os2_typo_descender: Option<i32>,
+    pub os2_typo_line_gap: Option<i32>,
+    pub os2_win_ascent: Option<u32>,
+    pub os2_win_descent: Option<u32>,
+    pub hhea_ascender: Option<i32>,
+    pub hhea_descender: Option<i32>,
+    pub hhea_line_gap: Option<i32>,
+    pub designer: Option<String>,
+    pub designer_url: Option<String>,
+    pub manufacturer: Option<String>,
+    pub copyright: Option<String>,
+    pub license: Option<String>,
+    pub license_url: Option<String>,
+    pub trademark: Option<String>,
+}
+
+/// Font info values that can be edited in the font info pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontInfoField {
+    FamilyName,
+    StyleName,
+    VersionMajor,
+    VersionMinor,
+    UnitsPerEm,
+    Ascender,
+    Descender,
+    XHeight,
+    CapHeight,
+    ItalicAngle,
+    Os2TypoAscender,
+    Os2TypoDescender,
+    Os2TypoLineGap,
+    Os2WinAscent,
+    Os2WinDescent,
+    HheaAscender,
+    HheaDescender,
+    HheaLineGap,
+    Designer,
+    DesignerUrl,
+    Manufacturer,
+    Copyright,
+    License,
+    LicenseUrl,
+    Trademark,
 }
 
 /// Font metrics for spacing and positioning
@@ -34,60 +84,49 @@ pub struct FontMetrics {
 impl FontInfo {
     /// Extract font info from norad Font
     pub fn from_norad_font(font: &Font) -> Self {
-        let units_per_em = font
-            .font_info
-            .units_per_em
-            .map(|v| v.to_string().parse().unwrap_or(1024.0))
-            .unwrap_or(1024.0);
-        let ascender = font.font_info.ascender;
-        let descender = font.font_info.descender;
-        let x_height = font.font_info.x_height;
-        let cap_height = font.font_info.cap_height;
-        let _italic_angle = font.font_info.italic_angle;
+        Self::from_norad_font_info(&font.font_info)
+    }
 
-        let metrics = FontMetrics::from_ufo(font);
+    /// Extract font info from a UFO's fontinfo
+    pub fn from_norad_font_info(font_info: &norad::FontInfo) -> Self {
+        let metrics = FontMetrics::from_font_info(font_info);
 
         Self {
-            family_name: Self::extract_string_field(
-                &font.font_info,
-                |info| &info.family_name,
-                "Untitled",
-            ),
-            style_name: Self::extract_string_field(
-                &font.font_info,
-                |info| &info.style_name,
-                "Regular",
-            ),
-            units_per_em,
+            family_name: font_info.family_name.clone(),
+            style_name: font_info.style_name.clone(),
+            units_per_em: units_per_em_of(font_info),
             metrics,
-            ascender,
-            descender,
-            x_height,
-            cap_height,
+            ascender: font_info.ascender,
+            descender: font_info.descender,
+            x_height: font_info.x_height,
+            cap_height: font_info.cap_height,
+            italic_angle: font_info.italic_angle,
+            version_major: font_info.version_major,
+            version_minor: font_info.version_minor,
+            os2_typo_ascender: font_info.open_type_os2_typo_ascender,
+            os2_typo_descender: font_info.open_type_os2_typo_descender,
+            os2_typo_line_gap: font_info.open_type_os2_typo_line_gap,
+            os2_win_ascent: font_info.open_type_os2_win_ascent,
+            os2_win_descent: font_info.open_type_os2_win_descent,
+            hhea_ascender: font_info.open_type_hhea_ascender,
+            hhea_descender: font_info.open_type_hhea_descender,
+            hhea_line_gap: font_info.open_type_hhea_line_gap,
+            designer: font_info.open_type_name_designer.clone(),
+            designer_url: font_info.open_type_name_designer_url.clone(),
+            manufacturer: font_info.open_type_name_manufacturer.clone(),
+            copyright: font_info.copyright.clone(),
+            license: font_info.open_type_name_license.clone(),
+            license_url: font_info.open_type_name_license_url.clone(),
+            trademark: font_info.trademark.clone(),
         }
     }
 
-    /// Helper to extract string fields with defaults
-    fn extract_string_field<F>(
-        font_info: &norad::FontInfo,
-        getter: F,
-        default: &str,
-    ) -> String
-    where
-        F: Fn(&norad::FontInfo) -> &Option<String>,
-    {
-        getter(font_info)
-            .as_ref()
-            .cloned()
-            .unwrap_or_else(|| default.to_string())
-    }
-
     /// Get a display name combining family and style names
     pub fn get_display_name(&self) -> String {
         let parts: Vec<&str> = [&self.family_name, &self.style_name]
             .iter()
+            .filter_map(|s| s.as_deref())
             .filter(|s| !s.is_empty())
-            .map(|s| s.as_str())
             .collect();
 
         if parts.is_empty() {
@@ -100,34 +139,237 @@ impl FontInfo {
     /// Convert back to norad FontInfo
     pub fn to_norad_font_info(&self) -> norad::FontInfo {
         let mut info = norad::FontInfo::default();
+        self.write_to_norad_font_info(&mut info);
+        info
+    }
 
-        // Set family and style names
-        if !self.family_name.is_empty() {
-            info.family_name = Some(self.family_name.clone());
-        }
-        if !self.style_name.is_empty() {
-            info.style_name = Some(self.style_name.clone());
-        }
+    /// Write these values into a UFO's fontinfo
+    ///
+    /// Fields this struct doesn't hold (guidelines, name records, ...) are
+    /// left as they are, and so are fields that are not set in either.
+    pub fn write_to_norad_font_info(&self, info: &mut norad::FontInfo) {
+        info.family_name = self.family_name.clone();
+        info.style_name = self.style_name.clone();
 
-        // Set numeric values
-        if let Some(units_per_em) =
-            norad::fontinfo::NonNegativeIntegerOrFloat::new(self.units_per_em)
-        {
-            info.units_per_em = Some(units_per_em);
+        // Rewriting an unchanged value could turn an integer into a float
+        if self.units_per_em != units_per_em_of(info) {
+            info.units_per_em = self
+                .units_per_em
+                .and_then(norad::fontinfo::NonNegativeIntegerOrFloat::new);
         }
         info.ascender = self.ascender;
         info.descender = self.descender;
         info.x_height = self.x_height;
         info.cap_height = self.cap_height;
-        info
+        info.italic_angle = self.italic_angle;
+        info.version_major = self.version_major;
+        info.version_minor = self.version_minor;
+        info.open_type_os2_typo_ascender = self.os2_typo_ascender;
+        info.open_type_os2_typo_descender = self.os2_typo_descender;
+        info.open_type_os2_typo_line_gap = self.os2_typo_line_gap;
+        info.open_type_os2_win_ascent = self.os2_win_ascent;
+        info.open_type_os2_win_descent = self.os2_win_descent;
+        info.open_type_hhea_ascender = self.hhea_ascender;
+        info.open_type_hhea_descender = self.hhea_descender;
+        info.open_type_hhea_line_gap = self.hhea_line_gap;
+        info.open_type_name_designer = self.designer.clone();
+        info.open_type_name_designer_url = self.designer_url.clone();
+        info.open_type_name_manufacturer = self.manufacturer.clone();
+        info.copyright = self.copyright.clone();
+        info.open_type_name_license = self.license.clone();
+        info.open_type_name_license_url = self.license_url.clone();
+        info.trademark = self.trademark.clone();
+    }
+
+    /// Display text of a field, empty when the field is not set
+    pub fn field_text(&self, field: FontInfoField) -> String {
+        use FontInfoField::*;
+
+        fn show<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(ToString::to_string).unwrap_or_default()
+        }
+
+        match field {
+            FamilyName => show(&self.family_name),
+            StyleName => show(&self.style_name),
+            VersionMajor => show(&self.version_major),
+            VersionMinor => show(&self.version_minor),
+            UnitsPerEm => show(&self.units_per_em),
+            Ascender => show(&self.ascender),
+            Descender => show(&self.descender),
+            XHeight => show(&self.x_height),
+            CapHeight => show(&self.cap_height),
+            ItalicAngle => show(&self.italic_angle),
+            Os2TypoAscender => show(&self.os2_typo_ascender),
+            Os2TypoDescender => show(&self.os2_typo_descender),
+            Os2TypoLineGap => show(&self.os2_typo_line_gap),
+            Os2WinAscent => show(&self.os2_win_ascent),
+            Os2WinDescent => show(&self.os2_win_descent),
+            HheaAscender => show(&self.hhea_ascender),
+            HheaDescender => show(&self.hhea_descender),
+            HheaLineGap => show(&self.hhea_line_gap),
+            Designer => show(&self.designer),
+            DesignerUrl => show(&self.designer_url),
+            Manufacturer => show(&self.manufacturer),
+            Copyright => show(&self.copyright),
+            License => show(&self.license),
+            LicenseUrl => show(&self.license_url),
+            Trademark => show(&self.trademark),
+        }
+    }
+
+    /// Set a field from text typed in the font info pane
+    ///
+    /// Empty text unsets the field. Numbers that don't parse, or a units per
+    /// em value that isn't positive, leave the field unchanged.
+    pub fn set_field(
+        &mut self,
+        field: FontInfoField,
+        text: &str,
+    ) -> Result<()> {
+        use FontInfoField::*;
+
+        let text = text.trim();
+        let name = || (!text.is_empty()).then(|| text.to_string());
+        match field {
+            FamilyName => self.family_name = name(),
+            StyleName => self.style_name = name(),
+            VersionMajor => self.version_major = parse_field(field, text)?,
+            VersionMinor => self.version_minor = parse_field(field, text)?,
+            UnitsPerEm => {
+                let units_per_em = parse_field(field, text)?
+                    .filter(|units_per_em: &f64| *units_per_em > 0.0)
+                    .ok_or_else(|| {
+                        anyhow!("Units per em must be a positive number")
+                    })?;
+                self.units_per_em = Some(units_per_em);
+            }
+            Ascender => self.ascender = parse_field(field, text)?,
+            Descender => self.descender = parse_field(field, text)?,
+            XHeight => self.x_height = parse_field(field, text)?,
+            CapHeight => self.cap_height = parse_field(field, text)?,
+            ItalicAngle => self.italic_angle = parse_field(field, text)?,
+            Os2TypoAscender => {
+                self.os2_typo_ascender = parse_field(field, text)?
+            }
+            Os2TypoDescender => {
+                self.os2_typo_descender = parse_field(field, text)?
+            }
+            Os2TypoLineGap => {
+                self.os2_typo_line_gap = parse_field(field, text)?
+            }
+            Os2WinAscent => self.os2_win_ascent = parse_field(field, text)?,
+            Os2WinDescent => self.os2_win_descent = parse_field(field, text)?,
+            HheaAscender => self.hhea_ascender = parse_field(field, text)?,
+            HheaDescender => self.hhea_descender = parse_field(field, text)?,
+            HheaLineGap => self.hhea_line_gap = parse_field(field, text)?,
+            Designer => self.designer = name(),
+            DesignerUrl => self.designer_url = name(),
+            Manufacturer => self.manufacturer = name(),
+            Copyright => self.copyright = name(),
+            License => self.license = name(),
+            LicenseUrl => self.license_url = name(),
+            Trademark => self.trademark = name(),
+        }
+
+        // Keep the derived metrics in step with the edited values
+        self.metrics = FontMetrics::from_font_info(&self.to_norad_font_info());
+        Ok(())
+    }
+}
+
+/// Units per em of a UFO's fontinfo, if it has a valid value
+fn units_per_em_of(font_info: &norad::FontInfo) -> Option<f64> {
+    font_info
+        .units_per_em
+        .and_then(|value| value.to_string().parse().ok())
+}
+
+/// Parse the text of a numeric field, with empty text meaning "not set"
+fn parse_field<T: FromStr>(
+    field: FontInfoField,
+    text: &str,
+) -> Result<Option<T>> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse()
+        .map(Some)
+        .map_err(|_| anyhow!("'{}' is not a valid {}", text, field.label()))
+}
+
+impl FontInfoField {
+    /// All fields, in the order the font info pane lists them
+    pub const ALL: [FontInfoField; 25] = [
+        FontInfoField::FamilyName,
+        FontInfoField::StyleName,
+        FontInfoField::VersionMajor,
+        FontInfoField::VersionMinor,
+        FontInfoField::UnitsPerEm,
+        FontInfoField::Ascender,
+        FontInfoField::Descender,
+        FontInfoField::XHeight,
+        FontInfoField::CapHeight,
+        FontInfoField::ItalicAngle,
+        FontInfoField::Os2TypoAscender,
+        FontInfoField::Os2TypoDescender,
+        FontInfoField::Os2TypoLineGap,
+        FontInfoField::Os2WinAscent,
+        FontInfoField::Os2WinDescent,
+        FontInfoField::HheaAscender,
+        FontInfoField::HheaDescender,
+        FontInfoField::HheaLineGap,
+        FontInfoField::Designer,
+        FontInfoField::DesignerUrl,
+        FontInfoField::Manufacturer,
+        FontInfoField::Copyright,
+        FontInfoField::License,
+        FontInfoField::LicenseUrl,
+        FontInfoField::Trademark,
+    ];
+
+    /// Label shown in the font info pane
+    pub fn label(self) -> &'static str {
+        use FontInfoField::*;
+
+        match self {
+            FamilyName => "Family",
+            StyleName => "Style",
+            VersionMajor => "Version Major",
+            VersionMinor => "Version Minor",
+            UnitsPerEm => "Units per Em",
+            Ascender => "Ascender",
+            Descender => "Descender",
+            XHeight => "x-Height",
+            CapHeight => "Cap Height",
+            ItalicAngle => "Italic Angle",
+            Os2TypoAscender => "OS/2 Typo Ascender",
+            Os2TypoDescender => "OS/2 Typo Descender",
+            Os2TypoLineGap => "OS/2 Typo Line Gap",
+            Os2WinAscent => "OS/2 Win Ascent",
+            Os2WinDescent => "OS/2 Win Descent",
+            HheaAscender => "hhea Ascender",
+            HheaDescender => "hhea Descender",
+            HheaLineGap => "hhea Line Gap",
+            Designer => "Designer",
+            DesignerUrl => "Designer URL",
+            Manufacturer => "Manufacturer",
+            Copyright => "Copyright",
+            License => "License",
+            LicenseUrl => "License URL",
+            Trademark => "Trademark",
+        }
     }
 }
 
 impl FontMetrics {
     /// Extract metrics from a UFO
     pub fn from_ufo(ufo: &Font) -> Self {
-        let font_info = &ufo.font_info;
+        Self::from_font_info(&ufo.font_info)
+    }
 
+    /// Extract metrics from a UFO's fontinfo
+    pub fn from_font_info(font_info: &norad::FontInfo) -> Self {
         let units_per_em = font_info
             .units_per_em
             .map(|v| v.to_string().parse().unwrap_or(1024.0))
@@ -139,7 +381,7 @@ impl FontMetrics {
             font_info.descender.or_else(|| Some(-(units_per_em * 0.2))); // -20% of UPM
         let x_height = font_info.x_height;
         let cap_height = font_info.cap_height;
-        let _italic_angle = font_info.italic_angle;
+        let italic_angle = font_info.italic_angle;
 
         let line_height = ascender.unwrap() - descender.unwrap();
 
@@ -149,8 +391,56 @@ impl FontMetrics {
             x_height,
             cap_height,
             ascender,
-            italic_angle: None,
+            italic_angle,
             line_height,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_info_field_editing() {
+        let mut info =
+            FontInfo::from_norad_font_info(&norad::FontInfo::default());
+
+        info.set_field(FontInfoField::XHeight, " 520 ").unwrap();
+        assert_eq!(info.x_height, Some(520.0));
+        assert_eq!(info.metrics.x_height, Some(520.0));
+        assert_eq!(info.field_text(FontInfoField::XHeight), "520");
+
+        // Empty text unsets a field
+        info.set_field(FontInfoField::XHeight, "").unwrap();
+        assert_eq!(info.x_height, None);
+
+        // Invalid values leave the field unchanged
+        assert!(info.set_field(FontInfoField::Os2WinAscent, "-5").is_err());
+        assert!(info.set_field(FontInfoField::UnitsPerEm, "0").is_err());
+        assert_eq!(info.units_per_em, None);
+
+        // Fields the struct doesn't hold survive the write-back
+        info.set_field(FontInfoField::Designer, "Jane Doe").unwrap();
+        let mut norad_info = norad::FontInfo {
+            note: Some("kept".to_string()),
+            ..Default::default()
+        };
+        info.write_to_norad_font_info(&mut norad_info);
+        assert_eq!(
+            norad_info.open_type_name_designer.as_deref(),
+            Some("Jane Doe")
+        );
+        assert_eq!(norad_info.note.as_deref(), Some("kept"));
+
+        // Names and units per em missing from the file stay missing
+        assert_eq!(norad_info.family_name, None);
+        assert_eq!(norad_info.style_name, None);
+        assert!(norad_info.units_per_em.is_none());
+        assert_eq!(info.field_text(FontInfoField::FamilyName), "");
+
+        info.set_field(FontInfoField::UnitsPerEm, "2048").unwrap();
+        info.write_to_norad_font_info(&mut norad_info);
+        assert_eq!(units_per_em_of(&norad_info), Some(2048.0));
+    }
+}
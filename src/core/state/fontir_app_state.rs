@@ -4,9 +4,31 @@
 //! instead of custom data types. This enables multi-format support and
 //! variable font handling.
 
+use crate::core::state::font_data::{
+    AnchorData, ComponentData, GuidelineData, ImageData,
+};
+use crate::core::state::font_metrics::{FontInfo, FontInfoField};
+use crate::data::compatibility::MasterGlyph;
+use crate::data::designspace::{
+    load_axes, load_instances, load_master_sources, location_value,
+    locations_match, DesignAxis, DesignInstance, MasterSource,
+};
+use crate::data::designspace_editing::{
+    add_instance, add_source, design_location, instance_name, new_source,
+    new_sparse_source, source_name,
+};
+use crate::data::glyph_set::{validate_glyph_name, GlyphSetChange};
+use crate::data::images::{png_image_data, unique_image_file_name};
+use crate::data::interpolation::{interpolate_contours, master_weights};
+use crate::data::layers::{
+    background_layer_name, load_glyph_layers, LayerGlyph, LayerGlyphs,
+};
+use crate::data::master_sync::sync_contours;
+use crate::data::ufo::build_codepoint_glyphs_map;
+use crate::geometry::quadratic;
 use anyhow::Result;
 use bevy::prelude::*;
-use fontdrasil::coords::NormalizedLocation;
+use fontdrasil::coords::{NormalizedCoord, NormalizedLocation};
 use fontdrasil::orchestration::Access;
 use fontdrasil::types::GlyphName;
 use fontir::ir::{Glyph as FontIRGlyph, GlyphInstance};
@@ -15,8 +37,9 @@ use fontir::paths::Paths;
 use fontir::source::Source;
 use kurbo::{Affine, BezPath, PathEl, Point};
 use norad::designspace::DesignSpaceDocument;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 use ufo2fontir::source::DesignSpaceIrSource;
@@ -39,6 +62,17 @@ pub struct EditableGlyphInstance {
     pub height: Option<f64>,
     pub vertical_origin: Option<f64>,
     pub contours: Vec<BezPath>,
+    /// Which points of each contour are on-curve points left implied in
+    /// the UFO, by flat point index; missing flags count as explicit
+    pub implied_on_curves: Vec<Vec<bool>>,
+    /// Component references, in UFO order
+    pub components: Vec<ComponentData>,
+    /// Anchors, in UFO order
+    pub anchors: Vec<AnchorData>,
+    /// Glyph-level guidelines, in UFO order
+    pub guidelines: Vec<GuidelineData>,
+    /// Reference image, if any
+    pub image: Option<ImageData>,
     /// Track if this instance has been modified from the original
     pub is_dirty: bool,
 }
@@ -50,6 +84,11 @@ impl From<&GlyphInstance> for EditableGlyphInstance {
             height: instance.height,
             vertical_origin: instance.vertical_origin,
             contours: instance.contours.clone(),
+            implied_on_curves: Vec::new(),
+            components: Vec::new(),
+            anchors: Vec::new(),
+            guidelines: Vec::new(),
+            image: None,
             is_dirty: false,
         }
     }
@@ -87,8 +126,137 @@ pub struct FontIRAppState {
     /// Kerning groups data loaded from UFO groups.plist
     /// Maps group name (e.g. "public.kern1.a") to list of glyph names
     pub kerning_groups: HashMap<String, Vec<String>>,
+
+    /// Glyph data FontIR does not keep (components, anchors, implied
+    /// on-curve points), per master
+    /// source, read from the UFOs
+    pub master_glyphs: Vec<(MasterSource, HashMap<String, MasterGlyphData>)>,
+
+    /// Glyphs created in the editor that are not in the sources yet, with
+    /// their codepoints; their outlines live only in the working copies
+    /// until they are saved, after which the font is reloaded
+    pub new_glyphs: HashMap<String, Vec<char>>,
+
+    /// Glyphs renamed or deleted since loading, in order, replayed on every
+    /// UFO on save
+    pub glyph_set_changes: Vec<GlyphSetChange>,
+
+    /// Changes whenever a glyph is added, renamed, deleted or given new
+    /// codepoints, so caches of the glyph set know when to refresh
+    pub glyph_set_generation: u64,
+
+    /// Names renamed, renamed to or deleted since loading, whose entries in
+    /// the FontIR context are out of date; lookups use `glyph_cache` instead
+    pub changed_glyph_names: HashSet<String>,
+
+    /// Font-level guidelines from each UFO's fontinfo.plist, keyed by UFO
+    /// path (masters stored as layers of one UFO share its guidelines)
+    pub font_guidelines: HashMap<PathBuf, Vec<GuidelineData>>,
+
+    /// Whether font-level guidelines were edited since loading
+    pub font_guidelines_dirty: bool,
+
+    /// Font info from each UFO's fontinfo.plist, keyed by UFO path like
+    /// `font_guidelines`
+    pub font_infos: HashMap<PathBuf, FontInfo>,
+
+    /// UFOs whose font info was edited since loading
+    pub font_info_dirty: HashSet<PathBuf>,
+
+    /// Changes whenever font info is loaded or edited, so caches of font
+    /// metrics know when to refresh
+    pub font_info_generation: u64,
+
+    /// Axes of the designspace, none for a single UFO
+    pub axes: Vec<DesignAxis>,
+
+    /// Named instances of the designspace, none for a single UFO
+    pub instances: Vec<DesignInstance>,
+
+    /// The designspace document with the edits made in the designspace
+    /// pane, `None` for a single UFO
+    pub designspace: Option<DesignSpaceDocument>,
+
+    /// Whether the designspace document was edited since loading; it is
+    /// written on save, and the font reloaded
+    pub designspace_dirty: bool,
+
+    /// UFOs to create on save for sources added in the editor, keyed by
+    /// filename, with the UFO each one starts as a copy of
+    pub new_source_seeds: HashMap<String, PathBuf>,
+
+    /// Intermediate masters created in the editor; their outlines live only
+    /// in the working copies until they are saved
+    pub new_intermediate_masters: Vec<NewIntermediateMaster>,
+
+    /// Layers of each UFO that aren't masters, such as backgrounds, keyed
+    /// by UFO path, then layer name
+    pub glyph_layers: HashMap<PathBuf, HashMap<String, LayerGlyphs>>,
+
+    /// Glyphs edited in those layers since loading, as (UFO path, layer
+    /// name, glyph name)
+    pub glyph_layers_dirty: HashSet<(PathBuf, String, String)>,
+
+    /// Glyph and location whose background is being edited; its working
+    /// copy holds the background meanwhile, and the background layer the
+    /// foreground, until they are swapped back
+    pub editing_background: Option<(String, NormalizedLocation)>,
+
+    /// PNG data of the reference images, keyed by UFO path, then file name
+    /// in its `images` directory; only images glyphs use are loaded
+    pub image_files: HashMap<PathBuf, HashMap<String, Arc<Vec<u8>>>>,
+
+    /// Images added in the editor, as (UFO path, file name), written into
+    /// the UFOs on save
+    pub new_image_files: HashSet<(PathBuf, String)>,
+}
+
+/// A master of a single glyph created in the editor between the font's
+/// masters
+#[derive(Clone, Debug)]
+pub struct NewIntermediateMaster {
+    pub glyph_name: String,
+    /// Location the glyph's working copy is stored at
+    pub location: NormalizedLocation,
+    /// Source the glyph is written to on save, often a sparse source added
+    /// along with the master
+    pub source: MasterSource,
 }
 
+/// Source of `font_info_generation` and `glyph_set_generation` values,
+/// unique across reloads so a replaced state never reuses the generation of
+/// the one before it
+static GENERATION: AtomicU64 = AtomicU64::new(1);
+
+fn next_generation() -> u64 {
+    GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Per-master glyph data kept in its UFO form, read directly from the UFO
+#[derive(Clone, Debug, Default)]
+pub struct MasterGlyphData {
+    /// Component references, in UFO order
+    ///
+    /// FontIR keeps components apart from outlines as well, but as its own
+    /// compilation input; reading them from the UFO keeps them the way
+    /// they are edited and saved, next to the anchors, guidelines and
+    /// image.
+    pub components: Vec<ComponentData>,
+    /// Anchors, in UFO order
+    pub anchors: Vec<AnchorData>,
+    /// Glyph-level guidelines, in UFO order
+    pub guidelines: Vec<GuidelineData>,
+    /// Reference image
+    pub image: Option<ImageData>,
+    /// Positions of the on-curve points the UFO leaves implied; FontIR
+    /// fills them in, and these tell them apart from explicit points
+    pub implied_on_curves: Vec<Point>,
+}
+
+/// Nesting depth at which component resolution gives up (guards against
+/// components that reference themselves)
+const MAX_COMPONENT_DEPTH: usize = 8;
+
 impl FontIRAppState {
     /// Create a new FontIR-based app state from a font file
     pub fn from_path(path: PathBuf) -> Result<Self> {
@@ -109,6 +277,27 @@ impl FontIRAppState {
             current_location,
             source_path: path.clone(),
             kerning_groups: HashMap::new(),
+            master_glyphs: Vec::new(),
+            new_glyphs: HashMap::new(),
+            glyph_set_changes: Vec::new(),
+            glyph_set_generation: next_generation(),
+            changed_glyph_names: HashSet::new(),
+            font_guidelines: HashMap::new(),
+            font_guidelines_dirty: false,
+            font_infos: HashMap::new(),
+            font_info_dirty: HashSet::new(),
+            font_info_generation: next_generation(),
+            axes: Vec::new(),
+            instances: Vec::new(),
+            designspace: None,
+            designspace_dirty: false,
+            new_source_seeds: HashMap::new(),
+            new_intermediate_masters: Vec::new(),
+            glyph_layers: HashMap::new(),
+            glyph_layers_dirty: HashSet::new(),
+            editing_background: None,
+            image_files: HashMap::new(),
+            new_image_files: HashSet::new(),
         };
 
         // Load glyphs into cache
@@ -121,6 +310,30 @@ impl FontIRAppState {
             warn!("Failed to load kerning groups during FontIR initialization: {}", e);
         }
 
+        // Load components, anchors, guidelines and font info from the UFO
+        // sources
+        if let Err(e) = app_state.load_master_glyphs() {
+            warn!(
+                "Failed to load UFO glyph data during FontIR initialization: {}",
+                e
+            );
+        }
+
+        match load_axes(&path) {
+            Ok(axes) => app_state.axes = axes,
+            Err(e) => warn!("Failed to load designspace axes: {}", e),
+        }
+        match load_instances(&path) {
+            Ok(instances) => app_state.instances = instances,
+            Err(e) => warn!("Failed to load designspace instances: {}", e),
+        }
+        if path.extension().and_then(|s| s.to_str()) == Some("designspace") {
+            match DesignSpaceDocument::load(&path) {
+                Ok(document) => app_state.designspace = Some(document),
+                Err(e) => warn!("Failed to load designspace document: {}", e),
+            }
+        }
+
         Ok(app_state)
     }
 
@@ -143,40 +356,27 @@ impl FontIRAppState {
     /// Get a glyph's path by name at the current location
     pub fn get_glyph_paths(&self, glyph_name: &str) -> Option<Vec<BezPath>> {
         // First try to get from FontIR context using the helper method
-        if let Some(ref context) = self.context {
+        if let Some(context) = self.current_context(glyph_name) {
             let glyph_name_typed: GlyphName = glyph_name.into();
             let work_id = WorkId::Glyph(glyph_name_typed);
 
             // Use try_get since we're not sure if the glyph exists
             if let Some(glyph) = context.glyphs.try_get(&work_id) {
-                // Try to get the instance at our current location
+                // Try to get the instance at our current location, falling
+                // back to the first available instance
                 if let Some(instance) =
-                    glyph.sources().get(&self.current_location)
+                    instance_at(&glyph, &self.current_location)
                 {
                     return Some(instance.contours.clone());
                 }
-
-                // Fallback: Use the first available instance if exact location doesn't exist
-                if let Some((_location, instance)) =
-                    glyph.sources().iter().next()
-                {
-                    info!("get_glyph_paths: Using first available instance for glyph '{}' with {} contours", glyph_name, instance.contours.len());
-                    return Some(instance.contours.clone());
-                }
             }
         }
 
         // Fall back to cached glyph data
         if let Some(glyph) = self.get_glyph(glyph_name) {
-            // Try to get the instance at our current location
-            if let Some(instance) = glyph.sources().get(&self.current_location)
-            {
-                return Some(instance.contours.clone());
-            }
-
-            // Fallback: Use the first available instance if exact location doesn't exist
-            if let Some((_location, instance)) = glyph.sources().iter().next() {
-                info!("get_glyph_paths: Using first available cached instance for glyph '{}' with {} contours", glyph_name, instance.contours.len());
+            // Try to get the instance at our current location, falling back
+            // to the first available instance
+            if let Some(instance) = instance_at(glyph, &self.current_location) {
                 return Some(instance.contours.clone());
             }
         }
@@ -211,11 +411,20 @@ impl FontIRAppState {
 
     /// Get or create a working copy of a glyph instance for editing
     /// This is optimized for zero-lag performance during editing
-    fn get_or_create_working_copy(
+    pub fn get_or_create_working_copy(
         &mut self,
         glyph_name: &str,
     ) -> Option<&mut EditableGlyphInstance> {
         let location = self.current_location.clone();
+        self.get_or_create_working_copy_at(glyph_name, &location)
+    }
+
+    /// Get or create a working copy of a glyph at a specific master location
+    pub fn get_or_create_working_copy_at(
+        &mut self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Option<&mut EditableGlyphInstance> {
         let key = (glyph_name.to_string(), location.clone());
 
         // Fast path: working copy already exists
@@ -227,13 +436,38 @@ impl FontIRAppState {
             return self.working_copies.get_mut(&key);
         }
 
+        // Only masters can be edited, not locations between them
+        if !self.is_glyph_master_at(glyph_name, location) {
+            debug!(
+                "FontIR: '{}' has no master at {:?}, not editing",
+                glyph_name, location
+            );
+            return None;
+        }
+
         // Slow path: create working copy from original FontIR data
         if let Some(fontir_glyph) = self.glyph_cache.get(glyph_name) {
             // Get the appropriate instance for our location
-            if let Some((_location, instance)) =
-                fontir_glyph.sources().iter().next()
-            {
-                let working_copy = EditableGlyphInstance::from(instance);
+            if let Some(instance) = instance_at(fontir_glyph, location) {
+                let mut working_copy = EditableGlyphInstance::from(instance);
+                if let Some(original) =
+                    self.original_glyph_data(glyph_name, location)
+                {
+                    working_copy.implied_on_curves = working_copy
+                        .contours
+                        .iter()
+                        .map(|contour| {
+                            quadratic::implied_flags_at(
+                                contour,
+                                &original.implied_on_curves,
+                            )
+                        })
+                        .collect();
+                    working_copy.components = original.components.clone();
+                    working_copy.anchors = original.anchors.clone();
+                    working_copy.guidelines = original.guidelines.clone();
+                    working_copy.image = original.image.clone();
+                }
                 info!("FontIR: Created new working copy for glyph '{}' with {} contours", 
                       glyph_name, working_copy.contours.len());
                 self.working_copies.insert(key.clone(), working_copy);
@@ -248,6 +482,622 @@ impl FontIRAppState {
         None
     }
 
+    /// Add a glyph that doesn't exist in the sources yet
+    ///
+    /// Each instance becomes the working copy at its location, marked dirty
+    /// so the glyph is written to the matching master on save.
+    pub fn add_glyph(
+        &mut self,
+        glyph_name: &str,
+        codepoints: Vec<char>,
+        instances: Vec<(NormalizedLocation, EditableGlyphInstance)>,
+    ) {
+        for (location, mut instance) in instances {
+            instance.is_dirty = true;
+            self.working_copies
+                .insert((glyph_name.to_string(), location), instance);
+        }
+        self.new_glyphs.insert(glyph_name.to_string(), codepoints);
+        self.glyph_set_generation = next_generation();
+        info!("Added glyph '{}'", glyph_name);
+    }
+
+    /// Rename a glyph in every master
+    ///
+    /// Components and kerning groups that use the glyph follow the new name.
+    /// The UFOs are updated on save, see `GlyphSetChange`.
+    pub fn rename_glyph(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<()> {
+        validate_glyph_name(new_name)?;
+        if !self.has_glyph(old_name) {
+            anyhow::bail!("Glyph '{old_name}' is not in the font");
+        }
+        if self.has_glyph(new_name) {
+            anyhow::bail!("A glyph named '{new_name}' already exists");
+        }
+
+        if let Some(glyph) = self.glyph_cache.remove(old_name) {
+            self.glyph_cache.insert(new_name.to_string(), glyph);
+        }
+        if let Some(codepoints) = self.new_glyphs.remove(old_name) {
+            self.new_glyphs.insert(new_name.to_string(), codepoints);
+        }
+        let keys: Vec<_> = self
+            .working_copies
+            .keys()
+            .filter(|(name, _)| name == old_name)
+            .cloned()
+            .collect();
+        for (name, location) in keys {
+            if let Some(working_copy) =
+                self.working_copies.remove(&(name, location.clone()))
+            {
+                self.working_copies
+                    .insert((new_name.to_string(), location), working_copy);
+            }
+        }
+
+        for (_, glyphs) in &mut self.master_glyphs {
+            if let Some(data) = glyphs.remove(old_name) {
+                glyphs.insert(new_name.to_string(), data);
+            }
+            for data in glyphs.values_mut() {
+                rename_component_bases(
+                    &mut data.components,
+                    old_name,
+                    new_name,
+                );
+            }
+        }
+        for working_copy in self.working_copies.values_mut() {
+            rename_component_bases(
+                &mut working_copy.components,
+                old_name,
+                new_name,
+            );
+        }
+        for members in self.kerning_groups.values_mut() {
+            for member in members.iter_mut() {
+                if member == old_name {
+                    *member = new_name.to_string();
+                }
+            }
+        }
+        if self.current_glyph.as_deref() == Some(old_name) {
+            self.current_glyph = Some(new_name.to_string());
+        }
+
+        self.changed_glyph_names.insert(old_name.to_string());
+        self.changed_glyph_names.insert(new_name.to_string());
+        self.glyph_set_changes.push(GlyphSetChange::Rename {
+            old_name: old_name.to_string(),
+            new_name: new_name.to_string(),
+        });
+        self.glyph_set_generation = next_generation();
+        info!("Renamed glyph '{}' to '{}'", old_name, new_name);
+        Ok(())
+    }
+
+    /// Delete a glyph from every master
+    ///
+    /// Composites that use the glyph keep their components, see
+    /// `composites_using`. The UFOs are updated on save.
+    pub fn delete_glyph(&mut self, glyph_name: &str) -> Result<()> {
+        if !self.has_glyph(glyph_name) {
+            anyhow::bail!("Glyph '{glyph_name}' is not in the font");
+        }
+
+        self.glyph_cache.remove(glyph_name);
+        self.new_glyphs.remove(glyph_name);
+        self.working_copies
+            .retain(|(name, _), _| name != glyph_name);
+        for (_, glyphs) in &mut self.master_glyphs {
+            glyphs.remove(glyph_name);
+        }
+        for members in self.kerning_groups.values_mut() {
+            members.retain(|member| member != glyph_name);
+        }
+        if self.current_glyph.as_deref() == Some(glyph_name) {
+            self.current_glyph = None;
+        }
+
+        self.changed_glyph_names.insert(glyph_name.to_string());
+        self.glyph_set_changes.push(GlyphSetChange::Delete {
+            glyph_name: glyph_name.to_string(),
+        });
+        self.glyph_set_generation = next_generation();
+        info!("Deleted glyph '{}'", glyph_name);
+        Ok(())
+    }
+
+    /// Glyphs with a component of the given glyph in any master, sorted
+    pub fn composites_using(&self, glyph_name: &str) -> Vec<String> {
+        let uses = |components: &[ComponentData]| {
+            components
+                .iter()
+                .any(|component| component.base_glyph == glyph_name)
+        };
+        let mut composites: Vec<String> = self
+            .master_glyphs
+            .iter()
+            .flat_map(|(_, glyphs)| glyphs.iter())
+            .filter(|(_, data)| uses(&data.components))
+            .map(|(name, _)| name.clone())
+            .chain(
+                self.working_copies
+                    .iter()
+                    .filter(|(_, working_copy)| uses(&working_copy.components))
+                    .map(|((name, _), _)| name.clone()),
+            )
+            .filter(|name| name != glyph_name && self.has_glyph(name))
+            .collect();
+        composites.sort();
+        composites.dedup();
+        composites
+    }
+
+    /// A name based on `base` that no glyph uses yet, like `base.1`
+    pub fn unique_glyph_name(&self, base: &str) -> String {
+        if !self.has_glyph(base) {
+            return base.to_string();
+        }
+        (1..)
+            .map(|suffix| format!("{base}.{suffix}"))
+            .find(|name| !self.has_glyph(name))
+            .unwrap_or_else(|| base.to_string())
+    }
+
+    /// Working copy locations of every master of the font
+    ///
+    /// Taken from the glyph with the most sources, with the same handling
+    /// of the current location as `master_locations`.
+    pub fn font_master_locations(&self) -> Vec<NormalizedLocation> {
+        self.glyph_cache
+            .iter()
+            .max_by_key(|(_, glyph)| glyph.sources().len())
+            .map(|(glyph_name, _)| self.master_locations(glyph_name))
+            .unwrap_or_else(|| vec![self.current_location.clone()])
+    }
+
+    /// The FontIR context, unless its entry for a glyph is out of date
+    /// because the glyph was renamed or deleted
+    fn current_context(&self, glyph_name: &str) -> Option<&Arc<Context>> {
+        if self.changed_glyph_names.contains(glyph_name) {
+            return None;
+        }
+        self.context.as_ref()
+    }
+
+    /// Whether a glyph exists, either in the sources or created in the editor
+    pub fn has_glyph(&self, glyph_name: &str) -> bool {
+        self.glyph_cache.contains_key(glyph_name)
+            || self.new_glyphs.contains_key(glyph_name)
+    }
+
+    /// Codepoints of a glyph, in ascending order
+    pub fn glyph_codepoints(&self, glyph_name: &str) -> Vec<char> {
+        if let Some(codepoints) = self.new_glyphs.get(glyph_name) {
+            return codepoints.clone();
+        }
+        let Some(glyph) = self.glyph_cache.get(glyph_name) else {
+            return Vec::new();
+        };
+        let mut codepoints: Vec<char> = glyph
+            .codepoints
+            .iter()
+            .filter_map(|&codepoint| char::from_u32(codepoint))
+            .collect();
+        codepoints.sort();
+        codepoints
+    }
+
+    /// Replace the codepoints of a glyph
+    ///
+    /// The glyph keeps its name; the UFOs are updated on save. Glyphs that
+    /// are not saved yet get their codepoints when they are written.
+    pub fn set_glyph_codepoints(
+        &mut self,
+        glyph_name: &str,
+        mut codepoints: Vec<char>,
+    ) -> Result<()> {
+        codepoints.sort();
+        codepoints.dedup();
+
+        if let Some(new_codepoints) = self.new_glyphs.get_mut(glyph_name) {
+            *new_codepoints = codepoints;
+        } else if let Some(glyph) = self.glyph_cache.get_mut(glyph_name) {
+            Arc::make_mut(glyph).codepoints =
+                codepoints.iter().map(|&c| c as u32).collect();
+            self.glyph_set_changes.push(GlyphSetChange::SetCodepoints {
+                glyph_name: glyph_name.to_string(),
+                codepoints: codepoints.clone(),
+            });
+        } else {
+            anyhow::bail!("Glyph '{glyph_name}' is not in the font");
+        }
+        self.glyph_set_generation = next_generation();
+        info!("Set codepoints of '{}' to {:?}", glyph_name, codepoints);
+        Ok(())
+    }
+
+    /// Map of every codepoint in the font to the glyphs that have it
+    pub fn codepoint_glyphs_map(&self) -> HashMap<String, Vec<String>> {
+        let glyph_names = self.get_glyph_names();
+        build_codepoint_glyphs_map(glyph_names.iter().map(|glyph_name| {
+            (glyph_name.as_str(), self.glyph_codepoints(glyph_name))
+        }))
+    }
+
+    /// Every codepoint that a glyph of the font has
+    pub fn font_codepoints(&self) -> HashSet<char> {
+        self.get_glyph_names()
+            .iter()
+            .flat_map(|glyph_name| self.glyph_codepoints(glyph_name))
+            .collect()
+    }
+
+    /// Whether a glyph was created or edited since the font was loaded
+    pub fn is_glyph_dirty(&self, glyph_name: &str) -> bool {
+        self.new_glyphs.contains_key(glyph_name)
+            || self.working_copies.iter().any(|((name, _), working_copy)| {
+                name == glyph_name && working_copy.is_dirty
+            })
+    }
+
+    /// Glyph created in the editor that has a codepoint assigned
+    pub fn new_glyph_for_codepoint(&self, codepoint: char) -> Option<&str> {
+        self.new_glyphs
+            .iter()
+            .find(|(_, codepoints)| codepoints.contains(&codepoint))
+            .map(|(glyph_name, _)| glyph_name.as_str())
+    }
+
+    /// Working copy locations of all master sources of a glyph
+    ///
+    /// The master at the current location is returned as `current_location`
+    /// so it shares the working copy used by regular editing.
+    pub fn master_locations(
+        &self,
+        glyph_name: &str,
+    ) -> Vec<NormalizedLocation> {
+        if self.new_glyphs.contains_key(glyph_name) {
+            return self
+                .working_copies
+                .keys()
+                .filter(|(name, _)| name == glyph_name)
+                .map(|(_, location)| location.clone())
+                .collect();
+        }
+        let Some(glyph) = self.glyph_cache.get(glyph_name) else {
+            return Vec::new();
+        };
+        glyph
+            .sources()
+            .keys()
+            .map(|location| {
+                if locations_match(location, &self.current_location) {
+                    self.current_location.clone()
+                } else {
+                    location.clone()
+                }
+            })
+            .chain(
+                self.new_intermediate_masters
+                    .iter()
+                    .filter(|master| master.glyph_name == glyph_name)
+                    .map(|master| master.location.clone()),
+            )
+            .collect()
+    }
+
+    /// Outlines, components and anchors of a glyph in each of its masters,
+    /// preferring working copies over the original FontIR data
+    ///
+    /// The default master comes first, to compare the others against.
+    pub fn glyph_masters(
+        &self,
+        glyph_name: &str,
+    ) -> Vec<(NormalizedLocation, MasterGlyph)> {
+        let mut masters: Vec<_> = self
+            .master_locations(glyph_name)
+            .into_iter()
+            .filter_map(|location| {
+                let key = (glyph_name.to_string(), location.clone());
+                let (contours, components, anchors) =
+                    match self.working_copies.get(&key) {
+                        Some(working_copy) => (
+                            working_copy.contours.clone(),
+                            working_copy.components.clone(),
+                            working_copy.anchors.clone(),
+                        ),
+                        None => {
+                            let glyph = self.glyph_cache.get(glyph_name)?;
+                            let original =
+                                self.original_glyph_data(glyph_name, &location);
+                            (
+                                instance_at(glyph, &location)?.contours.clone(),
+                                original
+                                    .map(|data| data.components.clone())
+                                    .unwrap_or_default(),
+                                original
+                                    .map(|data| data.anchors.clone())
+                                    .unwrap_or_default(),
+                            )
+                        }
+                    };
+                let master = MasterGlyph {
+                    master: self.master_name_at(&location),
+                    contours,
+                    components: components
+                        .into_iter()
+                        .map(|component| component.base_glyph)
+                        .collect(),
+                    anchors: anchors
+                        .into_iter()
+                        .map(|anchor| anchor.name)
+                        .collect(),
+                };
+                Some((location, master))
+            })
+            .collect();
+        masters.sort_by_key(|(location, _)| {
+            !location.iter().all(|(_, value)| value.to_f64() == 0.0)
+        });
+        masters
+    }
+
+    /// Display name of the master exactly at a location, or the location
+    /// itself when no master sits there
+    pub fn master_name_at(&self, location: &NormalizedLocation) -> String {
+        self.master_glyphs
+            .iter()
+            .map(|(master, _)| master)
+            .chain(
+                self.new_intermediate_masters
+                    .iter()
+                    .map(|master| &master.source),
+            )
+            .find(|master| master.matches(location))
+            .map(|master| master.name.clone())
+            .unwrap_or_else(|| {
+                location
+                    .iter()
+                    .map(|(tag, value)| format!("{tag}={:.2}", value.to_f64()))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+    }
+
+    /// Whether a glyph has a master at a location, and so can be edited
+    /// there
+    pub fn is_glyph_master_at(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> bool {
+        self.master_locations(glyph_name)
+            .iter()
+            .any(|master| locations_match(master, location))
+    }
+
+    /// Whether the current location is at one of the font's masters
+    pub fn is_at_master(&self) -> bool {
+        self.font_master_locations()
+            .iter()
+            .any(|master| locations_match(master, &self.current_location))
+    }
+
+    /// Move the current location, by normalized value per axis tag
+    ///
+    /// Axes left out keep their value. Moving onto a master takes that
+    /// master's own location, preferring the one its working copies are
+    /// stored at, so edits made there show up again.
+    pub fn set_current_axis_values(&mut self, values: &[(String, f64)]) {
+        let masters = self.font_master_locations();
+        let Some(all_axes) = masters
+            .iter()
+            .max_by_key(|location| location.iter().count())
+        else {
+            return;
+        };
+        let location: NormalizedLocation = all_axes
+            .iter()
+            .map(|(tag, _)| {
+                let tag_name = tag.to_string();
+                let value = values
+                    .iter()
+                    .find(|(t, _)| *t == tag_name)
+                    .map(|(_, value)| *value)
+                    .unwrap_or_else(|| {
+                        location_value(&self.current_location, &tag_name)
+                    });
+                (*tag, NormalizedCoord::new(value))
+            })
+            .collect();
+
+        let master = self
+            .working_copies
+            .keys()
+            .map(|(_, location)| location)
+            .chain(&masters)
+            .find(|master| locations_match(master, &location))
+            .cloned();
+        self.current_location = master.unwrap_or(location);
+    }
+
+    /// Weights of a glyph's masters at the current location, when it lies
+    /// between them
+    fn interpolation_weights(
+        &self,
+        glyph_name: &str,
+    ) -> Option<Vec<(NormalizedLocation, f64)>> {
+        let locations = self.master_locations(glyph_name);
+        if locations
+            .iter()
+            .any(|location| locations_match(location, &self.current_location))
+        {
+            return None;
+        }
+        let static_metadata = self.context.as_ref()?.static_metadata.get();
+        let weights = master_weights(
+            &locations,
+            &static_metadata.axes,
+            &self.current_location,
+        )?;
+        Some(
+            locations
+                .into_iter()
+                .zip(weights)
+                .filter(|(_, weight)| weight.abs() > f64::EPSILON)
+                .collect(),
+        )
+    }
+
+    /// Outlines of a glyph interpolated at the current location, when it
+    /// lies between the glyph's masters and they are compatible
+    pub fn interpolated_glyph_paths(
+        &self,
+        glyph_name: &str,
+    ) -> Option<Vec<BezPath>> {
+        let weights = self.interpolation_weights(glyph_name)?;
+        let mut masters = Vec::with_capacity(weights.len());
+        for (location, weight) in weights {
+            let key = (glyph_name.to_string(), location);
+            let contours = match self.working_copies.get(&key) {
+                Some(working_copy) => working_copy.contours.clone(),
+                None => {
+                    let glyph = self.glyph_cache.get(glyph_name)?;
+                    instance_at(glyph, &key.1)?.contours.clone()
+                }
+            };
+            masters.push((contours, weight));
+        }
+        let masters: Vec<(&[BezPath], f64)> = masters
+            .iter()
+            .map(|(contours, weight)| (contours.as_slice(), *weight))
+            .collect();
+        let contours = interpolate_contours(&masters);
+        if contours.is_none() {
+            debug!(
+                "Masters of '{}' are not compatible, showing a master instead",
+                glyph_name
+            );
+        }
+        contours
+    }
+
+    /// Advance width of a glyph interpolated at the current location, when
+    /// it lies between the glyph's masters
+    pub fn interpolated_glyph_width(&self, glyph_name: &str) -> Option<f64> {
+        self.interpolation_weights(glyph_name)?
+            .iter()
+            .map(|(location, weight)| {
+                Some(self.glyph_width_at(glyph_name, location)? * weight)
+            })
+            .sum()
+    }
+
+    /// Outline and components of a glyph at a master as loaded, before any
+    /// edits
+    pub fn original_glyph_outline(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Option<(Vec<BezPath>, Vec<ComponentData>)> {
+        let glyph = self.glyph_cache.get(glyph_name)?;
+        let contours = instance_at(glyph, location)?.contours.clone();
+        let components = self
+            .original_glyph_data(glyph_name, location)
+            .map(|data| data.components.clone())
+            .unwrap_or_default();
+        Some((contours, components))
+    }
+
+    /// Outline of a glyph at a master as loaded, with the implied on-curve
+    /// flags of each contour
+    pub fn original_implied_on_curves(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Option<(Vec<BezPath>, Vec<Vec<bool>>)> {
+        let glyph = self.glyph_cache.get(glyph_name)?;
+        let contours = instance_at(glyph, location)?.contours.clone();
+        let implied = self
+            .original_glyph_data(glyph_name, location)
+            .map_or(&[][..], |data| data.implied_on_curves.as_slice());
+        let flags = contours
+            .iter()
+            .map(|contour| quadratic::implied_flags_at(contour, implied))
+            .collect();
+        Some((contours, flags))
+    }
+
+    /// Repeat an edit of a glyph at the current location in the glyph's
+    /// other masters, see `master_sync`
+    ///
+    /// `before` is the outline and components the edit started from. Moved
+    /// points and components move in the other masters too when
+    /// `move_points` is set. Masters the edit can't be repeated in are left
+    /// alone. Returns how many masters were changed.
+    pub fn sync_edit_to_other_masters(
+        &mut self,
+        glyph_name: &str,
+        before: &(Vec<BezPath>, Vec<ComponentData>),
+        move_points: bool,
+    ) -> usize {
+        let location = self.current_location.clone();
+        let key = (glyph_name.to_string(), location.clone());
+        let Some(edited) = self.working_copies.get(&key) else {
+            return 0;
+        };
+        let (contours, components) =
+            (edited.contours.clone(), edited.components.clone());
+
+        let mut changed = 0;
+        for master in self.master_locations(glyph_name) {
+            if locations_match(&master, &location) {
+                continue;
+            }
+            let master_name = self.master_name_at(&master);
+            let Some(other) =
+                self.get_or_create_working_copy_at(glyph_name, &master)
+            else {
+                continue;
+            };
+            let synced = sync_contours(
+                &before.0,
+                &contours,
+                &other.contours,
+                move_points,
+            )
+            .zip(sync_components(
+                &before.1,
+                &components,
+                &other.components,
+                move_points,
+            ));
+            let Some((synced_contours, synced_components)) = synced else {
+                warn!(
+                    "Could not repeat the edit of '{}' in master {}",
+                    glyph_name, master_name
+                );
+                continue;
+            };
+            if synced_contours != other.contours
+                || synced_components != other.components
+            {
+                other.contours = synced_contours;
+                other.components = synced_components;
+                other.is_dirty = true;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
     /// Update a point position in a FontIR glyph (high-performance implementation)
     pub fn update_point_position(
         &mut self,
@@ -277,13 +1127,26 @@ impl FontIRAppState {
                     return Ok(false);
                 }
 
+                let contour = &mut working_copy.contours[contour_idx];
+
                 // Update the point in the working copy BezPath
                 if Self::update_point_in_bezpath_static(
-                    &mut working_copy.contours[contour_idx],
-                    point_idx,
-                    new_x,
-                    new_y,
+                    contour, point_idx, new_x, new_y,
                 ) {
+                    // Implied on-curve points follow their control points
+                    if let Some(implied) =
+                        working_copy.implied_on_curves.get_mut(contour_idx)
+                    {
+                        if implied.contains(&true) {
+                            // A dragged implied point becomes an explicit one
+                            if let Some(flag) = implied.get_mut(point_idx) {
+                                *flag = false;
+                            }
+                            quadratic::recenter_implied_on_curves(
+                                contour, implied,
+                            );
+                        }
+                    }
                     working_copy.is_dirty = true;
                     debug!(
                         "FontIR: Successfully updated point {} in working copy",
@@ -423,6 +1286,11 @@ impl FontIRAppState {
             return Some(working_copy.contours.clone());
         }
 
+        // Between masters, blend them
+        if let Some(paths) = self.interpolated_glyph_paths(glyph_name) {
+            return Some(paths);
+        }
+
         // Fallback to original FontIR data
         info!("*** FontIR: Using ORIGINAL DATA for glyph '{}' (no working copy found)", glyph_name);
         self.get_glyph_paths(glyph_name)
@@ -433,19 +1301,34 @@ impl FontIRAppState {
     pub fn get_glyph_paths_with_components(
         &self,
         glyph_name: &str,
+    ) -> Option<Vec<BezPath>> {
+        self.resolve_glyph_paths(glyph_name, 0)
+    }
+
+    /// Outline and component paths of a glyph, with nested components
+    /// resolved up to `MAX_COMPONENT_DEPTH`
+    fn resolve_glyph_paths(
+        &self,
+        glyph_name: &str,
+        depth: usize,
     ) -> Option<Vec<BezPath>> {
         let mut all_paths = Vec::new();
-        
+
         // Get the main outline paths (if any)
-        if let Some(outline_paths) = self.get_glyph_paths_with_edits(glyph_name) {
+        if let Some(outline_paths) = self.get_glyph_paths_with_edits(glyph_name)
+        {
             all_paths.extend(outline_paths);
         }
-        
-        // Load component data directly from UFO source for component resolution
-        if let Some(component_paths) = self.resolve_components(glyph_name) {
-            all_paths.extend(component_paths);
+
+        if depth < MAX_COMPONENT_DEPTH {
+            all_paths.extend(self.resolve_components(glyph_name, depth));
+        } else {
+            warn!(
+                "🧩 Components of '{}' nested too deeply, not resolving",
+                glyph_name
+            );
         }
-        
+
         if all_paths.is_empty() {
             None
         } else {
@@ -453,82 +1336,970 @@ impl FontIRAppState {
         }
     }
 
-    /// Resolve component references by loading UFO data directly
-    /// This provides access to component data that FontIR doesn't currently expose
-    fn resolve_components(&self, glyph_name: &str) -> Option<Vec<BezPath>> {
-        // Load the UFO font directly to access component data
-        // This is a temporary approach until FontIR provides better component access
-        let ufo_font = if self.source_path.extension()? == "ufo" {
-            match norad::Font::load(&self.source_path) {
-                Ok(font) => font,
-                Err(e) => {
-                    warn!("Failed to load UFO for component resolution: {}", e);
-                    return None;
+    /// Paths of each component of a glyph, with the component transforms applied
+    fn resolve_components(
+        &self,
+        glyph_name: &str,
+        depth: usize,
+    ) -> Vec<BezPath> {
+        let components = self.glyph_components(glyph_name);
+        let mut component_paths = Vec::new();
+
+        for component in &components {
+            let Some(base_paths) =
+                self.resolve_glyph_paths(&component.base_glyph, depth + 1)
+            else {
+                warn!(
+                    "🧩 Component base glyph '{}' not found for glyph '{}'",
+                    component.base_glyph, glyph_name
+                );
+                continue;
+            };
+            let affine = component.affine();
+            component_paths.extend(base_paths.iter().map(|path| affine * path));
+        }
+
+        component_paths
+    }
+
+    /// Component paths of a glyph, one entry per component
+    ///
+    /// Used for hit testing and highlighting individual components.
+    pub fn component_paths(&self, glyph_name: &str) -> Vec<Vec<BezPath>> {
+        self.glyph_components(glyph_name)
+            .iter()
+            .map(|component| {
+                let affine = component.affine();
+                self.resolve_glyph_paths(&component.base_glyph, 1)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|path| affine * path)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Decompose components of a glyph in every master, each into the
+    /// outlines its base glyph has at that master
+    ///
+    /// `indices` are ascending component indices, which are the same in
+    /// every master of a compatible glyph. Returns how many masters
+    /// changed.
+    pub fn decompose_components(
+        &mut self,
+        glyph_name: &str,
+        indices: &[usize],
+    ) -> usize {
+        let current_location = self.current_location.clone();
+        let mut changed = 0;
+        for location in self.master_locations(glyph_name) {
+            // Component outlines are resolved at the current location
+            self.current_location = location.clone();
+            let component_paths = self.component_paths(glyph_name);
+            let Some(working_copy) =
+                self.get_or_create_working_copy_at(glyph_name, &location)
+            else {
+                continue;
+            };
+            // Remove from the back so earlier indices stay valid
+            let mut decomposed = false;
+            for &index in indices.iter().rev() {
+                if index >= working_copy.components.len() {
+                    continue;
                 }
-            }
-        } else if self.source_path.extension()? == "designspace" {
-            // For .designspace files, load the first/default UFO source
-            let designspace_dir = self.source_path.parent()?;
-            let regular_ufo_path = designspace_dir.join("bezy-grotesk-regular.ufo");
-            debug!("🔧 Loading UFO from designspace for component resolution: {:?}", regular_ufo_path);
-            match norad::Font::load(&regular_ufo_path) {
-                Ok(font) => {
-                    debug!("✅ Successfully loaded UFO for component resolution");
-                    font
-                },
-                Err(e) => {
-                    warn!("Failed to load Regular UFO source from designspace for component resolution: {}", e);
-                    return None;
+                working_copy.components.remove(index);
+                if let Some(paths) = component_paths.get(index) {
+                    working_copy.contours.extend(paths.iter().cloned());
                 }
+                decomposed = true;
+            }
+            if decomposed {
+                working_copy.is_dirty = true;
+                changed += 1;
+            }
+        }
+        self.current_location = current_location;
+        changed
+    }
+
+    /// Components of a glyph at the current location, preferring the
+    /// working copy over the data loaded from the UFO
+    pub fn glyph_components(&self, glyph_name: &str) -> Vec<ComponentData> {
+        let key = (glyph_name.to_string(), self.current_location.clone());
+        match self.working_copies.get(&key) {
+            Some(working_copy) => working_copy.components.clone(),
+            None => self
+                .original_glyph_data(glyph_name, &self.current_location)
+                .map(|original| original.components.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Anchors of a glyph at the current location, preferring the working
+    /// copy over the data loaded from the UFO
+    pub fn glyph_anchors(&self, glyph_name: &str) -> Vec<AnchorData> {
+        self.glyph_anchors_at(glyph_name, &self.current_location)
+    }
+
+    /// Anchors of a glyph at a master location, preferring the working copy
+    /// over the data loaded from the UFO
+    pub fn glyph_anchors_at(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Vec<AnchorData> {
+        let key = (glyph_name.to_string(), location.clone());
+        match self.working_copies.get(&key) {
+            Some(working_copy) => working_copy.anchors.clone(),
+            None => self
+                .original_glyph_data(glyph_name, location)
+                .map(|original| original.anchors.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Glyph-level guidelines of a glyph at the current location, preferring
+    /// the working copy over the data loaded from the UFO
+    pub fn glyph_guidelines(&self, glyph_name: &str) -> Vec<GuidelineData> {
+        let key = (glyph_name.to_string(), self.current_location.clone());
+        match self.working_copies.get(&key) {
+            Some(working_copy) => working_copy.guidelines.clone(),
+            None => self
+                .original_glyph_data(glyph_name, &self.current_location)
+                .map(|original| original.guidelines.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Font-level guidelines of the master at the current location
+    pub fn current_font_guidelines(&self) -> &[GuidelineData] {
+        self.master_at(&self.current_location)
+            .and_then(|master| self.font_guidelines.get(&master.ufo_path))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Run `edit` on the font-level guidelines of the master at the
+    /// current location
+    ///
+    /// Marks the font guidelines as modified, so they are written on save,
+    /// if the edit changed them.
+    pub fn edit_current_font_guidelines<R>(
+        &mut self,
+        edit: impl FnOnce(&mut Vec<GuidelineData>) -> R,
+    ) -> Option<R> {
+        let ufo_path = self.master_at(&self.current_location)?.ufo_path.clone();
+        let guidelines = self.font_guidelines.entry(ufo_path).or_default();
+        let before = guidelines.clone();
+        let result = edit(guidelines);
+        if *guidelines != before {
+            self.font_guidelines_dirty = true;
+        }
+        Some(result)
+    }
+
+    /// Display name of the master at the current location
+    pub fn current_master_name(&self) -> Option<&str> {
+        self.master_at(&self.current_location)
+            .map(|master| master.name.as_str())
+    }
+
+    /// Font info of the master at the current location
+    pub fn current_font_info(&self) -> Option<&FontInfo> {
+        self.master_at(&self.current_location)
+            .and_then(|master| self.font_infos.get(&master.ufo_path))
+    }
+
+    /// Set a font info field of the master at the current location from
+    /// text typed in the font info pane
+    ///
+    /// Marks the UFO's font info as modified so it is written on save.
+    pub fn set_current_font_info_field(
+        &mut self,
+        field: FontInfoField,
+        text: &str,
+    ) -> Result<()> {
+        let ufo_path = self
+            .master_at(&self.current_location)
+            .ok_or_else(|| anyhow::anyhow!("No master source to edit"))?
+            .ufo_path
+            .clone();
+        let font_info = self
+            .font_infos
+            .get_mut(&ufo_path)
+            .ok_or_else(|| anyhow::anyhow!("Font info was not loaded"))?;
+        font_info.set_field(field, text)?;
+        self.font_info_dirty.insert(ufo_path);
+        self.font_info_generation = next_generation();
+        Ok(())
+    }
+
+    /// Apply an edit made in the designspace pane to the designspace
+    /// document
+    ///
+    /// Marks the document as modified so it is written on save.
+    pub fn edit_designspace<T>(
+        &mut self,
+        edit: impl FnOnce(&mut DesignSpaceDocument) -> Result<T>,
+    ) -> Result<T> {
+        let document = self.designspace.as_mut().ok_or_else(|| {
+            anyhow::anyhow!("Only designspace sources can be edited")
+        })?;
+        let result = edit(document)?;
+        self.designspace_dirty = true;
+        Ok(result)
+    }
+
+    /// Add a designspace source at the current location, returning its
+    /// name
+    ///
+    /// Its UFO is created on save as a copy of the nearest master that
+    /// isn't a layer of another UFO.
+    pub fn add_designspace_source(&mut self) -> Result<String> {
+        let distance = |master: &MasterSource| -> f64 {
+            master
+                .location
+                .iter()
+                .map(|(tag, value)| {
+                    (value - location_value(&self.current_location, tag))
+                        .powi(2)
+                })
+                .sum()
+        };
+        let seed = self
+            .master_glyphs
+            .iter()
+            .map(|(master, _)| master)
+            .filter(|master| master.layer.is_none())
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .ok_or_else(|| anyhow::anyhow!("No master to copy a source from"))?
+            .ufo_path
+            .clone();
+
+        let location = self.current_location_values();
+        let designspace_dir = self
+            .source_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let file_stem = self
+            .source_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (filename, name) = self.edit_designspace(|document| {
+            let location = design_location(document, &location);
+            let source =
+                new_source(document, &designspace_dir, &file_stem, location);
+            let added = (source.filename.clone(), source_name(&source));
+            add_source(document, source)?;
+            Ok(added)
+        })?;
+        self.new_source_seeds.insert(filename, seed);
+        Ok(name)
+    }
+
+    /// Add a designspace instance at the current location, returning its
+    /// name
+    pub fn add_designspace_instance(&mut self) -> Result<String> {
+        let location = self.current_location_values();
+        self.edit_designspace(|document| {
+            let location = design_location(document, &location);
+            let index = add_instance(document, location);
+            Ok(instance_name(&document.instances[index]))
+        })
+    }
+
+    /// Make the current location a master of a glyph, starting from the
+    /// glyph interpolated there, and return the master's name
+    ///
+    /// The glyph goes into the source already at the location, or else into
+    /// a new sparse source: a layer of the default master's UFO. The glyph,
+    /// and the layer and source when they are new, are written on save.
+    pub fn add_intermediate_master(
+        &mut self,
+        glyph_name: &str,
+    ) -> Result<String> {
+        let location = self.current_location.clone();
+        if self.is_glyph_master_at(glyph_name, &location) {
+            anyhow::bail!("'{}' already has a master here", glyph_name);
+        }
+        let weights =
+            self.interpolation_weights(glyph_name).ok_or_else(|| {
+                anyhow::anyhow!("'{}' has no masters to start from", glyph_name)
+            })?;
+        let contours =
+            self.interpolated_glyph_paths(glyph_name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "The masters of '{}' are not compatible",
+                    glyph_name
+                )
+            })?;
+        let width = self
+            .interpolated_glyph_width(glyph_name)
+            .unwrap_or_default();
+
+        let masters: Vec<_> = weights
+            .iter()
+            .map(|(master, weight)| {
+                let key = (glyph_name.to_string(), master.clone());
+                let data = match self.working_copies.get(&key) {
+                    Some(working_copy) => (
+                        working_copy.components.clone(),
+                        working_copy.anchors.clone(),
+                    ),
+                    None => self
+                        .original_glyph_data(glyph_name, master)
+                        .map(|data| {
+                            (data.components.clone(), data.anchors.clone())
+                        })
+                        .unwrap_or_default(),
+                };
+                (data, *weight)
+            })
+            .collect();
+        let (components, anchors) =
+            interpolate_components_and_anchors(&masters);
+        // Compatible masters share their points, implied ones included
+        let implied_on_curves = weights
+            .first()
+            .map(|(master, _)| self.implied_on_curves_at(glyph_name, master))
+            .unwrap_or_default();
+
+        let source = match self
+            .master_glyphs
+            .iter()
+            .map(|(master, _)| master)
+            .chain(
+                self.new_intermediate_masters
+                    .iter()
+                    .map(|master| &master.source),
+            )
+            .find(|master| master.matches(&location))
+        {
+            Some(source) => source.clone(),
+            None => self.add_sparse_source()?,
+        };
+
+        self.working_copies.insert(
+            (glyph_name.to_string(), location.clone()),
+            EditableGlyphInstance {
+                width,
+                height: None,
+                vertical_origin: None,
+                contours,
+                implied_on_curves,
+                components,
+                anchors,
+                guidelines: Vec::new(),
+                image: None,
+                is_dirty: true,
+            },
+        );
+        let name = source.name.clone();
+        self.new_intermediate_masters.push(NewIntermediateMaster {
+            glyph_name: glyph_name.to_string(),
+            location,
+            source,
+        });
+        Ok(name)
+    }
+
+    /// Add a sparse source at the current location, in the UFO of the
+    /// default master
+    fn add_sparse_source(&mut self) -> Result<MasterSource> {
+        let default_ufo = self
+            .master_glyphs
+            .iter()
+            .map(|(master, _)| master)
+            .find(|master| master.is_default() && master.layer.is_none())
+            .ok_or_else(|| {
+                anyhow::anyhow!("No default master to add a layer to")
+            })?
+            .ufo_path
+            .clone();
+        let designspace_dir = self
+            .source_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let location = self.current_location_values();
+        let layer = self.edit_designspace(|document| {
+            let filename = document
+                .sources
+                .iter()
+                .find(|source| {
+                    source.layer.is_none()
+                        && designspace_dir.join(&source.filename) == default_ufo
+                })
+                .map(|source| source.filename.clone())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "The default master is not in the designspace"
+                    )
+                })?;
+            let design = design_location(document, &location);
+            let source = new_sparse_source(document, &filename, design);
+            let layer = source.layer.clone().unwrap_or_default();
+            add_source(document, source)?;
+            Ok(layer)
+        })?;
+        Ok(MasterSource {
+            name: layer.clone(),
+            ufo_path: default_ufo,
+            layer: Some(layer),
+            location,
+        })
+    }
+
+    /// Whether a glyph is written to a source as a new intermediate master
+    pub fn is_new_intermediate_master(
+        &self,
+        glyph_name: &str,
+        source: &MasterSource,
+    ) -> bool {
+        self.new_intermediate_masters.iter().any(|master| {
+            master.glyph_name == glyph_name
+                && master.source.ufo_path == source.ufo_path
+                && master.source.layer == source.layer
+        })
+    }
+
+    /// Source a glyph's master at a location is stored in: an intermediate
+    /// master created in the editor, or the font master there
+    ///
+    /// Unlike `master_at` there is no fallback to another master.
+    fn glyph_source_at(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Option<&MasterSource> {
+        self.new_intermediate_masters
+            .iter()
+            .find(|master| {
+                master.glyph_name == glyph_name
+                    && locations_match(&master.location, location)
+            })
+            .map(|master| &master.source)
+            .or_else(|| {
+                self.master_glyphs
+                    .iter()
+                    .map(|(master, _)| master)
+                    .find(|master| master.matches(location))
+            })
+    }
+
+    /// Background layer of a glyph's master at a location, as (UFO path,
+    /// layer name)
+    fn background_layer_at(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Result<(PathBuf, String)> {
+        let source =
+            self.glyph_source_at(glyph_name, location).ok_or_else(|| {
+                anyhow::anyhow!("'{glyph_name}' has no master here")
+            })?;
+        Ok((
+            source.ufo_path.clone(),
+            background_layer_name(source.layer.as_deref()),
+        ))
+    }
+
+    /// Whether the background of a glyph is being edited at the current
+    /// location
+    pub fn is_editing_background(&self, glyph_name: &str) -> bool {
+        self.editing_background
+            .as_ref()
+            .is_some_and(|(editing, location)| {
+                editing == glyph_name
+                    && locations_match(location, &self.current_location)
+            })
+    }
+
+    /// The layer shown behind a glyph at the current location: its
+    /// background, or its foreground while the background is edited
+    pub fn background_glyph(&self, glyph_name: &str) -> Option<&LayerGlyph> {
+        let location = &self.current_location;
+        let (ufo_path, layer_name) =
+            self.background_layer_at(glyph_name, location).ok()?;
+        self.glyph_layers
+            .get(&ufo_path)?
+            .get(&layer_name)?
+            .get(glyph_name)
+            .filter(|glyph| !glyph.is_empty())
+    }
+
+    /// Paths of the layer shown behind a glyph, with its components
+    /// resolved
+    pub fn background_glyph_paths(&self, glyph_name: &str) -> Vec<BezPath> {
+        let Some(background) = self.background_glyph(glyph_name) else {
+            return Vec::new();
+        };
+        let mut paths = background.contours.clone();
+        for component in &background.components {
+            let affine = component.affine();
+            paths.extend(
+                self.resolve_glyph_paths(&component.base_glyph, 1)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|path| affine * path),
+            );
+        }
+        paths
+    }
+
+    /// Copy a glyph's outline and components at the current location into
+    /// its background, replacing what was there
+    ///
+    /// While the background is edited this copies the foreground into it
+    /// instead, so the command always overwrites the background.
+    pub fn copy_to_background(&mut self, glyph_name: &str) -> Result<()> {
+        let location = self.current_location.clone();
+        let editing = self.is_editing_background(glyph_name);
+        let (ufo_path, layer_name) =
+            self.background_layer_at(glyph_name, &location)?;
+        let working_copy = self
+            .get_or_create_working_copy_at(glyph_name, &location)
+            .ok_or_else(|| {
+                anyhow::anyhow!("'{glyph_name}' can't be edited here")
+            })?;
+        let foreground = LayerGlyph {
+            contours: working_copy.contours.clone(),
+            implied_on_curves: working_copy.implied_on_curves.clone(),
+            components: working_copy.components.clone(),
+        };
+
+        let layer_glyph = self
+            .glyph_layers
+            .entry(ufo_path.clone())
+            .or_default()
+            .entry(layer_name.clone())
+            .or_default()
+            .entry(glyph_name.to_string())
+            .or_default();
+        if editing {
+            let background = layer_glyph.clone();
+            let key = (glyph_name.to_string(), location);
+            if let Some(working_copy) = self.working_copies.get_mut(&key) {
+                working_copy.contours = background.contours;
+                working_copy.implied_on_curves = background.implied_on_curves;
+                working_copy.components = background.components;
+                working_copy.is_dirty = true;
             }
         } else {
-            debug!("Component resolution not implemented for file type: {:?}", self.source_path.extension());
-            return None;
+            *layer_glyph = foreground;
+        }
+        self.glyph_layers_dirty.insert((
+            ufo_path,
+            layer_name,
+            glyph_name.to_string(),
+        ));
+        Ok(())
+    }
+
+    /// Swap a glyph's outline and components at the current location with
+    /// its background
+    pub fn swap_background(&mut self, glyph_name: &str) -> Result<()> {
+        let location = self.current_location.clone();
+        self.swap_background_at(glyph_name, &location)
+    }
+
+    /// Start editing a glyph's background at the current location, or stop
+    /// if it is being edited already
+    ///
+    /// The foreground and background swap places, so every tool edits the
+    /// background through the working copy while the foreground shows
+    /// behind it.
+    pub fn toggle_edit_background(&mut self, glyph_name: &str) -> Result<()> {
+        if self.is_editing_background(glyph_name) {
+            return self.stop_editing_background();
+        }
+        self.stop_editing_background()?;
+        let location = self.current_location.clone();
+        self.swap_background_at(glyph_name, &location)?;
+        self.editing_background = Some((glyph_name.to_string(), location));
+        Ok(())
+    }
+
+    /// Put the foreground and background back in their layers, if a
+    /// background is being edited
+    pub fn stop_editing_background(&mut self) -> Result<()> {
+        match self.editing_background.take() {
+            Some((glyph_name, location)) => {
+                self.swap_background_at(&glyph_name, &location)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn swap_background_at(
+        &mut self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Result<()> {
+        let (ufo_path, layer_name) =
+            self.background_layer_at(glyph_name, location)?;
+        let working_copy = self
+            .get_or_create_working_copy_at(glyph_name, location)
+            .ok_or_else(|| {
+                anyhow::anyhow!("'{glyph_name}' can't be edited here")
+            })?;
+        let foreground = LayerGlyph {
+            contours: std::mem::take(&mut working_copy.contours),
+            implied_on_curves: std::mem::take(
+                &mut working_copy.implied_on_curves,
+            ),
+            components: std::mem::take(&mut working_copy.components),
         };
+        working_copy.is_dirty = true;
+
+        let layer_glyph = self
+            .glyph_layers
+            .entry(ufo_path.clone())
+            .or_default()
+            .entry(layer_name.clone())
+            .or_default()
+            .entry(glyph_name.to_string())
+            .or_default();
+        let background = std::mem::replace(layer_glyph, foreground);
+        let key = (glyph_name.to_string(), location.clone());
+        if let Some(working_copy) = self.working_copies.get_mut(&key) {
+            working_copy.contours = background.contours;
+            working_copy.implied_on_curves = background.implied_on_curves;
+            working_copy.components = background.components;
+        }
+        self.glyph_layers_dirty.insert((
+            ufo_path,
+            layer_name,
+            glyph_name.to_string(),
+        ));
+        Ok(())
+    }
 
-        // Get the glyph from UFO
-        let layer = ufo_font.default_layer();
-        let ufo_glyph = layer.get_glyph(glyph_name)?;
-        
-        // Check if this glyph has components
-        if ufo_glyph.components.is_empty() {
-            debug!("📭 Glyph '{}' has no components", glyph_name);
-            return None;
+    /// Reference image of a glyph at the current location, if it is a
+    /// master of the glyph
+    pub fn glyph_image(&self, glyph_name: &str) -> Option<ImageData> {
+        let location = &self.current_location;
+        self.glyph_source_at(glyph_name, location)?;
+        let key = (glyph_name.to_string(), location.clone());
+        match self.working_copies.get(&key) {
+            Some(working_copy) => working_copy.image.clone(),
+            None => self
+                .original_glyph_data(glyph_name, location)
+                .and_then(|original| original.image.clone()),
         }
-        
-        debug!("🧩 Resolving {} components for glyph '{}'", ufo_glyph.components.len(), glyph_name);
-        let mut component_paths = Vec::new();
-        
-        // Resolve each component
-        for (i, component) in ufo_glyph.components.iter().enumerate() {
-            let base_glyph_name = component.base.as_str();
-            info!("🧩 Component {}/{}: base='{}', transform=[{}, {}, {}, {}, {}, {}]", 
-                  i + 1, ufo_glyph.components.len(), base_glyph_name,
-                  component.transform.x_scale, component.transform.xy_scale, component.transform.yx_scale,
-                  component.transform.y_scale, component.transform.x_offset, component.transform.y_offset);
-            
-            // Get the base glyph's paths (recursively resolve components)
-            if let Some(base_paths) = self.get_glyph_paths_with_components(base_glyph_name) {
-                info!("🧩 Found {} paths for component '{}'", base_paths.len(), base_glyph_name);
-                for (path_idx, base_path) in base_paths.iter().enumerate() {
-                    // Apply the component's transformation matrix
-                    let transformed_path = apply_affine_transform(base_path, &component.transform);
-                    let path_elements = transformed_path.elements().len();
-                    info!("🧩 Applied transform to path {}: {} elements", path_idx, path_elements);
-                    component_paths.push(transformed_path);
+    }
+
+    /// Reference image of a glyph at the current location with its PNG
+    /// data and the UFO the data is stored in
+    pub fn glyph_image_file(
+        &self,
+        glyph_name: &str,
+    ) -> Option<(ImageData, PathBuf, Arc<Vec<u8>>)> {
+        let image = self.glyph_image(glyph_name)?;
+        let ufo_path = &self
+            .glyph_source_at(glyph_name, &self.current_location)?
+            .ufo_path;
+        let data = self.image_files.get(ufo_path)?.get(&image.file_name)?;
+        Some((image, ufo_path.clone(), data.clone()))
+    }
+
+    /// Add a PNG or JPEG file as the reference image of a glyph at the
+    /// current location, replacing the one it had
+    ///
+    /// The image is scaled to span the font from descender to ascender and
+    /// its file is copied into the UFO's `images` directory on save.
+    pub fn add_glyph_image(
+        &mut self,
+        glyph_name: &str,
+        path: &Path,
+    ) -> Result<ImageData> {
+        let ufo_path = self
+            .glyph_source_at(glyph_name, &self.current_location)
+            .ok_or_else(|| {
+                anyhow::anyhow!("'{glyph_name}' has no master here")
+            })?
+            .ufo_path
+            .clone();
+        let bytes = std::fs::read(path).map_err(|e| {
+            anyhow::anyhow!("cannot read {}: {e}", path.display())
+        })?;
+        let (png, _, height) = png_image_data(&bytes)?;
+
+        let files = self.image_files.entry(ufo_path.clone()).or_default();
+        let images_dir = ufo_path.join("images");
+        let file_name = unique_image_file_name(path, |name| {
+            files.contains_key(name) || images_dir.join(name).exists()
+        });
+        let image = self.image_spanning_font(file_name.clone(), height);
+
+        self.set_glyph_image(glyph_name, Some(image.clone()))?;
+        self.image_files
+            .entry(ufo_path.clone())
+            .or_default()
+            .insert(file_name.clone(), Arc::new(png));
+        self.new_image_files.insert((ufo_path, file_name));
+        Ok(image)
+    }
+
+    /// Placement of an image `height` pixels high that spans the font from
+    /// descender to ascender, standing on the descender
+    pub fn image_spanning_font(
+        &self,
+        file_name: String,
+        height: u32,
+    ) -> ImageData {
+        let metrics = self.get_font_metrics();
+        let descender = metrics.descender.unwrap_or(0.0) as f64;
+        let ascender = metrics.ascender.unwrap_or(metrics.units_per_em) as f64;
+        let mut image = ImageData::new(file_name);
+        if height > 0 {
+            image.x_scale = (ascender - descender) / height as f64;
+            image.y_scale = image.x_scale;
+        }
+        image.y = descender;
+        image
+    }
+
+    /// Place, change or remove the reference image of a glyph at the
+    /// current location
+    pub fn set_glyph_image(
+        &mut self,
+        glyph_name: &str,
+        image: Option<ImageData>,
+    ) -> Result<()> {
+        let working_copy =
+            self.get_or_create_working_copy(glyph_name).ok_or_else(|| {
+                anyhow::anyhow!("'{glyph_name}' can't be edited here")
+            })?;
+        working_copy.image = image;
+        working_copy.is_dirty = true;
+        Ok(())
+    }
+
+    /// The current location as (axis tag, value) pairs
+    fn current_location_values(&self) -> Vec<(String, f64)> {
+        self.current_location
+            .iter()
+            .map(|(tag, value)| (tag.to_string(), value.to_f64()))
+            .collect()
+    }
+
+    /// Advance width of a glyph at a master location, preferring the
+    /// working copy over the original FontIR data
+    pub fn glyph_width_at(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Option<f64> {
+        let key = (glyph_name.to_string(), location.clone());
+        if let Some(working_copy) = self.working_copies.get(&key) {
+            return Some(working_copy.width);
+        }
+        let glyph = self.glyph_cache.get(glyph_name)?;
+        instance_at(glyph, location).map(|instance| instance.width)
+    }
+
+    /// UFO glyph data of a glyph as stored in the master at a location
+    ///
+    /// Falls back to the default master, then the first one, if no master
+    /// sits exactly at the location.
+    fn original_glyph_data(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Option<&MasterGlyphData> {
+        self.master_glyph_data_at(location)
+            .and_then(|(_, glyphs)| glyphs.get(glyph_name))
+    }
+
+    /// Implied on-curve flags of a glyph's contours in the master at a
+    /// location, from its working copy or else as loaded
+    fn implied_on_curves_at(
+        &self,
+        glyph_name: &str,
+        location: &NormalizedLocation,
+    ) -> Vec<Vec<bool>> {
+        let key = (glyph_name.to_string(), location.clone());
+        match self.working_copies.get(&key) {
+            Some(working_copy) => working_copy.implied_on_curves.clone(),
+            None => self
+                .original_implied_on_curves(glyph_name, location)
+                .map(|(_, flags)| flags)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Master source at a location, with the same fallbacks as
+    /// `original_glyph_data`
+    fn master_at(
+        &self,
+        location: &NormalizedLocation,
+    ) -> Option<&MasterSource> {
+        self.master_glyph_data_at(location)
+            .map(|(master, _)| master)
+    }
+
+    fn master_glyph_data_at(
+        &self,
+        location: &NormalizedLocation,
+    ) -> Option<&(MasterSource, HashMap<String, MasterGlyphData>)> {
+        self.master_glyphs
+            .iter()
+            .find(|(master, _)| master.matches(location))
+            .or_else(|| {
+                self.master_glyphs
+                    .iter()
+                    .find(|(master, _)| master.is_default())
+            })
+            .or_else(|| self.master_glyphs.first())
+    }
+
+    /// Load the components, anchors, guidelines, implied on-curve points
+    /// and font info of every master source
+    pub fn load_master_glyphs(&mut self) -> Result<()> {
+        let masters = load_master_sources(&self.source_path)?;
+        self.master_glyphs.clear();
+        self.font_guidelines.clear();
+        self.font_guidelines_dirty = false;
+        self.font_infos.clear();
+        self.font_info_dirty.clear();
+        self.font_info_generation = next_generation();
+        self.glyph_layers.clear();
+        self.glyph_layers_dirty.clear();
+        self.editing_background = None;
+        self.image_files.clear();
+        self.new_image_files.clear();
+
+        for master in &masters {
+            let font = match norad::Font::load(&master.ufo_path) {
+                Ok(font) => font,
+                Err(e) => {
+                    warn!(
+                        "Failed to load {} for glyph data: {}",
+                        master.ufo_path.display(),
+                        e
+                    );
+                    continue;
                 }
-            } else {
-                warn!("🧩 Component base glyph '{}' not found for glyph '{}'", base_glyph_name, glyph_name);
+            };
+            let guidelines = font
+                .font_info
+                .guidelines
+                .iter()
+                .flatten()
+                .map(GuidelineData::from_norad_guideline)
+                .collect();
+            self.font_guidelines
+                .entry(master.ufo_path.clone())
+                .or_insert(guidelines);
+            self.font_infos
+                .entry(master.ufo_path.clone())
+                .or_insert_with(|| {
+                    FontInfo::from_norad_font_info(&font.font_info)
+                });
+            if !self.glyph_layers.contains_key(&master.ufo_path) {
+                let master_layers: Vec<Option<&str>> = masters
+                    .iter()
+                    .filter(|other| other.ufo_path == master.ufo_path)
+                    .map(|other| other.layer.as_deref())
+                    .collect();
+                self.glyph_layers.insert(
+                    master.ufo_path.clone(),
+                    load_glyph_layers(&font, &master_layers),
+                );
             }
+
+            let layer = match master.layer.as_deref() {
+                Some(layer_name) => font.layers.get(layer_name),
+                None => Some(font.default_layer()),
+            };
+            let Some(layer) = layer else {
+                warn!(
+                    "Layer {:?} not found in {}",
+                    master.layer,
+                    master.ufo_path.display()
+                );
+                continue;
+            };
+
+            let glyphs: HashMap<String, MasterGlyphData> = layer
+                .iter()
+                .filter_map(|glyph| {
+                    let implied_on_curves: Vec<Point> = glyph
+                        .contours
+                        .iter()
+                        .flat_map(quadratic::ufo_implied_on_curves)
+                        .collect();
+                    if glyph.components.is_empty()
+                        && glyph.anchors.is_empty()
+                        && glyph.guidelines.is_empty()
+                        && glyph.image.is_none()
+                        && implied_on_curves.is_empty()
+                    {
+                        return None;
+                    }
+                    let data = MasterGlyphData {
+                        components: glyph
+                            .components
+                            .iter()
+                            .map(ComponentData::from_norad_component)
+                            .collect(),
+                        anchors: glyph
+                            .anchors
+                            .iter()
+                            .map(AnchorData::from_norad_anchor)
+                            .collect(),
+                        guidelines: glyph
+                            .guidelines
+                            .iter()
+                            .map(GuidelineData::from_norad_guideline)
+                            .collect(),
+                        image: glyph
+                            .image
+                            .as_ref()
+                            .map(ImageData::from_norad_image),
+                        implied_on_curves,
+                    };
+                    Some((glyph.name().to_string(), data))
+                })
+                .collect();
+
+            let files =
+                self.image_files.entry(master.ufo_path.clone()).or_default();
+            for image in glyphs.values().filter_map(|data| data.image.as_ref())
+            {
+                if files.contains_key(&image.file_name) {
+                    continue;
+                }
+                match font.images.get(Path::new(&image.file_name)) {
+                    Some(Ok(data)) => {
+                        files.insert(
+                            image.file_name.clone(),
+                            Arc::new(data.to_vec()),
+                        );
+                    }
+                    Some(Err(e)) => {
+                        warn!("Failed to read image {}: {}", image.file_name, e)
+                    }
+                    None => warn!(
+                        "Image {} is missing from {}",
+                        image.file_name,
+                        master.ufo_path.display()
+                    ),
+                }
+            }
+
+            debug!(
+                "🧩 Loaded UFO data for {} glyphs from '{}'",
+                glyphs.len(),
+                master.name
+            );
+            self.master_glyphs.push((master.clone(), glyphs));
         }
-        
-        if component_paths.is_empty() {
-            warn!("🧩 No component paths resolved for glyph '{}'", glyph_name);
-            None
-        } else {
-            info!("🧩 Successfully resolved {} total paths for composite glyph '{}'", component_paths.len(), glyph_name);
-            Some(component_paths)
-        }
+
+        Ok(())
     }
 
     /// Load all glyphs into cache
@@ -738,7 +2509,31 @@ impl FontIRAppState {
     }
 
     /// Get font metrics from FontIR source
+    ///
+    /// Values set in the font info of the master at the current location
+    /// take precedence, so edits in the font info pane show up right away.
     pub fn get_font_metrics(&self) -> FontIRMetrics {
+        let mut metrics = self.get_fontir_metrics();
+        if let Some(font_info) = self.current_font_info() {
+            if let Some(units_per_em) = font_info.units_per_em {
+                metrics.units_per_em = units_per_em as f32;
+            }
+            let overrides = [
+                (&mut metrics.ascender, font_info.ascender),
+                (&mut metrics.descender, font_info.descender),
+                (&mut metrics.x_height, font_info.x_height),
+                (&mut metrics.cap_height, font_info.cap_height),
+            ];
+            for (metric, value) in overrides {
+                if let Some(value) = value {
+                    *metric = Some(value as f32);
+                }
+            }
+        }
+        metrics
+    }
+
+    fn get_fontir_metrics(&self) -> FontIRMetrics {
         if let Some(ref context) = self.context {
             // Get static metadata for units_per_em
             let static_metadata = context.static_metadata.get();
@@ -796,15 +2591,29 @@ impl FontIRAppState {
                 .collect();
 
             if !names.is_empty() {
+                // Renamed glyphs are only in the cache under their new name
+                names.retain(|name| !self.changed_glyph_names.contains(name));
+                names.extend(
+                    self.changed_glyph_names
+                        .iter()
+                        .filter(|name| self.glyph_cache.contains_key(*name))
+                        .cloned(),
+                );
+                names.extend(self.new_glyphs.keys().cloned());
                 names.sort();
+                names.dedup();
                 return names;
             }
         }
 
         // Fall back to cached glyph names if available
         if !self.glyph_cache.is_empty() {
-            let mut names: Vec<String> =
-                self.glyph_cache.keys().cloned().collect();
+            let mut names: Vec<String> = self
+                .glyph_cache
+                .keys()
+                .chain(self.new_glyphs.keys())
+                .cloned()
+                .collect();
             names.sort();
             return names;
         }
@@ -842,8 +2651,27 @@ impl FontIRAppState {
 
     /// Get advance width for a glyph
     pub fn get_glyph_advance_width(&self, glyph_name: &str) -> f32 {
+        if let Some(width) = self.interpolated_glyph_width(glyph_name) {
+            return width as f32;
+        }
+
+        // Glyphs and intermediate masters created in the editor only exist
+        // as working copies
+        let is_new_master =
+            self.new_intermediate_masters.iter().any(|master| {
+                master.glyph_name == glyph_name
+                    && locations_match(&master.location, &self.current_location)
+            });
+        if self.new_glyphs.contains_key(glyph_name) || is_new_master {
+            if let Some(width) =
+                self.glyph_width_at(glyph_name, &self.current_location)
+            {
+                return width as f32;
+            }
+        }
+
         // First try to get from FontIR context
-        if let Some(ref context) = self.context {
+        if let Some(context) = self.current_context(glyph_name) {
             let glyph_name_typed: GlyphName = glyph_name.into();
             let work_id = WorkId::Glyph(glyph_name_typed);
 
@@ -1242,6 +3070,163 @@ impl FontIRAppState {
     }
 }
 
+/// Point components of a renamed glyph at its new name
+fn rename_component_bases(
+    components: &mut [ComponentData],
+    old_name: &str,
+    new_name: &str,
+) {
+    for component in components {
+        if component.base_glyph == old_name {
+            component.base_glyph = new_name.to_string();
+        }
+    }
+}
+
+/// Components and anchors interpolated between weighted masters, or taken
+/// from the heaviest master when the masters don't have the same ones
+fn interpolate_components_and_anchors(
+    masters: &[((Vec<ComponentData>, Vec<AnchorData>), f64)],
+) -> (Vec<ComponentData>, Vec<AnchorData>) {
+    let Some(((components, anchors), _)) = masters
+        .iter()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .cloned()
+    else {
+        return Default::default();
+    };
+
+    let components_match = masters.iter().all(|((others, _), _)| {
+        others.len() == components.len()
+            && others.iter().zip(&components).all(|(other, component)| {
+                other.base_glyph == component.base_glyph
+            })
+    });
+    let components = if components_match {
+        let mut interpolated = components.clone();
+        for (index, component) in interpolated.iter_mut().enumerate() {
+            for (coefficient, value) in
+                component.transform.iter_mut().enumerate()
+            {
+                *value = masters
+                    .iter()
+                    .map(|((others, _), weight)| {
+                        others[index].transform[coefficient] * weight
+                    })
+                    .sum();
+            }
+        }
+        interpolated
+    } else {
+        components
+    };
+
+    let anchors_match = masters.iter().all(|((_, others), _)| {
+        others.len() == anchors.len()
+            && others
+                .iter()
+                .zip(&anchors)
+                .all(|(other, anchor)| other.name == anchor.name)
+    });
+    let anchors = if anchors_match {
+        let mut interpolated = anchors.clone();
+        for (index, anchor) in interpolated.iter_mut().enumerate() {
+            anchor.x = masters
+                .iter()
+                .map(|((_, others), weight)| others[index].x * weight)
+                .sum();
+            anchor.y = masters
+                .iter()
+                .map(|((_, others), weight)| others[index].y * weight)
+                .sum();
+        }
+        interpolated
+    } else {
+        anchors
+    };
+
+    (components, anchors)
+}
+
+/// Repeat a components edit in another master: components added, removed
+/// or given another base, and moved too when `move_components` is set
+fn sync_components(
+    before: &[ComponentData],
+    after: &[ComponentData],
+    other: &[ComponentData],
+    move_components: bool,
+) -> Option<Vec<ComponentData>> {
+    if other.len() != before.len() {
+        return None;
+    }
+    if after.len() == before.len() {
+        let synced = before.iter().zip(after).zip(other).map(
+            |((before, after), other)| {
+                let mut synced = other.clone();
+                if after.base_glyph != before.base_glyph {
+                    synced.base_glyph.clone_from(&after.base_glyph);
+                }
+                if move_components {
+                    for (value, (after, before)) in synced
+                        .transform
+                        .iter_mut()
+                        .zip(after.transform.iter().zip(&before.transform))
+                    {
+                        *value += after - before;
+                    }
+                }
+                synced
+            },
+        );
+        return Some(synced.collect());
+    }
+
+    let mut synced = Vec::new();
+    let mut next = 0;
+    if after.len() > before.len() {
+        for component in after {
+            if next < before.len() && before[next] == *component {
+                synced.push(other[next].clone());
+                next += 1;
+            } else {
+                synced.push(component.clone());
+            }
+        }
+        (next == before.len()).then_some(synced)
+    } else {
+        for (component, other) in before.iter().zip(other) {
+            if next < after.len() && after[next] == *component {
+                synced.push(other.clone());
+                next += 1;
+            }
+        }
+        (next == after.len()).then_some(synced)
+    }
+}
+
+/// Pick the glyph instance for a location
+///
+/// Prefers an exact match, then a source at the same coordinates (axes at
+/// their default may be omitted), and finally the first available source.
+pub fn instance_at<'a>(
+    glyph: &'a FontIRGlyph,
+    location: &NormalizedLocation,
+) -> Option<&'a GlyphInstance> {
+    let sources = glyph.sources();
+    sources
+        .get(location)
+        .or_else(|| {
+            sources
+                .iter()
+                .find(|(source_location, _)| {
+                    locations_match(source_location, location)
+                })
+                .map(|(_, instance)| instance)
+        })
+        .or_else(|| sources.values().next())
+}
+
+/// A FontIR location as normalized values by axis tag
 /// Helper to convert PathEl to a point position
 pub fn path_element_position(el: &PathEl) -> Option<Point> {
     match el {
@@ -1289,11 +3274,11 @@ impl From<&PathEl> for FontIRPointType {
 pub fn apply_affine_transform(path: &BezPath, transform: &norad::AffineTransform) -> BezPath {
     // Convert norad::AffineTransform to kurbo::Affine
     // norad format: [x_scale, xy_scale, yx_scale, y_scale, x_offset, y_offset]
-    // kurbo format: [xx, yx, xy, yy, x, y] (matrix form)
+    // kurbo uses the same PostScript coefficient order [a, b, c, d, e, f]
     let affine = Affine::new([
         transform.x_scale,
-        transform.yx_scale,
         transform.xy_scale,
+        transform.yx_scale,
         transform.y_scale,
         transform.x_offset,
         transform.y_offset,
@@ -9,6 +9,7 @@
 
 // Sub-modules
 pub mod app_state;
+pub mod checks;
 pub mod font_data;
 pub mod font_metrics;
 pub mod fontir_app_state;
@@ -20,6 +21,7 @@ mod test_components;
 
 // Re-export all public items to maintain the existing API
 pub use app_state::*;
+pub use checks::*;
 pub use font_data::*;
 pub use font_metrics::*;
 pub use fontir_app_state::*;
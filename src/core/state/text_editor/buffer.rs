@@ -1,6 +1,7 @@
 //! Gap buffer implementation and data types for text editor
 
 use bevy::prelude::*;
+use std::collections::HashMap;
 
 /// Text editor state for dynamic sort management
 #[derive(Resource, Clone, Default)]
@@ -69,6 +70,9 @@ pub struct SortBuffer {
     gap_start: usize,
     /// Gap end position (exclusive)
     gap_end: usize,
+    /// User-space axis coordinates text buffers are shown at through the
+    /// compiled variable font; other buffers show the masters being edited
+    variations: HashMap<BufferId, Vec<(String, f32)>>,
 }
 
 /// Unique identifier for text buffer flows
@@ -102,6 +106,17 @@ pub struct SortEntry {
     pub buffer_id: Option<BufferId>,
 }
 
+/// A combining mark attached to a preceding sort through matching anchors
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkAttachment {
+    /// Buffer index of the mark sort
+    pub mark_index: usize,
+    /// Buffer index of the sort the mark attaches to (a base or another mark)
+    pub base_index: usize,
+    /// Offset from the base sort origin to the mark sort origin
+    pub offset: Vec2,
+}
+
 /// Grid layout configuration
 #[derive(Clone)]
 pub struct GridConfig {
@@ -168,6 +183,7 @@ impl SortBuffer {
             buffer,
             gap_start: 0,
             gap_end: initial_capacity,
+            variations: HashMap::new(),
         }
     }
 
@@ -321,6 +337,64 @@ impl SortBuffer {
         }
         self.gap_start = 0;
         self.gap_end = self.buffer.len();
+        self.variations.clear();
+    }
+
+    /// Axis coordinates a text buffer is shown at through the compiled
+    /// variable font, if it is
+    pub fn variations(&self, buffer_id: BufferId) -> Option<&[(String, f32)]> {
+        self.variations.get(&buffer_id).map(Vec::as_slice)
+    }
+
+    /// Show a text buffer through the compiled variable font at the given
+    /// axis coordinates, or with the masters again for `None`
+    pub fn set_variations(
+        &mut self,
+        buffer_id: BufferId,
+        variations: Option<Vec<(String, f32)>>,
+    ) {
+        match variations {
+            Some(variations) => {
+                self.variations.insert(buffer_id, variations);
+            }
+            None => {
+                self.variations.remove(&buffer_id);
+            }
+        }
+    }
+
+    /// Set one axis coordinate of a text buffer shown through the compiled
+    /// variable font
+    pub fn set_variation(
+        &mut self,
+        buffer_id: BufferId,
+        tag: &str,
+        value: f32,
+    ) {
+        let Some(variations) = self.variations.get_mut(&buffer_id) else {
+            return;
+        };
+        match variations.iter_mut().find(|(axis, _)| axis == tag) {
+            Some((_, current)) => *current = value,
+            None => variations.push((tag.to_string(), value)),
+        }
+    }
+
+    /// Text buffers shown through the compiled variable font
+    pub fn variable_font_buffers(
+        &self,
+    ) -> impl Iterator<Item = (BufferId, &[(String, f32)])> {
+        self.variations
+            .iter()
+            .map(|(buffer_id, variations)| (*buffer_id, variations.as_slice()))
+    }
+
+    /// Whether the sort at a logical position belongs to a text buffer
+    /// shown through the compiled variable font
+    pub fn is_variable_font_sort(&self, index: usize) -> bool {
+        self.get(index)
+            .and_then(|entry| entry.buffer_id)
+            .is_some_and(|buffer_id| self.variations.contains_key(&buffer_id))
     }
 }
 
@@ -1,8 +1,9 @@
 //! Text editing operations and cursor management
 
 use super::buffer::*;
-use crate::core::state::FontMetrics;
+use crate::core::state::{AnchorData, FontMetrics};
 use bevy::prelude::*;
+use std::collections::HashMap;
 
 impl TextEditorState {
     /// Get only text sorts (sorts that flow like text)
@@ -49,6 +50,43 @@ impl TextEditorState {
         None
     }
 
+    /// Point the sorts of a renamed glyph at its new name
+    pub fn rename_glyph(&mut self, old_name: &str, new_name: &str) {
+        for i in 0..self.buffer.len() {
+            if let Some(SortEntry {
+                kind: SortKind::Glyph { glyph_name, .. },
+                ..
+            }) = self.buffer.get_mut(i)
+            {
+                if glyph_name == old_name {
+                    *glyph_name = new_name.to_string();
+                }
+            }
+        }
+    }
+
+    /// Look up the advance width of every glyph sort again, after moving
+    /// to another designspace location
+    pub fn update_advance_widths(
+        &mut self,
+        advance_width_of: impl Fn(&str) -> f32,
+    ) {
+        for i in 0..self.buffer.len() {
+            if let Some(SortEntry {
+                kind:
+                    SortKind::Glyph {
+                        glyph_name,
+                        advance_width,
+                        ..
+                    },
+                ..
+            }) = self.buffer.get_mut(i)
+            {
+                *advance_width = advance_width_of(glyph_name);
+            }
+        }
+    }
+
     /// Get all unique buffer IDs
     pub fn get_all_buffer_ids(&self) -> Vec<BufferId> {
         let mut buffer_ids = Vec::new();
@@ -237,6 +275,106 @@ impl TextEditorState {
         }
     }
 
+    /// Find combining marks in text buffers and the sorts they attach to
+    ///
+    /// A glyph is treated as a mark when it has `_`-prefixed anchors. Each
+    /// mark looks back through its own text buffer for the nearest sort
+    /// with a matching anchor (`_top` onto `top`), skipping over marks that
+    /// don't match so that e.g. a bottom mark still reaches the base after
+    /// a top mark. Marks stack onto earlier marks (mark-to-mark), and
+    /// ligature anchors (`top_1`, `top_2`, ...) are used in order when the
+    /// base has no plain anchor of that name.
+    pub fn mark_attachments(
+        &self,
+        anchors_for: impl Fn(&str) -> Vec<AnchorData>,
+    ) -> Vec<MarkAttachment> {
+        let mut anchor_cache: HashMap<String, Vec<AnchorData>> = HashMap::new();
+        let mut attachments = Vec::new();
+        // (base index, anchor name) of every attachment so far, used to
+        // pick the next ligature component anchor
+        let mut used_anchors: Vec<(usize, String)> = Vec::new();
+
+        for mark_index in 0..self.buffer.len() {
+            let Some(mark_sort) = self.text_glyph_sort(mark_index) else {
+                continue;
+            };
+            let mark_anchors = anchor_cache
+                .entry(mark_sort.kind.glyph_name().to_string())
+                .or_insert_with(|| anchors_for(mark_sort.kind.glyph_name()))
+                .clone();
+            if !mark_anchors.iter().any(AnchorData::is_mark) {
+                continue;
+            }
+
+            for base_index in (0..mark_index).rev() {
+                let Some(base_sort) = self.text_glyph_sort(base_index) else {
+                    break;
+                };
+                if base_sort.buffer_id != mark_sort.buffer_id {
+                    break;
+                }
+                let base_anchors = anchor_cache
+                    .entry(base_sort.kind.glyph_name().to_string())
+                    .or_insert_with(|| {
+                        anchors_for(base_sort.kind.glyph_name())
+                    });
+
+                let matched = mark_anchors.iter().find_map(|mark_anchor| {
+                    let name = mark_anchor.attached_name()?;
+                    let component = 1 + used_anchors
+                        .iter()
+                        .filter(|(index, used)| {
+                            *index == base_index && used == name
+                        })
+                        .count();
+                    let ligature_name = format!("{name}_{component}");
+                    base_anchors
+                        .iter()
+                        .find(|anchor| anchor.name == name)
+                        .or_else(|| {
+                            base_anchors
+                                .iter()
+                                .find(|anchor| anchor.name == ligature_name)
+                        })
+                        .map(|base_anchor| {
+                            (name.to_string(), base_anchor, mark_anchor)
+                        })
+                });
+
+                if let Some((name, base_anchor, mark_anchor)) = matched {
+                    attachments.push(MarkAttachment {
+                        mark_index,
+                        base_index,
+                        offset: Vec2::new(
+                            (base_anchor.x - mark_anchor.x) as f32,
+                            (base_anchor.y - mark_anchor.y) as f32,
+                        ),
+                    });
+                    used_anchors.push((base_index, name));
+                    break;
+                }
+
+                // Keep looking past marks that didn't match; stop at a base
+                if !base_anchors.iter().any(AnchorData::is_mark) {
+                    break;
+                }
+            }
+        }
+
+        attachments
+    }
+
+    /// Glyph sort at a buffer index if it is part of a text flow
+    fn text_glyph_sort(&self, index: usize) -> Option<&SortEntry> {
+        self.buffer.get(index).filter(|sort| {
+            sort.kind.is_glyph()
+                && matches!(
+                    sort.layout_mode,
+                    SortLayoutMode::LTRText | SortLayoutMode::RTLText
+                )
+        })
+    }
+
     /// Create a new text root at the specified world position
     pub fn create_text_root(
         &mut self,
@@ -1437,4 +1575,100 @@ mod tests {
             panic!("Should have flow position for third glyph");
         }
     }
+
+    #[test]
+    fn test_mark_attachments() {
+        let mut text_editor = TextEditorState::default();
+        text_editor
+            .create_text_root(Vec2::new(0.0, 0.0), SortLayoutMode::LTRText);
+        text_editor.insert_sort_at_cursor(
+            "acutecomb".to_string(),
+            0.0,
+            Some('\u{0301}'),
+        );
+        text_editor.insert_sort_at_cursor(
+            "dotbelowcomb".to_string(),
+            0.0,
+            Some('\u{0323}'),
+        );
+        text_editor.insert_sort_at_cursor(
+            "gravecomb".to_string(),
+            0.0,
+            Some('\u{0300}'),
+        );
+
+        let attachments = text_editor.mark_attachments(|glyph| match glyph {
+            "a" => vec![
+                AnchorData::new("top", 250.0, 500.0),
+                AnchorData::new("bottom", 250.0, 0.0),
+            ],
+            "acutecomb" | "gravecomb" => vec![
+                AnchorData::new("_top", 100.0, 450.0),
+                AnchorData::new("top", 100.0, 700.0),
+            ],
+            "dotbelowcomb" => vec![AnchorData::new("_bottom", 50.0, 0.0)],
+            _ => Vec::new(),
+        });
+
+        // The root placeholder "a" at index 0 is the base
+        assert_eq!(
+            attachments,
+            vec![
+                MarkAttachment {
+                    mark_index: 1,
+                    base_index: 0,
+                    offset: Vec2::new(150.0, 50.0),
+                },
+                // Skips the top mark to reach the base's bottom anchor
+                MarkAttachment {
+                    mark_index: 2,
+                    base_index: 0,
+                    offset: Vec2::new(200.0, 0.0),
+                },
+                // Stacks onto the acute through its top anchor
+                MarkAttachment {
+                    mark_index: 3,
+                    base_index: 1,
+                    offset: Vec2::new(0.0, 250.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_buffer_variations() {
+        let mut text_editor = TextEditorState::default();
+        text_editor
+            .create_text_root(Vec2::new(0.0, 0.0), SortLayoutMode::LTRText);
+        text_editor.add_freeform_sort(
+            "b".to_string(),
+            Vec2::new(800.0, 0.0),
+            500.0,
+            Some('b'),
+        );
+        let buffer_id = text_editor.buffer.get(0).unwrap().buffer_id.unwrap();
+
+        // Buffers show the masters until given coordinates
+        assert!(text_editor.buffer.variations(buffer_id).is_none());
+        text_editor.buffer.set_variation(buffer_id, "wght", 700.0);
+        assert!(text_editor.buffer.variations(buffer_id).is_none());
+
+        text_editor
+            .buffer
+            .set_variations(buffer_id, Some(vec![("wght".to_string(), 400.0)]));
+        text_editor.buffer.set_variation(buffer_id, "wght", 650.0);
+        text_editor.buffer.set_variation(buffer_id, "wdth", 75.0);
+        assert_eq!(
+            text_editor.buffer.variations(buffer_id),
+            Some(
+                &[("wght".to_string(), 650.0), ("wdth".to_string(), 75.0)][..]
+            )
+        );
+        assert!(text_editor.buffer.is_variable_font_sort(0));
+        // Freeform sorts belong to no text buffer
+        assert!(!text_editor.buffer.is_variable_font_sort(1));
+
+        text_editor.buffer.set_variations(buffer_id, None);
+        assert!(!text_editor.buffer.is_variable_font_sort(0));
+    }
 }
@@ -0,0 +1,229 @@
+//! Character sets and language coverage
+//!
+//! Bundled definitions of the characters a font needs for a character set
+//! or a language, and a report of which ones a font is missing. The
+//! definitions list the letters, digits and punctuation a language needs
+//! beyond the basics, so the Latin languages are checked together with
+//! `Latin Core`. Everything works offline from the tables in this module.
+
+use std::collections::HashSet;
+
+/// Characters needed to support a character set or a language
+#[derive(Debug, Clone, Copy)]
+pub struct CharacterSet {
+    pub name: &'static str,
+    /// Characters listed one by one
+    pub characters: &'static str,
+    /// Inclusive codepoint ranges, in addition to `characters`
+    pub ranges: &'static [(u32, u32)],
+}
+
+impl CharacterSet {
+    /// Every character of the set, sorted and without duplicates
+    pub fn chars(&self) -> Vec<char> {
+        let mut chars: Vec<char> = self
+            .characters
+            .chars()
+            .chain(
+                self.ranges
+                    .iter()
+                    .flat_map(|&(start, end)| start..=end)
+                    .filter_map(char::from_u32),
+            )
+            .collect();
+        chars.sort();
+        chars.dedup();
+        chars
+    }
+}
+
+/// The bundled character sets and languages
+pub const CHARACTER_SETS: &[CharacterSet] = &[
+    CharacterSet {
+        name: "Latin Core",
+        characters: "",
+        ranges: &[(0x0020, 0x007E)],
+    },
+    CharacterSet {
+        name: "Latin-1 Supplement",
+        characters: "",
+        ranges: &[(0x00A0, 0x00FF)],
+    },
+    CharacterSet {
+        name: "Latin Extended-A",
+        characters: "",
+        ranges: &[(0x0100, 0x017F)],
+    },
+    CharacterSet {
+        name: "French",
+        characters: "ÀÂÆÇÈÉÊËÎÏÔŒÙÛÜŸàâæçèéêëîïôœùûüÿ«»",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "German",
+        characters: "ÄÖÜẞäöüß„“‚‘",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Spanish",
+        characters: "ÁÉÍÑÓÚÜáéíñóúü¡¿«»",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Portuguese",
+        characters: "ÀÁÂÃÇÉÊÍÓÔÕÚàáâãçéêíóôõú",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Italian",
+        characters: "ÀÈÉÌÒÙàèéìòù",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Dutch",
+        characters: "ÁÄÉËÍÏÓÖÚÜáäéëíïóöúü",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Polish",
+        characters: "ĄĆĘŁŃÓŚŹŻąćęłńóśźż„”",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Czech",
+        characters: "ÁČĎÉĚÍŇÓŘŠŤÚŮÝŽáčďéěíňóřšťúůýž„“",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Hungarian",
+        characters: "ÁÉÍÓÖŐÚÜŰáéíóöőúüű„”",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Romanian",
+        characters: "ĂÂÎȘȚăâîșț„”",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Turkish",
+        characters: "ÇĞİÖŞÜçğıöşü",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Vietnamese",
+        characters: "ÀÁÂÃÈÉÊÌÍÒÓÔÕÙÚÝàáâãèéêìíòóôõùúýĂăĐđĨĩŨũƠơƯư₫",
+        ranges: &[(0x1EA0, 0x1EF9)],
+    },
+    CharacterSet {
+        name: "Greek",
+        characters: "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩαβγδεζηθικλμνξοπρςστυφχψω\
+                     ΆΈΉΊΌΎΏάέήίόύώϊϋΐΰ;·",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Russian",
+        characters: "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ\
+                     абвгдеёжзийклмнопрстуфхцчшщъыьэюя«»„“№",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Ukrainian",
+        characters: "АБВГҐДЕЄЖЗИІЇЙКЛМНОПРСТУФХЦЧШЩЬЮЯ\
+                     абвгґдеєжзиіїйклмнопрстуфхцчшщьюяʼ«»№",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Arabic",
+        characters: "ءآأؤإئابةتثجحخدذرزسشصضطظعغفقكلمنهوىي\
+                     \u{064B}\u{064C}\u{064D}\u{064E}\
+                     \u{064F}\u{0650}\u{0651}\u{0652}\
+                     ٠١٢٣٤٥٦٧٨٩،؛؟",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Persian",
+        characters: "ءآأؤئابپتثجچحخدذرزژسشصضطظعغفقکگلمنوهی\
+                     ۰۱۲۳۴۵۶۷۸۹،؛؟«»",
+        ranges: &[],
+    },
+    CharacterSet {
+        name: "Urdu",
+        characters: "ءآأؤئابپتٹثجچحخدڈذرڑزژسشصضطظعغفقکگلمنںوہھۂۃیےۓ\
+                     ۰۱۲۳۴۵۶۷۸۹،؛؟۔",
+        ranges: &[],
+    },
+];
+
+/// How much of a character set a font supports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub name: &'static str,
+    /// Number of characters in the set
+    pub total: usize,
+    /// Characters of the set the font has no glyph for, sorted
+    pub missing: Vec<char>,
+}
+
+impl Coverage {
+    /// Number of characters of the set the font supports
+    pub fn supported(&self) -> usize {
+        self.total - self.missing.len()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Coverage of every bundled character set by a font's codepoints
+pub fn coverage_report(codepoints: &HashSet<char>) -> Vec<Coverage> {
+    CHARACTER_SETS
+        .iter()
+        .map(|set| {
+            let chars = set.chars();
+            Coverage {
+                name: set.name,
+                total: chars.len(),
+                missing: chars
+                    .into_iter()
+                    .filter(|c| !codepoints.contains(c))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(name: &str) -> CharacterSet {
+        *CHARACTER_SETS.iter().find(|set| set.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_character_sets() {
+        assert_eq!(set("Latin Core").chars().len(), 95);
+        assert_eq!(set("Latin Extended-A").chars().len(), 128);
+        assert_eq!(set("Russian").chars().len(), 71);
+        assert_eq!(set("Vietnamese").chars().len(), 134 + 1);
+        assert!(set("Urdu").chars().contains(&'\u{06D2}'));
+        assert!(set("Persian").chars().contains(&'\u{06CC}'));
+        assert!(!set("Persian").chars().contains(&'\u{064A}'));
+        assert!(set("Romanian").chars().contains(&'\u{0219}'));
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let codepoints: HashSet<char> =
+            (' '..='~').chain("ÄÖÜäöüß".chars()).collect();
+        let report = coverage_report(&codepoints);
+        let coverage = |name| report.iter().find(|c| c.name == name).unwrap();
+
+        assert!(coverage("Latin Core").is_complete());
+        let german = coverage("German");
+        assert_eq!(german.supported(), 7);
+        assert_eq!(german.missing, vec!['ẞ', '‘', '‚', '“', '„']);
+        assert_eq!(coverage("Arabic").supported(), 0);
+    }
+}
@@ -0,0 +1,614 @@
+//! Interpolation compatibility across masters
+//!
+//! A glyph only interpolates when every master has the same structure: the
+//! same contours in the same order, each with the same points of the same
+//! types starting at the same place, and the same components and anchors.
+//! Masters are compared against a reference master, normally the default
+//! one, and every difference is reported with the master, contour and point
+//! it is in.
+//!
+//! Points are numbered as in the UFO: a closed contour starts with its
+//! first on-curve point, which takes the type of the segment closing the
+//! contour, and does not repeat it at the end. Contours that moved in the
+//! contour order and start points that moved along a contour are found by
+//! comparing positions within the glyph and the contour.
+
+use kurbo::{BezPath, PathEl, Point, Rect};
+
+/// Normalized distance below which a point counts as being in the same
+/// place as in the reference master
+const SAME_PLACE_DISTANCE: f64 = 0.25;
+
+/// Distances below this are treated as zero
+const EPSILON: f64 = 1e-6;
+
+/// A glyph as drawn in one master
+#[derive(Debug, Clone, Default)]
+pub struct MasterGlyph {
+    /// Display name of the master
+    pub master: String,
+    pub contours: Vec<BezPath>,
+    /// Base glyph of each component, in order
+    pub components: Vec<String>,
+    /// Anchor names, in order
+    pub anchors: Vec<String>,
+}
+
+/// Type of a point, as stored in a UFO
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointKind {
+    Move,
+    Line,
+    Curve,
+    QCurve,
+    OffCurve,
+}
+
+impl PointKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PointKind::Move => "move",
+            PointKind::Line => "line",
+            PointKind::Curve => "curve",
+            PointKind::QCurve => "qcurve",
+            PointKind::OffCurve => "off-curve",
+        }
+    }
+}
+
+/// How a master differs from the reference master
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incompatibility {
+    ContourCount {
+        expected: usize,
+        found: usize,
+    },
+    /// The contour is at another index in the reference master
+    ContourOrder {
+        expected: usize,
+    },
+    PointCount {
+        expected: usize,
+        found: usize,
+    },
+    PointType {
+        point: usize,
+        expected: PointKind,
+        found: PointKind,
+    },
+    /// The reference master's start point is another point of the contour
+    StartPoint {
+        expected: usize,
+    },
+    Components {
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+    Anchors {
+        missing: Vec<String>,
+        extra: Vec<String>,
+    },
+}
+
+/// A difference between a master and the reference master
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompatibilityIssue {
+    /// Index of the master in the masters checked
+    pub master: usize,
+    pub contour: Option<usize>,
+    pub incompatibility: Incompatibility,
+    /// The point that differs, in the master's glyph coordinates
+    pub position: Option<Point>,
+    /// The matching point in the reference master
+    pub reference_position: Option<Point>,
+}
+
+impl CompatibilityIssue {
+    /// What differs, with contours and points numbered from 1
+    pub fn description(&self) -> String {
+        let contour = self.contour.map_or(0, |contour| contour + 1);
+        match &self.incompatibility {
+            Incompatibility::ContourCount { expected, found } => {
+                format!("{found} contours, expected {expected}")
+            }
+            Incompatibility::ContourOrder { expected } => format!(
+                "contour {contour} is contour {} in the reference",
+                expected + 1
+            ),
+            Incompatibility::PointCount { expected, found } => {
+                format!(
+                    "contour {contour}: {found} points, expected {expected}"
+                )
+            }
+            Incompatibility::PointType {
+                point,
+                expected,
+                found,
+            } => format!(
+                "contour {contour} point {}: {} point, expected {}",
+                point + 1,
+                found.name(),
+                expected.name()
+            ),
+            Incompatibility::StartPoint { expected } => format!(
+                "contour {contour}: starts at point 1, expected point {}",
+                expected + 1
+            ),
+            Incompatibility::Components { expected, found } => format!(
+                "components {}, expected {}",
+                name_list(found),
+                name_list(expected)
+            ),
+            Incompatibility::Anchors { missing, extra } => {
+                let mut parts = Vec::new();
+                if !missing.is_empty() {
+                    parts.push(format!(
+                        "missing anchors {}",
+                        missing.join(", ")
+                    ));
+                }
+                if !extra.is_empty() {
+                    parts.push(format!("extra anchors {}", extra.join(", ")));
+                }
+                parts.join("; ")
+            }
+        }
+    }
+}
+
+fn name_list(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Compare every master of a glyph against the reference master
+pub fn check_compatibility(
+    masters: &[MasterGlyph],
+    reference: usize,
+) -> Vec<CompatibilityIssue> {
+    let Some(reference_glyph) = masters.get(reference) else {
+        return Vec::new();
+    };
+    let reference_points: Vec<_> = reference_glyph
+        .contours
+        .iter()
+        .map(contour_points)
+        .collect();
+
+    let mut issues = Vec::new();
+    for (index, glyph) in masters.iter().enumerate() {
+        if index == reference {
+            continue;
+        }
+        let issue = |contour, incompatibility, position, reference_position| {
+            CompatibilityIssue {
+                master: index,
+                contour,
+                incompatibility,
+                position,
+                reference_position,
+            }
+        };
+
+        if glyph.components != reference_glyph.components {
+            issues.push(issue(
+                None,
+                Incompatibility::Components {
+                    expected: reference_glyph.components.clone(),
+                    found: glyph.components.clone(),
+                },
+                None,
+                None,
+            ));
+        }
+        let missing = names_not_in(&reference_glyph.anchors, &glyph.anchors);
+        let extra = names_not_in(&glyph.anchors, &reference_glyph.anchors);
+        if !missing.is_empty() || !extra.is_empty() {
+            issues.push(issue(
+                None,
+                Incompatibility::Anchors { missing, extra },
+                None,
+                None,
+            ));
+        }
+
+        if glyph.contours.len() != reference_glyph.contours.len() {
+            issues.push(issue(
+                None,
+                Incompatibility::ContourCount {
+                    expected: reference_glyph.contours.len(),
+                    found: glyph.contours.len(),
+                },
+                None,
+                None,
+            ));
+            continue;
+        }
+
+        let points: Vec<_> =
+            glyph.contours.iter().map(contour_points).collect();
+        let centers = contour_centers(&glyph.contours);
+        let reference_centers = contour_centers(&reference_glyph.contours);
+        for (contour, contour_pts) in points.iter().enumerate() {
+            let start = contour_pts.first().map(|(p, _)| *p);
+            if let Some(expected) = moved_contour(
+                contour,
+                &points,
+                &centers,
+                &reference_points,
+                &reference_centers,
+            ) {
+                issues.push(issue(
+                    Some(contour),
+                    Incompatibility::ContourOrder { expected },
+                    start,
+                    reference_points[expected].first().map(|(p, _)| *p),
+                ));
+                continue;
+            }
+            if let Some((incompatibility, point, reference_point)) =
+                compare_contours(
+                    contour_pts,
+                    &reference_points[contour],
+                    &glyph.contours[contour],
+                    &reference_glyph.contours[contour],
+                )
+            {
+                issues.push(issue(
+                    Some(contour),
+                    incompatibility,
+                    Some(point),
+                    Some(reference_point),
+                ));
+            }
+        }
+    }
+    issues
+}
+
+/// Names in `names` that are not in `others`
+fn names_not_in(names: &[String], others: &[String]) -> Vec<String> {
+    names
+        .iter()
+        .filter(|name| !others.contains(name))
+        .cloned()
+        .collect()
+}
+
+/// The points of a contour with their types, numbered as in the UFO
+pub fn contour_points(contour: &BezPath) -> Vec<(Point, PointKind)> {
+    let mut points = Vec::new();
+    let mut closed = false;
+    for el in contour.elements() {
+        match *el {
+            PathEl::MoveTo(p) => points.push((p, PointKind::Move)),
+            PathEl::LineTo(p) => points.push((p, PointKind::Line)),
+            PathEl::QuadTo(p1, p2) => {
+                points.push((p1, PointKind::OffCurve));
+                points.push((p2, PointKind::QCurve));
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                points.push((p1, PointKind::OffCurve));
+                points.push((p2, PointKind::OffCurve));
+                points.push((p3, PointKind::Curve));
+            }
+            PathEl::ClosePath => closed = true,
+        }
+    }
+
+    if closed && points.len() > 1 {
+        // The start point takes the type of the closing segment
+        let (start, _) = points[0];
+        let (end, end_kind) = points[points.len() - 1];
+        points[0].1 = if end.distance(start) < EPSILON {
+            points.pop();
+            end_kind
+        } else {
+            PointKind::Line
+        };
+    }
+    points
+}
+
+/// Center of each contour's bounds, relative to the bounds of the glyph
+fn contour_centers(contours: &[BezPath]) -> Vec<Point> {
+    let bounds: Vec<Rect> = contours.iter().map(control_bounds).collect();
+    let Some(glyph) = bounds.iter().copied().reduce(|a, b| a.union(b)) else {
+        return Vec::new();
+    };
+    bounds
+        .iter()
+        .map(|rect| normalize(rect.center(), glyph))
+        .collect()
+}
+
+fn control_bounds(contour: &BezPath) -> Rect {
+    contour
+        .elements()
+        .iter()
+        .flat_map(|el| match *el {
+            PathEl::MoveTo(p) | PathEl::LineTo(p) => vec![p],
+            PathEl::QuadTo(p1, p2) => vec![p1, p2],
+            PathEl::CurveTo(p1, p2, p3) => vec![p1, p2, p3],
+            PathEl::ClosePath => Vec::new(),
+        })
+        .map(|p| Rect::from_points(p, p))
+        .reduce(|a, b| a.union(b))
+        .unwrap_or_default()
+}
+
+/// A point's position within bounds, from 0 to 1 on both axes
+fn normalize(point: Point, bounds: Rect) -> Point {
+    Point::new(
+        (point.x - bounds.x0) / bounds.width().max(1.0),
+        (point.y - bounds.y0) / bounds.height().max(1.0),
+    )
+}
+
+/// The reference contour a contour belongs in, if it is not its own index
+///
+/// A contour has moved when a reference contour with as many points sits
+/// where it is, while the reference contour at its index either has a
+/// different number of points or is clearly further away.
+fn moved_contour(
+    contour: usize,
+    points: &[Vec<(Point, PointKind)>],
+    centers: &[Point],
+    reference_points: &[Vec<(Point, PointKind)>],
+    reference_centers: &[Point],
+) -> Option<usize> {
+    let count = points[contour].len();
+    let center = centers[contour];
+    let (nearest, distance) = reference_centers
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| reference_points[*index].len() == count)
+        .map(|(index, reference)| (index, reference.distance(center)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    if nearest == contour {
+        return None;
+    }
+    let own_distance = reference_centers[contour].distance(center);
+    let own_fits = reference_points[contour].len() == count
+        && own_distance <= 2.0 * distance + SAME_PLACE_DISTANCE;
+    (!own_fits).then_some(nearest)
+}
+
+/// The first difference between a contour and the reference contour, with
+/// the point it is at in both
+fn compare_contours(
+    points: &[(Point, PointKind)],
+    reference: &[(Point, PointKind)],
+    contour: &BezPath,
+    reference_contour: &BezPath,
+) -> Option<(Incompatibility, Point, Point)> {
+    let (start, _) = *points.first()?;
+    let (reference_start, _) = *reference.first()?;
+    if points.len() != reference.len() {
+        return Some((
+            Incompatibility::PointCount {
+                expected: reference.len(),
+                found: points.len(),
+            },
+            start,
+            reference_start,
+        ));
+    }
+
+    let kinds: Vec<PointKind> = points.iter().map(|(_, kind)| *kind).collect();
+    let reference_kinds: Vec<PointKind> =
+        reference.iter().map(|(_, kind)| *kind).collect();
+    let is_closed = |contour: &BezPath| {
+        contour.elements().last() == Some(&PathEl::ClosePath)
+    };
+    let closed = is_closed(contour) && is_closed(reference_contour);
+
+    // Where the reference start point is, relative to the contour's bounds
+    let bounds = control_bounds(contour);
+    let target = normalize(reference_start, control_bounds(reference_contour));
+    let distance_to_target =
+        |index: usize| normalize(points[index].0, bounds).distance(target);
+
+    // Start points that would make the point types match
+    let rotations: Vec<usize> = if closed {
+        (0..points.len())
+            .filter(|&shift| {
+                (0..points.len()).all(|i| {
+                    kinds[(i + shift) % points.len()] == reference_kinds[i]
+                })
+            })
+            .collect()
+    } else if kinds == reference_kinds {
+        vec![0]
+    } else {
+        Vec::new()
+    };
+
+    let Some(&best) = rotations.iter().min_by(|a, b| {
+        distance_to_target(**a).total_cmp(&distance_to_target(**b))
+    }) else {
+        let point =
+            (0..points.len()).find(|&i| kinds[i] != reference_kinds[i])?;
+        return Some((
+            Incompatibility::PointType {
+                point,
+                expected: reference_kinds[point],
+                found: kinds[point],
+            },
+            points[point].0,
+            reference[point].0,
+        ));
+    };
+
+    let start_fits = rotations.contains(&0)
+        && distance_to_target(0)
+            <= distance_to_target(best) + SAME_PLACE_DISTANCE;
+    if start_fits {
+        return None;
+    }
+    Some((
+        Incompatibility::StartPoint { expected: best },
+        start,
+        reference_start,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f64, f64)]) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to(points[0]);
+        for &p in &points[1..] {
+            path.line_to(p);
+        }
+        path.close_path();
+        path
+    }
+
+    fn square(x: f64, y: f64, size: f64) -> BezPath {
+        polygon(&[(x, y), (x + size, y), (x + size, y + size), (x, y + size)])
+    }
+
+    fn master(name: &str, contours: Vec<BezPath>) -> MasterGlyph {
+        MasterGlyph {
+            master: name.to_string(),
+            contours,
+            components: Vec::new(),
+            anchors: vec!["top".to_string()],
+        }
+    }
+
+    fn incompatibilities(masters: &[MasterGlyph]) -> Vec<Incompatibility> {
+        check_compatibility(masters, 0)
+            .into_iter()
+            .map(|issue| issue.incompatibility)
+            .collect()
+    }
+
+    #[test]
+    fn test_contour_points() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((100.0, 0.0));
+        path.curve_to((100.0, 50.0), (50.0, 100.0), (0.0, 100.0));
+        path.curve_to((-50.0, 100.0), (0.0, 50.0), (0.0, 0.0));
+        path.close_path();
+        let kinds: Vec<_> = contour_points(&path)
+            .iter()
+            .map(|(_, kind)| *kind)
+            .collect();
+        use PointKind::*;
+        assert_eq!(
+            kinds,
+            vec![Curve, Line, OffCurve, OffCurve, Curve, OffCurve, OffCurve]
+        );
+        assert_eq!(contour_points(&square(0.0, 0.0, 10.0))[0].1, Line);
+    }
+
+    #[test]
+    fn test_compatible_masters() {
+        let light = master("Light", vec![square(0.0, 0.0, 100.0)]);
+        let bold = master("Bold", vec![square(0.0, 0.0, 180.0)]);
+        assert!(check_compatibility(&[light, bold], 0).is_empty());
+    }
+
+    #[test]
+    fn test_point_differences() {
+        let light = master("Light", vec![square(0.0, 0.0, 100.0)]);
+        let triangle = polygon(&[(0.0, 0.0), (100.0, 0.0), (50.0, 100.0)]);
+        assert_eq!(
+            incompatibilities(&[light.clone(), master("Bold", vec![triangle])]),
+            vec![Incompatibility::PointCount {
+                expected: 4,
+                found: 3
+            }]
+        );
+
+        let mut curved = BezPath::new();
+        curved.move_to((0.0, 0.0));
+        curved.line_to((100.0, 0.0));
+        curved.line_to((100.0, 100.0));
+        curved.quad_to((50.0, 150.0), (0.0, 100.0));
+        curved.close_path();
+        let mut lines = BezPath::new();
+        lines.move_to((0.0, 0.0));
+        lines.line_to((100.0, 0.0));
+        lines.line_to((100.0, 100.0));
+        lines.line_to((50.0, 150.0));
+        lines.line_to((0.0, 100.0));
+        lines.close_path();
+        let issues = check_compatibility(
+            &[master("Light", vec![curved]), master("Bold", vec![lines])],
+            0,
+        );
+        assert_eq!(
+            issues[0].incompatibility,
+            Incompatibility::PointType {
+                point: 3,
+                expected: PointKind::OffCurve,
+                found: PointKind::Line,
+            }
+        );
+        assert_eq!(issues[0].position, Some(Point::new(50.0, 150.0)));
+        assert_eq!(
+            issues[0].description(),
+            "contour 1 point 4: line point, expected off-curve"
+        );
+    }
+
+    #[test]
+    fn test_start_point_and_contour_order() {
+        let light = master("Light", vec![square(0.0, 0.0, 100.0)]);
+        let rotated =
+            polygon(&[(180.0, 180.0), (0.0, 180.0), (0.0, 0.0), (180.0, 0.0)]);
+        let issues =
+            check_compatibility(&[light, master("Bold", vec![rotated])], 0);
+        assert_eq!(
+            issues[0].incompatibility,
+            Incompatibility::StartPoint { expected: 2 }
+        );
+        assert_eq!(issues[0].position, Some(Point::new(180.0, 180.0)));
+
+        let dots = vec![square(0.0, 600.0, 80.0), square(200.0, 600.0, 80.0)];
+        let swapped =
+            vec![square(210.0, 600.0, 100.0), square(0.0, 600.0, 100.0)];
+        assert_eq!(
+            incompatibilities(&[
+                master("Light", dots),
+                master("Bold", swapped)
+            ]),
+            vec![
+                Incompatibility::ContourOrder { expected: 1 },
+                Incompatibility::ContourOrder { expected: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_components_and_anchors() {
+        let mut light = master("Light", Vec::new());
+        light.components = vec!["a".to_string(), "acutecomb".to_string()];
+        let mut bold = light.clone();
+        bold.components.reverse();
+        bold.anchors = vec!["bottom".to_string()];
+        bold.contours.push(square(0.0, 0.0, 10.0));
+
+        let issues = check_compatibility(&[light, bold], 0);
+        let descriptions: Vec<_> =
+            issues.iter().map(CompatibilityIssue::description).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "components acutecomb, a, expected a, acutecomb",
+                "missing anchors top; extra anchors bottom",
+                "1 contours, expected 0",
+            ]
+        );
+        assert!(issues.iter().all(|issue| issue.master == 1));
+    }
+}
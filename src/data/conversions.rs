@@ -5,9 +5,10 @@
 //! logic - serialization and deserialization between equivalent representations.
 
 use crate::core::state::{
-    ComponentData, ContourData, FontData, FontInfo, GlyphData, OutlineData, PointData,
-    PointTypeData,
+    AnchorData, ComponentData, ContourData, FontData, FontInfo, GlyphData, GuidelineData,
+    ImageData, OutlineData, PointData, PointTypeData, DEFAULT_IMAGE_OPACITY,
 };
+use kurbo::{BezPath, PathEl, Point};
 use norad::Font;
 use std::path::PathBuf;
 
@@ -25,6 +26,14 @@ impl GlyphData {
             .map(ComponentData::from_norad_component)
             .collect();
 
+        let anchors = norad_glyph.anchors.iter()
+            .map(AnchorData::from_norad_anchor)
+            .collect();
+
+        let guidelines = norad_glyph.guidelines.iter()
+            .map(GuidelineData::from_norad_guideline)
+            .collect();
+
         Self {
             name: norad_glyph.name().to_string(),
             advance_width: norad_glyph.width,
@@ -32,6 +41,8 @@ impl GlyphData {
             unicode_values: norad_glyph.codepoints.iter().collect(),
             outline,
             components,
+            anchors,
+            guidelines,
         }
     }
 
@@ -55,10 +66,151 @@ impl GlyphData {
             .map(ComponentData::to_norad_component)
             .collect();
 
+        glyph.anchors = self.anchors.iter()
+            .map(AnchorData::to_norad_anchor)
+            .collect();
+
+        glyph.guidelines = self.guidelines.iter()
+            .map(GuidelineData::to_norad_guideline)
+            .collect();
+
         glyph
     }
 }
 
+impl AnchorData {
+    /// Convert from norad anchor to our thread-safe version
+    pub fn from_norad_anchor(norad_anchor: &norad::Anchor) -> Self {
+        Self {
+            name: norad_anchor
+                .name
+                .as_ref()
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+            x: norad_anchor.x,
+            y: norad_anchor.y,
+            identifier: norad_anchor
+                .identifier()
+                .map(|identifier| identifier.as_str().to_string()),
+            color: norad_anchor.color.as_ref().map(norad::Color::channels),
+        }
+    }
+
+    /// Convert back to norad anchor
+    pub fn to_norad_anchor(&self) -> norad::Anchor {
+        // Anchors without a valid name are written unnamed
+        let name: Option<norad::Name> = self.name.parse().ok();
+        let color = self
+            .color
+            .and_then(|(r, g, b, a)| norad::Color::new(r, g, b, a).ok());
+        let identifier = self
+            .identifier
+            .as_deref()
+            .and_then(|identifier| norad::Identifier::new(identifier).ok());
+        norad::Anchor::new(self.x, self.y, name, color, identifier)
+    }
+}
+
+impl GuidelineData {
+    /// Convert from norad guideline to our thread-safe version
+    pub fn from_norad_guideline(norad_guideline: &norad::Guideline) -> Self {
+        let mut guideline = match norad_guideline.line {
+            norad::Line::Horizontal(y) => GuidelineData::horizontal(y),
+            norad::Line::Vertical(x) => GuidelineData::vertical(x),
+            norad::Line::Angle { x, y, degrees } => {
+                GuidelineData::new(x, y, degrees)
+            }
+        };
+        guideline.name = norad_guideline
+            .name
+            .as_ref()
+            .map(|name| name.to_string());
+        guideline
+    }
+
+    /// Convert back to norad guideline
+    ///
+    /// Horizontal and vertical guidelines are written in their short form.
+    pub fn to_norad_guideline(&self) -> norad::Guideline {
+        let line = if self.is_horizontal() {
+            norad::Line::Horizontal(self.y)
+        } else if self.is_vertical() {
+            norad::Line::Vertical(self.x)
+        } else {
+            norad::Line::Angle {
+                x: self.x,
+                y: self.y,
+                degrees: self.angle,
+            }
+        };
+        // Guidelines without a valid name are written unnamed
+        let name: Option<norad::Name> =
+            self.name.as_ref().and_then(|name| name.parse().ok());
+        norad::Guideline::new(line, name, None, None)
+    }
+}
+
+impl ImageData {
+    /// Convert from norad image to our thread-safe version
+    ///
+    /// The opacity is kept in the alpha of the image's color; images
+    /// without a color get the default opacity.
+    pub fn from_norad_image(norad_image: &norad::Image) -> Self {
+        let transform = &norad_image.transform;
+        let mut image =
+            ImageData::new(norad_image.file_name.to_string_lossy());
+        image.set_affine(kurbo::Affine::new([
+            transform.x_scale,
+            transform.xy_scale,
+            transform.yx_scale,
+            transform.y_scale,
+            transform.x_offset,
+            transform.y_offset,
+        ]));
+        if let Some(color) = &norad_image.color {
+            let (red, green, blue, alpha) = color.channels();
+            image.opacity = alpha;
+            image.color = Some((red, green, blue));
+        }
+        image
+    }
+
+    /// Convert back to norad image
+    ///
+    /// The opacity replaces the alpha of the image's color. Images without
+    /// a color are only given one, white, once their opacity differs from
+    /// the default. The placement is rounded to nine decimals, so images
+    /// that weren't moved are written with the transform they were read
+    /// with rather than one off by rounding errors.
+    pub fn to_norad_image(&self) -> norad::Image {
+        let [x_scale, xy_scale, yx_scale, y_scale, x_offset, y_offset] = self
+            .affine()
+            .as_coeffs()
+            .map(|coeff| (coeff * 1e9).round() / 1e9 + 0.0);
+        let transform = norad::AffineTransform {
+            x_scale,
+            xy_scale,
+            yx_scale,
+            y_scale,
+            x_offset,
+            y_offset,
+        };
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        let color = match self.color {
+            Some((red, green, blue)) => {
+                norad::Color::new(red, green, blue, opacity).ok()
+            }
+            None if opacity == DEFAULT_IMAGE_OPACITY => None,
+            None => norad::Color::new(1.0, 1.0, 1.0, opacity).ok(),
+        };
+        norad::Image {
+            file_name: PathBuf::from(&self.file_name),
+            color,
+            transform,
+        }
+    }
+}
+
 impl ComponentData {
     /// Convert from norad component to our thread-safe version
     pub fn from_norad_component(norad_component: &norad::Component) -> Self {
@@ -129,9 +281,72 @@ impl ContourData {
         // Use constructor with required arguments
         norad::Contour::new(points, None)
     }
+
+    /// Convert a single-contour BezPath into UFO-style contour points
+    ///
+    /// Closed contours follow the UFO convention: there is no move point, and
+    /// the off-curve points leading into the start point sit at the end of
+    /// the list.
+    pub fn from_bezpath(path: &BezPath) -> Self {
+        let mut points = Vec::new();
+        let mut start = None;
+        let mut closed = false;
+
+        for element in path.elements() {
+            let new_points: Vec<(Point, PointTypeData)> = match *element {
+                PathEl::MoveTo(pt) => {
+                    start = Some(pt);
+                    vec![(pt, PointTypeData::Move)]
+                }
+                PathEl::LineTo(pt) => vec![(pt, PointTypeData::Line)],
+                PathEl::QuadTo(c, pt) => vec![
+                    (c, PointTypeData::OffCurve),
+                    (pt, PointTypeData::QCurve),
+                ],
+                PathEl::CurveTo(c1, c2, pt) => vec![
+                    (c1, PointTypeData::OffCurve),
+                    (c2, PointTypeData::OffCurve),
+                    (pt, PointTypeData::Curve),
+                ],
+                PathEl::ClosePath => {
+                    closed = true;
+                    Vec::new()
+                }
+            };
+            points.extend(
+                new_points
+                    .into_iter()
+                    .map(|(pt, ty)| PointData::new(pt.x, pt.y, ty)),
+            );
+        }
+
+        if closed && !points.is_empty() {
+            let start = start.unwrap_or_default();
+            let last = &points[points.len() - 1];
+            let ends_at_start = last.point_type != PointTypeData::OffCurve
+                && (last.x - start.x).abs() < 1e-9
+                && (last.y - start.y).abs() < 1e-9;
+            if ends_at_start && points.len() > 1 {
+                // The final segment lands on the start point, so that point
+                // replaces the move and becomes the first point
+                points.remove(0);
+                points.rotate_right(1);
+            } else {
+                // Implicit closing line back to the start point
+                points[0].point_type = PointTypeData::Line;
+            }
+        }
+
+        Self { points }
+    }
 }
 
 impl PointData {
+    /// Create a new point
+    pub fn new(x: f64, y: f64, point_type: PointTypeData) -> Self {
+        Self { x, y, point_type }
+    }
+
     pub fn from_norad_point(norad_point: &norad::ContourPoint) -> Self {
         Self {
             x: norad_point.x,
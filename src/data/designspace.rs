@@ -0,0 +1,363 @@
+//! Designspace source lookup
+//!
+//! FontIR identifies masters by their normalized location, while the files
+//! that have to be written on save are listed in the `.designspace` document
+//! with locations in design coordinates. This module normalizes those source
+//! locations so edits made at a FontIR location can be routed back to the
+//! UFO (and layer) they came from.
+
+use anyhow::Result;
+use fontdrasil::coords::NormalizedLocation;
+use norad::designspace::{Axis, DesignSpaceDocument, Dimension};
+use std::path::{Path, PathBuf};
+
+/// Tolerance used when comparing normalized coordinates
+const LOCATION_EPSILON: f64 = 1e-6;
+
+/// A master (designspace source) and where it lives on disk
+#[derive(Debug, Clone)]
+pub struct MasterSource {
+    /// Display name of the source
+    pub name: String,
+    /// Path to the source UFO
+    pub ufo_path: PathBuf,
+    /// Layer within the UFO, `None` for the default layer
+    pub layer: Option<String>,
+    /// Normalized location as (axis tag, value) pairs
+    pub location: Vec<(String, f64)>,
+}
+
+impl MasterSource {
+    /// Whether this source sits at the given FontIR location
+    ///
+    /// Axes missing from either side are treated as being at their default.
+    pub fn matches(&self, location: &NormalizedLocation) -> bool {
+        let mut tags: Vec<String> =
+            self.location.iter().map(|(tag, _)| tag.clone()).collect();
+        tags.extend(location.iter().map(|(tag, _)| tag.to_string()));
+
+        tags.iter().all(|tag| {
+            let ours = self
+                .location
+                .iter()
+                .find(|(t, _)| t == tag)
+                .map_or(0.0, |(_, v)| *v);
+            let theirs = location
+                .iter()
+                .find(|(t, _)| t.to_string() == *tag)
+                .map_or(0.0, |(_, v)| v.to_f64());
+            (ours - theirs).abs() < LOCATION_EPSILON
+        })
+    }
+
+    /// Whether this source is the default master
+    pub fn is_default(&self) -> bool {
+        self.location
+            .iter()
+            .all(|(_, value)| value.abs() < LOCATION_EPSILON)
+    }
+}
+
+/// Whether two normalized locations describe the same designspace point
+///
+/// Axes missing from either location are treated as being at their default.
+pub fn locations_match(a: &NormalizedLocation, b: &NormalizedLocation) -> bool {
+    a.iter()
+        .chain(b.iter())
+        .map(|(tag, _)| tag.to_string())
+        .all(|tag| {
+            (location_value(a, &tag) - location_value(b, &tag)).abs()
+                < LOCATION_EPSILON
+        })
+}
+
+/// Value of a location on an axis, 0 when the axis is left out
+pub fn location_value(location: &NormalizedLocation, tag: &str) -> f64 {
+    location
+        .iter()
+        .find(|(t, _)| t.to_string() == tag)
+        .map_or(0.0, |(_, v)| v.to_f64())
+}
+
+/// List the masters of a font source
+///
+/// A designspace yields one entry per source; a single UFO yields itself at
+/// the default location.
+pub fn load_master_sources(source_path: &Path) -> Result<Vec<MasterSource>> {
+    if source_path.extension().and_then(|s| s.to_str()) != Some("designspace") {
+        let name = source_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        return Ok(vec![MasterSource {
+            name,
+            ufo_path: source_path.to_path_buf(),
+            layer: None,
+            location: Vec::new(),
+        }]);
+    }
+
+    let designspace = DesignSpaceDocument::load(source_path)?;
+    let designspace_dir =
+        source_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let masters = designspace
+        .sources
+        .iter()
+        .map(|source| MasterSource {
+            name: source
+                .name
+                .clone()
+                .unwrap_or_else(|| source.filename.clone()),
+            ufo_path: designspace_dir.join(&source.filename),
+            layer: source.layer.clone(),
+            location: normalized_location(&designspace.axes, &source.location),
+        })
+        .collect();
+
+    Ok(masters)
+}
+
+/// A named instance of a designspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignInstance {
+    /// Display name of the instance
+    pub name: String,
+    /// Normalized location as (axis tag, value) pairs
+    pub location: Vec<(String, f64)>,
+}
+
+/// List the instances of a font source, none for a single UFO
+pub fn load_instances(source_path: &Path) -> Result<Vec<DesignInstance>> {
+    if source_path.extension().and_then(|s| s.to_str()) != Some("designspace") {
+        return Ok(Vec::new());
+    }
+    let designspace = DesignSpaceDocument::load(source_path)?;
+    Ok(designspace
+        .instances
+        .iter()
+        .map(|instance| DesignInstance {
+            name: instance
+                .stylename
+                .clone()
+                .or_else(|| instance.name.clone())
+                .unwrap_or_default(),
+            location: normalized_location(
+                &designspace.axes,
+                &instance.location,
+            ),
+        })
+        .collect())
+}
+
+/// Normalize a location given in design coordinates, with axes left out
+/// at their default
+fn normalized_location(
+    axes: &[Axis],
+    dimensions: &[Dimension],
+) -> Vec<(String, f64)> {
+    axes.iter()
+        .map(|axis| {
+            let design_value = dimensions
+                .iter()
+                .find(|dim| dim.name == axis.name)
+                .and_then(|dim| dim.xvalue)
+                .map(f64::from)
+                .unwrap_or_else(|| map_to_design(axis, axis.default));
+            (axis.tag.clone(), normalize_design_value(axis, design_value))
+        })
+        .collect()
+}
+
+/// A designspace axis, with its range in user coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignAxis {
+    pub name: String,
+    pub tag: String,
+    pub minimum: f64,
+    pub default: f64,
+    pub maximum: f64,
+    /// Axis map as (user, design) pairs, empty when the two are the same
+    pub map: Vec<(f64, f64)>,
+}
+
+impl DesignAxis {
+    /// Normalized coordinate of a user-space value
+    pub fn normalize(&self, user_value: f64) -> f64 {
+        normalize(
+            self.to_design(user_value),
+            self.to_design(self.minimum),
+            self.to_design(self.default),
+            self.to_design(self.maximum),
+        )
+    }
+
+    /// Normalized coordinates of the ends of the axis: -1 or 0 and 0 or 1
+    pub fn normalized_range(&self) -> (f64, f64) {
+        let minimum = if self.minimum < self.default {
+            -1.0
+        } else {
+            0.0
+        };
+        let maximum = if self.maximum > self.default {
+            1.0
+        } else {
+            0.0
+        };
+        (minimum, maximum)
+    }
+
+    /// User-space value of a normalized coordinate
+    pub fn user_value(&self, normalized: f64) -> f64 {
+        let default = self.to_design(self.default);
+        let design_value = if normalized < 0.0 {
+            default + normalized * (default - self.to_design(self.minimum))
+        } else {
+            default + normalized * (self.to_design(self.maximum) - default)
+        };
+        if self.map.is_empty() {
+            return design_value;
+        }
+        let inverse: Vec<(f64, f64)> = self
+            .map
+            .iter()
+            .map(|&(user, design)| (design, user))
+            .collect();
+        piecewise_linear(&inverse, design_value)
+    }
+
+    fn to_design(&self, user_value: f64) -> f64 {
+        if self.map.is_empty() {
+            user_value
+        } else {
+            piecewise_linear(&self.map, user_value)
+        }
+    }
+}
+
+impl From<&Axis> for DesignAxis {
+    fn from(axis: &Axis) -> Self {
+        Self {
+            name: axis.name.clone(),
+            tag: axis.tag.clone(),
+            minimum: f64::from(axis.minimum.unwrap_or(axis.default)),
+            default: f64::from(axis.default),
+            maximum: f64::from(axis.maximum.unwrap_or(axis.default)),
+            map: axis
+                .map
+                .iter()
+                .flatten()
+                .map(|m| (f64::from(m.input), f64::from(m.output)))
+                .collect(),
+        }
+    }
+}
+
+/// List the axes of a font source, none for a single UFO
+pub fn load_axes(source_path: &Path) -> Result<Vec<DesignAxis>> {
+    if source_path.extension().and_then(|s| s.to_str()) != Some("designspace") {
+        return Ok(Vec::new());
+    }
+    let designspace = DesignSpaceDocument::load(source_path)?;
+    Ok(designspace.axes.iter().map(DesignAxis::from).collect())
+}
+
+/// Normalize a design-space coordinate to the -1..=1 range of an axis
+pub fn normalize_design_value(axis: &Axis, design_value: f64) -> f64 {
+    let default = map_to_design(axis, axis.default);
+    let minimum = map_to_design(axis, axis.minimum.unwrap_or(axis.default));
+    let maximum = map_to_design(axis, axis.maximum.unwrap_or(axis.default));
+    normalize(design_value, minimum, default, maximum)
+}
+
+/// Map a user-space coordinate to design space through the axis map
+pub fn map_to_design(axis: &Axis, user_value: f32) -> f64 {
+    let user_value = f64::from(user_value);
+    let Some(map) = axis.map.as_ref().filter(|map| !map.is_empty()) else {
+        return user_value;
+    };
+    let points: Vec<(f64, f64)> = map
+        .iter()
+        .map(|m| (f64::from(m.input), f64::from(m.output)))
+        .collect();
+    piecewise_linear(&points, user_value)
+}
+
+/// Normalize a value against an axis' minimum, default and maximum
+fn normalize(value: f64, minimum: f64, default: f64, maximum: f64) -> f64 {
+    let normalized = if value < default {
+        if default > minimum {
+            (value - default) / (default - minimum)
+        } else {
+            0.0
+        }
+    } else if maximum > default {
+        (value - default) / (maximum - default)
+    } else {
+        0.0
+    };
+    normalized.clamp(-1.0, 1.0)
+}
+
+/// Interpolate through sorted (input, output) pairs, clamping at the ends
+fn piecewise_linear(points: &[(f64, f64)], value: f64) -> f64 {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (first, last) = (sorted[0], sorted[sorted.len() - 1]);
+    if value <= first.0 {
+        return first.1;
+    }
+    if value >= last.0 {
+        return last.1;
+    }
+    sorted
+        .windows(2)
+        .find(|pair| value >= pair[0].0 && value <= pair[1].0)
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            let t = (value - a.0) / (b.0 - a.0);
+            a.1 + (b.1 - a.1) * t
+        })
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(400.0, 100.0, 400.0, 900.0), 0.0);
+        assert_eq!(normalize(900.0, 100.0, 400.0, 900.0), 1.0);
+        assert_eq!(normalize(100.0, 100.0, 400.0, 900.0), -1.0);
+        assert_eq!(normalize(650.0, 100.0, 400.0, 900.0), 0.5);
+    }
+
+    #[test]
+    fn test_piecewise_linear() {
+        let map = [(100.0, 20.0), (400.0, 80.0), (900.0, 200.0)];
+        assert_eq!(piecewise_linear(&map, 400.0), 80.0);
+        assert_eq!(piecewise_linear(&map, 650.0), 140.0);
+        assert_eq!(piecewise_linear(&map, 50.0), 20.0);
+    }
+
+    #[test]
+    fn test_design_axis() {
+        let mut axis = DesignAxis {
+            name: "Weight".to_string(),
+            tag: "wght".to_string(),
+            minimum: 100.0,
+            default: 400.0,
+            maximum: 900.0,
+            map: Vec::new(),
+        };
+        assert_eq!(axis.normalize(650.0), 0.5);
+        assert_eq!(axis.user_value(-1.0), 100.0);
+        assert_eq!(axis.user_value(0.5), 650.0);
+
+        axis.map = vec![(100.0, 20.0), (400.0, 80.0), (900.0, 200.0)];
+        assert_eq!(axis.normalize(650.0), 0.5);
+        assert_eq!(axis.user_value(0.5), 650.0);
+        assert_eq!(axis.user_value(0.25), 525.0);
+    }
+}
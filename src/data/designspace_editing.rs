@@ -0,0 +1,1009 @@
+//! Designspace document editing
+//!
+//! Edits made in the designspace pane to the axes, sources, instances and
+//! rules of a `.designspace` document. Every edit is checked before the
+//! document is touched, so a rejected edit leaves it as it was.
+//!
+//! Fields are read and written as text:
+//! - locations as `wght=400 wdth=100`, in design coordinates
+//! - axis maps as `user:design` pairs, e.g. `100:20 400:80 900:200`
+//! - rule conditions as `wght=600..900 wdth=..80`, with condition sets
+//!   separated by `|`
+//! - substitutions as `dollar>dollar.rvrn cent>cent.rvrn`
+
+use crate::data::designspace::map_to_design;
+use anyhow::{anyhow, bail, Result};
+use norad::designspace::{
+    Axis, AxisMapping, Condition, ConditionSet, DesignSpaceDocument, Dimension,
+    Instance, Rule, Source, Substitution,
+};
+use std::path::Path;
+
+/// Tolerance used when comparing design coordinates
+const DESIGN_EPSILON: f64 = 1e-3;
+
+/// An editable field of a designspace document, with the index of its axis,
+/// source, instance or rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DesignspaceField {
+    AxisName(usize),
+    AxisTag(usize),
+    AxisMinimum(usize),
+    AxisDefault(usize),
+    AxisMaximum(usize),
+    AxisMap(usize),
+    SourceName(usize),
+    SourceLocation(usize),
+    InstanceName(usize),
+    InstanceLocation(usize),
+    RuleName(usize),
+    RuleConditions(usize),
+    RuleSubstitutions(usize),
+}
+
+impl DesignspaceField {
+    /// Text of the field, empty when its axis, source, instance or rule is
+    /// gone
+    pub fn text(self, document: &DesignSpaceDocument) -> String {
+        use DesignspaceField::*;
+        let axis = |index: usize| document.axes.get(index);
+        let source = |index: usize| document.sources.get(index);
+        let instance = |index: usize| document.instances.get(index);
+        let rule = |index: usize| document.rules.rules.get(index);
+        let text = match self {
+            AxisName(i) => axis(i).map(|axis| axis.name.clone()),
+            AxisTag(i) => axis(i).map(|axis| axis.tag.clone()),
+            AxisMinimum(i) => axis(i)
+                .map(|axis| axis.minimum.unwrap_or(axis.default).to_string()),
+            AxisDefault(i) => axis(i).map(|axis| axis.default.to_string()),
+            AxisMaximum(i) => axis(i)
+                .map(|axis| axis.maximum.unwrap_or(axis.default).to_string()),
+            AxisMap(i) => axis(i).map(format_axis_map),
+            SourceName(i) => source(i).map(source_name),
+            SourceLocation(i) => source(i)
+                .map(|source| format_location(document, &source.location)),
+            InstanceName(i) => instance(i).map(instance_name),
+            InstanceLocation(i) => instance(i)
+                .map(|instance| format_location(document, &instance.location)),
+            RuleName(i) => {
+                rule(i).map(|rule| rule.name.clone().unwrap_or_default())
+            }
+            RuleConditions(i) => {
+                rule(i).map(|rule| format_conditions(document, rule))
+            }
+            RuleSubstitutions(i) => rule(i).map(format_substitutions),
+        };
+        text.unwrap_or_default()
+    }
+
+    /// Set the field from text
+    pub fn set(
+        self,
+        document: &mut DesignSpaceDocument,
+        text: &str,
+    ) -> Result<()> {
+        use DesignspaceField::*;
+        let text = text.trim();
+        match self {
+            AxisName(i) => rename_axis(document, i, text),
+            AxisTag(i) => set_axis_tag(document, i, text),
+            AxisMinimum(i) | AxisDefault(i) | AxisMaximum(i) => {
+                let value = parse_number(text)?;
+                let axis = axis_mut(document, i)?;
+                let mut range = (
+                    axis.minimum.unwrap_or(axis.default),
+                    axis.default,
+                    axis.maximum.unwrap_or(axis.default),
+                );
+                match self {
+                    AxisMinimum(_) => range.0 = value,
+                    AxisDefault(_) => range.1 = value,
+                    _ => range.2 = value,
+                }
+                if !(range.0 <= range.1 && range.1 <= range.2) {
+                    bail!("The axis default has to lie between its minimum and maximum");
+                }
+                (axis.minimum, axis.default, axis.maximum) =
+                    (Some(range.0), range.1, Some(range.2));
+                Ok(())
+            }
+            AxisMap(i) => {
+                let map = parse_axis_map(text)?;
+                axis_mut(document, i)?.map = map;
+                Ok(())
+            }
+            SourceName(i) => {
+                let source = document
+                    .sources
+                    .get_mut(i)
+                    .ok_or_else(|| anyhow!("No source {}", i + 1))?;
+                source.stylename = non_empty(text, "Sources")?;
+                Ok(())
+            }
+            SourceLocation(i) => {
+                let location = parse_location(document, text)?;
+                let Some(source) = document.sources.get(i) else {
+                    bail!("No source {}", i + 1);
+                };
+                check_source_location(
+                    document,
+                    &location,
+                    source.layer.as_deref(),
+                    Some(i),
+                )?;
+                document.sources[i].location = location;
+                Ok(())
+            }
+            InstanceName(i) => {
+                let name = non_empty(text, "Instances")?;
+                let instance = document
+                    .instances
+                    .get_mut(i)
+                    .ok_or_else(|| anyhow!("No instance {}", i + 1))?;
+                instance.stylename = name;
+                Ok(())
+            }
+            InstanceLocation(i) => {
+                let location = parse_location(document, text)?;
+                let instance = document
+                    .instances
+                    .get_mut(i)
+                    .ok_or_else(|| anyhow!("No instance {}", i + 1))?;
+                instance.location = location;
+                Ok(())
+            }
+            RuleName(i) => {
+                let name = non_empty(text, "Rules")?;
+                rule_mut(document, i)?.name = name;
+                Ok(())
+            }
+            RuleConditions(i) => {
+                let condition_sets = parse_conditions(document, text)?;
+                rule_mut(document, i)?.condition_sets = condition_sets;
+                Ok(())
+            }
+            RuleSubstitutions(i) => {
+                let substitutions = parse_substitutions(text)?;
+                rule_mut(document, i)?.substitutions = substitutions;
+                Ok(())
+            }
+        }
+    }
+}
+
+// ============================================================================
+// AXES
+// ============================================================================
+
+/// Add an axis running from 0 to 100 with its default at 0, returning its
+/// index
+///
+/// Sources and instances sit at the default of the new axis until they are
+/// given a location on it.
+pub fn add_axis(document: &mut DesignSpaceDocument) -> usize {
+    let number = (1..)
+        .find(|n| {
+            let (name, tag) = (format!("Axis {n}"), format!("AX{n:02}"));
+            !document
+                .axes
+                .iter()
+                .any(|axis| axis.name == name || axis.tag == tag)
+        })
+        .unwrap_or_default();
+    document.axes.push(Axis {
+        name: format!("Axis {number}"),
+        tag: format!("AX{number:02}"),
+        default: 0.0,
+        minimum: Some(0.0),
+        maximum: Some(100.0),
+        ..Default::default()
+    });
+    document.axes.len() - 1
+}
+
+/// Remove an axis, along with its coordinates in locations and its rule
+/// conditions
+///
+/// Refused while sources sit off the axis' default, as they would collapse
+/// onto other sources.
+pub fn remove_axis(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+) -> Result<()> {
+    let axis = document
+        .axes
+        .get(index)
+        .ok_or_else(|| anyhow!("No axis {}", index + 1))?;
+    let default = map_to_design(axis, axis.default);
+    if let Some(source) = document.sources.iter().find(|source| {
+        (dimension_value(axis, &source.location) - default).abs()
+            > DESIGN_EPSILON
+    }) {
+        bail!(
+            "Source '{}' sits off the default of {}, remove or move it first",
+            source_name(source),
+            axis.name
+        );
+    }
+
+    let name = document.axes.remove(index).name;
+    for source in &mut document.sources {
+        source.location.retain(|dimension| dimension.name != name);
+    }
+    for instance in &mut document.instances {
+        instance.location.retain(|dimension| dimension.name != name);
+    }
+    for rule in &mut document.rules.rules {
+        for condition_set in &mut rule.condition_sets {
+            condition_set
+                .conditions
+                .retain(|condition| condition.name != name);
+        }
+        rule.condition_sets
+            .retain(|condition_set| !condition_set.conditions.is_empty());
+    }
+    Ok(())
+}
+
+fn axis_mut(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+) -> Result<&mut Axis> {
+    document
+        .axes
+        .get_mut(index)
+        .ok_or_else(|| anyhow!("No axis {}", index + 1))
+}
+
+/// Rename an axis, and the locations and conditions that refer to it
+fn rename_axis(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+    name: &str,
+) -> Result<()> {
+    if name.is_empty() {
+        bail!("Axes need a name");
+    }
+    if document
+        .axes
+        .iter()
+        .enumerate()
+        .any(|(other, axis)| other != index && axis.name == name)
+    {
+        bail!("There already is an axis named '{}'", name);
+    }
+    let old_name = std::mem::replace(
+        &mut axis_mut(document, index)?.name,
+        name.to_string(),
+    );
+
+    let locations = document
+        .sources
+        .iter_mut()
+        .map(|source| &mut source.location)
+        .chain(
+            document
+                .instances
+                .iter_mut()
+                .map(|instance| &mut instance.location),
+        );
+    for location in locations {
+        for dimension in location.iter_mut().filter(|d| d.name == old_name) {
+            dimension.name = name.to_string();
+        }
+    }
+    for condition in document
+        .rules
+        .rules
+        .iter_mut()
+        .flat_map(|rule| &mut rule.condition_sets)
+        .flat_map(|condition_set| &mut condition_set.conditions)
+        .filter(|condition| condition.name == old_name)
+    {
+        condition.name = name.to_string();
+    }
+    Ok(())
+}
+
+fn set_axis_tag(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+    tag: &str,
+) -> Result<()> {
+    if tag.is_empty()
+        || tag.len() > 4
+        || !tag.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        bail!("Axis tags are one to four letters or digits");
+    }
+    if document
+        .axes
+        .iter()
+        .enumerate()
+        .any(|(other, axis)| other != index && axis.tag == tag)
+    {
+        bail!("There already is an axis tagged '{}'", tag);
+    }
+    axis_mut(document, index)?.tag = tag.to_string();
+    Ok(())
+}
+
+fn format_axis_map(axis: &Axis) -> String {
+    axis.map
+        .iter()
+        .flatten()
+        .map(|mapping| format!("{}:{}", mapping.input, mapping.output))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse `user:design` pairs, `None` for an empty map
+fn parse_axis_map(text: &str) -> Result<Option<Vec<AxisMapping>>> {
+    let mut map = text
+        .split_whitespace()
+        .map(|pair| {
+            let (input, output) = pair.split_once(':').ok_or_else(|| {
+                anyhow!("Write axis maps as user:design pairs")
+            })?;
+            Ok(AxisMapping {
+                input: parse_number(input)?,
+                output: parse_number(output)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if map.is_empty() {
+        return Ok(None);
+    }
+    map.sort_by(|a, b| a.input.total_cmp(&b.input));
+    if map.windows(2).any(|pair| {
+        pair[0].input == pair[1].input || pair[0].output > pair[1].output
+    }) {
+        bail!("Axis maps have to keep increasing");
+    }
+    Ok(Some(map))
+}
+
+// ============================================================================
+// SOURCES
+// ============================================================================
+
+/// A new source at a location, with a style name and UFO filename that
+/// aren't used yet
+///
+/// The UFO file is named after `file_stem`, and doesn't exist in the
+/// designspace's directory.
+pub fn new_source(
+    document: &DesignSpaceDocument,
+    designspace_dir: &Path,
+    file_stem: &str,
+    location: Vec<Dimension>,
+) -> Source {
+    let number = (document.sources.len() + 1..)
+        .find(|n| {
+            let style_name = format!("Source {n}");
+            let filename = format!("{file_stem}-Source{n}.ufo");
+            !designspace_dir.join(&filename).exists()
+                && !document.sources.iter().any(|source| {
+                    source.filename == filename
+                        || source.stylename.as_deref()
+                            == Some(style_name.as_str())
+                })
+        })
+        .unwrap_or_default();
+    Source {
+        familyname: document
+            .sources
+            .iter()
+            .find_map(|source| source.familyname.clone()),
+        stylename: Some(format!("Source {number}")),
+        filename: format!("{file_stem}-Source{number}.ufo"),
+        location,
+        ..Default::default()
+    }
+}
+
+/// A sparse source at a location: a layer in the UFO of a full source,
+/// named after the location in braces like `{550, 100}`
+///
+/// Sparse sources hold only the glyphs that need a master there, such as
+/// intermediate masters.
+pub fn new_sparse_source(
+    document: &DesignSpaceDocument,
+    filename: &str,
+    location: Vec<Dimension>,
+) -> Source {
+    let values: Vec<String> = location
+        .iter()
+        .map(|dimension| dimension.xvalue.unwrap_or_default().to_string())
+        .collect();
+    let layer = format!("{{{}}}", values.join(", "));
+    Source {
+        familyname: document
+            .sources
+            .iter()
+            .find_map(|source| source.familyname.clone()),
+        stylename: Some(layer.clone()),
+        filename: filename.to_string(),
+        layer: Some(layer),
+        location,
+        ..Default::default()
+    }
+}
+
+/// Add a source, unless another source of the same layer sits at its
+/// location
+pub fn add_source(
+    document: &mut DesignSpaceDocument,
+    source: Source,
+) -> Result<()> {
+    check_source_location(
+        document,
+        &source.location,
+        source.layer.as_deref(),
+        None,
+    )?;
+    document.sources.push(source);
+    Ok(())
+}
+
+/// Remove a source from the document; its UFO stays on disk
+///
+/// Refused for the only full source at the default location.
+pub fn remove_source(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+) -> Result<()> {
+    let source = document
+        .sources
+        .get(index)
+        .ok_or_else(|| anyhow!("No source {}", index + 1))?;
+    let is_default = |source: &Source| {
+        source.layer.is_none()
+            && is_default_location(document, &source.location)
+    };
+    if is_default(source)
+        && !document
+            .sources
+            .iter()
+            .enumerate()
+            .any(|(other, source)| other != index && is_default(source))
+    {
+        bail!("The default source can't be removed");
+    }
+    document.sources.remove(index);
+    Ok(())
+}
+
+/// Display name of a source
+pub fn source_name(source: &Source) -> String {
+    source
+        .stylename
+        .clone()
+        .or_else(|| source.name.clone())
+        .unwrap_or_else(|| source.filename.clone())
+}
+
+/// Fails when another source of the same layer sits at a location
+fn check_source_location(
+    document: &DesignSpaceDocument,
+    location: &[Dimension],
+    layer: Option<&str>,
+    skip: Option<usize>,
+) -> Result<()> {
+    let taken = document.sources.iter().enumerate().find(|(index, source)| {
+        Some(*index) != skip
+            && source.layer.as_deref() == layer
+            && same_location(document, &source.location, location)
+    });
+    if let Some((_, source)) = taken {
+        bail!("Source '{}' already sits there", source_name(source));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// INSTANCES
+// ============================================================================
+
+/// Add an instance at a location, with a style name not used yet,
+/// returning its index
+pub fn add_instance(
+    document: &mut DesignSpaceDocument,
+    location: Vec<Dimension>,
+) -> usize {
+    let number = (document.instances.len() + 1..)
+        .find(|n| {
+            let style_name = format!("Instance {n}");
+            !document.instances.iter().any(|instance| {
+                instance.stylename.as_deref() == Some(style_name.as_str())
+            })
+        })
+        .unwrap_or_default();
+    document.instances.push(Instance {
+        familyname: document
+            .sources
+            .iter()
+            .find_map(|source| source.familyname.clone()),
+        stylename: Some(format!("Instance {number}")),
+        location,
+        ..Default::default()
+    });
+    document.instances.len() - 1
+}
+
+/// Remove an instance
+pub fn remove_instance(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+) -> Result<()> {
+    if index >= document.instances.len() {
+        bail!("No instance {}", index + 1);
+    }
+    document.instances.remove(index);
+    Ok(())
+}
+
+/// Display name of an instance
+pub fn instance_name(instance: &Instance) -> String {
+    instance
+        .stylename
+        .clone()
+        .or_else(|| instance.name.clone())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// RULES
+// ============================================================================
+
+/// Add a rule without conditions or substitutions, returning its index
+pub fn add_rule(document: &mut DesignSpaceDocument) -> usize {
+    let rules = &mut document.rules.rules;
+    let number = (rules.len() + 1..)
+        .find(|n| {
+            let name = format!("Rule {n}");
+            !rules
+                .iter()
+                .any(|rule| rule.name.as_deref() == Some(name.as_str()))
+        })
+        .unwrap_or_default();
+    rules.push(Rule {
+        name: Some(format!("Rule {number}")),
+        ..Default::default()
+    });
+    rules.len() - 1
+}
+
+/// Remove a rule
+pub fn remove_rule(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+) -> Result<()> {
+    if index >= document.rules.rules.len() {
+        bail!("No rule {}", index + 1);
+    }
+    document.rules.rules.remove(index);
+    Ok(())
+}
+
+fn rule_mut(
+    document: &mut DesignSpaceDocument,
+    index: usize,
+) -> Result<&mut Rule> {
+    document
+        .rules
+        .rules
+        .get_mut(index)
+        .ok_or_else(|| anyhow!("No rule {}", index + 1))
+}
+
+fn format_conditions(document: &DesignSpaceDocument, rule: &Rule) -> String {
+    rule.condition_sets
+        .iter()
+        .map(|condition_set| {
+            condition_set
+                .conditions
+                .iter()
+                .map(|condition| {
+                    let tag = document
+                        .axes
+                        .iter()
+                        .find(|axis| axis.name == condition.name)
+                        .map_or(condition.name.as_str(), |axis| {
+                            axis.tag.as_str()
+                        });
+                    let bound = |value: Option<f32>| {
+                        value.map(|value| value.to_string()).unwrap_or_default()
+                    };
+                    format!(
+                        "{}={}..{}",
+                        tag,
+                        bound(condition.minimum),
+                        bound(condition.maximum)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Parse condition sets of `tag=minimum..maximum` conditions, where either
+/// bound may be left out
+fn parse_conditions(
+    document: &DesignSpaceDocument,
+    text: &str,
+) -> Result<Vec<ConditionSet>> {
+    text.split('|')
+        .filter(|condition_set| !condition_set.trim().is_empty())
+        .map(|condition_set| {
+            let conditions = condition_set
+                .split_whitespace()
+                .map(|condition| {
+                    let (tag, range) = condition
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("Write conditions as tag=minimum..maximum"))?;
+                    let (minimum, maximum) = range
+                        .split_once("..")
+                        .ok_or_else(|| anyhow!("Write conditions as tag=minimum..maximum"))?;
+                    let bound = |text: &str| {
+                        (!text.is_empty()).then(|| parse_number(text)).transpose()
+                    };
+                    let (minimum, maximum) = (bound(minimum)?, bound(maximum)?);
+                    match (minimum, maximum) {
+                        (None, None) => bail!("Condition on {} needs a minimum or maximum", tag),
+                        (Some(minimum), Some(maximum)) if minimum > maximum => {
+                            bail!("Condition on {} has its minimum above its maximum", tag)
+                        }
+                        _ => {}
+                    }
+                    Ok(Condition {
+                        name: axis_by_tag(document, tag)?.name.clone(),
+                        minimum,
+                        maximum,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(ConditionSet { conditions })
+        })
+        .collect()
+}
+
+fn format_substitutions(rule: &Rule) -> String {
+    rule.substitutions
+        .iter()
+        .map(|substitution| {
+            format!("{}>{}", substitution.name, substitution.with)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse `glyph>replacement` pairs
+fn parse_substitutions(text: &str) -> Result<Vec<Substitution>> {
+    text.split_whitespace()
+        .map(|pair| match pair.split_once('>') {
+            Some((name, with)) if !name.is_empty() && !with.is_empty() => {
+                Ok(Substitution {
+                    name: name.to_string(),
+                    with: with.to_string(),
+                })
+            }
+            _ => bail!("Write substitutions as glyph>replacement"),
+        })
+        .collect()
+}
+
+// ============================================================================
+// LOCATIONS
+// ============================================================================
+
+/// Design location of a normalized location given as (axis tag, value)
+/// pairs, with axes left out at their default
+pub fn design_location(
+    document: &DesignSpaceDocument,
+    normalized: &[(String, f64)],
+) -> Vec<Dimension> {
+    document
+        .axes
+        .iter()
+        .map(|axis| {
+            let (minimum, default, maximum) = design_range(axis);
+            let value = normalized
+                .iter()
+                .find(|(tag, _)| *tag == axis.tag)
+                .map_or(0.0, |(_, value)| *value);
+            let design_value = if value < 0.0 {
+                default + value * (default - minimum)
+            } else {
+                default + value * (maximum - default)
+            };
+            Dimension {
+                name: axis.name.clone(),
+                xvalue: Some(design_value as f32),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn format_location(
+    document: &DesignSpaceDocument,
+    location: &[Dimension],
+) -> String {
+    document
+        .axes
+        .iter()
+        .map(|axis| {
+            format!("{}={}", axis.tag, dimension_value(axis, location) as f32)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a location of `tag=value` pairs in design coordinates, with axes
+/// left out at their default
+fn parse_location(
+    document: &DesignSpaceDocument,
+    text: &str,
+) -> Result<Vec<Dimension>> {
+    let mut values: Vec<(&Axis, f32)> = Vec::new();
+    for pair in text.split_whitespace() {
+        let (tag, value) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Write locations as tag=value"))?;
+        let axis = axis_by_tag(document, tag)?;
+        let value = parse_number(value)?;
+        let (minimum, _, maximum) = design_range(axis);
+        if f64::from(value) < minimum - DESIGN_EPSILON
+            || f64::from(value) > maximum + DESIGN_EPSILON
+        {
+            bail!(
+                "{}={} is outside the axis, {}..{}",
+                tag,
+                value,
+                minimum,
+                maximum
+            );
+        }
+        if values.iter().any(|(other, _)| other.tag == axis.tag) {
+            bail!("{} is given twice", tag);
+        }
+        values.push((axis, value));
+    }
+
+    Ok(document
+        .axes
+        .iter()
+        .map(|axis| {
+            let value = values
+                .iter()
+                .find(|(other, _)| other.tag == axis.tag)
+                .map_or_else(
+                    || map_to_design(axis, axis.default) as f32,
+                    |(_, value)| *value,
+                );
+            Dimension {
+                name: axis.name.clone(),
+                xvalue: Some(value),
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+/// Design coordinate of a location on an axis, its default when left out
+fn dimension_value(axis: &Axis, location: &[Dimension]) -> f64 {
+    location
+        .iter()
+        .find(|dimension| dimension.name == axis.name)
+        .and_then(|dimension| dimension.xvalue)
+        .map_or_else(|| map_to_design(axis, axis.default), f64::from)
+}
+
+fn same_location(
+    document: &DesignSpaceDocument,
+    a: &[Dimension],
+    b: &[Dimension],
+) -> bool {
+    document.axes.iter().all(|axis| {
+        (dimension_value(axis, a) - dimension_value(axis, b)).abs()
+            < DESIGN_EPSILON
+    })
+}
+
+fn is_default_location(
+    document: &DesignSpaceDocument,
+    location: &[Dimension],
+) -> bool {
+    same_location(document, location, &[])
+}
+
+/// Minimum, default and maximum of an axis in design coordinates
+fn design_range(axis: &Axis) -> (f64, f64, f64) {
+    (
+        map_to_design(axis, axis.minimum.unwrap_or(axis.default)),
+        map_to_design(axis, axis.default),
+        map_to_design(axis, axis.maximum.unwrap_or(axis.default)),
+    )
+}
+
+fn axis_by_tag<'a>(
+    document: &'a DesignSpaceDocument,
+    tag: &str,
+) -> Result<&'a Axis> {
+    document
+        .axes
+        .iter()
+        .find(|axis| axis.tag == tag)
+        .ok_or_else(|| anyhow!("There is no axis tagged '{}'", tag))
+}
+
+fn parse_number(text: &str) -> Result<f32> {
+    let text = text.trim();
+    text.parse()
+        .map_err(|_| anyhow!("'{}' is not a number", text))
+}
+
+/// The text as a name, failing when it's empty
+fn non_empty(text: &str, kind: &str) -> Result<Option<String>> {
+    if text.is_empty() {
+        bail!("{} need a name", kind);
+    }
+    Ok(Some(text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight_axis() -> Axis {
+        Axis {
+            name: "Weight".to_string(),
+            tag: "wght".to_string(),
+            default: 400.0,
+            minimum: Some(100.0),
+            maximum: Some(900.0),
+            ..Default::default()
+        }
+    }
+
+    fn source(style_name: &str, weight: f32) -> Source {
+        Source {
+            stylename: Some(style_name.to_string()),
+            filename: format!("Test-{style_name}.ufo"),
+            location: vec![Dimension {
+                name: "Weight".to_string(),
+                xvalue: Some(weight),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn document() -> DesignSpaceDocument {
+        DesignSpaceDocument {
+            format: 5.0,
+            axes: vec![weight_axis()],
+            sources: vec![source("Regular", 400.0), source("Bold", 900.0)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_locations() {
+        let mut document = document();
+        let location = DesignspaceField::SourceLocation(1);
+        assert_eq!(location.text(&document), "wght=900");
+
+        add_axis(&mut document);
+        assert_eq!(location.text(&document), "wght=900 AX01=0");
+        assert!(location.set(&mut document, "wght=400").is_err());
+        assert!(location.set(&mut document, "wght=1000").is_err());
+        assert!(location.set(&mut document, "opsz=12").is_err());
+        location.set(&mut document, "wght=700 AX01=50").unwrap();
+        assert_eq!(location.text(&document), "wght=700 AX01=50");
+
+        let design = design_location(
+            &document,
+            &[("wght".to_string(), -0.5), ("AX01".to_string(), 1.0)],
+        );
+        assert_eq!(design[0].xvalue, Some(250.0));
+        assert_eq!(design[1].xvalue, Some(100.0));
+    }
+
+    #[test]
+    fn test_axes() {
+        let mut document = document();
+        assert!(DesignspaceField::AxisDefault(0)
+            .set(&mut document, "950")
+            .is_err());
+        DesignspaceField::AxisMinimum(0)
+            .set(&mut document, "200")
+            .unwrap();
+        assert_eq!(document.axes[0].minimum, Some(200.0));
+
+        let map = DesignspaceField::AxisMap(0);
+        assert!(map.set(&mut document, "200:80 400:60").is_err());
+        map.set(&mut document, "900:200 200:20 400:80").unwrap();
+        assert_eq!(map.text(&document), "200:20 400:80 900:200");
+        map.set(&mut document, "").unwrap();
+        assert_eq!(document.axes[0].map, None);
+
+        DesignspaceField::AxisName(0)
+            .set(&mut document, "Boldness")
+            .unwrap();
+        assert_eq!(document.sources[1].location[0].name, "Boldness");
+        assert!(DesignspaceField::AxisTag(0)
+            .set(&mut document, "toolong")
+            .is_err());
+    }
+
+    #[test]
+    fn test_remove_axis() {
+        let mut document = document();
+        let index = add_axis(&mut document);
+        document.rules.rules.push(Rule {
+            condition_sets: vec![ConditionSet {
+                conditions: vec![Condition {
+                    name: "Axis 1".to_string(),
+                    minimum: Some(50.0),
+                    maximum: None,
+                }],
+            }],
+            ..Default::default()
+        });
+        assert!(remove_axis(&mut document, 0).is_err());
+        remove_axis(&mut document, index).unwrap();
+        assert_eq!(document.axes.len(), 1);
+        assert!(document.rules.rules[0].condition_sets.is_empty());
+    }
+
+    #[test]
+    fn test_sources() {
+        let mut document = document();
+        let location =
+            design_location(&document, &[("wght".to_string(), -1.0)]);
+        let light =
+            new_source(&document, Path::new("/nonexistent"), "Test", location);
+        assert_eq!(light.filename, "Test-Source3.ufo");
+        add_source(&mut document, light.clone()).unwrap();
+        assert!(add_source(&mut document, light).is_err());
+
+        assert!(remove_source(&mut document, 0).is_err());
+        remove_source(&mut document, 1).unwrap();
+        assert_eq!(document.sources.len(), 2);
+
+        let location = design_location(&document, &[("wght".to_string(), 0.5)]);
+        let sparse = new_sparse_source(&document, "Test-Regular.ufo", location);
+        assert_eq!(sparse.layer.as_deref(), Some("{650}"));
+        add_source(&mut document, sparse).unwrap();
+    }
+
+    #[test]
+    fn test_rules() {
+        let mut document = document();
+        let index = add_rule(&mut document);
+        assert_eq!(DesignspaceField::RuleName(index).text(&document), "Rule 1");
+
+        let conditions = DesignspaceField::RuleConditions(index);
+        conditions
+            .set(&mut document, "wght=600..900 | wght=..200")
+            .unwrap();
+        assert_eq!(
+            document.rules.rules[0].condition_sets[1].conditions[0].name,
+            "Weight"
+        );
+        assert_eq!(conditions.text(&document), "wght=600..900 | wght=..200");
+        assert!(conditions.set(&mut document, "wght=..").is_err());
+        assert!(conditions.set(&mut document, "wght=900..600").is_err());
+
+        let substitutions = DesignspaceField::RuleSubstitutions(index);
+        substitutions
+            .set(&mut document, "dollar>dollar.rvrn cent>cent.rvrn")
+            .unwrap();
+        assert_eq!(document.rules.rules[0].substitutions.len(), 2);
+        assert!(substitutions.set(&mut document, "dollar>").is_err());
+    }
+}
@@ -0,0 +1,598 @@
+//! Composite glyph construction from recipes
+//!
+//! A recipe describes a composite glyph as a list of components, one per
+//! line in a recipe file:
+//!
+//! ```text
+//! # name = components | codepoints
+//! aacute = a + acutecomb@top | 00E1
+//! ecircumflexacute = e + circumflexcomb@top + acutecomb@top
+//! f_f = f + f
+//! ```
+//!
+//! Components without an anchor are bases, set side by side. Marks attach to
+//! the most recently placed component that has the anchor they ask for, so
+//! marks stack onto earlier marks. A mark without an explicit anchor attaches
+//! through its first `_name` anchor that has a counterpart. Recipes for the
+//! accented Latin letters can also be derived from Unicode canonical
+//! decomposition.
+
+use crate::core::state::{AnchorData, ComponentData};
+use anyhow::{anyhow, bail, Result};
+
+/// Glyph names for the combining marks used by `CANONICAL_DECOMPOSITIONS`
+const MARK_GLYPH_NAMES: &[(char, &str)] = &[
+    ('\u{0300}', "gravecomb"),
+    ('\u{0301}', "acutecomb"),
+    ('\u{0302}', "circumflexcomb"),
+    ('\u{0303}', "tildecomb"),
+    ('\u{0304}', "macroncomb"),
+    ('\u{0306}', "brevecomb"),
+    ('\u{0307}', "dotaccentcomb"),
+    ('\u{0308}', "dieresiscomb"),
+    ('\u{030A}', "ringcomb"),
+    ('\u{030B}', "hungarumlautcomb"),
+    ('\u{030C}', "caroncomb"),
+    ('\u{0326}', "commaaccentcomb"),
+    ('\u{0327}', "cedillacomb"),
+    ('\u{0328}', "ogonekcomb"),
+];
+
+/// Combining marks drawn below the base letter
+const BELOW_MARKS: &[char] = &['\u{0326}', '\u{0327}', '\u{0328}'];
+
+/// Accented letters of Latin-1 Supplement and Latin Extended-A, with their
+/// full canonical decomposition into an ASCII base and combining marks
+///
+/// The letters that Unicode decomposes with a cedilla but that are drawn
+/// with a comma below (Ģ, Ķ, Ļ, Ņ, Ŗ) use the comma accent instead.
+const CANONICAL_DECOMPOSITIONS: &[(char, char, &[char])] = &[
+    ('\u{00C0}', 'A', &['\u{0300}']), // À
+    ('\u{00C1}', 'A', &['\u{0301}']), // Á
+    ('\u{00C2}', 'A', &['\u{0302}']), // Â
+    ('\u{00C3}', 'A', &['\u{0303}']), // Ã
+    ('\u{00C4}', 'A', &['\u{0308}']), // Ä
+    ('\u{00C5}', 'A', &['\u{030A}']), // Å
+    ('\u{00C7}', 'C', &['\u{0327}']), // Ç
+    ('\u{00C8}', 'E', &['\u{0300}']), // È
+    ('\u{00C9}', 'E', &['\u{0301}']), // É
+    ('\u{00CA}', 'E', &['\u{0302}']), // Ê
+    ('\u{00CB}', 'E', &['\u{0308}']), // Ë
+    ('\u{00CC}', 'I', &['\u{0300}']), // Ì
+    ('\u{00CD}', 'I', &['\u{0301}']), // Í
+    ('\u{00CE}', 'I', &['\u{0302}']), // Î
+    ('\u{00CF}', 'I', &['\u{0308}']), // Ï
+    ('\u{00D1}', 'N', &['\u{0303}']), // Ñ
+    ('\u{00D2}', 'O', &['\u{0300}']), // Ò
+    ('\u{00D3}', 'O', &['\u{0301}']), // Ó
+    ('\u{00D4}', 'O', &['\u{0302}']), // Ô
+    ('\u{00D5}', 'O', &['\u{0303}']), // Õ
+    ('\u{00D6}', 'O', &['\u{0308}']), // Ö
+    ('\u{00D9}', 'U', &['\u{0300}']), // Ù
+    ('\u{00DA}', 'U', &['\u{0301}']), // Ú
+    ('\u{00DB}', 'U', &['\u{0302}']), // Û
+    ('\u{00DC}', 'U', &['\u{0308}']), // Ü
+    ('\u{00DD}', 'Y', &['\u{0301}']), // Ý
+    ('\u{00E0}', 'a', &['\u{0300}']), // à
+    ('\u{00E1}', 'a', &['\u{0301}']), // á
+    ('\u{00E2}', 'a', &['\u{0302}']), // â
+    ('\u{00E3}', 'a', &['\u{0303}']), // ã
+    ('\u{00E4}', 'a', &['\u{0308}']), // ä
+    ('\u{00E5}', 'a', &['\u{030A}']), // å
+    ('\u{00E7}', 'c', &['\u{0327}']), // ç
+    ('\u{00E8}', 'e', &['\u{0300}']), // è
+    ('\u{00E9}', 'e', &['\u{0301}']), // é
+    ('\u{00EA}', 'e', &['\u{0302}']), // ê
+    ('\u{00EB}', 'e', &['\u{0308}']), // ë
+    ('\u{00EC}', 'i', &['\u{0300}']), // ì
+    ('\u{00ED}', 'i', &['\u{0301}']), // í
+    ('\u{00EE}', 'i', &['\u{0302}']), // î
+    ('\u{00EF}', 'i', &['\u{0308}']), // ï
+    ('\u{00F1}', 'n', &['\u{0303}']), // ñ
+    ('\u{00F2}', 'o', &['\u{0300}']), // ò
+    ('\u{00F3}', 'o', &['\u{0301}']), // ó
+    ('\u{00F4}', 'o', &['\u{0302}']), // ô
+    ('\u{00F5}', 'o', &['\u{0303}']), // õ
+    ('\u{00F6}', 'o', &['\u{0308}']), // ö
+    ('\u{00F9}', 'u', &['\u{0300}']), // ù
+    ('\u{00FA}', 'u', &['\u{0301}']), // ú
+    ('\u{00FB}', 'u', &['\u{0302}']), // û
+    ('\u{00FC}', 'u', &['\u{0308}']), // ü
+    ('\u{00FD}', 'y', &['\u{0301}']), // ý
+    ('\u{00FF}', 'y', &['\u{0308}']), // ÿ
+    ('\u{0100}', 'A', &['\u{0304}']), // Ā
+    ('\u{0101}', 'a', &['\u{0304}']), // ā
+    ('\u{0102}', 'A', &['\u{0306}']), // Ă
+    ('\u{0103}', 'a', &['\u{0306}']), // ă
+    ('\u{0104}', 'A', &['\u{0328}']), // Ą
+    ('\u{0105}', 'a', &['\u{0328}']), // ą
+    ('\u{0106}', 'C', &['\u{0301}']), // Ć
+    ('\u{0107}', 'c', &['\u{0301}']), // ć
+    ('\u{0108}', 'C', &['\u{0302}']), // Ĉ
+    ('\u{0109}', 'c', &['\u{0302}']), // ĉ
+    ('\u{010A}', 'C', &['\u{0307}']), // Ċ
+    ('\u{010B}', 'c', &['\u{0307}']), // ċ
+    ('\u{010C}', 'C', &['\u{030C}']), // Č
+    ('\u{010D}', 'c', &['\u{030C}']), // č
+    ('\u{010E}', 'D', &['\u{030C}']), // Ď
+    ('\u{010F}', 'd', &['\u{030C}']), // ď
+    ('\u{0112}', 'E', &['\u{0304}']), // Ē
+    ('\u{0113}', 'e', &['\u{0304}']), // ē
+    ('\u{0114}', 'E', &['\u{0306}']), // Ĕ
+    ('\u{0115}', 'e', &['\u{0306}']), // ĕ
+    ('\u{0116}', 'E', &['\u{0307}']), // Ė
+    ('\u{0117}', 'e', &['\u{0307}']), // ė
+    ('\u{0118}', 'E', &['\u{0328}']), // Ę
+    ('\u{0119}', 'e', &['\u{0328}']), // ę
+    ('\u{011A}', 'E', &['\u{030C}']), // Ě
+    ('\u{011B}', 'e', &['\u{030C}']), // ě
+    ('\u{011C}', 'G', &['\u{0302}']), // Ĝ
+    ('\u{011D}', 'g', &['\u{0302}']), // ĝ
+    ('\u{011E}', 'G', &['\u{0306}']), // Ğ
+    ('\u{011F}', 'g', &['\u{0306}']), // ğ
+    ('\u{0120}', 'G', &['\u{0307}']), // Ġ
+    ('\u{0121}', 'g', &['\u{0307}']), // ġ
+    ('\u{0122}', 'G', &['\u{0326}']), // Ģ
+    ('\u{0123}', 'g', &['\u{0326}']), // ģ
+    ('\u{0124}', 'H', &['\u{0302}']), // Ĥ
+    ('\u{0125}', 'h', &['\u{0302}']), // ĥ
+    ('\u{0128}', 'I', &['\u{0303}']), // Ĩ
+    ('\u{0129}', 'i', &['\u{0303}']), // ĩ
+    ('\u{012A}', 'I', &['\u{0304}']), // Ī
+    ('\u{012B}', 'i', &['\u{0304}']), // ī
+    ('\u{012C}', 'I', &['\u{0306}']), // Ĭ
+    ('\u{012D}', 'i', &['\u{0306}']), // ĭ
+    ('\u{012E}', 'I', &['\u{0328}']), // Į
+    ('\u{012F}', 'i', &['\u{0328}']), // į
+    ('\u{0130}', 'I', &['\u{0307}']), // İ
+    ('\u{0134}', 'J', &['\u{0302}']), // Ĵ
+    ('\u{0135}', 'j', &['\u{0302}']), // ĵ
+    ('\u{0136}', 'K', &['\u{0326}']), // Ķ
+    ('\u{0137}', 'k', &['\u{0326}']), // ķ
+    ('\u{0139}', 'L', &['\u{0301}']), // Ĺ
+    ('\u{013A}', 'l', &['\u{0301}']), // ĺ
+    ('\u{013B}', 'L', &['\u{0326}']), // Ļ
+    ('\u{013C}', 'l', &['\u{0326}']), // ļ
+    ('\u{013D}', 'L', &['\u{030C}']), // Ľ
+    ('\u{013E}', 'l', &['\u{030C}']), // ľ
+    ('\u{0143}', 'N', &['\u{0301}']), // Ń
+    ('\u{0144}', 'n', &['\u{0301}']), // ń
+    ('\u{0145}', 'N', &['\u{0326}']), // Ņ
+    ('\u{0146}', 'n', &['\u{0326}']), // ņ
+    ('\u{0147}', 'N', &['\u{030C}']), // Ň
+    ('\u{0148}', 'n', &['\u{030C}']), // ň
+    ('\u{014C}', 'O', &['\u{0304}']), // Ō
+    ('\u{014D}', 'o', &['\u{0304}']), // ō
+    ('\u{014E}', 'O', &['\u{0306}']), // Ŏ
+    ('\u{014F}', 'o', &['\u{0306}']), // ŏ
+    ('\u{0150}', 'O', &['\u{030B}']), // Ő
+    ('\u{0151}', 'o', &['\u{030B}']), // ő
+    ('\u{0154}', 'R', &['\u{0301}']), // Ŕ
+    ('\u{0155}', 'r', &['\u{0301}']), // ŕ
+    ('\u{0156}', 'R', &['\u{0326}']), // Ŗ
+    ('\u{0157}', 'r', &['\u{0326}']), // ŗ
+    ('\u{0158}', 'R', &['\u{030C}']), // Ř
+    ('\u{0159}', 'r', &['\u{030C}']), // ř
+    ('\u{015A}', 'S', &['\u{0301}']), // Ś
+    ('\u{015B}', 's', &['\u{0301}']), // ś
+    ('\u{015C}', 'S', &['\u{0302}']), // Ŝ
+    ('\u{015D}', 's', &['\u{0302}']), // ŝ
+    ('\u{015E}', 'S', &['\u{0327}']), // Ş
+    ('\u{015F}', 's', &['\u{0327}']), // ş
+    ('\u{0160}', 'S', &['\u{030C}']), // Š
+    ('\u{0161}', 's', &['\u{030C}']), // š
+    ('\u{0162}', 'T', &['\u{0327}']), // Ţ
+    ('\u{0163}', 't', &['\u{0327}']), // ţ
+    ('\u{0164}', 'T', &['\u{030C}']), // Ť
+    ('\u{0165}', 't', &['\u{030C}']), // ť
+    ('\u{0168}', 'U', &['\u{0303}']), // Ũ
+    ('\u{0169}', 'u', &['\u{0303}']), // ũ
+    ('\u{016A}', 'U', &['\u{0304}']), // Ū
+    ('\u{016B}', 'u', &['\u{0304}']), // ū
+    ('\u{016C}', 'U', &['\u{0306}']), // Ŭ
+    ('\u{016D}', 'u', &['\u{0306}']), // ŭ
+    ('\u{016E}', 'U', &['\u{030A}']), // Ů
+    ('\u{016F}', 'u', &['\u{030A}']), // ů
+    ('\u{0170}', 'U', &['\u{030B}']), // Ű
+    ('\u{0171}', 'u', &['\u{030B}']), // ű
+    ('\u{0172}', 'U', &['\u{0328}']), // Ų
+    ('\u{0173}', 'u', &['\u{0328}']), // ų
+    ('\u{0174}', 'W', &['\u{0302}']), // Ŵ
+    ('\u{0175}', 'w', &['\u{0302}']), // ŵ
+    ('\u{0176}', 'Y', &['\u{0302}']), // Ŷ
+    ('\u{0177}', 'y', &['\u{0302}']), // ŷ
+    ('\u{0178}', 'Y', &['\u{0308}']), // Ÿ
+    ('\u{0179}', 'Z', &['\u{0301}']), // Ź
+    ('\u{017A}', 'z', &['\u{0301}']), // ź
+    ('\u{017B}', 'Z', &['\u{0307}']), // Ż
+    ('\u{017C}', 'z', &['\u{0307}']), // ż
+    ('\u{017D}', 'Z', &['\u{030C}']), // Ž
+    ('\u{017E}', 'z', &['\u{030C}']), // ž
+];
+
+/// One component of a recipe
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecipeComponent {
+    /// Name of the glyph used as a component
+    pub glyph_name: String,
+    /// Anchor of an earlier component to attach to, for marks
+    pub anchor: Option<String>,
+}
+
+/// How to build one composite glyph
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphRecipe {
+    /// Name of the composite glyph
+    pub glyph_name: String,
+    /// Components in order, bases first
+    pub components: Vec<RecipeComponent>,
+    /// Codepoints to assign to the glyph
+    pub codepoints: Vec<char>,
+}
+
+/// A composite glyph as built in one master
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuiltComposite {
+    /// Components, positioned by their anchors
+    pub components: Vec<ComponentData>,
+    /// Anchors carried over from the components, for attaching further marks
+    pub anchors: Vec<AnchorData>,
+    /// Advance width, the sum of the bases' advances
+    pub width: f64,
+}
+
+/// Parse a recipe file
+///
+/// Blank lines and `#` comments are ignored.
+pub fn parse_recipes(text: &str) -> Result<Vec<GlyphRecipe>> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            (!line.is_empty()).then_some((index + 1, line))
+        })
+        .map(|(line_number, line)| {
+            parse_recipe(line)
+                .map_err(|e| anyhow!("Recipe line {line_number}: {e}"))
+        })
+        .collect()
+}
+
+/// Parse a single `name = a + b@anchor | XXXX` recipe
+fn parse_recipe(line: &str) -> Result<GlyphRecipe> {
+    let (glyph_name, definition) = line
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected 'name = components'"))?;
+    let glyph_name = glyph_name.trim();
+    if glyph_name.is_empty() || glyph_name.contains(char::is_whitespace) {
+        bail!("invalid glyph name '{glyph_name}'");
+    }
+
+    let (components, codepoints) = match definition.split_once('|') {
+        Some((components, codepoints)) => (components, codepoints),
+        None => (definition, ""),
+    };
+
+    let components = components
+        .split('+')
+        .map(|component| {
+            let component = component.trim();
+            let (glyph_name, anchor) = match component.split_once('@') {
+                Some((glyph_name, anchor)) => {
+                    (glyph_name.trim(), Some(anchor.trim().to_string()))
+                }
+                None => (component, None),
+            };
+            if glyph_name.is_empty() || anchor.as_deref() == Some("") {
+                bail!("invalid component '{component}'");
+            }
+            Ok(RecipeComponent {
+                glyph_name: glyph_name.to_string(),
+                anchor,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let codepoints = codepoints
+        .split([',', ' '])
+        .filter(|hex| !hex.is_empty())
+        .map(|hex| {
+            let digits = hex.trim_start_matches("U+").trim_start_matches("u+");
+            u32::from_str_radix(digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| anyhow!("invalid codepoint '{hex}'"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(GlyphRecipe {
+        glyph_name: glyph_name.to_string(),
+        components,
+        codepoints,
+    })
+}
+
+/// Recipes for accented Latin letters from their canonical decomposition
+///
+/// Glyph names follow the usual base + accent convention, e.g. `Aacute`.
+pub fn unicode_recipes() -> Vec<GlyphRecipe> {
+    CANONICAL_DECOMPOSITIONS
+        .iter()
+        .filter_map(|&(composed, base, marks)| {
+            let mark_names = marks
+                .iter()
+                .map(|mark| mark_glyph_name(*mark))
+                .collect::<Option<Vec<_>>>()?;
+
+            let glyph_name = accented_glyph_name(base, &mark_names);
+            let components = std::iter::once(base_component_name(base, marks))
+                .chain(mark_names.iter().map(|name| name.to_string()))
+                .map(|glyph_name| RecipeComponent {
+                    glyph_name,
+                    anchor: None,
+                })
+                .collect();
+
+            Some(GlyphRecipe {
+                glyph_name,
+                components,
+                codepoints: vec![composed],
+            })
+        })
+        .collect()
+}
+
+/// Glyph name of the base component of an accented letter
+///
+/// `i` and `j` lose their dot under marks above them, so those are built on
+/// `dotlessi` and `dotlessj`.
+fn base_component_name(base: char, marks: &[char]) -> String {
+    let has_top_mark = marks.iter().any(|mark| !BELOW_MARKS.contains(mark));
+    match base {
+        'i' if has_top_mark => "dotlessi".to_string(),
+        'j' if has_top_mark => "dotlessj".to_string(),
+        _ => base.to_string(),
+    }
+}
+
+/// Glyph name of a combining mark
+pub fn mark_glyph_name(mark: char) -> Option<&'static str> {
+    MARK_GLYPH_NAMES
+        .iter()
+        .find(|(codepoint, _)| *codepoint == mark)
+        .map(|(_, name)| *name)
+}
+
+/// Base letter and combining marks of an accented letter
+pub fn canonical_decomposition(
+    composed: char,
+) -> Option<(char, &'static [char])> {
+    CANONICAL_DECOMPOSITIONS
+        .iter()
+        .find(|(codepoint, _, _)| *codepoint == composed)
+        .map(|&(_, base, marks)| (base, marks))
+}
+
+/// Glyph name of an accented letter from its decomposition, e.g. `Aacute`
+pub fn decomposed_glyph_name(composed: char) -> Option<String> {
+    let (base, marks) = canonical_decomposition(composed)?;
+    let mark_names = marks
+        .iter()
+        .map(|mark| mark_glyph_name(*mark))
+        .collect::<Option<Vec<_>>>()?;
+    Some(accented_glyph_name(base, &mark_names))
+}
+
+/// The base letter followed by the accent names, without their `comb`
+fn accented_glyph_name(base: char, mark_names: &[&str]) -> String {
+    let mut glyph_name = base.to_string();
+    for mark_name in mark_names {
+        glyph_name.push_str(mark_name.trim_end_matches("comb"));
+    }
+    glyph_name
+}
+
+/// Position the components of a recipe in one master
+///
+/// `glyph_data` returns the advance width and anchors of a glyph in that
+/// master, or `None` if the glyph doesn't exist there.
+pub fn build_composite(
+    recipe: &GlyphRecipe,
+    glyph_data: impl Fn(&str) -> Option<(f64, Vec<AnchorData>)>,
+) -> Result<BuiltComposite> {
+    let mut components = Vec::new();
+    // Anchors of the placed components, in composite coordinates
+    let mut placed_anchors: Vec<Vec<AnchorData>> = Vec::new();
+    let mut width = 0.0;
+
+    for component in &recipe.components {
+        let (advance, anchors) =
+            glyph_data(&component.glyph_name).ok_or_else(|| {
+                anyhow!("component glyph '{}' not found", component.glyph_name)
+            })?;
+        let is_mark = component.anchor.is_some()
+            || anchors.iter().any(AnchorData::is_mark);
+
+        let offset = if is_mark {
+            attachment_offset(component, &anchors, &placed_anchors).ok_or_else(
+                || anyhow!("no anchor to attach '{}' to", component.glyph_name),
+            )?
+        } else {
+            let offset = (width, 0.0);
+            width += advance;
+            offset
+        };
+
+        let mut component_data = ComponentData::new(&component.glyph_name);
+        component_data.transform[4] = offset.0;
+        component_data.transform[5] = offset.1;
+        components.push(component_data);
+        placed_anchors.push(
+            anchors
+                .iter()
+                .map(|anchor| {
+                    AnchorData::new(
+                        &anchor.name,
+                        anchor.x + offset.0,
+                        anchor.y + offset.1,
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    // Later components win, so `top` ends up on the topmost mark
+    let mut anchors: Vec<AnchorData> = Vec::new();
+    for anchor in placed_anchors.into_iter().flatten() {
+        if anchor.is_mark() {
+            continue;
+        }
+        match anchors
+            .iter_mut()
+            .find(|existing| existing.name == anchor.name)
+        {
+            Some(existing) => *existing = anchor,
+            None => anchors.push(anchor),
+        }
+    }
+
+    Ok(BuiltComposite {
+        components,
+        anchors,
+        width,
+    })
+}
+
+/// Offset that puts a mark's `_name` anchor on the latest placed `name`
+fn attachment_offset(
+    component: &RecipeComponent,
+    mark_anchors: &[AnchorData],
+    placed_anchors: &[Vec<AnchorData>],
+) -> Option<(f64, f64)> {
+    let names: Vec<&str> = match &component.anchor {
+        Some(name) => vec![name.as_str()],
+        None => mark_anchors
+            .iter()
+            .filter_map(AnchorData::attached_name)
+            .collect(),
+    };
+
+    names.iter().find_map(|name| {
+        let mark_anchor = mark_anchors
+            .iter()
+            .find(|anchor| anchor.attached_name() == Some(*name))?;
+        let base_anchor = placed_anchors
+            .iter()
+            .rev()
+            .find_map(|anchors| anchors.iter().find(|a| a.name == *name))?;
+        Some((base_anchor.x - mark_anchor.x, base_anchor.y - mark_anchor.y))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_glyph(name: &str) -> Option<(f64, Vec<AnchorData>)> {
+        match name {
+            "a" => Some((500.0, vec![AnchorData::new("top", 250.0, 500.0)])),
+            "f" => Some((300.0, Vec::new())),
+            "acutecomb" | "circumflexcomb" => Some((
+                0.0,
+                vec![
+                    AnchorData::new("_top", 100.0, 500.0),
+                    AnchorData::new("top", 100.0, 700.0),
+                ],
+            )),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_recipes() {
+        let recipes = parse_recipes(
+            "# Latin\n\naacute = a + acutecomb@top | 00E1\nf_f = f+f\n",
+        )
+        .unwrap();
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(recipes[0].glyph_name, "aacute");
+        assert_eq!(recipes[0].codepoints, vec!['\u{00E1}']);
+        assert_eq!(
+            recipes[0].components[1],
+            RecipeComponent {
+                glyph_name: "acutecomb".to_string(),
+                anchor: Some("top".to_string()),
+            }
+        );
+        assert!(recipes[1].codepoints.is_empty());
+
+        assert!(parse_recipes("aacute a + acutecomb").is_err());
+        assert!(parse_recipes("aacute = a + | 00E1").is_err());
+    }
+
+    #[test]
+    fn test_unicode_recipes() {
+        let recipes = unicode_recipes();
+        let aacute = recipes
+            .iter()
+            .find(|recipe| recipe.codepoints == vec!['\u{00E1}'])
+            .unwrap();
+        assert_eq!(aacute.glyph_name, "aacute");
+        let gcommaaccent = recipes
+            .iter()
+            .find(|recipe| recipe.codepoints == vec!['\u{0123}'])
+            .unwrap();
+        assert_eq!(gcommaaccent.glyph_name, "gcommaaccent");
+
+        let base_of = |composed: char| {
+            let recipe = recipes
+                .iter()
+                .find(|recipe| recipe.codepoints == vec![composed])
+                .unwrap();
+            (
+                recipe.glyph_name.clone(),
+                recipe.components[0].glyph_name.clone(),
+            )
+        };
+        assert_eq!(
+            base_of('\u{00ED}'),
+            ("iacute".to_string(), "dotlessi".to_string())
+        );
+        assert_eq!(
+            base_of('\u{0135}'),
+            ("jcircumflex".to_string(), "dotlessj".to_string())
+        );
+        assert_eq!(
+            base_of('\u{012F}'),
+            ("iogonek".to_string(), "i".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_composite() {
+        let recipe = parse_recipes(
+            "acircumflexacute = a + circumflexcomb + acutecomb@top",
+        )
+        .unwrap()
+        .remove(0);
+        let built = build_composite(&recipe, test_glyph).unwrap();
+
+        assert_eq!(built.width, 500.0);
+        let offsets: Vec<(f64, f64)> = built
+            .components
+            .iter()
+            .map(|component| (component.transform[4], component.transform[5]))
+            .collect();
+        // The acute stacks on the circumflex's top anchor
+        assert_eq!(offsets, vec![(0.0, 0.0), (150.0, 0.0), (150.0, 200.0)]);
+        assert_eq!(built.anchors, vec![AnchorData::new("top", 250.0, 900.0)]);
+
+        let ligature = parse_recipes("f_f = f + f").unwrap().remove(0);
+        let built = build_composite(&ligature, test_glyph).unwrap();
+        assert_eq!(built.width, 600.0);
+        assert_eq!(built.components[1].transform[4], 300.0);
+
+        let missing = parse_recipes("x = a + tildecomb").unwrap().remove(0);
+        assert!(build_composite(&missing, test_glyph).is_err());
+    }
+}
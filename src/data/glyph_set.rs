@@ -0,0 +1,319 @@
+//! Renaming and deleting glyphs in UFO sources
+//!
+//! Renames and deletions made in the editor are recorded as
+//! `GlyphSetChange`s and replayed on every UFO when saving. A rename moves
+//! the glyph in every layer and updates whatever refers to it by name:
+//! component references, kerning pairs, groups, the glyph name lists and
+//! maps in the font lib, and the feature file. A deletion removes the glyph
+//! from every layer, kerning pair, group and lib list; components and
+//! features that still use it are left for the designer to fix. Codepoint
+//! edits are recorded the same way and set on the glyph in the default
+//! layer, where UFOs keep a glyph's codepoints.
+//!
+//! Glyph names in the feature file are matched as whole names outside of
+//! comments and strings. Class names (`@name`) are never touched, and
+//! ranges like `a-z` are taken as a single name, so ranges ending at a
+//! renamed glyph keep the old name.
+
+use anyhow::{anyhow, bail, Result};
+use norad::{Font, Name};
+use std::ops::Range;
+
+/// Font lib keys holding a list of glyph names
+const GLYPH_NAME_LISTS: &[&str] =
+    &["public.glyphOrder", "public.skipExportGlyphs"];
+
+/// Font lib keys holding a dictionary keyed by glyph name
+const GLYPH_NAME_MAPS: &[&str] =
+    &["public.postscriptNames", "public.openTypeCategories"];
+
+/// A glyph renamed, deleted or given new codepoints in the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlyphSetChange {
+    Rename {
+        old_name: String,
+        new_name: String,
+    },
+    Delete {
+        glyph_name: String,
+    },
+    SetCodepoints {
+        glyph_name: String,
+        codepoints: Vec<char>,
+    },
+}
+
+impl GlyphSetChange {
+    /// Apply the change to a UFO, in every layer
+    pub fn apply_to_ufo(&self, font: &mut Font) -> Result<()> {
+        match self {
+            GlyphSetChange::Rename { old_name, new_name } => {
+                rename_in_ufo(font, old_name, new_name)
+            }
+            GlyphSetChange::Delete { glyph_name } => {
+                delete_from_ufo(font, glyph_name);
+                Ok(())
+            }
+            GlyphSetChange::SetCodepoints {
+                glyph_name,
+                codepoints,
+            } => {
+                let Some(glyph) =
+                    font.default_layer_mut().get_glyph_mut(glyph_name.as_str())
+                else {
+                    bail!("glyph '{glyph_name}' is not in the default layer");
+                };
+                glyph.codepoints =
+                    norad::Codepoints::new(codepoints.iter().copied());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Check that a name can be used for a glyph
+pub fn validate_glyph_name(glyph_name: &str) -> Result<()> {
+    if glyph_name.is_empty() {
+        bail!("glyph names cannot be empty");
+    }
+    if let Some(c) = glyph_name
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_control())
+    {
+        bail!("invalid character {c:?} in glyph name '{glyph_name}'");
+    }
+    Ok(())
+}
+
+fn rename_in_ufo(
+    font: &mut Font,
+    old_name: &str,
+    new_name: &str,
+) -> Result<()> {
+    let new: Name = new_name
+        .parse()
+        .map_err(|e| anyhow!("invalid glyph name '{new_name}': {e}"))?;
+    let rename = |name: Name| {
+        if name.as_str() == old_name {
+            new.clone()
+        } else {
+            name
+        }
+    };
+
+    for layer in font.layers.iter_mut() {
+        if layer.contains_glyph(old_name) {
+            layer.rename_glyph(old_name, new_name, false).map_err(|e| {
+                anyhow!(
+                    "cannot rename '{old_name}' in layer '{}': {e}",
+                    layer.name()
+                )
+            })?;
+        }
+        for glyph in layer.iter_mut() {
+            for component in &mut glyph.components {
+                if component.base.as_str() == old_name {
+                    component.base = new.clone();
+                }
+            }
+        }
+    }
+
+    font.kerning = std::mem::take(&mut font.kerning)
+        .into_iter()
+        .map(|(first, pairs)| {
+            let pairs = pairs
+                .into_iter()
+                .map(|(second, value)| (rename(second), value))
+                .collect();
+            (rename(first), pairs)
+        })
+        .collect();
+    for members in font.groups.values_mut() {
+        for member in members.iter_mut() {
+            if member.as_str() == old_name {
+                *member = new.clone();
+            }
+        }
+    }
+
+    for key in GLYPH_NAME_LISTS {
+        let Some(names) =
+            font.lib.get_mut(key).and_then(plist::Value::as_array_mut)
+        else {
+            continue;
+        };
+        for name in names.iter_mut() {
+            if name.as_string() == Some(old_name) {
+                *name = plist::Value::String(new_name.to_string());
+            }
+        }
+    }
+    for key in GLYPH_NAME_MAPS {
+        let Some(map) = font
+            .lib
+            .get_mut(key)
+            .and_then(plist::Value::as_dictionary_mut)
+        else {
+            continue;
+        };
+        if let Some(value) = map.remove(old_name) {
+            map.insert(new_name.to_string(), value);
+        }
+    }
+
+    font.features = rename_in_features(&font.features, old_name, new_name);
+    Ok(())
+}
+
+fn delete_from_ufo(font: &mut Font, glyph_name: &str) {
+    for layer in font.layers.iter_mut() {
+        layer.remove_glyph(glyph_name);
+    }
+
+    font.kerning.retain(|first, _| first.as_str() != glyph_name);
+    for pairs in font.kerning.values_mut() {
+        pairs.retain(|second, _| second.as_str() != glyph_name);
+    }
+    font.kerning.retain(|_, pairs| !pairs.is_empty());
+    for members in font.groups.values_mut() {
+        members.retain(|member| member.as_str() != glyph_name);
+    }
+
+    for key in GLYPH_NAME_LISTS {
+        if let Some(names) =
+            font.lib.get_mut(key).and_then(plist::Value::as_array_mut)
+        {
+            names.retain(|name| name.as_string() != Some(glyph_name));
+        }
+    }
+    for key in GLYPH_NAME_MAPS {
+        if let Some(map) = font
+            .lib
+            .get_mut(key)
+            .and_then(plist::Value::as_dictionary_mut)
+        {
+            map.remove(glyph_name);
+        }
+    }
+}
+
+/// Whether feature code uses a glyph by name
+pub fn features_reference(features: &str, glyph_name: &str) -> bool {
+    glyph_name_tokens(features).any(|range| &features[range] == glyph_name)
+}
+
+/// Feature code with every use of a glyph name replaced by another
+pub fn rename_in_features(
+    features: &str,
+    old_name: &str,
+    new_name: &str,
+) -> String {
+    let mut renamed = String::with_capacity(features.len());
+    let mut copied = 0;
+    for range in glyph_name_tokens(features) {
+        if features[range.clone()] == *old_name {
+            renamed.push_str(&features[copied..range.start]);
+            renamed.push_str(new_name);
+            copied = range.end;
+        }
+    }
+    renamed.push_str(&features[copied..]);
+    renamed
+}
+
+/// Characters that can be part of a glyph name in feature code
+fn is_fea_name_char(c: char) -> bool {
+    c.is_alphanumeric()
+        || matches!(c, '.' | '_' | '-' | '*' | '+' | '^' | '|' | '~')
+}
+
+/// Byte ranges of the names in feature code that can be glyph names
+///
+/// Skips comments, quoted strings and class names. Keywords and numbers are
+/// returned too, they only ever match glyphs named like them.
+fn glyph_name_tokens(
+    features: &str,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = 0;
+    let mut previous = None;
+    std::iter::from_fn(move || loop {
+        let rest = &features[start..];
+        let c = rest.chars().next()?;
+        let len = match c {
+            '#' => rest.find('\n').unwrap_or(rest.len()),
+            '"' => rest[1..].find('"').map_or(rest.len(), |end| end + 2),
+            c if is_fea_name_char(c) => {
+                rest.find(|c| !is_fea_name_char(c)).unwrap_or(rest.len())
+            }
+            c => c.len_utf8(),
+        };
+        let range = start..start + len;
+        let is_class = previous == Some('@');
+        previous = rest[..len].chars().last();
+        start = range.end;
+        if is_fea_name_char(c) && !is_class {
+            return Some(range);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_in_features() {
+        let features = "\
+# kerning for a
+@vowels = [a e o];
+feature liga {
+    sub f i by f_i;
+    sub \\a' by a.alt; # a
+} liga;
+feature ss01 {
+    featureNames { name \"Single-storey a\"; };
+    sub @a by a.ss01;
+} ss01;
+";
+        let renamed = rename_in_features(features, "a", "a.sc");
+        assert_eq!(
+            renamed,
+            "\
+# kerning for a
+@vowels = [a.sc e o];
+feature liga {
+    sub f i by f_i;
+    sub \\a.sc' by a.alt; # a
+} liga;
+feature ss01 {
+    featureNames { name \"Single-storey a\"; };
+    sub @a by a.ss01;
+} ss01;
+"
+        );
+        assert_eq!(
+            rename_in_features("sub f i by f_i;", "f_i", "fi"),
+            "sub f i by fi;"
+        );
+    }
+
+    #[test]
+    fn test_features_reference() {
+        let features = "pos A V -80; # Aacute\n@caps = [B C];";
+        assert!(features_reference(features, "A"));
+        assert!(features_reference(features, "C"));
+        assert!(!features_reference(features, "Aacute"));
+        assert!(!features_reference(features, "caps"));
+        assert!(!features_reference(features, "80"));
+    }
+
+    #[test]
+    fn test_validate_glyph_name() {
+        assert!(validate_glyph_name("a.sc").is_ok());
+        assert!(validate_glyph_name("uni0628.init").is_ok());
+        assert!(validate_glyph_name("").is_err());
+        assert!(validate_glyph_name("a b").is_err());
+        assert!(validate_glyph_name("a\tb").is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Image files for reference images
+//!
+//! UFOs keep the images glyphs refer to as PNG files in their `images`
+//! directory. Images added in the editor are read from PNG or JPEG files,
+//! converted to PNG when needed, and given a file name no other image in
+//! the UFO uses. Autotracing reads images as gray levels.
+
+use anyhow::{anyhow, Result};
+use image::{DynamicImage, GrayImage, ImageFormat, ImageReader};
+use std::io::Cursor;
+use std::path::Path;
+
+/// Extensions of the image files that can be added to a glyph
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+/// Whether a path looks like an image file that can be added to a glyph
+pub fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Decode a PNG or JPEG file's contents
+pub fn decode_image(bytes: &[u8]) -> Result<DynamicImage> {
+    image::load_from_memory(bytes).map_err(|e| anyhow!("unreadable image: {e}"))
+}
+
+/// Size in pixels of a PNG or JPEG file's image, read without decoding it
+pub fn image_size(bytes: &[u8]) -> Result<(u32, u32)> {
+    ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| anyhow!("unreadable image: {e}"))?
+        .into_dimensions()
+        .map_err(|e| anyhow!("unreadable image: {e}"))
+}
+
+/// An image as gray levels from 0 (black) to 255 (white), with transparent
+/// parts turning white the way they show over the canvas
+pub fn grayscale_image(image: &DynamicImage) -> GrayImage {
+    let gray = image.to_luma_alpha8();
+    let mut result = GrayImage::new(gray.width(), gray.height());
+    for (pixel, source) in result.pixels_mut().zip(gray.pixels()) {
+        let [luma, alpha] = source.0;
+        let ink = (255 - luma as u32) * alpha as u32 / 255;
+        pixel.0 = [(255 - ink) as u8];
+    }
+    result
+}
+
+/// A PNG or JPEG file's contents as PNG, with the image's size in pixels
+///
+/// PNG files are kept as they are.
+pub fn png_image_data(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32)> {
+    let image = decode_image(bytes)?;
+    let (width, height) = (image.width(), image.height());
+    if image::guess_format(bytes).ok() == Some(ImageFormat::Png) {
+        return Ok((bytes.to_vec(), width, height));
+    }
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| anyhow!("cannot convert the image to PNG: {e}"))?;
+    Ok((png, width, height))
+}
+
+/// Size in pixels of a PNG image, read from its header
+pub fn png_size(png: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if png.len() < 24 || !png.starts_with(SIGNATURE) || &png[12..16] != b"IHDR"
+    {
+        return None;
+    }
+    let width = u32::from_be_bytes(png[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(png[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// PNG file name for an image file, made unique with a number when the
+/// name is taken
+pub fn unique_image_file_name(
+    path: &Path,
+    is_taken: impl Fn(&str) -> bool,
+) -> String {
+    let stem: String = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = if stem.is_empty() { "image" } else { &stem };
+
+    let mut file_name = format!("{stem}.png");
+    let mut number = 2;
+    while is_taken(&file_name) {
+        file_name = format!("{stem}-{number}.png");
+        number += 1;
+    }
+    file_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_image_file() {
+        assert!(is_image_file(Path::new("sketches/a.png")));
+        assert!(is_image_file(Path::new("scan.JPG")));
+        assert!(is_image_file(Path::new("scan.jpeg")));
+        assert!(!is_image_file(Path::new("font.ufo")));
+        assert!(!is_image_file(Path::new("png")));
+    }
+
+    #[test]
+    fn test_unique_image_file_name() {
+        let taken = ["a sketch.png", "a_sketch.png", "a_sketch-2.png"];
+        let is_taken = |name: &str| taken.contains(&name);
+        assert_eq!(
+            unique_image_file_name(Path::new("/tmp/a sketch.jpg"), is_taken),
+            "a_sketch-3.png"
+        );
+        assert_eq!(
+            unique_image_file_name(Path::new("b.PNG"), is_taken),
+            "b.png"
+        );
+        assert_eq!(
+            unique_image_file_name(Path::new(""), is_taken),
+            "image.png"
+        );
+    }
+
+    #[test]
+    fn test_png_image_data() {
+        let mut jpeg = Vec::new();
+        DynamicImage::new_luma8(3, 2)
+            .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+            .unwrap();
+        let (png, width, height) = png_image_data(&jpeg).unwrap();
+        assert_eq!((width, height), (3, 2));
+        assert_eq!(image::guess_format(&png).unwrap(), ImageFormat::Png);
+        assert_eq!(png_image_data(&png).unwrap().0, png);
+        assert_eq!(png_size(&png), Some((3, 2)));
+        assert_eq!(png_size(&jpeg), None);
+        assert_eq!(image_size(&jpeg).unwrap(), (3, 2));
+        assert!(image_size(b"not an image").is_err());
+        assert!(png_image_data(b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_grayscale_image() {
+        let mut rgba = image::RgbaImage::new(4, 1);
+        rgba.put_pixel(0, 0, image::Rgba([0, 0, 0, 255]));
+        rgba.put_pixel(1, 0, image::Rgba([0, 0, 0, 0]));
+        rgba.put_pixel(2, 0, image::Rgba([0, 0, 0, 128]));
+        rgba.put_pixel(3, 0, image::Rgba([255, 255, 255, 255]));
+        let gray = grayscale_image(&DynamicImage::ImageRgba8(rgba));
+        assert_eq!(gray.as_raw(), &[0, 255, 127, 255]);
+    }
+}
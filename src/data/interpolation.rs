@@ -0,0 +1,157 @@
+//! Interpolation between masters
+//!
+//! Glyphs between masters are previewed with fontir's `VariationModel`, the
+//! one fontc builds the variable font with, so the preview matches the
+//! compiled font. The model hands out deltas per region of the designspace;
+//! here they are turned into one weight per master, so outlines can be
+//! blended point by point.
+
+use fontdrasil::coords::NormalizedLocation;
+use fontdrasil::types::Axis;
+use fontir::variations::VariationModel;
+use kurbo::{BezPath, PathEl, Point};
+use std::collections::HashMap;
+
+/// Weight of each master at a location, in the order the master locations
+/// are given; the weights add up to 1
+///
+/// Returns `None` without a master at the default location, with two
+/// masters at the same location, or with masters on axes the font doesn't
+/// have.
+pub fn master_weights(
+    masters: &[NormalizedLocation],
+    axes: &[Axis],
+    location: &NormalizedLocation,
+) -> Option<Vec<f64>> {
+    // Each master's value is 1 for itself and 0 for the others, so the
+    // deltas of a region are how much of each master it adds
+    let values: HashMap<NormalizedLocation, Vec<f64>> = masters
+        .iter()
+        .enumerate()
+        .map(|(index, master)| {
+            let mut value = vec![0.0; masters.len()];
+            value[index] = 1.0;
+            (master.clone(), value)
+        })
+        .collect();
+    if values.len() != masters.len() {
+        return None;
+    }
+
+    let model =
+        VariationModel::new(masters.iter().cloned().collect(), axes.to_vec())
+            .ok()?;
+    let deltas = model.deltas::<f64, f64>(&values).ok()?;
+    let mut weights = vec![0.0; masters.len()];
+    for (region, delta) in &deltas {
+        let scalar = region.scalar_at(location).into_inner() as f64;
+        if scalar == 0.0 {
+            continue;
+        }
+        for (weight, value) in weights.iter_mut().zip(delta) {
+            *weight += scalar * value;
+        }
+    }
+    Some(weights)
+}
+
+/// Blend the outlines of masters by weight
+///
+/// Returns `None` unless every master has the same contours with the same
+/// kinds of segments, see `data::compatibility` for the reasons why not.
+pub fn interpolate_contours(
+    masters: &[(&[BezPath], f64)],
+) -> Option<Vec<BezPath>> {
+    let (first, _) = masters.first()?;
+    let mut contours = Vec::with_capacity(first.len());
+    for (index, contour) in first.iter().enumerate() {
+        let mut elements = contour.elements().to_vec();
+        for element in &mut elements {
+            scale_points(element, 0.0);
+        }
+        for (master, weight) in masters {
+            let other = master.get(index)?.elements();
+            if other.len() != elements.len() {
+                return None;
+            }
+            for (element, other) in elements.iter_mut().zip(other) {
+                add_weighted(element, other, *weight)?;
+            }
+        }
+        contours.push(BezPath::from_vec(elements));
+    }
+    masters
+        .iter()
+        .all(|(master, _)| master.len() == first.len())
+        .then_some(contours)
+}
+
+fn scale_points(element: &mut PathEl, factor: f64) {
+    let scale = |p: &mut Point| *p = (p.to_vec2() * factor).to_point();
+    match element {
+        PathEl::MoveTo(p) | PathEl::LineTo(p) => scale(p),
+        PathEl::QuadTo(p1, p2) => {
+            scale(p1);
+            scale(p2);
+        }
+        PathEl::CurveTo(p1, p2, p3) => {
+            scale(p1);
+            scale(p2);
+            scale(p3);
+        }
+        PathEl::ClosePath => {}
+    }
+}
+
+/// Add `weight` times the points of `other` to `element`, if both are the
+/// same kind of element
+fn add_weighted(
+    element: &mut PathEl,
+    other: &PathEl,
+    weight: f64,
+) -> Option<()> {
+    let add = |p: &mut Point, q: &Point| *p += q.to_vec2() * weight;
+    match (element, other) {
+        (PathEl::MoveTo(p), PathEl::MoveTo(q))
+        | (PathEl::LineTo(p), PathEl::LineTo(q)) => add(p, q),
+        (PathEl::QuadTo(p1, p2), PathEl::QuadTo(q1, q2)) => {
+            add(p1, q1);
+            add(p2, q2);
+        }
+        (PathEl::CurveTo(p1, p2, p3), PathEl::CurveTo(q1, q2, q3)) => {
+            add(p1, q1);
+            add(p2, q2);
+            add(p3, q3);
+        }
+        (PathEl::ClosePath, PathEl::ClosePath) => {}
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_contours() {
+        let square = |size: f64| {
+            let mut path = BezPath::new();
+            path.move_to((0.0, 0.0));
+            path.line_to((size, 0.0));
+            path.curve_to((size, size), (size, size), (0.0, size));
+            path.close_path();
+            vec![path]
+        };
+        let light = square(100.0);
+        let bold = square(200.0);
+        let blended =
+            interpolate_contours(&[(&light, 0.25), (&bold, 0.75)]).unwrap();
+        assert_eq!(blended, square(175.0));
+
+        let mut open = square(100.0);
+        open[0] = BezPath::from_vec(open[0].elements()[..3].to_vec());
+        assert!(interpolate_contours(&[(&light, 0.5), (&open, 0.5)]).is_none());
+        assert!(interpolate_contours(&[(&light, 0.5), (&[], 0.5)]).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+//! Glyph layers besides the masters' own
+//!
+//! A UFO can hold more layers than the default layer and the layers sparse
+//! masters are stored in, most often a background layer per master that
+//! keeps a tracing reference or an older version of each glyph. These are
+//! loaded so they can be shown and edited next to the master they belong
+//! to; the save path writes back the ones edited and leaves the rest alone.
+
+use crate::core::state::font_data::ComponentData;
+use crate::geometry::quadratic;
+use bevy::log::warn;
+use kurbo::{BezPath, Point};
+use std::collections::HashMap;
+
+/// Name of the background layer of the default layer
+pub const DEFAULT_BACKGROUND_LAYER: &str = "public.background";
+
+/// Glyphs of one layer by glyph name
+pub type LayerGlyphs = HashMap<String, LayerGlyph>;
+
+/// Outline and components of a glyph in a non-master layer
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LayerGlyph {
+    pub contours: Vec<BezPath>,
+    /// Implied on-curve flags of each contour, as on working copies
+    pub implied_on_curves: Vec<Vec<bool>>,
+    pub components: Vec<ComponentData>,
+}
+
+impl LayerGlyph {
+    /// Read a glyph's outline and components; contours that aren't valid
+    /// outlines are skipped
+    pub fn from_norad_glyph(glyph: &norad::Glyph) -> Self {
+        let implied: Vec<Point> = glyph
+            .contours
+            .iter()
+            .flat_map(quadratic::ufo_implied_on_curves)
+            .collect();
+        let contours: Vec<BezPath> = glyph
+            .contours
+            .iter()
+            .filter_map(|contour| match contour.to_kurbo() {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    warn!(
+                        "Skipping contour of '{}' in a layer: {}",
+                        glyph.name(),
+                        e
+                    );
+                    None
+                }
+            })
+            .collect();
+        let implied_on_curves = contours
+            .iter()
+            .map(|contour| quadratic::implied_flags_at(contour, &implied))
+            .collect();
+        let components = glyph
+            .components
+            .iter()
+            .map(ComponentData::from_norad_component)
+            .collect();
+        Self {
+            contours,
+            implied_on_curves,
+            components,
+        }
+    }
+
+    /// Whether the glyph has neither contours nor components
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty() && self.components.is_empty()
+    }
+}
+
+/// Name of the background layer of a master stored in `master_layer`,
+/// `None` being the default layer
+///
+/// Follows the naming most editors and fontmake use: `public.background`
+/// for the default layer, and `<layer>.background` for a sparse master's
+/// layer.
+pub fn background_layer_name(master_layer: Option<&str>) -> String {
+    match master_layer {
+        Some(layer) => format!("{layer}.background"),
+        None => DEFAULT_BACKGROUND_LAYER.to_string(),
+    }
+}
+
+/// Whether a layer holds glyphs of its own rather than a master's
+pub fn is_extra_layer(
+    layer_name: &str,
+    is_default: bool,
+    master_layers: &[Option<&str>],
+) -> bool {
+    !is_default && !master_layers.contains(&Some(layer_name))
+}
+
+/// Load every layer of a UFO that isn't its default layer or one of the
+/// given master layers, by layer name
+pub fn load_glyph_layers(
+    font: &norad::Font,
+    master_layers: &[Option<&str>],
+) -> HashMap<String, LayerGlyphs> {
+    let default_name = font.default_layer().name().to_string();
+    font.layers
+        .iter()
+        .filter(|layer| {
+            let name = layer.name().as_str();
+            is_extra_layer(name, name == default_name, master_layers)
+        })
+        .map(|layer| {
+            let glyphs = layer
+                .iter()
+                .map(|glyph| {
+                    let name = glyph.name().to_string();
+                    (name, LayerGlyph::from_norad_glyph(glyph))
+                })
+                .collect();
+            (layer.name().to_string(), glyphs)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_layer_name() {
+        assert_eq!(background_layer_name(None), "public.background");
+        assert_eq!(
+            background_layer_name(Some("wght=450")),
+            "wght=450.background"
+        );
+    }
+
+    #[test]
+    fn test_is_extra_layer() {
+        let master_layers = [None, Some("Bold Support")];
+        assert!(!is_extra_layer("public.default", true, &master_layers));
+        assert!(!is_extra_layer("Bold Support", false, &master_layers));
+        assert!(is_extra_layer("public.background", false, &master_layers));
+        assert!(is_extra_layer(
+            "Bold Support.background",
+            false,
+            &master_layers
+        ));
+    }
+}
@@ -0,0 +1,614 @@
+//! Repeating outline edits in other masters
+//!
+//! With "edit all masters" on, an edit made to a glyph in one master is
+//! repeated in the glyph's other masters so they stay compatible. The edit
+//! is worked out by comparing the edited contours before and after it:
+//!
+//! - Contours added are copied, contours removed are removed
+//! - A new start point or direction is applied as is
+//! - Points inserted on a segment split the other masters' segment at the
+//!   same curve parameters; other inserted or removed points are placed
+//!   relative to the unchanged points around them
+//! - Moved points move by the same offset, if asked for
+//!
+//! Edits that can't be matched up, such as cutting a contour in two, stay
+//! in the master they were made in.
+
+use crate::geometry::simplify::{append_segments, split_subpaths, Subpath};
+use kurbo::{
+    Affine, BezPath, ParamCurve, ParamCurveNearest, PathEl, PathSeg, Point,
+};
+
+/// Tolerance for points counting as unchanged
+const EPSILON: f64 = 1e-6;
+
+/// How far an inserted point may be from the segment it splits, in units
+const ON_SEGMENT_TOLERANCE: f64 = 0.5;
+
+/// Repeat the edit that turned `before` into `after` on another master's
+/// contours, compatible with `before`
+///
+/// Moved points only move in the other master when `move_points` is set.
+/// Returns `None` if the edit can't be repeated there.
+pub fn sync_contours(
+    before: &[BezPath],
+    after: &[BezPath],
+    other: &[BezPath],
+    move_points: bool,
+) -> Option<Vec<BezPath>> {
+    if other.len() != before.len() {
+        return None;
+    }
+
+    if after.len() == before.len() {
+        return before
+            .iter()
+            .zip(after)
+            .zip(other)
+            .map(|((before, after), other)| {
+                if contours_equal(before, after) {
+                    Some(other.clone())
+                } else {
+                    sync_contour(before, after, other, move_points)
+                }
+            })
+            .collect();
+    }
+
+    // Contours added or removed, the others left as they were
+    let mut synced = Vec::new();
+    let mut next = 0;
+    if after.len() > before.len() {
+        for contour in after {
+            if next < before.len() && contours_equal(&before[next], contour) {
+                synced.push(other[next].clone());
+                next += 1;
+            } else {
+                synced.push(contour.clone());
+            }
+        }
+        (next == before.len()).then_some(synced)
+    } else {
+        for (contour, other) in before.iter().zip(other) {
+            if next < after.len() && contours_equal(contour, &after[next]) {
+                synced.push(other.clone());
+                next += 1;
+            }
+        }
+        (next == after.len()).then_some(synced)
+    }
+}
+
+/// Repeat the edit of a single contour
+fn sync_contour(
+    before: &BezPath,
+    after: &BezPath,
+    other: &BezPath,
+    move_points: bool,
+) -> Option<BezPath> {
+    let (before, after, other) = (
+        single_subpath(before)?,
+        single_subpath(after)?,
+        single_subpath(other)?,
+    );
+    if !same_structure(&before, &other) {
+        return None;
+    }
+
+    let segments =
+        if let Some((reversed, shift)) = find_reordering(&before, &after) {
+            reorder(&other.segments, reversed, shift)
+        } else if same_structure(&before, &after) {
+            moved_segments(
+                &before.segments,
+                &after.segments,
+                &other.segments,
+                move_points,
+            )
+        } else if !before.closed && after.closed {
+            closed_segments(&before, &after, &other)?
+        } else if before.closed == after.closed {
+            restructured_segments(&before, &after, &other, move_points)?
+        } else {
+            return None;
+        };
+
+    let mut contour = BezPath::new();
+    append_segments(&mut contour, &segments, after.closed);
+    Some(contour)
+}
+
+/// The one subpath of a contour
+fn single_subpath(contour: &BezPath) -> Option<Subpath> {
+    let mut subpaths = split_subpaths(contour);
+    (subpaths.len() == 1).then(|| subpaths.remove(0))
+}
+
+/// A new start point and/or direction turning `before` into `after`, as
+/// (reversed, segments shifted)
+fn find_reordering(before: &Subpath, after: &Subpath) -> Option<(bool, usize)> {
+    if before.closed != after.closed
+        || before.segments.len() != after.segments.len()
+    {
+        return None;
+    }
+    let shifts = if before.closed {
+        before.segments.len()
+    } else {
+        1
+    };
+    [false, true]
+        .into_iter()
+        .flat_map(|reversed| (0..shifts).map(move |shift| (reversed, shift)))
+        .filter(|&reordering| reordering != (false, 0))
+        .find(|&(reversed, shift)| {
+            segments_equal(
+                &reorder(&before.segments, reversed, shift),
+                &after.segments,
+            )
+        })
+}
+
+fn reorder(segments: &[PathSeg], reversed: bool, shift: usize) -> Vec<PathSeg> {
+    let mut segments: Vec<PathSeg> = if reversed {
+        segments.iter().rev().map(|seg| seg.reverse()).collect()
+    } else {
+        segments.to_vec()
+    };
+    segments.rotate_left(shift);
+    segments
+}
+
+/// The other master's segments, moved like the edited ones if asked for
+fn moved_segments(
+    before: &[PathSeg],
+    after: &[PathSeg],
+    other: &[PathSeg],
+    move_points: bool,
+) -> Vec<PathSeg> {
+    if !move_points {
+        return other.to_vec();
+    }
+    before
+        .iter()
+        .zip(after)
+        .zip(other)
+        .map(|((before, after), other)| {
+            let deltas: Vec<_> = segment_points(after)
+                .into_iter()
+                .zip(segment_points(before))
+                .map(|(after, before)| after - before)
+                .collect();
+            let points: Vec<Point> = segment_points(other)
+                .into_iter()
+                .zip(deltas)
+                .map(|(point, delta)| point + delta)
+                .collect();
+            with_points(*other, &points)
+        })
+        .collect()
+}
+
+/// An open contour closed by the edit, maybe with a new closing segment
+fn closed_segments(
+    before: &Subpath,
+    after: &Subpath,
+    other: &Subpath,
+) -> Option<Vec<PathSeg>> {
+    let count = before.segments.len();
+    if after.segments.len() < count
+        || !segments_equal(&after.segments[..count], &before.segments)
+    {
+        return None;
+    }
+    let (first, last) = (before.segments.first()?, before.segments.last()?);
+    let (other_first, other_last) =
+        (other.segments.first()?, other.segments.last()?);
+    let transform = chord_transform(
+        (last.end(), first.start()),
+        (other_last.end(), other_first.start()),
+    );
+    let mut segments = other.segments.clone();
+    segments.extend(after.segments[count..].iter().map(|seg| transform * *seg));
+    Some(segments)
+}
+
+/// Points inserted or removed: the runs of segments between points the
+/// edit left alone are repeated one by one
+fn restructured_segments(
+    before: &Subpath,
+    after: &Subpath,
+    other: &Subpath,
+    move_points: bool,
+) -> Option<Vec<PathSeg>> {
+    let before_nodes = nodes(before);
+    let after_nodes = nodes(after);
+
+    // Points of `after` that were already in `before`, in order
+    let mut unchanged = Vec::new();
+    let mut next = 0;
+    for (after_index, point) in after_nodes.iter().enumerate() {
+        if let Some(offset) = before_nodes[next..]
+            .iter()
+            .position(|node| node.distance(*point) < EPSILON)
+        {
+            unchanged.push((after_index, next + offset));
+            next += offset + 1;
+        }
+    }
+    if unchanged.first() != Some(&(0, 0)) {
+        return None;
+    }
+
+    let mut extension = None;
+    if before.closed {
+        unchanged.push((after.segments.len(), before.segments.len()));
+    } else {
+        let &(after_last, before_last) = unchanged.last()?;
+        if before_last != before.segments.len() {
+            return None;
+        }
+        if after_last < after.segments.len() {
+            extension = Some(after_last);
+        }
+    }
+
+    let mut segments = Vec::new();
+    for pair in unchanged.windows(2) {
+        let (after_start, before_start) = pair[0];
+        let (after_end, before_end) = pair[1];
+        segments.extend(sync_run(
+            &before.segments[before_start..before_end],
+            &after.segments[after_start..after_end],
+            &other.segments[before_start..before_end],
+            move_points,
+        ));
+    }
+
+    // Segments added to the end of an open contour follow its end point
+    if let Some(after_last) = extension {
+        let delta =
+            other.segments.last()?.end() - before.segments.last()?.end();
+        segments.extend(
+            after.segments[after_last..]
+                .iter()
+                .map(|seg| Affine::translate(delta) * *seg),
+        );
+    }
+    Some(segments)
+}
+
+/// Repeat the edit of the segments between two unchanged points
+fn sync_run(
+    before: &[PathSeg],
+    after: &[PathSeg],
+    other: &[PathSeg],
+    move_points: bool,
+) -> Vec<PathSeg> {
+    if before.len() == after.len()
+        && before.iter().zip(after).all(|(b, a)| same_kind(b, a))
+    {
+        return moved_segments(before, after, other, move_points);
+    }
+    if let [before] = before {
+        if let Some(ts) = split_parameters(before, after) {
+            return ts
+                .windows(2)
+                .map(|range| other[0].subsegment(range[0]..range[1]))
+                .collect();
+        }
+    }
+    let (Some(first), Some(last)) = (before.first(), before.last()) else {
+        return after.to_vec();
+    };
+    let transform = chord_transform(
+        (first.start(), last.end()),
+        (other[0].start(), other[other.len() - 1].end()),
+    );
+    after.iter().map(|seg| transform * *seg).collect()
+}
+
+/// Curve parameters, from 0 to 1, at which `before` was split into the
+/// segments of `after`, if that is all the edit did
+fn split_parameters(before: &PathSeg, after: &[PathSeg]) -> Option<Vec<f64>> {
+    if after.len() < 2 || !after.iter().all(|seg| same_kind(seg, before)) {
+        return None;
+    }
+    let mut ts = vec![0.0];
+    for seg in &after[..after.len() - 1] {
+        let nearest = before.nearest(seg.end(), 1e-9);
+        if nearest.distance_sq.sqrt() > ON_SEGMENT_TOLERANCE
+            || nearest.t <= ts[ts.len() - 1]
+        {
+            return None;
+        }
+        ts.push(nearest.t);
+    }
+    ts.push(1.0);
+
+    let is_split = after.iter().zip(ts.windows(2)).all(|(seg, range)| {
+        let piece = before.subsegment(range[0]..range[1]);
+        segment_points(seg)
+            .into_iter()
+            .zip(segment_points(&piece))
+            .all(|(a, b)| a.distance(b) < ON_SEGMENT_TOLERANCE)
+    });
+    is_split.then_some(ts)
+}
+
+/// The similarity transform (move, rotate and scale) taking one chord onto
+/// another
+fn chord_transform(from: (Point, Point), to: (Point, Point)) -> Affine {
+    let (u, v) = (from.1 - from.0, to.1 - to.0);
+    let length = u.hypot2();
+    if length < EPSILON || v.hypot2() < EPSILON {
+        return Affine::translate(to.0 - from.0);
+    }
+    let a = (v.x * u.x + v.y * u.y) / length;
+    let b = (v.y * u.x - v.x * u.y) / length;
+    Affine::translate(to.0.to_vec2())
+        * Affine::new([a, b, -b, a, 0.0, 0.0])
+        * Affine::translate(-from.0.to_vec2())
+}
+
+/// On-curve points of a subpath, each once
+fn nodes(subpath: &Subpath) -> Vec<Point> {
+    let mut nodes: Vec<Point> = subpath
+        .segments
+        .first()
+        .map(|seg| seg.start())
+        .into_iter()
+        .chain(subpath.segments.iter().map(|seg| seg.end()))
+        .collect();
+    if subpath.closed {
+        nodes.pop();
+    }
+    nodes
+}
+
+fn same_structure(a: &Subpath, b: &Subpath) -> bool {
+    a.closed == b.closed
+        && a.segments.len() == b.segments.len()
+        && a.segments
+            .iter()
+            .zip(&b.segments)
+            .all(|(a, b)| same_kind(a, b))
+}
+
+fn same_kind(a: &PathSeg, b: &PathSeg) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+fn segments_equal(a: &[PathSeg], b: &[PathSeg]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            same_kind(a, b)
+                && segment_points(a)
+                    .into_iter()
+                    .zip(segment_points(b))
+                    .all(|(a, b)| a.distance(b) < EPSILON)
+        })
+}
+
+fn contours_equal(a: &BezPath, b: &BezPath) -> bool {
+    let (a, b) = (a.elements(), b.elements());
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            std::mem::discriminant(a) == std::mem::discriminant(b)
+                && element_points(a)
+                    .into_iter()
+                    .zip(element_points(b))
+                    .all(|(a, b)| a.distance(b) < EPSILON)
+        })
+}
+
+fn element_points(el: &PathEl) -> Vec<Point> {
+    match *el {
+        PathEl::MoveTo(p) | PathEl::LineTo(p) => vec![p],
+        PathEl::QuadTo(p1, p2) => vec![p1, p2],
+        PathEl::CurveTo(p1, p2, p3) => vec![p1, p2, p3],
+        PathEl::ClosePath => Vec::new(),
+    }
+}
+
+fn segment_points(seg: &PathSeg) -> Vec<Point> {
+    match *seg {
+        PathSeg::Line(line) => vec![line.p0, line.p1],
+        PathSeg::Quad(quad) => vec![quad.p0, quad.p1, quad.p2],
+        PathSeg::Cubic(cubic) => vec![cubic.p0, cubic.p1, cubic.p2, cubic.p3],
+    }
+}
+
+fn with_points(seg: PathSeg, points: &[Point]) -> PathSeg {
+    match (seg, points) {
+        (PathSeg::Line(mut line), &[p0, p1]) => {
+            (line.p0, line.p1) = (p0, p1);
+            PathSeg::Line(line)
+        }
+        (PathSeg::Quad(mut quad), &[p0, p1, p2]) => {
+            (quad.p0, quad.p1, quad.p2) = (p0, p1, p2);
+            PathSeg::Quad(quad)
+        }
+        (PathSeg::Cubic(mut cubic), &[p0, p1, p2, p3]) => {
+            (cubic.p0, cubic.p1, cubic.p2, cubic.p3) = (p0, p1, p2, p3);
+            PathSeg::Cubic(cubic)
+        }
+        _ => seg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f64, f64)]) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to(points[0]);
+        for &point in &points[1..] {
+            path.line_to(point);
+        }
+        path.close_path();
+        path
+    }
+
+    fn square(x: f64, y: f64, size: f64) -> BezPath {
+        polygon(&[(x, y), (x + size, y), (x + size, y + size), (x, y + size)])
+    }
+
+    fn on_curve_points(contour: &BezPath) -> Vec<Point> {
+        contour
+            .elements()
+            .iter()
+            .filter_map(|el| match *el {
+                PathEl::MoveTo(p) | PathEl::LineTo(p) => Some(p),
+                PathEl::QuadTo(_, p) | PathEl::CurveTo(_, _, p) => Some(p),
+                PathEl::ClosePath => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_inserted_point_splits_at_same_parameter() {
+        let before = [square(0.0, 0.0, 100.0)];
+        let other = [square(0.0, 0.0, 200.0)];
+        let after = [polygon(&[
+            (0.0, 0.0),
+            (25.0, 0.0),
+            (100.0, 0.0),
+            (100.0, 100.0),
+            (0.0, 100.0),
+        ])];
+
+        let synced = sync_contours(&before, &after, &other, false).unwrap();
+        assert_eq!(
+            on_curve_points(&synced[0]),
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(50.0, 0.0),
+                Point::new(200.0, 0.0),
+                Point::new(200.0, 200.0),
+                Point::new(0.0, 200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_curve_split_in_other_master() {
+        let mut before = BezPath::new();
+        before.move_to((0.0, 0.0));
+        before.curve_to((0.0, 50.0), (50.0, 100.0), (100.0, 100.0));
+        before.line_to((100.0, 0.0));
+        before.close_path();
+        let other = Affine::scale(2.0) * before.clone();
+
+        let subpath = single_subpath(&before).unwrap();
+        let curve = subpath.segments[0];
+        let mut after = BezPath::new();
+        append_segments(
+            &mut after,
+            &[
+                curve.subsegment(0.0..0.5),
+                curve.subsegment(0.5..1.0),
+                subpath.segments[1],
+                subpath.segments[2],
+            ],
+            true,
+        );
+
+        let synced =
+            sync_contours(&[before], &[after.clone()], &[other], false)
+                .unwrap();
+        let expected = Affine::scale(2.0) * after;
+        assert!(contours_equal(&synced[0], &expected));
+    }
+
+    #[test]
+    fn test_removed_point_merges_segments() {
+        let before = [polygon(&[
+            (0.0, 0.0),
+            (50.0, 0.0),
+            (100.0, 0.0),
+            (100.0, 100.0),
+        ])];
+        let other = [polygon(&[
+            (0.0, 0.0),
+            (80.0, 10.0),
+            (200.0, 0.0),
+            (200.0, 200.0),
+        ])];
+        let after = [polygon(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0)])];
+
+        let synced = sync_contours(&before, &after, &other, false).unwrap();
+        assert_eq!(
+            on_curve_points(&synced[0]),
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(200.0, 0.0),
+                Point::new(200.0, 200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_direction_and_start_point() {
+        let before = [square(0.0, 0.0, 100.0)];
+        let other = [square(10.0, 10.0, 300.0)];
+
+        let reversed = [before[0].reverse_subpaths()];
+        let synced = sync_contours(&before, &reversed, &other, false).unwrap();
+        assert!(contours_equal(&synced[0], &other[0].reverse_subpaths()));
+
+        let restarted = [polygon(&[
+            (100.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 0.0),
+            (100.0, 0.0),
+        ])];
+        let synced = sync_contours(&before, &restarted, &other, false).unwrap();
+        assert_eq!(on_curve_points(&synced[0])[0], Point::new(310.0, 310.0));
+    }
+
+    #[test]
+    fn test_contours_added_and_removed() {
+        let before = [square(0.0, 0.0, 100.0), square(200.0, 0.0, 100.0)];
+        let other = [square(0.0, 0.0, 150.0), square(300.0, 0.0, 150.0)];
+
+        let added = square(500.0, 500.0, 10.0);
+        let after = [before[0].clone(), added.clone(), before[1].clone()];
+        let synced = sync_contours(&before, &after, &other, false).unwrap();
+        assert_eq!(synced.len(), 3);
+        assert!(contours_equal(&synced[1], &added));
+        assert!(contours_equal(&synced[2], &other[1]));
+
+        let synced =
+            sync_contours(&before, &before[1..], &other, false).unwrap();
+        assert_eq!(synced.len(), 1);
+        assert!(contours_equal(&synced[0], &other[1]));
+    }
+
+    #[test]
+    fn test_moved_points_only_when_asked() {
+        let before = [square(0.0, 0.0, 100.0)];
+        let other = [square(0.0, 0.0, 200.0)];
+        let after = [polygon(&[
+            (0.0, 0.0),
+            (110.0, 0.0),
+            (100.0, 100.0),
+            (0.0, 100.0),
+        ])];
+
+        let kept = sync_contours(&before, &after, &other, false).unwrap();
+        assert!(contours_equal(&kept[0], &other[0]));
+
+        let moved = sync_contours(&before, &after, &other, true).unwrap();
+        assert_eq!(on_curve_points(&moved[0])[1], Point::new(210.0, 0.0));
+    }
+
+    #[test]
+    fn test_incompatible_master_left_alone() {
+        let before = [square(0.0, 0.0, 100.0)];
+        let other = [polygon(&[(0.0, 0.0), (100.0, 0.0), (50.0, 100.0)])];
+        let after = [before[0].reverse_subpaths()];
+        assert!(sync_contours(&before, &after, &other, false).is_none());
+    }
+}
@@ -3,7 +3,29 @@
 //! This module handles all font-related data operations:
 //! - UFO (Unified Font Object) file format support
 //! - UFO format conversions and serialization
+//! - Character set and language coverage
+//! - Designspace source lookup for saving edits per master
+//! - Editing designspace axes, sources, instances and rules
+//! - Composite glyph recipes
+//! - Interpolation compatibility between masters
+//! - Interpolating glyphs between masters
+//! - Glyph layers besides the masters', such as backgrounds
+//! - Image files for reference images
+//! - Repeating outline edits in other masters
+//! - Renaming and deleting glyphs in UFO sources
+//! - Unicode names, scripts and categories, and glyph naming
 
+pub mod charsets;
+pub mod compatibility;
 pub mod conversions;
+pub mod designspace;
+pub mod designspace_editing;
 pub mod fontir_adapter;
+pub mod glyph_construction;
+pub mod glyph_set;
+pub mod images;
+pub mod interpolation;
+pub mod layers;
+pub mod master_sync;
 pub mod ufo;
+pub mod unicode;
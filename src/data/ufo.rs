@@ -38,31 +38,76 @@ pub fn load_ufo_from_path_compat(
 // Unicode Codepoint Mapping --------------------------------------------------
 
 /// Convert a Unicode character to its hex codepoint string
-fn char_to_hex_codepoint(unicode_char: char) -> String {
+pub fn char_to_hex_codepoint(unicode_char: char) -> String {
     format!("{:04X}", unicode_char as u32)
 }
 
 /// Add all codepoints from a glyph to the mapping
 fn add_glyph_codepoints_to_map(
-    map: &mut HashMap<String, String>,
-    glyph: &norad::Glyph,
+    map: &mut HashMap<String, Vec<String>>,
+    glyph_name: &str,
+    codepoints: &[char],
 ) {
-    for &unicode_char in &glyph.codepoints {
+    for &unicode_char in codepoints {
         let codepoint_hex = char_to_hex_codepoint(unicode_char);
-        map.insert(codepoint_hex, glyph.name().to_string());
+        map.entry(codepoint_hex)
+            .or_default()
+            .push(glyph_name.to_string());
     }
 }
 
+/// Build a map of Unicode codepoints to every glyph that has them
+///
+/// A codepoint should belong to one glyph only, see `duplicate_codepoints`.
+pub fn build_codepoint_glyphs_map<'a>(
+    glyphs: impl IntoIterator<Item = (&'a str, Vec<char>)>,
+) -> HashMap<String, Vec<String>> {
+    let mut codepoint_to_glyphs = HashMap::new();
+    for (glyph_name, codepoints) in glyphs {
+        add_glyph_codepoints_to_map(
+            &mut codepoint_to_glyphs,
+            glyph_name,
+            &codepoints,
+        );
+    }
+    codepoint_to_glyphs
+}
+
 /// Build a map of Unicode codepoints to glyph names for efficient lookups
+///
+/// A codepoint assigned to several glyphs maps to the first name in
+/// alphabetical order.
 fn build_codepoint_glyph_map(font: &Font) -> HashMap<String, String> {
-    let mut codepoint_to_glyph = HashMap::new();
-
     let layer = font.default_layer();
 
-    for glyph in layer.iter() {
-        add_glyph_codepoints_to_map(&mut codepoint_to_glyph, glyph);
-    }
-    codepoint_to_glyph
+    let glyphs = layer.iter().map(|glyph| {
+        let codepoints: Vec<char> = glyph.codepoints.iter().collect();
+        (glyph.name().as_str(), codepoints)
+    });
+    build_codepoint_glyphs_map(glyphs)
+        .into_iter()
+        .filter_map(|(codepoint_hex, glyph_names)| {
+            let glyph_name = glyph_names.into_iter().min()?;
+            Some((codepoint_hex, glyph_name))
+        })
+        .collect()
+}
+
+/// Codepoints assigned to more than one glyph, with those glyphs, sorted
+pub fn duplicate_codepoints(
+    codepoint_to_glyphs: &HashMap<String, Vec<String>>,
+) -> Vec<(String, Vec<String>)> {
+    let mut duplicates: Vec<(String, Vec<String>)> = codepoint_to_glyphs
+        .iter()
+        .filter(|(_, glyph_names)| glyph_names.len() > 1)
+        .map(|(codepoint_hex, glyph_names)| {
+            let mut glyph_names = glyph_names.clone();
+            glyph_names.sort();
+            (codepoint_hex.clone(), glyph_names)
+        })
+        .collect();
+    duplicates.sort();
+    duplicates
 }
 
 // Public Glyph Lookup API ---------------------------------------------------
@@ -0,0 +1,455 @@
+//! Unicode script, category and name lookup
+//!
+//! Scripts, general categories and character names come from the Unicode
+//! Character Database, through the `unicode-script`,
+//! `unicode-general-category` and `unicode_names2` crates. Categories are
+//! grouped into the broad general category groups, with letters split by
+//! case, which is what the glyph overview filters by.
+//!
+//! Suggested glyph names follow the Adobe Glyph List where it has a name,
+//! use the base + accent convention of the glyph recipes for accented
+//! letters and marks, and fall back to `uniXXXX` or `uXXXXX`.
+
+use crate::data::glyph_construction::{decomposed_glyph_name, mark_glyph_name};
+use anyhow::{anyhow, Result};
+use std::fmt;
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_script::UnicodeScript;
+
+/// Unicode script of a codepoint
+///
+/// Ordered by script name, so filters list scripts alphabetically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Script(unicode_script::Script);
+
+/// Broad Unicode general category of a codepoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    Uppercase,
+    Lowercase,
+    /// Letters without case, like Arabic or Han
+    Letter,
+    Mark,
+    Number,
+    Punctuation,
+    Symbol,
+    Separator,
+    /// Control, format, private use and unassigned characters
+    Other,
+}
+
+/// Adobe Glyph List names of characters whose glyph name isn't derived
+/// from a pattern, sorted by codepoint
+const GLYPH_NAMES: &[(char, &str)] = &[
+    (' ', "space"),
+    ('!', "exclam"),
+    ('"', "quotedbl"),
+    ('#', "numbersign"),
+    ('$', "dollar"),
+    ('%', "percent"),
+    ('&', "ampersand"),
+    ('\'', "quotesingle"),
+    ('(', "parenleft"),
+    (')', "parenright"),
+    ('*', "asterisk"),
+    ('+', "plus"),
+    (',', "comma"),
+    ('-', "hyphen"),
+    ('.', "period"),
+    ('/', "slash"),
+    (':', "colon"),
+    (';', "semicolon"),
+    ('<', "less"),
+    ('=', "equal"),
+    ('>', "greater"),
+    ('?', "question"),
+    ('@', "at"),
+    ('[', "bracketleft"),
+    ('\\', "backslash"),
+    (']', "bracketright"),
+    ('^', "asciicircum"),
+    ('_', "underscore"),
+    ('`', "grave"),
+    ('{', "braceleft"),
+    ('|', "bar"),
+    ('}', "braceright"),
+    ('~', "asciitilde"),
+    ('\u{00A0}', "uni00A0"),
+    ('\u{00A1}', "exclamdown"),
+    ('\u{00A2}', "cent"),
+    ('\u{00A3}', "sterling"),
+    ('\u{00A4}', "currency"),
+    ('\u{00A5}', "yen"),
+    ('\u{00A6}', "brokenbar"),
+    ('\u{00A7}', "section"),
+    ('\u{00A8}', "dieresis"),
+    ('\u{00A9}', "copyright"),
+    ('\u{00AA}', "ordfeminine"),
+    ('\u{00AB}', "guillemotleft"),
+    ('\u{00AC}', "logicalnot"),
+    ('\u{00AD}', "uni00AD"),
+    ('\u{00AE}', "registered"),
+    ('\u{00AF}', "macron"),
+    ('\u{00B0}', "degree"),
+    ('\u{00B1}', "plusminus"),
+    ('\u{00B2}', "twosuperior"),
+    ('\u{00B3}', "threesuperior"),
+    ('\u{00B4}', "acute"),
+    ('\u{00B5}', "mu"),
+    ('\u{00B6}', "paragraph"),
+    ('\u{00B7}', "periodcentered"),
+    ('\u{00B8}', "cedilla"),
+    ('\u{00B9}', "onesuperior"),
+    ('\u{00BA}', "ordmasculine"),
+    ('\u{00BB}', "guillemotright"),
+    ('\u{00BC}', "onequarter"),
+    ('\u{00BD}', "onehalf"),
+    ('\u{00BE}', "threequarters"),
+    ('\u{00BF}', "questiondown"),
+    ('\u{00C6}', "AE"),
+    ('\u{00D0}', "Eth"),
+    ('\u{00D7}', "multiply"),
+    ('\u{00D8}', "Oslash"),
+    ('\u{00DE}', "Thorn"),
+    ('\u{00DF}', "germandbls"),
+    ('\u{00E6}', "ae"),
+    ('\u{00F0}', "eth"),
+    ('\u{00F7}', "divide"),
+    ('\u{00F8}', "oslash"),
+    ('\u{00FE}', "thorn"),
+    ('\u{0110}', "Dcroat"),
+    ('\u{0111}', "dcroat"),
+    ('\u{0126}', "Hbar"),
+    ('\u{0127}', "hbar"),
+    ('\u{0131}', "dotlessi"),
+    ('\u{0132}', "IJ"),
+    ('\u{0133}', "ij"),
+    ('\u{0138}', "kgreenlandic"),
+    ('\u{013F}', "Ldot"),
+    ('\u{0140}', "ldot"),
+    ('\u{0141}', "Lslash"),
+    ('\u{0142}', "lslash"),
+    ('\u{0149}', "napostrophe"),
+    ('\u{014A}', "Eng"),
+    ('\u{014B}', "eng"),
+    ('\u{0152}', "OE"),
+    ('\u{0153}', "oe"),
+    ('\u{0166}', "Tbar"),
+    ('\u{0167}', "tbar"),
+    ('\u{017F}', "longs"),
+    ('\u{0192}', "florin"),
+    ('\u{0218}', "Scommaaccent"),
+    ('\u{0219}', "scommaaccent"),
+    ('\u{021A}', "uni021A"),
+    ('\u{021B}', "uni021B"),
+    ('\u{0237}', "uni0237"),
+    ('\u{02C6}', "circumflex"),
+    ('\u{02C7}', "caron"),
+    ('\u{02D8}', "breve"),
+    ('\u{02D9}', "dotaccent"),
+    ('\u{02DA}', "ring"),
+    ('\u{02DB}', "ogonek"),
+    ('\u{02DC}', "tilde"),
+    ('\u{02DD}', "hungarumlaut"),
+    ('\u{0394}', "uni0394"),
+    ('\u{03A9}', "uni03A9"),
+    ('\u{03BC}', "uni03BC"),
+    ('\u{2010}', "uni2010"),
+    ('\u{2013}', "endash"),
+    ('\u{2014}', "emdash"),
+    ('\u{2018}', "quoteleft"),
+    ('\u{2019}', "quoteright"),
+    ('\u{201A}', "quotesinglbase"),
+    ('\u{201C}', "quotedblleft"),
+    ('\u{201D}', "quotedblright"),
+    ('\u{201E}', "quotedblbase"),
+    ('\u{2020}', "dagger"),
+    ('\u{2021}', "daggerdbl"),
+    ('\u{2022}', "bullet"),
+    ('\u{2026}', "ellipsis"),
+    ('\u{2030}', "perthousand"),
+    ('\u{2039}', "guilsinglleft"),
+    ('\u{203A}', "guilsinglright"),
+    ('\u{2044}', "fraction"),
+    ('\u{20AC}', "Euro"),
+    ('\u{2122}', "trademark"),
+    ('\u{2202}', "partialdiff"),
+    ('\u{2212}', "minus"),
+    ('\u{221A}', "radical"),
+    ('\u{221E}', "infinity"),
+    ('\u{222B}', "integral"),
+    ('\u{2248}', "approxequal"),
+    ('\u{2260}', "notequal"),
+    ('\u{2264}', "lessequal"),
+    ('\u{2265}', "greaterequal"),
+    ('\u{25CA}', "lozenge"),
+    ('\u{FB01}', "fi"),
+    ('\u{FB02}', "fl"),
+];
+
+/// Names of the digits, in order
+const DIGIT_NAMES: [&str; 10] = [
+    "ZERO", "ONE", "TWO", "THREE", "FOUR", "FIVE", "SIX", "SEVEN", "EIGHT",
+    "NINE",
+];
+
+/// Glyph names of the basic Greek letters, in order from alpha; final
+/// sigma only exists in lowercase
+const GREEK_LETTERS: [&str; 25] = [
+    "alpha",
+    "beta",
+    "gamma",
+    "delta",
+    "epsilon",
+    "zeta",
+    "eta",
+    "theta",
+    "iota",
+    "kappa",
+    "lambda",
+    "mu",
+    "nu",
+    "xi",
+    "omicron",
+    "pi",
+    "rho",
+    "sigmafinal",
+    "sigma",
+    "tau",
+    "upsilon",
+    "phi",
+    "chi",
+    "psi",
+    "omega",
+];
+
+/// Script of a codepoint
+pub fn script(c: char) -> Script {
+    Script(c.script())
+}
+
+/// Broad general category of a codepoint
+pub fn category(c: char) -> Category {
+    match get_general_category(c) {
+        GeneralCategory::UppercaseLetter | GeneralCategory::TitlecaseLetter => {
+            Category::Uppercase
+        }
+        GeneralCategory::LowercaseLetter => Category::Lowercase,
+        GeneralCategory::ModifierLetter | GeneralCategory::OtherLetter => {
+            Category::Letter
+        }
+        GeneralCategory::NonspacingMark
+        | GeneralCategory::SpacingMark
+        | GeneralCategory::EnclosingMark => Category::Mark,
+        GeneralCategory::DecimalNumber
+        | GeneralCategory::LetterNumber
+        | GeneralCategory::OtherNumber => Category::Number,
+        GeneralCategory::ConnectorPunctuation
+        | GeneralCategory::DashPunctuation
+        | GeneralCategory::OpenPunctuation
+        | GeneralCategory::ClosePunctuation
+        | GeneralCategory::InitialPunctuation
+        | GeneralCategory::FinalPunctuation
+        | GeneralCategory::OtherPunctuation => Category::Punctuation,
+        GeneralCategory::MathSymbol
+        | GeneralCategory::CurrencySymbol
+        | GeneralCategory::ModifierSymbol
+        | GeneralCategory::OtherSymbol => Category::Symbol,
+        GeneralCategory::SpaceSeparator
+        | GeneralCategory::LineSeparator
+        | GeneralCategory::ParagraphSeparator => Category::Separator,
+        // Control, format, private use, surrogate and unassigned
+        _ => Category::Other,
+    }
+}
+
+/// Unicode name of a character, if it has one
+pub fn char_name(c: char) -> Option<String> {
+    unicode_names2::name(c).map(|name| name.to_string())
+}
+
+/// Suggested glyph name for a character
+///
+/// Uses the Adobe Glyph List name where there is one, base + accent names
+/// like `Aacute` for accented letters and the recipe names like
+/// `acutecomb` for combining marks, and `uniXXXX` or `uXXXXX` otherwise.
+pub fn glyph_name_for_char(c: char) -> String {
+    if let Some(glyph_name) = agl_glyph_name(c) {
+        return glyph_name.to_string();
+    }
+    if c.is_ascii_alphabetic() {
+        return c.to_string();
+    }
+    if c.is_ascii_digit() {
+        return DIGIT_NAMES[c as usize - '0' as usize].to_lowercase();
+    }
+    if let Some(glyph_name) = mark_glyph_name(c) {
+        return glyph_name.to_string();
+    }
+    if let Some(glyph_name) = decomposed_glyph_name(c) {
+        return glyph_name;
+    }
+    let codepoint = c as u32;
+    match codepoint {
+        0x0391..=0x03A9 if codepoint != 0x03A2 => {
+            let mut glyph_name =
+                GREEK_LETTERS[(codepoint - 0x0391) as usize].to_string();
+            glyph_name[..1].make_ascii_uppercase();
+            glyph_name
+        }
+        0x03B1..=0x03C9 => {
+            GREEK_LETTERS[(codepoint - 0x03B1) as usize].to_string()
+        }
+        _ => uni_glyph_name(c),
+    }
+}
+
+/// The `uniXXXX` glyph name of a character, or `uXXXXX` outside the Basic
+/// Multilingual Plane
+pub fn uni_glyph_name(c: char) -> String {
+    let codepoint = c as u32;
+    if codepoint <= 0xFFFF {
+        format!("uni{codepoint:04X}")
+    } else {
+        format!("u{codepoint:05X}")
+    }
+}
+
+/// Codepoints typed as hex values, like `0041 00C1` or `U+0041, U+00C1`
+pub fn parse_codepoints(text: &str) -> Result<Vec<char>> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let hex = token
+                .strip_prefix("U+")
+                .or_else(|| token.strip_prefix("u+"))
+                .unwrap_or(token);
+            u32::from_str_radix(hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| anyhow!("invalid codepoint '{token}'"))
+        })
+        .collect()
+}
+
+/// Glyph name of a character in `GLYPH_NAMES`
+fn agl_glyph_name(c: char) -> Option<&'static str> {
+    GLYPH_NAMES
+        .binary_search_by_key(&c, |&(codepoint, _)| codepoint)
+        .ok()
+        .map(|index| GLYPH_NAMES[index].1)
+}
+
+impl PartialOrd for Script {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Script {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.full_name().cmp(other.0.full_name())
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.full_name())
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let name = |c| script(c).to_string();
+        assert_eq!(name('a'), "Latin");
+        assert_eq!(name('\u{00E9}'), "Latin");
+        assert_eq!(name('\u{00D7}'), "Common");
+        assert_eq!(name('\u{0301}'), "Inherited");
+        assert_eq!(name('\u{03B1}'), "Greek");
+        assert_eq!(name('\u{0416}'), "Cyrillic");
+        assert_eq!(name('\u{0628}'), "Arabic");
+        assert_eq!(name('\u{060C}'), "Common");
+        assert_eq!(name('\u{1EA1}'), "Latin");
+        assert_eq!(name('1'), "Common");
+        assert_eq!(name('\u{1F600}'), "Common");
+        assert!(script('\u{0628}') < script('a'));
+    }
+
+    #[test]
+    fn test_category() {
+        assert_eq!(category('A'), Category::Uppercase);
+        assert_eq!(category('\u{00E9}'), Category::Lowercase);
+        assert_eq!(category('\u{0628}'), Category::Letter);
+        assert_eq!(category('\u{0301}'), Category::Mark);
+        assert_eq!(category('\u{064E}'), Category::Mark);
+        assert_eq!(category('7'), Category::Number);
+        assert_eq!(category(' '), Category::Separator);
+        assert_eq!(category(','), Category::Punctuation);
+        assert_eq!(category('\u{2014}'), Category::Punctuation);
+        assert_eq!(category('+'), Category::Symbol);
+        assert_eq!(category('\u{20AC}'), Category::Symbol);
+    }
+
+    #[test]
+    fn test_char_name() {
+        let name = |c| char_name(c).unwrap();
+        assert_eq!(name('A'), "LATIN CAPITAL LETTER A");
+        assert_eq!(name('z'), "LATIN SMALL LETTER Z");
+        assert_eq!(name('7'), "DIGIT SEVEN");
+        assert_eq!(name('&'), "AMPERSAND");
+        assert_eq!(name('\u{00C1}'), "LATIN CAPITAL LETTER A WITH ACUTE");
+        assert_eq!(name('\u{0123}'), "LATIN SMALL LETTER G WITH CEDILLA");
+        assert_eq!(name('\u{0301}'), "COMBINING ACUTE ACCENT");
+        assert_eq!(name('\u{03A3}'), "GREEK CAPITAL LETTER SIGMA");
+        assert_eq!(name('\u{03C2}'), "GREEK SMALL LETTER FINAL SIGMA");
+        assert_eq!(name('\u{0416}'), "CYRILLIC CAPITAL LETTER ZHE");
+        assert_eq!(name('\u{4E00}'), "CJK UNIFIED IDEOGRAPH-4E00");
+        assert_eq!(name('\u{AC00}'), "HANGUL SYLLABLE GA");
+        assert_eq!(name('\u{D7A3}'), "HANGUL SYLLABLE HIH");
+        assert_eq!(name('\u{0628}'), "ARABIC LETTER BEH");
+        assert_eq!(char_name('\u{03A2}'), None);
+    }
+
+    #[test]
+    fn test_glyph_name_for_char() {
+        assert_eq!(glyph_name_for_char('a'), "a");
+        assert_eq!(glyph_name_for_char('0'), "zero");
+        assert_eq!(glyph_name_for_char('-'), "hyphen");
+        assert_eq!(glyph_name_for_char('\u{00E9}'), "eacute");
+        assert_eq!(glyph_name_for_char('\u{0122}'), "Gcommaaccent");
+        assert_eq!(glyph_name_for_char('\u{0142}'), "lslash");
+        assert_eq!(glyph_name_for_char('\u{0302}'), "circumflexcomb");
+        assert_eq!(glyph_name_for_char('\u{0393}'), "Gamma");
+        assert_eq!(glyph_name_for_char('\u{03BB}'), "lambda");
+        assert_eq!(glyph_name_for_char('\u{03A9}'), "uni03A9");
+        assert_eq!(glyph_name_for_char('\u{20AC}'), "Euro");
+        assert_eq!(glyph_name_for_char('\u{0628}'), "uni0628");
+        assert_eq!(glyph_name_for_char('\u{1F600}'), "u1F600");
+    }
+
+    #[test]
+    fn test_parse_codepoints() {
+        assert_eq!(
+            parse_codepoints("0041 u+00c1, U+1F600").unwrap(),
+            vec!['A', '\u{00C1}', '\u{1F600}']
+        );
+        assert_eq!(parse_codepoints("  ").unwrap(), vec![]);
+        assert!(parse_codepoints("0041 Aacute").is_err());
+        assert!(parse_codepoints("D800").is_err());
+    }
+
+    #[test]
+    fn test_glyph_names_sorted() {
+        assert!(GLYPH_NAMES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
@@ -0,0 +1,530 @@
+//! Anchor editing
+//!
+//! Anchors are named positions in a glyph used for mark attachment and for
+//! building composites. Each anchor of the active sort is spawned as a
+//! selectable entity, so the regular selection, drag and nudge systems work
+//! on anchors just like on points. Moved anchors are written back into the
+//! FontIR working copy, which is saved into the glif's anchor list.
+//!
+//! Keyboard shortcuts:
+//! - Command/Ctrl+Shift+N adds an anchor under the pointer
+//! - Command/Ctrl+Shift+L renames the selected anchors to the next standard
+//!   name (holding Alt as well goes back to the previous name)
+//! - Command/Ctrl+Shift+U toggles the `_` mark prefix of the selected anchors
+//! - Command/Ctrl+Alt+N types a new name for the selected anchors into the
+//!   name entry pane
+//! - Delete/Backspace removes the selected anchors
+
+use crate::core::io::input::{helpers, InputMode, InputState};
+use crate::core::io::pointer::PointerInfo;
+use crate::core::state::{AnchorData, FontIRAppState, MARK_ANCHOR_PREFIX};
+use crate::editing::selection::components::{Selectable, Selected};
+use crate::editing::selection::events::AppStateChanged;
+use crate::editing::selection::nudge::PointCoordinates;
+use crate::editing::selection::SelectionState;
+use crate::editing::sort::{ActiveSort, Sort};
+use crate::rendering::unified_glyph_editing::SortVisualUpdateTracker;
+use crate::systems::sort_manager::SortPointEntity;
+use bevy::prelude::*;
+
+/// Common anchor names, in the order they are offered when adding or
+/// renaming anchors
+pub const STANDARD_ANCHOR_NAMES: &[&str] = &[
+    "top",
+    "bottom",
+    "center",
+    "ogonek",
+    "cedilla",
+    "horn",
+    "topleft",
+    "topright",
+    "bottomleft",
+    "bottomright",
+    "entry",
+    "exit",
+];
+
+/// Z-level of anchor entities (same layer as points)
+const ANCHOR_Z: f32 = 10.0;
+
+/// Component that links an entity to an anchor of a glyph
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct AnchorReference {
+    /// Name of the glyph this anchor belongs to
+    pub glyph_name: String,
+    /// Index of the anchor within the glyph's anchor list
+    pub anchor_index: usize,
+}
+
+/// How to rename the selected anchors
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnchorRename {
+    /// Use this exact name
+    Set(String),
+    /// Next name in `STANDARD_ANCHOR_NAMES`
+    Next,
+    /// Previous name in `STANDARD_ANCHOR_NAMES`
+    Previous,
+    /// Add or remove the `_` mark prefix
+    ToggleMark,
+}
+
+/// Add an anchor to the current glyph
+#[derive(Event)]
+pub struct AddAnchorEvent {
+    pub name: String,
+    /// Position in glyph coordinates
+    pub position: Vec2,
+}
+
+/// Rename the selected anchors
+#[derive(Event)]
+pub struct RenameAnchorsEvent {
+    pub rename: AnchorRename,
+}
+
+/// Remove the selected anchors
+#[derive(Event)]
+pub struct DeleteAnchorsEvent;
+
+pub struct AnchorPlugin;
+
+impl Plugin for AnchorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AnchorReference>()
+            .add_event::<AddAnchorEvent>()
+            .add_event::<RenameAnchorsEvent>()
+            .add_event::<DeleteAnchorsEvent>()
+            .add_systems(
+                Update,
+                (
+                    handle_anchor_shortcuts,
+                    handle_add_anchor,
+                    handle_rename_anchors,
+                    handle_delete_anchors,
+                    sync_anchor_entities,
+                    update_anchors_from_entities,
+                )
+                    .chain()
+                    .after(
+                        crate::editing::selection::nudge::handle_nudge_input,
+                    ),
+            );
+    }
+}
+
+/// Next (or previous) standard name for an anchor, keeping its mark prefix
+///
+/// Names that are not in the standard list start over at the first one.
+pub fn cycle_anchor_name(name: &str, forward: bool) -> String {
+    let is_mark = name.starts_with(MARK_ANCHOR_PREFIX);
+    let base = name.trim_start_matches(MARK_ANCHOR_PREFIX);
+    let count = STANDARD_ANCHOR_NAMES.len();
+    let next = match STANDARD_ANCHOR_NAMES.iter().position(|n| *n == base) {
+        Some(index) if forward => STANDARD_ANCHOR_NAMES[(index + 1) % count],
+        Some(index) => STANDARD_ANCHOR_NAMES[(index + count - 1) % count],
+        None => STANDARD_ANCHOR_NAMES[0],
+    };
+    if is_mark {
+        format!("{MARK_ANCHOR_PREFIX}{next}")
+    } else {
+        next.to_string()
+    }
+}
+
+/// Add or remove the `_` prefix of a mark anchor name
+pub fn toggle_mark_prefix(name: &str) -> String {
+    match name.strip_prefix(MARK_ANCHOR_PREFIX) {
+        Some(base) => base.to_string(),
+        None => format!("{MARK_ANCHOR_PREFIX}{name}"),
+    }
+}
+
+/// First standard name not used by any of the given anchors
+///
+/// Falls back to numbered names once all standard names are taken.
+pub fn unused_anchor_name(anchors: &[AnchorData]) -> String {
+    let is_used = |name: &str| anchors.iter().any(|a| a.name == name);
+    STANDARD_ANCHOR_NAMES
+        .iter()
+        .find(|name| !is_used(name))
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| {
+            (1..)
+                .map(|n| format!("anchor{n}"))
+                .find(|name| !is_used(name))
+                .unwrap_or_default()
+        })
+}
+
+/// System to handle keyboard shortcuts for anchors
+#[allow(clippy::too_many_arguments)]
+pub fn handle_anchor_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    input_state: Res<InputState>,
+    pointer_info: Res<PointerInfo>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    active_sort: Query<(&Sort, &Transform), With<ActiveSort>>,
+    selected_anchors: Query<(), (With<AnchorReference>, With<Selected>)>,
+    mut add_event: EventWriter<AddAnchorEvent>,
+    mut rename_event: EventWriter<RenameAnchorsEvent>,
+    mut delete_event: EventWriter<DeleteAnchorsEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+
+    if !modifier_pressed
+        && (keyboard.just_pressed(KeyCode::Delete)
+            || keyboard.just_pressed(KeyCode::Backspace))
+        && helpers::is_input_mode(&input_state, InputMode::Select)
+        && !selected_anchors.is_empty()
+    {
+        delete_event.write(DeleteAnchorsEvent);
+        return;
+    }
+
+    if !(modifier_pressed && shift_pressed) {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyN) {
+        let Some(fontir_state) = fontir_state else {
+            return;
+        };
+        let Ok((sort, sort_transform)) = active_sort.single() else {
+            debug!("Add anchor: no active sort");
+            return;
+        };
+        let position = (pointer_info.design.to_raw()
+            - sort_transform.translation.truncate())
+        .round();
+        add_event.write(AddAnchorEvent {
+            name: unused_anchor_name(
+                &fontir_state.glyph_anchors(&sort.glyph_name),
+            ),
+            position,
+        });
+    } else if keyboard.just_pressed(KeyCode::KeyL) {
+        let rename = if alt_pressed {
+            AnchorRename::Previous
+        } else {
+            AnchorRename::Next
+        };
+        rename_event.write(RenameAnchorsEvent { rename });
+    } else if keyboard.just_pressed(KeyCode::KeyU) {
+        rename_event.write(RenameAnchorsEvent {
+            rename: AnchorRename::ToggleMark,
+        });
+    }
+}
+
+/// Handler for adding anchors to the glyph of the active sort
+fn handle_add_anchor(
+    mut events: EventReader<AddAnchorEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    active_sort: Query<&Sort, With<ActiveSort>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Add anchor requested but FontIR state is not available");
+            return;
+        };
+        let Ok(sort) = active_sort.single() else {
+            continue;
+        };
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&sort.glyph_name)
+        else {
+            continue;
+        };
+
+        working_copy.anchors.push(AnchorData::new(
+            event.name.clone(),
+            event.position.x as f64,
+            event.position.y as f64,
+        ));
+        working_copy.is_dirty = true;
+        visual_update.needs_update = true;
+        info!(
+            "Added anchor '{}' to '{}' at ({}, {})",
+            event.name, sort.glyph_name, event.position.x, event.position.y
+        );
+    }
+}
+
+/// Handler for renaming the selected anchors
+fn handle_rename_anchors(
+    mut events: EventReader<RenameAnchorsEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    selected_anchors: Query<&AnchorReference, With<Selected>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        for anchor_ref in selected_anchors.iter() {
+            let Some(working_copy) =
+                fontir_state.get_or_create_working_copy(&anchor_ref.glyph_name)
+            else {
+                continue;
+            };
+            let Some(anchor) =
+                working_copy.anchors.get_mut(anchor_ref.anchor_index)
+            else {
+                continue;
+            };
+
+            let name = match &event.rename {
+                AnchorRename::Set(name) => name.clone(),
+                AnchorRename::Next => cycle_anchor_name(&anchor.name, true),
+                AnchorRename::Previous => {
+                    cycle_anchor_name(&anchor.name, false)
+                }
+                AnchorRename::ToggleMark => toggle_mark_prefix(&anchor.name),
+            };
+            info!(
+                "Renamed anchor '{}' of '{}' to '{}'",
+                anchor.name, anchor_ref.glyph_name, name
+            );
+            anchor.name = name;
+            working_copy.is_dirty = true;
+            visual_update.needs_update = true;
+        }
+    }
+}
+
+/// Handler for removing the selected anchors
+fn handle_delete_anchors(
+    mut events: EventReader<DeleteAnchorsEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    selected_anchors: Query<&AnchorReference, With<Selected>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    for _ in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        let mut targets: Vec<&AnchorReference> =
+            selected_anchors.iter().collect();
+        // Remove from the back so earlier indices stay valid
+        targets.sort_by_key(|anchor_ref| {
+            std::cmp::Reverse(anchor_ref.anchor_index)
+        });
+
+        for anchor_ref in targets {
+            let Some(working_copy) =
+                fontir_state.get_or_create_working_copy(&anchor_ref.glyph_name)
+            else {
+                continue;
+            };
+            if anchor_ref.anchor_index < working_copy.anchors.len() {
+                let anchor =
+                    working_copy.anchors.remove(anchor_ref.anchor_index);
+                working_copy.is_dirty = true;
+                visual_update.needs_update = true;
+                info!(
+                    "Removed anchor '{}' from '{}'",
+                    anchor.name, anchor_ref.glyph_name
+                );
+            }
+        }
+    }
+}
+
+/// Keep one anchor entity per anchor of the active sort's glyph
+///
+/// Anchor entities are respawned when the active sort or its glyph changes,
+/// when anchors are added or removed, and when the font data changes.
+/// Otherwise unselected anchors just follow the sort position.
+#[allow(clippy::type_complexity)]
+pub fn sync_anchor_entities(
+    mut commands: Commands,
+    mut app_state_events: EventReader<AppStateChanged>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    active_sort: Query<(Entity, &Sort, &Transform), With<ActiveSort>>,
+    mut anchor_entities: Query<
+        (
+            Entity,
+            &AnchorReference,
+            &SortPointEntity,
+            &mut Transform,
+            Has<Selected>,
+        ),
+        Without<Sort>,
+    >,
+    mut selection_state: ResMut<SelectionState>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    let font_changed = app_state_events.read().count() > 0;
+    let active = active_sort.single().ok();
+    let anchors = match (&fontir_state, active) {
+        (Some(fontir_state), Some((_, sort, _))) => {
+            fontir_state.glyph_anchors(&sort.glyph_name)
+        }
+        _ => Vec::new(),
+    };
+
+    let stale = anchor_entities.iter().any(|(_, anchor_ref, parent, _, _)| {
+        active.is_none_or(|(sort_entity, sort, _)| {
+            parent.sort_entity != sort_entity
+                || anchor_ref.glyph_name != sort.glyph_name
+        })
+    });
+    let rebuild = font_changed
+        || stale
+        || anchor_entities.iter().count() != anchors.len();
+
+    if !rebuild {
+        // Selected anchors are moved by drag and nudge, the rest follow the
+        // sort
+        let Some((_, _, sort_transform)) = active else {
+            return;
+        };
+        let origin = sort_transform.translation.truncate();
+        for (_, anchor_ref, _, mut transform, selected) in
+            anchor_entities.iter_mut()
+        {
+            let Some(anchor) = anchors.get(anchor_ref.anchor_index) else {
+                continue;
+            };
+            let position = origin + Vec2::new(anchor.x as f32, anchor.y as f32);
+            if !selected && transform.translation.truncate() != position {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
+        }
+        return;
+    }
+
+    for (entity, _, _, _, _) in anchor_entities.iter() {
+        selection_state.selected.remove(&entity);
+        commands.entity(entity).try_despawn();
+    }
+
+    let Some((sort_entity, sort, sort_transform)) = active else {
+        return;
+    };
+    let origin = sort_transform.translation.truncate();
+    for (anchor_index, anchor) in anchors.iter().enumerate() {
+        let position = origin + Vec2::new(anchor.x as f32, anchor.y as f32);
+        commands.spawn((
+            Transform::from_xyz(position.x, position.y, ANCHOR_Z),
+            GlobalTransform::default(),
+            Visibility::default(),
+            InheritedVisibility::default(),
+            ViewVisibility::default(),
+            PointCoordinates {
+                x: position.x,
+                y: position.y,
+            },
+            AnchorReference {
+                glyph_name: sort.glyph_name.clone(),
+                anchor_index,
+            },
+            Selectable,
+            SortPointEntity { sort_entity },
+            Name::new(format!("Anchor[{}]", anchor.name)),
+        ));
+    }
+
+    debug!(
+        "Spawned {} anchor entities for '{}'",
+        anchors.len(),
+        sort.glyph_name
+    );
+    visual_update.needs_update = true;
+}
+
+/// Write positions of dragged or nudged anchors into the working copy
+#[allow(clippy::type_complexity)]
+pub fn update_anchors_from_entities(
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    moved_anchors: Query<
+        (&AnchorReference, &SortPointEntity, &Transform),
+        (With<Selected>, Changed<Transform>, Without<Sort>),
+    >,
+    sort_query: Query<&Transform, With<Sort>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    let Some(fontir_state) = fontir_state.as_mut() else {
+        return;
+    };
+
+    for (anchor_ref, parent, transform) in moved_anchors.iter() {
+        let Ok(sort_transform) = sort_query.get(parent.sort_entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate()
+            - sort_transform.translation.truncate();
+        let (x, y) = (position.x as f64, position.y as f64);
+
+        // Only touch the working copy when the anchor actually moved
+        let unchanged = fontir_state
+            .glyph_anchors(&anchor_ref.glyph_name)
+            .get(anchor_ref.anchor_index)
+            .is_none_or(|anchor| anchor.x == x && anchor.y == y);
+        if unchanged {
+            continue;
+        }
+
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&anchor_ref.glyph_name)
+        else {
+            continue;
+        };
+        if let Some(anchor) =
+            working_copy.anchors.get_mut(anchor_ref.anchor_index)
+        {
+            anchor.x = x;
+            anchor.y = y;
+            working_copy.is_dirty = true;
+            visual_update.needs_update = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_anchor_name() {
+        assert_eq!(cycle_anchor_name("top", true), "bottom");
+        assert_eq!(cycle_anchor_name("top", false), "exit");
+        assert_eq!(cycle_anchor_name("exit", true), "top");
+        assert_eq!(cycle_anchor_name("_top", true), "_bottom");
+        assert_eq!(cycle_anchor_name("custom", true), "top");
+    }
+
+    #[test]
+    fn test_toggle_mark_prefix() {
+        assert_eq!(toggle_mark_prefix("top"), "_top");
+        assert_eq!(toggle_mark_prefix("_top"), "top");
+    }
+
+    #[test]
+    fn test_unused_anchor_name() {
+        assert_eq!(unused_anchor_name(&[]), "top");
+
+        let anchors = vec![
+            AnchorData::new("top", 0.0, 0.0),
+            AnchorData::new("bottom", 0.0, 0.0),
+        ];
+        assert_eq!(unused_anchor_name(&anchors), "center");
+
+        let all: Vec<AnchorData> = STANDARD_ANCHOR_NAMES
+            .iter()
+            .map(|name| AnchorData::new(*name, 0.0, 0.0))
+            .collect();
+        assert_eq!(unused_anchor_name(&all), "anchor1");
+    }
+}
@@ -0,0 +1,167 @@
+//! Edit all masters
+//!
+//! With the mode on (Command/Ctrl+Alt+L), edits made to a glyph in the
+//! current master are repeated in its other masters: points inserted or
+//! removed, contours added or removed, a new start point or direction, and
+//! components added, removed or given another base. Moved points and
+//! components are moved by the same offset in the other masters too when
+//! asked for (Command/Ctrl+Alt+O).
+//!
+//! Edits are found by comparing each glyph's working copy at the current
+//! master with how it looked the frame before, so every tool takes part
+//! without knowing about the mode; see `data::master_sync` for how an edit
+//! is repeated.
+
+use crate::core::state::font_data::ComponentData;
+use crate::core::state::FontIRAppState;
+use crate::systems::commands::BackgroundLayerEvent;
+use bevy::prelude::*;
+use fontdrasil::coords::NormalizedLocation;
+use kurbo::BezPath;
+use std::collections::HashMap;
+
+/// Whether edits are repeated in all masters
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct EditAllMasters {
+    pub enabled: bool,
+    /// Also move points and components in the other masters by the offset
+    /// they were moved by
+    pub move_points: bool,
+}
+
+/// Outline and components of a glyph as of the last frame
+type GlyphSnapshot = (Vec<BezPath>, Vec<ComponentData>);
+
+/// Snapshots of the glyphs edited at a location
+type Snapshots = Option<(NormalizedLocation, HashMap<String, GlyphSnapshot>)>;
+
+pub struct EditAllMastersPlugin;
+
+impl Plugin for EditAllMastersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditAllMasters>()
+            .add_systems(Update, handle_edit_all_masters_shortcuts)
+            .add_systems(PostUpdate, sync_edits_to_other_masters);
+    }
+}
+
+/// Toggles the mode with Command/Ctrl+Alt+L, and moving points with
+/// Command/Ctrl+Alt+O
+///
+/// Command/Ctrl+Shift+W would close the window on macOS.
+fn handle_edit_all_masters_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut edit_all_masters: ResMut<EditAllMasters>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+    if !modifier_pressed || !alt_pressed || shift_pressed {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyO) {
+        edit_all_masters.move_points = !edit_all_masters.move_points;
+        info!(
+            "Edit all masters: moving points {}",
+            if edit_all_masters.move_points {
+                "on"
+            } else {
+                "off"
+            }
+        );
+    } else if keyboard.just_pressed(KeyCode::KeyL) {
+        edit_all_masters.enabled = !edit_all_masters.enabled;
+        info!(
+            "Edit all masters {}",
+            if edit_all_masters.enabled {
+                "on"
+            } else {
+                "off"
+            }
+        );
+    }
+}
+
+/// Repeats the edits made this frame at the current master in the other
+/// masters
+///
+/// Working copies are compared with their snapshot from the last frame, or
+/// with the glyph as loaded if the working copy was created this frame. The
+/// snapshots are retaken whenever the mode is switched on or the location
+/// moves, so earlier edits aren't repeated. Swapping a glyph with its
+/// background isn't an edit, and edits to a background stay in it.
+pub(crate) fn sync_edits_to_other_masters(
+    edit_all_masters: Res<EditAllMasters>,
+    fontir_state: Option<ResMut<FontIRAppState>>,
+    mut background_events: EventReader<BackgroundLayerEvent>,
+    mut snapshots: Local<Snapshots>,
+) {
+    let Some(mut fontir_state) = fontir_state else {
+        *snapshots = None;
+        return;
+    };
+    let background_changed = background_events.read().count() > 0;
+    if !edit_all_masters.enabled
+        || !fontir_state.is_at_master()
+        || fontir_state.editing_background.is_some()
+        || background_changed
+    {
+        *snapshots = None;
+        return;
+    }
+
+    let location = fontir_state.current_location.clone();
+    let is_current = snapshots
+        .as_ref()
+        .is_some_and(|(snapshot_location, _)| *snapshot_location == location);
+    if is_current && !fontir_state.is_changed() {
+        return;
+    }
+
+    let edited: Vec<(String, GlyphSnapshot)> = fontir_state
+        .working_copies
+        .iter()
+        .filter(|((_, working_location), _)| *working_location == location)
+        .map(|((glyph_name, _), working_copy)| {
+            let snapshot = (
+                working_copy.contours.clone(),
+                working_copy.components.clone(),
+            );
+            (glyph_name.clone(), snapshot)
+        })
+        .collect();
+    if !is_current {
+        *snapshots = Some((location, edited.into_iter().collect()));
+        return;
+    }
+    let Some((_, glyphs)) = snapshots.as_mut() else {
+        return;
+    };
+
+    for (glyph_name, current) in edited {
+        let before = match glyphs.get(&glyph_name) {
+            Some(before) => Some(before.clone()),
+            None => fontir_state.original_glyph_outline(&glyph_name, &location),
+        };
+        if let Some(before) = before.filter(|before| *before != current) {
+            let changed = fontir_state.sync_edit_to_other_masters(
+                &glyph_name,
+                &before,
+                edit_all_masters.move_points,
+            );
+            if changed > 0 {
+                debug!(
+                    "Repeated the edit of '{}' in {} other master(s)",
+                    glyph_name, changed
+                );
+            }
+        }
+        glyphs.insert(glyph_name, current);
+    }
+}
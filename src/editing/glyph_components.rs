@@ -0,0 +1,593 @@
+//! Component editing
+//!
+//! Composite glyphs reference other glyphs through components, each with its
+//! own affine transform. This module lets the user select a component of the
+//! active glyph by clicking inside it, move it with the arrow keys, scale or
+//! flip it, swap its base glyph, decompose it into plain contours, and open
+//! its base glyph in a new sort. Components are added and their base glyphs
+//! swapped by typing a glyph name into the name entry pane. All edits go to
+//! the FontIR working copy, so they are written back to the UFO component
+//! data on save.
+
+use crate::core::io::input::{helpers, InputEvent, InputMode, InputState};
+use crate::core::settings::BezySettings;
+use crate::core::state::{ComponentData, FontIRAppState, TextEditorState};
+use crate::editing::selection::components::{Selectable, Selected};
+use crate::editing::selection::events::{AppStateChanged, SELECTION_MARGIN};
+use crate::editing::sort::{ActiveSort, Sort};
+use crate::rendering::unified_glyph_editing::SortVisualUpdateTracker;
+use crate::ui::themes::CurrentTheme;
+use bevy::input::mouse::MouseButton;
+use bevy::prelude::*;
+use kurbo::{Affine, BezPath, PathEl, Point, Rect, Shape};
+
+/// Gap between a composite glyph and a sort opened for its base glyph
+const BASE_SORT_GAP: f32 = 100.0;
+
+/// Scale factor applied by a single scale shortcut
+const SCALE_STEP: f64 = 1.1;
+
+/// Tolerance used when flattening component outlines for highlighting
+const HIGHLIGHT_TOLERANCE: f64 = 0.5;
+
+/// The currently selected component, if any
+#[derive(Resource, Debug, Default, Clone)]
+pub struct ComponentSelection {
+    /// Glyph containing the component and the component's index in it
+    pub selected: Option<(String, usize)>,
+}
+
+impl ComponentSelection {
+    /// Index of the selected component if it belongs to `glyph_name`
+    pub fn index_in(&self, glyph_name: &str) -> Option<usize> {
+        self.selected
+            .as_ref()
+            .filter(|(name, _)| name == glyph_name)
+            .map(|(_, index)| *index)
+    }
+}
+
+/// A change to the transform of the selected component
+///
+/// Scaling and flipping happen around the center of the component's bounds
+/// so the component stays in place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentTransform {
+    /// Move by (x, y) font units
+    Translate(f64, f64),
+    /// Scale horizontally and vertically
+    Scale(f64, f64),
+    FlipHorizontal,
+    FlipVertical,
+    /// Replace the transform with a UFO matrix (xx, xy, yx, yy, x, y)
+    Set([f64; 6]),
+}
+
+/// Add a component referencing `base_glyph` to the current glyph
+#[derive(Event)]
+pub struct AddComponentEvent {
+    pub base_glyph: String,
+}
+
+/// Change the transform of the selected component
+#[derive(Event)]
+pub struct TransformComponentEvent {
+    pub transform: ComponentTransform,
+}
+
+/// Make the selected component reference a different base glyph
+#[derive(Event)]
+pub struct SetComponentBaseEvent {
+    pub base_glyph: String,
+}
+
+/// Replace components with their outlines
+///
+/// Decomposes the selected component, or every component of the current
+/// glyph when `all` is set, in every master so they stay compatible.
+#[derive(Event)]
+pub struct DecomposeComponentsEvent {
+    pub all: bool,
+}
+
+/// Open the base glyph of the selected component in a new sort
+#[derive(Event)]
+pub struct OpenComponentBaseEvent;
+
+pub struct GlyphComponentsPlugin;
+
+impl Plugin for GlyphComponentsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ComponentSelection>()
+            .add_event::<AddComponentEvent>()
+            .add_event::<TransformComponentEvent>()
+            .add_event::<SetComponentBaseEvent>()
+            .add_event::<DecomposeComponentsEvent>()
+            .add_event::<OpenComponentBaseEvent>()
+            .add_systems(
+                Update,
+                (
+                    select_component_on_click,
+                    handle_component_shortcuts,
+                    handle_add_component,
+                    handle_transform_component,
+                    handle_set_component_base,
+                    handle_decompose_components,
+                    handle_open_component_base,
+                )
+                    .chain(),
+            )
+            .add_systems(PostUpdate, render_selected_component);
+    }
+}
+
+/// Select the component under a click on the active sort
+///
+/// Clicks near a point are left to point selection; clicks anywhere else
+/// either select the component they land in or clear the selection.
+pub fn select_component_on_click(
+    mut input_events: EventReader<InputEvent>,
+    input_state: Res<InputState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    active_sort: Query<(&Sort, &Transform), With<ActiveSort>>,
+    selectables: Query<&GlobalTransform, With<Selectable>>,
+    mut selection: ResMut<ComponentSelection>,
+) {
+    for event in input_events.read() {
+        let InputEvent::MouseClick {
+            button: MouseButton::Left,
+            position,
+            ..
+        } = event
+        else {
+            continue;
+        };
+        if !helpers::is_input_mode(&input_state, InputMode::Select)
+            || helpers::is_ui_consuming(&input_state)
+        {
+            continue;
+        }
+        let Some(fontir_state) = fontir_state.as_ref() else {
+            continue;
+        };
+        let Ok((sort, sort_transform)) = active_sort.single() else {
+            continue;
+        };
+
+        let world_position = position.to_raw();
+        let on_point = selectables.iter().any(|transform| {
+            transform.translation().truncate().distance(world_position)
+                < SELECTION_MARGIN
+        });
+        if on_point {
+            continue;
+        }
+
+        let local = world_position - sort_transform.translation.truncate();
+        let hit = component_at(
+            fontir_state,
+            &sort.glyph_name,
+            Point::new(local.x as f64, local.y as f64),
+        );
+        let new_selection = hit.map(|index| (sort.glyph_name.clone(), index));
+        if selection.selected != new_selection {
+            if let Some((glyph_name, index)) = &new_selection {
+                info!("Selected component {} of '{}'", index, glyph_name);
+            }
+            selection.selected = new_selection;
+        }
+    }
+}
+
+/// Index of the topmost component of a glyph containing a point
+fn component_at(
+    fontir_state: &FontIRAppState,
+    glyph_name: &str,
+    point: Point,
+) -> Option<usize> {
+    fontir_state
+        .component_paths(glyph_name)
+        .iter()
+        .rposition(|paths| {
+            paths.iter().map(|path| path.winding(point)).sum::<i32>() != 0
+        })
+}
+
+/// System to handle keyboard shortcuts for the selected component
+///
+/// With a component selected and no points selected, the arrow keys move the
+/// component by the nudge amounts. Command/Ctrl+Shift+H and +V flip it,
+/// Command/Ctrl+Alt+= and +- scale it up and down, Command/Ctrl+Shift+J opens
+/// its base glyph, and Command/Ctrl+Shift+D decomposes it (holding Alt as well
+/// decomposes every component of the glyph).
+pub fn handle_component_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<BezySettings>,
+    selection: Res<ComponentSelection>,
+    selected_points: Query<(), With<Selected>>,
+    mut transform_event: EventWriter<TransformComponentEvent>,
+    mut decompose_event: EventWriter<DecomposeComponentsEvent>,
+    mut open_base_event: EventWriter<OpenComponentBaseEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+
+    if modifier_pressed && shift_pressed && keyboard.just_pressed(KeyCode::KeyD)
+    {
+        decompose_event.write(DecomposeComponentsEvent { all: alt_pressed });
+        return;
+    }
+
+    if selection.selected.is_none() {
+        return;
+    }
+
+    let mut transform = None;
+    if modifier_pressed && shift_pressed {
+        if keyboard.just_pressed(KeyCode::KeyH) {
+            transform = Some(ComponentTransform::FlipHorizontal);
+        } else if keyboard.just_pressed(KeyCode::KeyV) {
+            transform = Some(ComponentTransform::FlipVertical);
+        } else if keyboard.just_pressed(KeyCode::KeyJ) {
+            open_base_event.write(OpenComponentBaseEvent);
+        }
+    } else if modifier_pressed && alt_pressed {
+        if keyboard.just_pressed(KeyCode::Equal) {
+            transform = Some(ComponentTransform::Scale(SCALE_STEP, SCALE_STEP));
+        } else if keyboard.just_pressed(KeyCode::Minus) {
+            transform = Some(ComponentTransform::Scale(
+                1.0 / SCALE_STEP,
+                1.0 / SCALE_STEP,
+            ));
+        }
+    } else if selected_points.is_empty() {
+        // Points take precedence over the component for nudging
+        let amount = if shift_pressed {
+            settings.nudge.shift
+        } else if modifier_pressed {
+            settings.nudge.cmd
+        } else {
+            settings.nudge.default
+        } as f64;
+        let delta = if keyboard.just_pressed(KeyCode::ArrowLeft) {
+            Some((-amount, 0.0))
+        } else if keyboard.just_pressed(KeyCode::ArrowRight) {
+            Some((amount, 0.0))
+        } else if keyboard.just_pressed(KeyCode::ArrowUp) {
+            Some((0.0, amount))
+        } else if keyboard.just_pressed(KeyCode::ArrowDown) {
+            Some((0.0, -amount))
+        } else {
+            None
+        };
+        transform = delta.map(|(x, y)| ComponentTransform::Translate(x, y));
+    }
+
+    if let Some(transform) = transform {
+        transform_event.write(TransformComponentEvent { transform });
+    }
+}
+
+/// Handler for adding components to the current glyph
+fn handle_add_component(
+    mut events: EventReader<AddComponentEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut selection: ResMut<ComponentSelection>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Add component requested but FontIR state is not available");
+            return;
+        };
+        let Some(glyph_name) = fontir_state.current_glyph.clone() else {
+            continue;
+        };
+        if fontir_state.get_glyph(&event.base_glyph).is_none() {
+            warn!(
+                "Cannot add component: no glyph named '{}'",
+                event.base_glyph
+            );
+            continue;
+        }
+        if event.base_glyph == glyph_name {
+            warn!("Cannot add '{}' as a component of itself", glyph_name);
+            continue;
+        }
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&glyph_name)
+        else {
+            continue;
+        };
+
+        working_copy
+            .components
+            .push(ComponentData::new(event.base_glyph.clone()));
+        working_copy.is_dirty = true;
+        let index = working_copy.components.len() - 1;
+        selection.selected = Some((glyph_name.clone(), index));
+
+        info!("Added component '{}' to '{}'", event.base_glyph, glyph_name);
+        app_state_changed.write(AppStateChanged);
+        visual_update.needs_update = true;
+    }
+}
+
+/// Handler for moving, scaling and flipping the selected component
+fn handle_transform_component(
+    mut events: EventReader<TransformComponentEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    selection: Res<ComponentSelection>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        let Some((glyph_name, index)) = selection.selected.clone() else {
+            continue;
+        };
+
+        // Scale and flip around the middle of the component's outline
+        let center = fontir_state
+            .component_paths(&glyph_name)
+            .get(index)
+            .and_then(|paths| bounds(paths.as_slice()))
+            .map(|rect| rect.center())
+            .unwrap_or_default();
+
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&glyph_name)
+        else {
+            continue;
+        };
+        let Some(component) = working_copy.components.get_mut(index) else {
+            warn!(
+                "Cannot transform component {} of '{}': no such component",
+                index, glyph_name
+            );
+            continue;
+        };
+
+        let around_center = |affine: Affine| {
+            Affine::translate(center.to_vec2())
+                * affine
+                * Affine::translate(-center.to_vec2())
+        };
+        let change = match event.transform {
+            ComponentTransform::Translate(x, y) => Affine::translate((x, y)),
+            ComponentTransform::Scale(x, y) => {
+                around_center(Affine::scale_non_uniform(x, y))
+            }
+            ComponentTransform::FlipHorizontal => around_center(Affine::FLIP_X),
+            ComponentTransform::FlipVertical => around_center(Affine::FLIP_Y),
+            ComponentTransform::Set(matrix) => {
+                component.transform = matrix;
+                Affine::IDENTITY
+            }
+        };
+        component.set_affine(change * component.affine());
+
+        debug!(
+            "Component {} of '{}' transform is now {:?}",
+            index, glyph_name, component.transform
+        );
+        working_copy.is_dirty = true;
+        app_state_changed.write(AppStateChanged);
+        visual_update.needs_update = true;
+    }
+}
+
+/// Handler for swapping the base glyph of the selected component
+fn handle_set_component_base(
+    mut events: EventReader<SetComponentBaseEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    selection: Res<ComponentSelection>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        let Some((glyph_name, index)) = selection.selected.clone() else {
+            continue;
+        };
+        if fontir_state.get_glyph(&event.base_glyph).is_none()
+            || event.base_glyph == glyph_name
+        {
+            warn!(
+                "Cannot use '{}' as the base of a component in '{}'",
+                event.base_glyph, glyph_name
+            );
+            continue;
+        }
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&glyph_name)
+        else {
+            continue;
+        };
+        let Some(component) = working_copy.components.get_mut(index) else {
+            continue;
+        };
+
+        info!(
+            "Component {} of '{}' now uses '{}' instead of '{}'",
+            index, glyph_name, event.base_glyph, component.base_glyph
+        );
+        component.base_glyph = event.base_glyph.clone();
+        working_copy.is_dirty = true;
+        app_state_changed.write(AppStateChanged);
+        visual_update.needs_update = true;
+    }
+}
+
+/// Handler for decomposing components into contours
+fn handle_decompose_components(
+    mut events: EventReader<DecomposeComponentsEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut selection: ResMut<ComponentSelection>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        let Some(glyph_name) = fontir_state.current_glyph.clone() else {
+            continue;
+        };
+
+        let indices: Vec<usize> = if event.all {
+            (0..fontir_state.glyph_components(&glyph_name).len()).collect()
+        } else {
+            match selection.index_in(&glyph_name) {
+                Some(index) => vec![index],
+                None => {
+                    debug!("Decompose: no component selected");
+                    continue;
+                }
+            }
+        };
+        if indices.is_empty() {
+            continue;
+        }
+
+        let masters = fontir_state.decompose_components(&glyph_name, &indices);
+        if masters == 0 {
+            continue;
+        }
+        selection.selected = None;
+
+        info!(
+            "Decomposed {} component(s) of '{}' in {} master(s)",
+            indices.len(),
+            glyph_name,
+            masters
+        );
+        app_state_changed.write(AppStateChanged);
+        visual_update.needs_update = true;
+    }
+}
+
+/// Handler for opening the base glyph of the selected component
+fn handle_open_component_base(
+    mut events: EventReader<OpenComponentBaseEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut text_editor_state: ResMut<TextEditorState>,
+    selection: Res<ComponentSelection>,
+    active_sort: Query<(&Sort, &Transform), With<ActiveSort>>,
+) {
+    for _ in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        let Some((glyph_name, index)) = selection.selected.clone() else {
+            continue;
+        };
+        let Some(component) = fontir_state
+            .glyph_components(&glyph_name)
+            .get(index)
+            .cloned()
+        else {
+            continue;
+        };
+
+        // Place the new sort to the right of the composite glyph
+        let origin = active_sort
+            .single()
+            .map(|(_, transform)| transform.translation.truncate())
+            .unwrap_or_default();
+        let position = origin
+            + Vec2::new(
+                fontir_state.get_glyph_advance_width(&glyph_name)
+                    + BASE_SORT_GAP,
+                0.0,
+            );
+        let advance_width =
+            fontir_state.get_glyph_advance_width(&component.base_glyph);
+
+        text_editor_state.add_freeform_sort(
+            component.base_glyph.clone(),
+            position,
+            advance_width,
+            None,
+        );
+        fontir_state.set_current_glyph(Some(component.base_glyph.clone()));
+        info!(
+            "Opened base glyph '{}' of component {} in '{}'",
+            component.base_glyph, index, glyph_name
+        );
+    }
+}
+
+/// Highlight the outline of the selected component
+pub fn render_selected_component(
+    mut gizmos: Gizmos,
+    fontir_state: Option<Res<FontIRAppState>>,
+    selection: Res<ComponentSelection>,
+    active_sort: Query<(&Sort, &Transform), With<ActiveSort>>,
+    theme: Res<CurrentTheme>,
+) {
+    let Some(fontir_state) = fontir_state else {
+        return;
+    };
+    let Ok((sort, sort_transform)) = active_sort.single() else {
+        return;
+    };
+    let Some(index) = selection.index_in(&sort.glyph_name) else {
+        return;
+    };
+    let Some(paths) = fontir_state
+        .component_paths(&sort.glyph_name)
+        .into_iter()
+        .nth(index)
+    else {
+        return;
+    };
+
+    let offset = sort_transform.translation.truncate();
+    let color = theme.theme().selected_primary_color();
+    let to_world = |p: Point| offset + Vec2::new(p.x as f32, p.y as f32);
+
+    for path in &paths {
+        let mut start = None;
+        let mut last = None;
+        path.flatten(HIGHLIGHT_TOLERANCE, |el| match el {
+            PathEl::MoveTo(p) => {
+                start = Some(to_world(p));
+                last = start;
+            }
+            PathEl::LineTo(p) => {
+                let p = to_world(p);
+                if let Some(previous) = last {
+                    gizmos.line_2d(previous, p, color);
+                }
+                last = Some(p);
+            }
+            PathEl::ClosePath => {
+                if let (Some(previous), Some(first)) = (last, start) {
+                    gizmos.line_2d(previous, first, color);
+                }
+                last = start;
+            }
+            _ => {}
+        });
+    }
+}
+
+/// Bounding box of a set of paths
+fn bounds(paths: &[BezPath]) -> Option<Rect> {
+    paths
+        .iter()
+        .map(|path| path.bounding_box())
+        .reduce(|a, b| a.union(b))
+}
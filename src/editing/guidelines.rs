@@ -0,0 +1,686 @@
+//! Guideline editing
+//!
+//! Guidelines come from two places in a UFO: font-level guidelines in
+//! fontinfo.plist, shared by every glyph, and glyph-level guidelines in each
+//! glif. Both are shown with the active sort and drawn by the metrics
+//! renderer. Each unlocked guideline gets a selectable handle entity at its
+//! origin, so the regular selection, drag and nudge systems move guidelines
+//! just like points; moved handles are written back into the FontIR state.
+//!
+//! New guidelines are dragged out of the viewport edges: the left and right
+//! edges give vertical guidelines, the top and bottom edges horizontal ones.
+//! They are added to the active glyph, or to the font when Shift is held on
+//! release.
+//!
+//! Keyboard shortcuts:
+//! - Command/Ctrl+Shift+K locks or unlocks the selected guidelines (holding
+//!   Alt as well unlocks every guideline of the active sort)
+//! - Command/Ctrl+Shift+] and [ rotate the selected guidelines by 15°
+//!   counter-clockwise and clockwise (1° with Alt)
+//! - Delete/Backspace removes the selected guidelines
+
+use crate::core::io::input::{helpers, InputMode, InputState};
+use crate::core::io::pointer::PointerInfo;
+use crate::core::state::{FontIRAppState, GuidelineData};
+use crate::editing::selection::components::{Selectable, Selected};
+use crate::editing::selection::events::AppStateChanged;
+use crate::editing::selection::nudge::PointCoordinates;
+use crate::editing::selection::SelectionState;
+use crate::editing::sort::{ActiveSort, Sort};
+use crate::systems::sort_manager::SortPointEntity;
+use crate::systems::ui_interaction::{detect_ui_hover, UiHoverState};
+use crate::ui::theme::{FONT_GUIDELINE_COLOR, GLYPH_GUIDELINE_COLOR};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// Width in pixels of the viewport strips guidelines are dragged out of
+const EDGE_MARGIN: f32 = 8.0;
+
+/// Rotation step of the rotate shortcuts in degrees
+const ROTATION_STEP: f64 = 15.0;
+
+/// Rotation step of the rotate shortcuts with Alt held, in degrees
+const FINE_ROTATION_STEP: f64 = 1.0;
+
+/// Half length of the preview line shown while dragging out a guideline
+const PREVIEW_HALF_LENGTH: f32 = 20_000.0;
+
+/// Z-level of guideline handle entities (same layer as anchors)
+const GUIDELINE_Z: f32 = 10.0;
+
+/// Which guideline list a guideline belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum GuidelineTarget {
+    /// Index into the font-level guidelines of the current master
+    Font(usize),
+    /// Index into the glyph-level guidelines of the glyph
+    Glyph(usize),
+}
+
+impl GuidelineTarget {
+    pub fn is_font(&self) -> bool {
+        matches!(self, GuidelineTarget::Font(_))
+    }
+
+    pub fn index(&self) -> usize {
+        match self {
+            GuidelineTarget::Font(index) | GuidelineTarget::Glyph(index) => {
+                *index
+            }
+        }
+    }
+}
+
+/// Component that links a handle entity to a guideline
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct GuidelineReference {
+    /// Name of the glyph the guideline is shown with
+    pub glyph_name: String,
+    pub target: GuidelineTarget,
+    /// Lock state when the handle was spawned; locked guidelines get a
+    /// handle that is not selectable
+    pub locked: bool,
+}
+
+/// Orientation of a guideline dragged out of a viewport edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuidelineAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// State of guideline editing
+#[derive(Resource, Default)]
+pub struct GuidelineState {
+    /// Guideline currently being dragged out of a viewport edge
+    pub creating: Option<GuidelineAxis>,
+    /// Guidelines of the active sort in world coordinates, used for snapping
+    pub snap_lines: Vec<GuidelineData>,
+}
+
+/// How to change the selected guidelines
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuidelineEdit {
+    /// Lock unlocked guidelines and unlock locked ones
+    ToggleLock,
+    /// Unlock every guideline of the active sort, selected or not
+    UnlockAll,
+    /// Rotate by degrees, counter-clockwise
+    Rotate(f64),
+    /// Set the angle in degrees
+    SetAngle(f64),
+    /// Remove the guidelines
+    Delete,
+}
+
+/// Add a guideline to the active sort's glyph or to the font
+#[derive(Event)]
+pub struct AddGuidelineEvent {
+    /// Guideline in glyph coordinates
+    pub guideline: GuidelineData,
+    /// Add to the font-level guidelines instead of the glyph
+    pub font_level: bool,
+}
+
+/// Change the selected guidelines
+#[derive(Event)]
+pub struct EditGuidelinesEvent {
+    pub edit: GuidelineEdit,
+}
+
+pub struct GuidelinePlugin;
+
+impl Plugin for GuidelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GuidelineReference>()
+            .init_resource::<GuidelineState>()
+            .add_event::<AddGuidelineEvent>()
+            .add_event::<EditGuidelinesEvent>()
+            .add_systems(
+                Update,
+                (
+                    handle_guideline_edge_drag.after(detect_ui_hover),
+                    draw_guideline_preview,
+                    (
+                        handle_guideline_shortcuts,
+                        handle_add_guideline,
+                        handle_edit_guidelines,
+                        sync_guideline_entities,
+                        update_guidelines_from_entities,
+                    )
+                        .chain()
+                        .after(
+                            crate::editing::selection::nudge::handle_nudge_input,
+                        ),
+                ),
+            );
+    }
+}
+
+/// Guidelines shown with a glyph: the font's first, then the glyph's own
+pub fn sort_guidelines(
+    fontir_state: &FontIRAppState,
+    glyph_name: &str,
+) -> Vec<(GuidelineTarget, GuidelineData)> {
+    let font = fontir_state
+        .current_font_guidelines()
+        .iter()
+        .enumerate()
+        .map(|(index, guideline)| {
+            (GuidelineTarget::Font(index), guideline.clone())
+        });
+    let glyph = fontir_state
+        .glyph_guidelines(glyph_name)
+        .into_iter()
+        .enumerate()
+        .map(|(index, guideline)| (GuidelineTarget::Glyph(index), guideline));
+    font.chain(glyph).collect()
+}
+
+/// Run `edit` on the font-level or glyph-level guideline list, marking it
+/// as modified if the edit changed it
+fn edit_guideline_list<R>(
+    fontir_state: &mut FontIRAppState,
+    glyph_name: &str,
+    font_level: bool,
+    edit: impl FnOnce(&mut Vec<GuidelineData>) -> R,
+) -> Option<R> {
+    if font_level {
+        return fontir_state.edit_current_font_guidelines(edit);
+    }
+    let working_copy = fontir_state.get_or_create_working_copy(glyph_name)?;
+    let before = working_copy.guidelines.clone();
+    let result = edit(&mut working_copy.guidelines);
+    if working_copy.guidelines != before {
+        working_copy.is_dirty = true;
+    }
+    Some(result)
+}
+
+/// The viewport edge strip a cursor position is in, if any
+fn edge_axis(cursor: Vec2, window_size: Vec2) -> Option<GuidelineAxis> {
+    if cursor.x <= EDGE_MARGIN || cursor.x >= window_size.x - EDGE_MARGIN {
+        Some(GuidelineAxis::Vertical)
+    } else if cursor.y <= EDGE_MARGIN || cursor.y >= window_size.y - EDGE_MARGIN
+    {
+        Some(GuidelineAxis::Horizontal)
+    } else {
+        None
+    }
+}
+
+/// System to drag new guidelines out of the viewport edges
+///
+/// The edge strips count as UI, so other tools and the selection ignore
+/// clicks there and while a guideline is being dragged.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_guideline_edge_drag(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    pointer_info: Res<PointerInfo>,
+    active_sort: Query<&Transform, With<ActiveSort>>,
+    mut ui_hover_state: ResMut<UiHoverState>,
+    mut guideline_state: ResMut<GuidelineState>,
+    mut add_event: EventWriter<AddGuidelineEvent>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let edge = window
+        .cursor_position()
+        .and_then(|cursor| edge_axis(cursor, window.size()));
+
+    if guideline_state.creating.is_none()
+        && !ui_hover_state.is_hovering_ui
+        && mouse.just_pressed(MouseButton::Left)
+    {
+        guideline_state.creating = edge;
+    }
+
+    if edge.is_some() || guideline_state.creating.is_some() {
+        ui_hover_state.is_hovering_ui = true;
+    }
+
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(axis) = guideline_state.creating.take() else {
+        return;
+    };
+    // Letting go over an edge strip cancels the new guideline
+    if edge.is_some() || window.cursor_position().is_none() {
+        return;
+    }
+    let Ok(sort_transform) = active_sort.single() else {
+        debug!("Add guideline: no active sort");
+        return;
+    };
+
+    let position = (pointer_info.design.to_raw()
+        - sort_transform.translation.truncate())
+    .round();
+    let guideline = match axis {
+        GuidelineAxis::Horizontal => {
+            GuidelineData::horizontal(position.y as f64)
+        }
+        GuidelineAxis::Vertical => GuidelineData::vertical(position.x as f64),
+    };
+    let font_level = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    add_event.write(AddGuidelineEvent {
+        guideline,
+        font_level,
+    });
+}
+
+/// Draw the guideline being dragged out of a viewport edge
+fn draw_guideline_preview(
+    mut gizmos: Gizmos,
+    guideline_state: Res<GuidelineState>,
+    pointer_info: Res<PointerInfo>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    let Some(axis) = guideline_state.creating else {
+        return;
+    };
+    let position = pointer_info.design.to_raw();
+    let direction = match axis {
+        GuidelineAxis::Horizontal => Vec2::X,
+        GuidelineAxis::Vertical => Vec2::Y,
+    };
+    let color = if keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight)
+    {
+        FONT_GUIDELINE_COLOR
+    } else {
+        GLYPH_GUIDELINE_COLOR
+    };
+    gizmos.line_2d(
+        position - direction * PREVIEW_HALF_LENGTH,
+        position + direction * PREVIEW_HALF_LENGTH,
+        color,
+    );
+}
+
+/// System to handle keyboard shortcuts for guidelines
+pub fn handle_guideline_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    input_state: Res<InputState>,
+    selected_guidelines: Query<(), (With<GuidelineReference>, With<Selected>)>,
+    mut edit_event: EventWriter<EditGuidelinesEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+
+    if !modifier_pressed
+        && (keyboard.just_pressed(KeyCode::Delete)
+            || keyboard.just_pressed(KeyCode::Backspace))
+        && helpers::is_input_mode(&input_state, InputMode::Select)
+        && !selected_guidelines.is_empty()
+    {
+        edit_event.write(EditGuidelinesEvent {
+            edit: GuidelineEdit::Delete,
+        });
+        return;
+    }
+
+    if !(modifier_pressed && shift_pressed) {
+        return;
+    }
+
+    let step = if alt_pressed {
+        FINE_ROTATION_STEP
+    } else {
+        ROTATION_STEP
+    };
+    let edit = if keyboard.just_pressed(KeyCode::KeyK) {
+        if alt_pressed {
+            GuidelineEdit::UnlockAll
+        } else {
+            GuidelineEdit::ToggleLock
+        }
+    } else if keyboard.just_pressed(KeyCode::BracketRight) {
+        GuidelineEdit::Rotate(step)
+    } else if keyboard.just_pressed(KeyCode::BracketLeft) {
+        GuidelineEdit::Rotate(-step)
+    } else {
+        return;
+    };
+    edit_event.write(EditGuidelinesEvent { edit });
+}
+
+/// Handler for adding guidelines
+fn handle_add_guideline(
+    mut events: EventReader<AddGuidelineEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    active_sort: Query<&Sort, With<ActiveSort>>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Add guideline requested but FontIR state is not available");
+            return;
+        };
+        let Ok(sort) = active_sort.single() else {
+            continue;
+        };
+        let added = edit_guideline_list(
+            fontir_state,
+            &sort.glyph_name,
+            event.font_level,
+            |guidelines| guidelines.push(event.guideline.clone()),
+        );
+        if added.is_some() {
+            let owner = if event.font_level {
+                "the font".to_string()
+            } else {
+                format!("'{}'", sort.glyph_name)
+            };
+            info!(
+                "Added guideline to {} at ({}, {}), {}°",
+                owner,
+                event.guideline.x,
+                event.guideline.y,
+                event.guideline.angle
+            );
+        }
+    }
+}
+
+/// Handler for changing the selected guidelines
+fn handle_edit_guidelines(
+    mut events: EventReader<EditGuidelinesEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    active_sort: Query<&Sort, With<ActiveSort>>,
+    selected_guidelines: Query<&GuidelineReference, With<Selected>>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+
+        if event.edit == GuidelineEdit::UnlockAll {
+            let Ok(sort) = active_sort.single() else {
+                continue;
+            };
+            let guidelines = sort_guidelines(fontir_state, &sort.glyph_name);
+            for font_level in [true, false] {
+                // Leave lists without locked guidelines unmodified
+                let has_locked =
+                    guidelines.iter().any(|(target, guideline)| {
+                        target.is_font() == font_level && guideline.locked
+                    });
+                if !has_locked {
+                    continue;
+                }
+                edit_guideline_list(
+                    fontir_state,
+                    &sort.glyph_name,
+                    font_level,
+                    |guidelines| {
+                        for guideline in guidelines {
+                            guideline.locked = false;
+                        }
+                    },
+                );
+            }
+            info!("Unlocked all guidelines of '{}'", sort.glyph_name);
+            continue;
+        }
+
+        let mut targets: Vec<&GuidelineReference> =
+            selected_guidelines.iter().collect();
+        // Remove from the back so earlier indices stay valid
+        targets.sort_by_key(|guideline_ref| {
+            std::cmp::Reverse(guideline_ref.target.index())
+        });
+
+        for guideline_ref in targets {
+            let index = guideline_ref.target.index();
+            edit_guideline_list(
+                fontir_state,
+                &guideline_ref.glyph_name,
+                guideline_ref.target.is_font(),
+                |guidelines| {
+                    if index >= guidelines.len() {
+                        return;
+                    }
+                    if event.edit == GuidelineEdit::Delete {
+                        guidelines.remove(index);
+                        return;
+                    }
+                    let guideline = &mut guidelines[index];
+                    match event.edit {
+                        GuidelineEdit::ToggleLock => {
+                            guideline.locked = !guideline.locked
+                        }
+                        GuidelineEdit::Rotate(degrees) => {
+                            guideline.rotate(degrees)
+                        }
+                        GuidelineEdit::SetAngle(degrees) => {
+                            guideline.angle =
+                                crate::core::state::normalize_guideline_angle(
+                                    degrees,
+                                )
+                        }
+                        GuidelineEdit::Delete | GuidelineEdit::UnlockAll => {}
+                    }
+                },
+            );
+        }
+        debug!("Applied {:?} to the selected guidelines", event.edit);
+    }
+}
+
+/// Keep one handle entity per guideline of the active sort
+///
+/// Handles are respawned when the active sort or its glyph changes, when
+/// guidelines are added, removed, locked or unlocked, and when the font
+/// data changes. Otherwise unselected handles just follow the sort position.
+/// Also refreshes the world-space guidelines used for snapping.
+#[allow(clippy::type_complexity)]
+pub fn sync_guideline_entities(
+    mut commands: Commands,
+    mut app_state_events: EventReader<AppStateChanged>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    active_sort: Query<(Entity, &Sort, &Transform), With<ActiveSort>>,
+    mut handle_entities: Query<
+        (
+            Entity,
+            &GuidelineReference,
+            &SortPointEntity,
+            &mut Transform,
+            Has<Selected>,
+        ),
+        Without<Sort>,
+    >,
+    mut selection_state: ResMut<SelectionState>,
+    mut guideline_state: ResMut<GuidelineState>,
+) {
+    let font_changed = app_state_events.read().count() > 0;
+    let active = active_sort.single().ok();
+    let guidelines = match (&fontir_state, active) {
+        (Some(fontir_state), Some((_, sort, _))) => {
+            sort_guidelines(fontir_state, &sort.glyph_name)
+        }
+        _ => Vec::new(),
+    };
+
+    guideline_state.snap_lines = match active {
+        Some((_, _, sort_transform)) => {
+            let origin = sort_transform.translation;
+            guidelines
+                .iter()
+                .map(|(_, guideline)| GuidelineData {
+                    x: guideline.x + origin.x as f64,
+                    y: guideline.y + origin.y as f64,
+                    ..guideline.clone()
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let stale =
+        handle_entities
+            .iter()
+            .any(|(_, guideline_ref, parent, _, _)| {
+                let locked_changed = guidelines
+                    .iter()
+                    .find(|(target, _)| *target == guideline_ref.target)
+                    .is_none_or(|(_, guideline)| {
+                        guideline.locked != guideline_ref.locked
+                    });
+                locked_changed
+                    || active.is_none_or(|(sort_entity, sort, _)| {
+                        parent.sort_entity != sort_entity
+                            || guideline_ref.glyph_name != sort.glyph_name
+                    })
+            });
+    let rebuild = font_changed
+        || stale
+        || handle_entities.iter().count() != guidelines.len();
+
+    if !rebuild {
+        // Selected handles are moved by drag and nudge, the rest follow the
+        // sort
+        let Some((_, _, sort_transform)) = active else {
+            return;
+        };
+        let origin = sort_transform.translation.truncate();
+        for (_, guideline_ref, _, mut transform, selected) in
+            handle_entities.iter_mut()
+        {
+            let Some((_, guideline)) = guidelines
+                .iter()
+                .find(|(target, _)| *target == guideline_ref.target)
+            else {
+                continue;
+            };
+            let position =
+                origin + Vec2::new(guideline.x as f32, guideline.y as f32);
+            if !selected && transform.translation.truncate() != position {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
+        }
+        return;
+    }
+
+    for (entity, _, _, _, _) in handle_entities.iter() {
+        selection_state.selected.remove(&entity);
+        commands.entity(entity).try_despawn();
+    }
+
+    let Some((sort_entity, sort, sort_transform)) = active else {
+        return;
+    };
+    let origin = sort_transform.translation.truncate();
+    for (target, guideline) in &guidelines {
+        let position =
+            origin + Vec2::new(guideline.x as f32, guideline.y as f32);
+        let mut handle = commands.spawn((
+            Transform::from_xyz(position.x, position.y, GUIDELINE_Z),
+            GlobalTransform::default(),
+            Visibility::default(),
+            InheritedVisibility::default(),
+            ViewVisibility::default(),
+            PointCoordinates {
+                x: position.x,
+                y: position.y,
+            },
+            GuidelineReference {
+                glyph_name: sort.glyph_name.clone(),
+                target: *target,
+                locked: guideline.locked,
+            },
+            SortPointEntity { sort_entity },
+            Name::new(format!("Guideline[{target:?}]")),
+        ));
+        if !guideline.locked {
+            handle.insert(Selectable);
+        }
+    }
+
+    debug!(
+        "Spawned {} guideline handles for '{}'",
+        guidelines.len(),
+        sort.glyph_name
+    );
+}
+
+/// Write positions of dragged or nudged guideline handles into the FontIR
+/// state
+#[allow(clippy::type_complexity)]
+pub fn update_guidelines_from_entities(
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    moved_handles: Query<
+        (&GuidelineReference, &SortPointEntity, &Transform),
+        (With<Selected>, Changed<Transform>, Without<Sort>),
+    >,
+    sort_query: Query<&Transform, With<Sort>>,
+) {
+    let Some(fontir_state) = fontir_state.as_mut() else {
+        return;
+    };
+
+    for (guideline_ref, parent, transform) in moved_handles.iter() {
+        let Ok(sort_transform) = sort_query.get(parent.sort_entity) else {
+            continue;
+        };
+        let position = transform.translation.truncate()
+            - sort_transform.translation.truncate();
+        let (x, y) = (position.x as f64, position.y as f64);
+
+        // Only touch the guidelines when the handle actually moved
+        let unchanged =
+            sort_guidelines(fontir_state, &guideline_ref.glyph_name)
+                .into_iter()
+                .find(|(target, _)| *target == guideline_ref.target)
+                .is_none_or(|(_, guideline)| {
+                    guideline.x == x && guideline.y == y
+                });
+        if unchanged {
+            continue;
+        }
+
+        let index = guideline_ref.target.index();
+        edit_guideline_list(
+            fontir_state,
+            &guideline_ref.glyph_name,
+            guideline_ref.target.is_font(),
+            |guidelines| {
+                if let Some(guideline) = guidelines.get_mut(index) {
+                    guideline.x = x;
+                    guideline.y = y;
+                }
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_axis() {
+        let size = Vec2::new(1000.0, 800.0);
+        assert_eq!(
+            edge_axis(Vec2::new(2.0, 400.0), size),
+            Some(GuidelineAxis::Vertical)
+        );
+        assert_eq!(
+            edge_axis(Vec2::new(500.0, 797.0), size),
+            Some(GuidelineAxis::Horizontal)
+        );
+        assert_eq!(edge_axis(Vec2::new(500.0, 400.0), size), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Implied on-curve points through edits
+//!
+//! Working copies flag which on-curve points the UFO left implied, by point
+//! index, so only those are written back as implied. Tools edit the
+//! contours without knowing about the flags, so like edit all masters this
+//! compares each flagged working copy with how it looked the frame before
+//! and carries the flags over to the edited contours; see
+//! `geometry::quadratic::remap_implied_flags`.
+
+use crate::core::state::FontIRAppState;
+use crate::editing::edit_all_masters::sync_edits_to_other_masters;
+use crate::geometry::quadratic;
+use bevy::prelude::*;
+use fontdrasil::coords::NormalizedLocation;
+use kurbo::BezPath;
+use std::collections::{HashMap, HashSet};
+
+/// Contours and implied flags of a working copy as of the last frame
+type ImpliedSnapshot = (Vec<BezPath>, Vec<Vec<bool>>);
+
+pub struct ImpliedOnCurvesPlugin;
+
+impl Plugin for ImpliedOnCurvesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            track_implied_on_curves.after(sync_edits_to_other_masters),
+        );
+    }
+}
+
+/// Carries the implied flags of working copies over this frame's edits
+///
+/// Working copies are compared with their snapshot from the last frame, or
+/// with the glyph as loaded if there is none yet. Flags changed along with
+/// the contours were set by the edit itself, such as a curve conversion or
+/// a dragged implied point, and are kept as they are. Working copies
+/// without implied points have no flags to carry over.
+fn track_implied_on_curves(
+    fontir_state: Option<ResMut<FontIRAppState>>,
+    mut snapshots: Local<
+        HashMap<(String, NormalizedLocation), ImpliedSnapshot>,
+    >,
+) {
+    let Some(mut fontir_state) = fontir_state else {
+        snapshots.clear();
+        return;
+    };
+    if !fontir_state.is_changed() {
+        return;
+    }
+
+    // Updating the flags is bookkeeping, not an edit to react to
+    let fontir_state = fontir_state.bypass_change_detection();
+    let tracked: HashSet<(String, NormalizedLocation)> = fontir_state
+        .working_copies
+        .iter()
+        .filter(|(_, working_copy)| {
+            has_implied(&working_copy.implied_on_curves)
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+    snapshots.retain(|key, _| tracked.contains(key));
+
+    for key in tracked {
+        let before = match snapshots.remove(&key) {
+            Some(snapshot) => Some(snapshot),
+            None => fontir_state.original_implied_on_curves(&key.0, &key.1),
+        };
+        let Some(working_copy) = fontir_state.working_copies.get_mut(&key)
+        else {
+            continue;
+        };
+        if let Some((contours, flags)) = before {
+            if flags == working_copy.implied_on_curves
+                && contours != working_copy.contours
+            {
+                working_copy.implied_on_curves =
+                    remap_flags(&contours, &working_copy.contours, &flags);
+            }
+        }
+        if has_implied(&working_copy.implied_on_curves) {
+            let snapshot = (
+                working_copy.contours.clone(),
+                working_copy.implied_on_curves.clone(),
+            );
+            snapshots.insert(key, snapshot);
+        }
+    }
+}
+
+fn has_implied(flags: &[Vec<bool>]) -> bool {
+    flags.iter().flatten().any(|flag| *flag)
+}
+
+/// Implied flags of each edited contour
+///
+/// Contours are matched by index while their number stays the same;
+/// otherwise every contour takes the flags at the positions of the points
+/// implied before.
+fn remap_flags(
+    before: &[BezPath],
+    after: &[BezPath],
+    flags: &[Vec<bool>],
+) -> Vec<Vec<bool>> {
+    let flags_of =
+        |index: usize| flags.get(index).map_or(&[][..], Vec::as_slice);
+    if before.len() == after.len() {
+        return before
+            .iter()
+            .zip(after)
+            .enumerate()
+            .map(|(index, (before, after))| {
+                quadratic::remap_implied_flags(before, after, flags_of(index))
+            })
+            .collect();
+    }
+
+    let implied: Vec<_> = before
+        .iter()
+        .enumerate()
+        .flat_map(|(index, contour)| {
+            quadratic::flagged_points(contour, flags_of(index))
+        })
+        .collect();
+    after
+        .iter()
+        .map(|contour| quadratic::implied_flags_at(contour, &implied))
+        .collect()
+}
@@ -3,15 +3,23 @@
 //! This module contains all editing-related functionality:
 //! - Edit sessions for managing editing state
 //! - Edit types and mode definitions
+//! - Repeating edits in all masters of a glyph
+//! - Keeping implied on-curve points implied through edits
 //! - Selection management for points, paths, and objects
 //! - Undo/redo system for reversible operations
 //! - Sort system for movable type placement and editing
 
 #![allow(unused_imports)]
 
+pub mod anchors;
+pub mod edit_all_masters;
 pub mod edit_session;
 pub mod edit_type;
+pub mod glyph_components;
+pub mod guidelines;
+pub mod implied_on_curves;
 pub mod selection;
+pub mod snapping;
 pub mod sort;
 pub mod sort_plugin;
 pub mod system_sets;
@@ -20,8 +28,14 @@ pub mod undo;
 pub mod undo_plugin;
 
 // Re-export commonly used items
+pub use anchors::AnchorPlugin;
+pub use edit_all_masters::EditAllMastersPlugin;
 pub use edit_session::EditSessionPlugin;
+pub use glyph_components::GlyphComponentsPlugin;
+pub use guidelines::GuidelinePlugin;
+pub use implied_on_curves::ImpliedOnCurvesPlugin;
 pub use selection::SelectionPlugin;
+pub use snapping::SnappingPlugin;
 pub use sort_plugin::SortPlugin;
 pub use system_sets::{FontEditorSets, FontEditorSystemSetsPlugin};
 pub use text_editor_plugin::TextEditorPlugin;
@@ -221,12 +221,7 @@ fn spawn_appstate_points(
                             },
                             glyph_point_ref,
                             PointType {
-                                is_on_curve: matches!(
-                                    point.point_type,
-                                    PointTypeData::Move
-                                        | PointTypeData::Line
-                                        | PointTypeData::Curve
-                                ),
+                                is_on_curve: point.point_type.is_on_curve(),
                             },
                             Transform::from_translation(
                                 point_world_pos.extend(0.0),
@@ -7,6 +7,7 @@ use crate::editing::edit_type::EditType;
 use crate::editing::selection::components::{GlyphPointReference, Selected};
 use crate::editing::selection::nudge::{EditEvent, PointCoordinates};
 use crate::editing::selection::DragPointState;
+use crate::editing::snapping::SnapState;
 use bevy::input::ButtonInput;
 use bevy::log::{debug, warn};
 use bevy::prelude::*;
@@ -31,6 +32,7 @@ pub fn handle_point_drag(
     mut fontir_app_state: Option<ResMut<FontIRAppState>>,
     mut event_writer: EventWriter<EditEvent>,
     settings: Res<BezySettings>,
+    mut snap_state: ResMut<SnapState>,
 ) {
     
     // Only drag if the resource says we are
@@ -58,6 +60,35 @@ pub fn handle_point_drag(
 
         let mut updated_count = 0;
 
+        // Snap the dragged points as a group: every point moves by the
+        // smallest snap offset any of them has, so shapes keep their form
+        let mut snap_offset = Vec2::ZERO;
+        let mut best_snap = None;
+        for (entity, _, _, point_ref, _) in query.iter() {
+            let Some(original_pos) =
+                drag_point_state.original_positions.get(&entity)
+            else {
+                continue;
+            };
+            if point_ref.is_none() {
+                continue;
+            }
+            let grid_pos = settings.apply_grid_snap(*original_pos + movement);
+            let (snapped_pos, result) =
+                snap_state.snap(*original_pos + movement, &settings);
+            let offset = snapped_pos - grid_pos;
+            if result.is_some()
+                && (best_snap.is_none()
+                    || offset.length_squared() < snap_offset.length_squared())
+            {
+                snap_offset = offset;
+                best_snap = result;
+            }
+        }
+        if best_snap.is_some() {
+            snap_state.active = best_snap;
+        }
+
         for (
             entity,
             mut transform,
@@ -81,8 +112,10 @@ pub fn handle_point_drag(
                 }
                 // Handle glyph point drag (with snapping)
                 else if let Some(point_ref) = point_ref {
-                    // Apply grid snapping if enabled
-                    let snapped_pos = settings.apply_grid_snap(new_pos);
+                    // Apply grid snapping if enabled, then the group's
+                    // snap to nearby targets
+                    let snapped_pos =
+                        settings.apply_grid_snap(new_pos) + snap_offset;
 
                     transform.translation.x = snapped_pos.x;
                     transform.translation.y = snapped_pos.y;
@@ -27,7 +27,8 @@ pub fn handle_nudge_input(
             (
                 Entity,
                 &mut Transform,
-                &crate::editing::selection::components::GlyphPointReference,
+                // Anchors are nudged too and have no point reference
+                Option<&crate::editing::selection::components::GlyphPointReference>,
                 Option<&crate::systems::sort_manager::SortPointEntity>,
             ),
             (With<Selected>, With<SortPointEntity>),
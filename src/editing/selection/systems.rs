@@ -305,10 +305,7 @@ pub fn spawn_active_sort_points(
                                     },
                                     glyph_point_ref,
                                     crate::editing::selection::components::PointType {
-                                        is_on_curve: matches!(point.point_type,
-                                            crate::core::state::font_data::PointTypeData::Move |
-                                            crate::core::state::font_data::PointTypeData::Line |
-                                            crate::core::state::font_data::PointTypeData::Curve),
+                                        is_on_curve: point.point_type.is_on_curve(),
                                     },
                                     Transform::from_translation(point_world_pos.extend(0.0)),
                                     Visibility::Visible,
@@ -0,0 +1,273 @@
+//! Snapping
+//!
+//! Collects the snap targets around the active sort every frame: its
+//! on-curve points and curve extrema, the font's metrics lines, guidelines,
+//! and the horizontal and vertical alignment of its points. Point drags and
+//! the pen and shapes tools snap through `SnapState`, after grid snapping.
+//! Selected points, and the extrema of contours with selected points, are
+//! left out of the targets so dragged points don't snap to themselves.
+//!
+//! Which kinds of targets are used, their priority and their reach are set
+//! in `SnapSettings`. The target of the latest snap is highlighted on the
+//! canvas.
+
+use crate::core::settings::BezySettings;
+use crate::core::state::FontIRAppState;
+use crate::editing::guidelines::GuidelineState;
+use crate::editing::selection::components::{
+    GlyphPointReference, PointType, Selected,
+};
+use crate::editing::sort::{ActiveSort, Sort};
+use crate::geometry::snapping::{
+    contour_extrema, snap, SnapGeometry, SnapKind, SnapResult, SnapTarget,
+};
+use crate::rendering::camera_responsive::CameraResponsiveScale;
+use crate::rendering::metrics::GlyphMetricsCache;
+use crate::systems::sort_manager::SortPointEntity;
+use crate::ui::theme::SNAP_HIGHLIGHT_COLOR;
+use bevy::prelude::*;
+use kurbo::Point;
+use std::collections::HashSet;
+
+/// Size of the marker drawn at a snapped position (before camera scaling)
+const SNAP_MARKER_SIZE: f32 = 6.0;
+
+/// Half length of highlighted metrics lines and guidelines
+const HIGHLIGHT_HALF_LENGTH: f32 = 20_000.0;
+
+/// Snap targets and the latest snap
+#[derive(Resource, Default)]
+pub struct SnapState {
+    /// Targets around the active sort, in world coordinates
+    pub targets: Vec<SnapTarget>,
+    /// Multiplier for snap thresholds, following the camera zoom
+    pub threshold_scale: f64,
+    /// Snap made this frame, highlighted on the canvas
+    pub active: Option<SnapResult>,
+    /// Extrema of each contour of the active glyph, in glyph coordinates
+    extrema: Option<(String, Vec<Vec<Point>>)>,
+}
+
+impl SnapState {
+    /// Snap a position to the grid, then to the best target in reach
+    ///
+    /// Returns the snapped position and the snap, if a target was in reach.
+    pub fn snap(
+        &self,
+        position: Vec2,
+        settings: &BezySettings,
+    ) -> (Vec2, Option<SnapResult>) {
+        let grid_position = settings.apply_grid_snap(position);
+        if !settings.snap.enabled {
+            return (grid_position, None);
+        }
+        let point = Point::new(grid_position.x as f64, grid_position.y as f64);
+        match snap(
+            point,
+            &self.targets,
+            &settings.snap.rules,
+            self.threshold_scale,
+        ) {
+            Some(result) => (to_vec2(result.position), Some(result)),
+            None => (grid_position, None),
+        }
+    }
+
+    /// Snap a position like `snap` and highlight the target it snapped to
+    pub fn snap_and_highlight(
+        &mut self,
+        position: Vec2,
+        settings: &BezySettings,
+    ) -> Vec2 {
+        let (position, result) = self.snap(position, settings);
+        if result.is_some() {
+            self.active = result;
+        }
+        position
+    }
+}
+
+pub struct SnappingPlugin;
+
+impl Plugin for SnappingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SnapState>()
+            .add_systems(PreUpdate, update_snap_targets)
+            .add_systems(PostUpdate, draw_snap_highlight);
+    }
+}
+
+fn to_vec2(point: Point) -> Vec2 {
+    Vec2::new(point.x as f32, point.y as f32)
+}
+
+/// Outline of a glyph at the current location, preferring the working copy
+fn glyph_contours(
+    fontir_state: &FontIRAppState,
+    glyph_name: &str,
+) -> Vec<kurbo::BezPath> {
+    let key = (
+        glyph_name.to_string(),
+        fontir_state.current_location.clone(),
+    );
+    match fontir_state.working_copies.get(&key) {
+        Some(working_copy) => working_copy.contours.clone(),
+        None => fontir_state.get_glyph_paths(glyph_name).unwrap_or_default(),
+    }
+}
+
+/// System to collect the snap targets around the active sort
+///
+/// Also clears the highlighted snap of the previous frame.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn update_snap_targets(
+    mut snap_state: ResMut<SnapState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut metrics_cache: ResMut<GlyphMetricsCache>,
+    guideline_state: Res<GuidelineState>,
+    camera_scale: Res<CameraResponsiveScale>,
+    active_sort: Query<(Entity, &Sort, &Transform), With<ActiveSort>>,
+    points: Query<(
+        &Transform,
+        &GlyphPointReference,
+        &PointType,
+        &SortPointEntity,
+        Has<Selected>,
+    )>,
+) {
+    snap_state.active = None;
+    snap_state.threshold_scale = camera_scale.scale_factor as f64;
+    snap_state.targets.clear();
+
+    let Ok((sort_entity, sort, sort_transform)) = active_sort.single() else {
+        return;
+    };
+    let origin = sort_transform.translation.truncate();
+    let mut targets = Vec::new();
+
+    // Metrics lines
+    if let Some(fontir_state) = fontir_state.as_ref() {
+        let metrics = metrics_cache.get_font_metrics(fontir_state);
+        let heights = [
+            Some(0.0),
+            metrics.x_height,
+            metrics.cap_height,
+            metrics.ascender,
+            metrics.descender,
+        ];
+        for height in heights.into_iter().flatten() {
+            let y = (origin.y + height) as f64;
+            targets.push(SnapTarget::horizontal(
+                SnapKind::Metric,
+                Point::new(origin.x as f64, y),
+            ));
+        }
+    }
+
+    // Guidelines
+    for guideline in &guideline_state.snap_lines {
+        targets.push(SnapTarget::line(
+            SnapKind::Guideline,
+            Point::new(guideline.x, guideline.y),
+            guideline.angle,
+        ));
+    }
+
+    // On-curve points and their alignments
+    let mut selected_contours = HashSet::new();
+    for (transform, point_ref, point_type, parent, selected) in points.iter() {
+        if parent.sort_entity != sort_entity {
+            continue;
+        }
+        if selected {
+            selected_contours.insert(point_ref.contour_index);
+            continue;
+        }
+        if !point_type.is_on_curve {
+            continue;
+        }
+        let position = Point::new(
+            transform.translation.x as f64,
+            transform.translation.y as f64,
+        );
+        targets.push(SnapTarget::point(SnapKind::Point, position));
+        targets.push(SnapTarget::horizontal(SnapKind::Alignment, position));
+        targets.push(SnapTarget::vertical(SnapKind::Alignment, position));
+    }
+
+    // Curve extrema, recomputed when the outline changes
+    if let Some(fontir_state) = fontir_state.as_ref() {
+        let stale = fontir_state.is_changed()
+            || snap_state
+                .extrema
+                .as_ref()
+                .is_none_or(|(glyph_name, _)| *glyph_name != sort.glyph_name);
+        if stale {
+            let extrema = glyph_contours(fontir_state, &sort.glyph_name)
+                .iter()
+                .map(contour_extrema)
+                .collect();
+            snap_state.extrema = Some((sort.glyph_name.clone(), extrema));
+        }
+    }
+    if let Some((_, extrema)) = &snap_state.extrema {
+        let offset = kurbo::Vec2::new(origin.x as f64, origin.y as f64);
+        for (contour_index, contour_extrema) in extrema.iter().enumerate() {
+            if selected_contours.contains(&contour_index) {
+                continue;
+            }
+            targets.extend(contour_extrema.iter().map(|extremum| {
+                SnapTarget::point(SnapKind::Extremum, *extremum + offset)
+            }));
+        }
+    }
+
+    snap_state.targets = targets;
+}
+
+/// Highlight the target of the latest snap
+fn draw_snap_highlight(
+    mut gizmos: Gizmos,
+    snap_state: Res<SnapState>,
+    camera_scale: Res<CameraResponsiveScale>,
+) {
+    let Some(result) = &snap_state.active else {
+        return;
+    };
+    let position = to_vec2(result.position);
+    let size = camera_scale.adjusted_point_size(SNAP_MARKER_SIZE);
+
+    for target in &result.targets {
+        match target.geometry {
+            SnapGeometry::Point(point) => {
+                gizmos.circle_2d(to_vec2(point), size, SNAP_HIGHLIGHT_COLOR);
+            }
+            // Alignment is shown from the aligned point to the snapped one
+            SnapGeometry::Line { origin, .. }
+                if target.kind == SnapKind::Alignment =>
+            {
+                gizmos.line_2d(to_vec2(origin), position, SNAP_HIGHLIGHT_COLOR);
+            }
+            SnapGeometry::Line { direction, .. } => {
+                let direction = to_vec2(direction.to_point());
+                gizmos.line_2d(
+                    position - direction * HIGHLIGHT_HALF_LENGTH,
+                    position + direction * HIGHLIGHT_HALF_LENGTH,
+                    SNAP_HIGHLIGHT_COLOR,
+                );
+            }
+        }
+    }
+
+    // Cross at the snapped position
+    gizmos.line_2d(
+        position - Vec2::splat(size),
+        position + Vec2::splat(size),
+        SNAP_HIGHLIGHT_COLOR,
+    );
+    gizmos.line_2d(
+        position + Vec2::new(-size, size),
+        position + Vec2::new(size, -size),
+        SNAP_HIGHLIGHT_COLOR,
+    );
+}
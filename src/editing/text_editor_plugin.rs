@@ -22,6 +22,7 @@ use crate::systems::text_editor_sorts::{
     spawn_active_sort_points_optimized, // NEW: Optimized instant point spawning
     spawn_missing_sort_entities, // NEW: Spawn ECS entities for buffer sorts
     sync_buffer_sort_activation_state, // NEW: Sync activation state from buffer to entities
+    sync_implied_on_curve_points,
 };
 
 use bevy::prelude::*;
@@ -48,6 +49,7 @@ impl Plugin for TextEditorPlugin {
                 spawn_missing_sort_entities,
                 sync_buffer_sort_activation_state, // NEW: Sync activation state after spawning
                 crate::systems::text_editor_sorts::sort_entities::update_buffer_sort_positions,
+                crate::systems::text_editor_sorts::sort_entities::attach_mark_sorts,
                 crate::systems::text_editor_sorts::sort_entities::auto_activate_selected_sorts,
                 manage_sort_activation,
             ).chain().in_set(super::FontEditorSets::EntitySync))
@@ -57,6 +59,7 @@ impl Plugin for TextEditorPlugin {
                 spawn_active_sort_points_optimized,
                 despawn_inactive_sort_points_optimized,
                 regenerate_points_on_fontir_change, // Regenerate when FontIR data changes
+                sync_implied_on_curve_points, // Keep implied quadratic on-curves centred
             ).chain().in_set(super::FontEditorSets::EntitySync))
             // Rendering systems
             .add_systems(Update, (
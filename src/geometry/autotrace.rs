@@ -0,0 +1,566 @@
+//! Bitmap autotracing
+//!
+//! Turns a grayscale bitmap, such as a scanned sketch, into closed contours.
+//! Pixels darker than a threshold are ink, and the edges of the ink are
+//! extracted as isolines with `contour-isobands` (marching squares). Corners
+//! are found by how sharply each isoline turns over a few pixels; the
+//! stretches between corners are smoothed to take out the pixel steps and
+//! refitted with as few cubic curves as the tolerance allows.
+//!
+//! Outer contours are wound counter-clockwise and counters clockwise, the
+//! usual PostScript direction, whatever the bitmap's placement.
+
+use crate::geometry::simplify::{append_segments, split_at_extrema};
+use contour_isobands::ContourBuilder;
+use kurbo::{
+    fit_to_bezpath_opt, Affine, BezPath, CurveFitSample, Line, ParamCurveFit,
+    PathSeg, Point, Vec2,
+};
+use std::ops::Range;
+
+/// How far along an isoline, in pixels, to look on either side of a point
+/// when measuring how sharply the outline turns there
+const CORNER_REACH: f64 = 2.0;
+
+/// Smoothing passes over the points between corners
+const SMOOTHING_PASSES: usize = 2;
+
+/// Upper end of the band of ink values; ink is never darker than 1
+const INK_BAND_TOP: f64 = 2.0;
+
+/// Points closer than this, in pixels, are merged
+const POINT_EPSILON: f64 = 1e-6;
+
+/// User-adjustable parameters for autotracing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceParams {
+    /// Darkness from 0 (white) to 1 (black) above which a pixel is ink
+    pub threshold: f64,
+    /// Maximum distance between the isolines and the fitted curves, in
+    /// font units
+    pub tolerance: f64,
+    /// Isolines turning by more than this many degrees over a few pixels
+    /// get a corner
+    pub corner_angle: f64,
+    /// Shapes and counters smaller than this many square pixels are left
+    /// out as specks
+    pub despeckle_area: f64,
+}
+
+impl Default for TraceParams {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            tolerance: 2.0,
+            corner_angle: 55.0,
+            despeckle_area: 4.0,
+        }
+    }
+}
+
+/// An outline of ink as pixel positions: its outer ring and the rings of
+/// its counters
+type InkPolygon = (Vec<Point>, Vec<Vec<Point>>);
+
+/// Trace a grayscale bitmap into closed contours
+///
+/// `pixels` holds one byte per pixel, 0 for black and 255 for white, row
+/// by row from the top. Pixel positions are mapped into the result by
+/// `transform` with the bitmap's bottom left corner at the origin and y
+/// going up, the way UFO glyph images are placed.
+pub fn trace_bitmap(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    transform: Affine,
+    params: &TraceParams,
+) -> Vec<BezPath> {
+    if width == 0 || height == 0 || pixels.len() < width * height {
+        return Vec::new();
+    }
+    let polygons = ink_outlines(pixels, width, height, params.threshold);
+    trace_polygons(&polygons, transform, params)
+}
+
+/// Edges of the ink in pixel positions, with y going up from the bitmap's
+/// bottom edge
+fn ink_outlines(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    threshold: f64,
+) -> Vec<InkPolygon> {
+    // A blank border around the bitmap closes shapes touching its edges
+    let (columns, rows) = (width + 2, height + 2);
+    let mut darkness = vec![0.0; columns * rows];
+    for row in 0..height {
+        // Grid rows go up from the bottom, bitmap rows down from the top
+        let grid_row = height - row;
+        for column in 0..width {
+            darkness[grid_row * columns + column + 1] =
+                1.0 - pixels[row * width + column] as f64 / 255.0;
+        }
+    }
+
+    // Grid values sit at pixel centers
+    let bands = ContourBuilder::new(columns, rows)
+        .x_origin(-0.5)
+        .y_origin(-0.5)
+        .x_step(1.0)
+        .y_step(1.0)
+        .contours(&darkness, &[threshold, INK_BAND_TOP])
+        .unwrap_or_default();
+    bands
+        .iter()
+        .flat_map(|band| band.geometry().0.iter())
+        .map(|polygon| {
+            let exterior = polygon
+                .exterior()
+                .0
+                .iter()
+                .map(|c| Point::new(c.x, c.y))
+                .collect();
+            let interiors = polygon
+                .interiors()
+                .iter()
+                .map(|ring| {
+                    ring.0.iter().map(|c| Point::new(c.x, c.y)).collect()
+                })
+                .collect();
+            (exterior, interiors)
+        })
+        .collect()
+}
+
+/// Fit and orient the rings of ink polygons, leaving out specks
+fn trace_polygons(
+    polygons: &[InkPolygon],
+    transform: Affine,
+    params: &TraceParams,
+) -> Vec<BezPath> {
+    let mut contours = Vec::new();
+    for (exterior, interiors) in polygons {
+        if polygon_area(exterior).abs() < params.despeckle_area {
+            continue;
+        }
+        if let Some(outer) = fit_ring(exterior, transform, params) {
+            contours.push(orient(outer, true));
+        }
+        for interior in interiors {
+            if polygon_area(interior).abs() < params.despeckle_area {
+                continue;
+            }
+            if let Some(counter) = fit_ring(interior, transform, params) {
+                contours.push(orient(counter, false));
+            }
+        }
+    }
+    contours
+}
+
+/// Fit a closed isoline with lines and cubic curves, keeping its corners
+fn fit_ring(
+    ring: &[Point],
+    transform: Affine,
+    params: &TraceParams,
+) -> Option<BezPath> {
+    let points = distinct_points(ring);
+    if points.len() < 3 {
+        return None;
+    }
+    let (points, mut corners) = sharpen_corners(points, params.corner_angle);
+    if points.len() < 3 {
+        return None;
+    }
+
+    // A ring without corners is split at its top and bottom, where the
+    // fitted halves meet on horizontal tangents
+    if corners.is_empty() {
+        let height = |i: usize| (transform * points[i]).y;
+        let by_height =
+            |&a: &usize, &b: &usize| height(a).total_cmp(&height(b));
+        let top = (0..points.len()).max_by(by_height).unwrap_or(0);
+        let bottom = (0..points.len()).min_by(by_height).unwrap_or(0);
+        corners = vec![top.min(bottom), top.max(bottom)];
+        corners.dedup();
+    }
+
+    // Runs between corners are smoothed and fitted one by one, so corners
+    // are never smoothed away
+    let first_corner = corners[0];
+    let mut segments: Vec<PathSeg> = Vec::new();
+    for (i, &start) in corners.iter().enumerate() {
+        let end = corners.get(i + 1).copied().unwrap_or(first_corner);
+        let mut run: Vec<Point> = if end > start {
+            points[start..=end].to_vec()
+        } else {
+            points[start..]
+                .iter()
+                .chain(&points[..=end])
+                .copied()
+                .collect()
+        };
+        smooth_run(&mut run);
+        let run: Vec<Point> = run.into_iter().map(|p| transform * p).collect();
+        segments.extend(fit_run(run, params.tolerance));
+    }
+    let mut contour = BezPath::new();
+    append_segments(&mut contour, &segments, true);
+    Some(contour)
+}
+
+/// The points of a ring without repeats or the closing point
+fn distinct_points(ring: &[Point]) -> Vec<Point> {
+    let mut points: Vec<Point> = Vec::with_capacity(ring.len());
+    for &point in ring {
+        if points
+            .last()
+            .is_none_or(|last| last.distance(point) > POINT_EPSILON)
+        {
+            points.push(point);
+        }
+    }
+    while points.len() > 1
+        && points[0].distance(points[points.len() - 1]) <= POINT_EPSILON
+    {
+        points.pop();
+    }
+    points
+}
+
+/// Find the corners of a ring, returning the ring with each corner reduced
+/// to a single point and the indices of the corners
+///
+/// Marching squares cuts a corner with a short chamfer, so points next to
+/// each other that all turn sharply belong to one corner. The point turning
+/// the most is kept as the corner and the rest are dropped.
+fn sharpen_corners(
+    points: Vec<Point>,
+    corner_angle: f64,
+) -> (Vec<Point>, Vec<usize>) {
+    let count = points.len();
+    let angles: Vec<f64> =
+        (0..count).map(|i| turning_angle(&points, i)).collect();
+    let is_sharp = |i: usize| angles[i] > corner_angle;
+
+    // Walk the ring from a point that isn't sharp, so no group of sharp
+    // points is split at the seam
+    let Some(start) = (0..count).find(|&i| !is_sharp(i)) else {
+        // Every point is sharp, as in a tiny blob; keep the sharpest
+        let sharpest = (0..count)
+            .max_by(|&a, &b| angles[a].total_cmp(&angles[b]))
+            .unwrap_or(0);
+        return (points, vec![sharpest]);
+    };
+
+    let mut kept = Vec::with_capacity(count);
+    let mut corners = Vec::new();
+    let mut group: Vec<usize> = Vec::new();
+    for step in 1..=count {
+        let i = (start + step) % count;
+        if is_sharp(i) {
+            group.push(i);
+            continue;
+        }
+        if let Some(&corner) = group
+            .iter()
+            .max_by(|&&a, &&b| angles[a].total_cmp(&angles[b]))
+        {
+            corners.push(kept.len());
+            kept.push(points[corner]);
+            group.clear();
+        }
+        kept.push(points[i]);
+    }
+    (kept, corners)
+}
+
+/// How sharply, in degrees, a ring turns at a point, measured between
+/// points about `CORNER_REACH` pixels away on either side
+fn turning_angle(points: &[Point], index: usize) -> f64 {
+    let point = points[index];
+    let incoming = point - point_at_reach(points, index, false);
+    let outgoing = point_at_reach(points, index, true) - point;
+    if incoming.hypot() <= POINT_EPSILON || outgoing.hypot() <= POINT_EPSILON {
+        return 0.0;
+    }
+    incoming
+        .cross(outgoing)
+        .atan2(incoming.dot(outgoing))
+        .abs()
+        .to_degrees()
+}
+
+/// First point of a ring at least `CORNER_REACH` away from a point, going
+/// forwards or backwards; the nearest neighbour if none is that far
+fn point_at_reach(points: &[Point], index: usize, forwards: bool) -> Point {
+    let count = points.len();
+    let at = |step: usize| {
+        if forwards {
+            points[(index + step) % count]
+        } else {
+            points[(index + count - step % count) % count]
+        }
+    };
+    (1..count)
+        .map(at)
+        .find(|p| p.distance(points[index]) >= CORNER_REACH)
+        .unwrap_or_else(|| at(1))
+}
+
+/// Smooth the points of a run between two corners, which stay in place
+fn smooth_run(run: &mut [Point]) {
+    for _ in 0..SMOOTHING_PASSES {
+        let previous = run.to_vec();
+        for i in 1..run.len().saturating_sub(1) {
+            run[i] = average(previous[i - 1], previous[i], previous[i + 1]);
+        }
+    }
+}
+
+/// A point pulled towards its neighbours
+fn average(previous: Point, point: Point, next: Point) -> Point {
+    let sum: Vec2 = previous.to_vec2() + 2.0 * point.to_vec2() + next.to_vec2();
+    (sum / 4.0).to_point()
+}
+
+/// Fit a run of points with a line if it is straight, and with as few
+/// cubic curves as the tolerance allows otherwise, split at extrema
+fn fit_run(run: Vec<Point>, tolerance: f64) -> Vec<PathSeg> {
+    let (start, end) = (run[0], run[run.len() - 1]);
+    let chord = end - start;
+    let is_straight = run.len() < 3
+        || (chord.hypot() > POINT_EPSILON
+            && run.iter().all(|&p| {
+                chord.cross(p - start).abs() / chord.hypot() <= tolerance
+            }));
+    if is_straight {
+        return vec![PathSeg::Line(Line::new(start, end))];
+    }
+    fit_to_bezpath_opt(&Isoline::new(run), tolerance.max(1e-3))
+        .segments()
+        .flat_map(split_at_extrema)
+        .collect()
+}
+
+/// A run of isoline points as a curve to fit, with tangents that turn
+/// gradually from point to point rather than at each one
+struct Isoline {
+    points: Vec<Point>,
+    tangents: Vec<Vec2>,
+}
+
+impl Isoline {
+    fn new(points: Vec<Point>) -> Self {
+        let last = points.len() - 1;
+        let tangents = (0..=last)
+            .map(|i| {
+                let direction =
+                    points[(i + 1).min(last)] - points[i.max(1) - 1];
+                // A run doubling back on itself has no direction here
+                if direction.hypot() > POINT_EPSILON {
+                    direction.normalize()
+                } else {
+                    Vec2::ZERO
+                }
+            })
+            .collect();
+        Self { points, tangents }
+    }
+
+    /// Index of the line a parameter falls on, and the parameter along it
+    fn locate(&self, t: f64) -> (usize, f64) {
+        let lines = self.points.len() - 1;
+        let position = t.clamp(0.0, 1.0) * lines as f64;
+        let index = (position.floor() as usize).min(lines - 1);
+        (index, position - index as f64)
+    }
+}
+
+impl ParamCurveFit for Isoline {
+    fn sample_pt_deriv(&self, t: f64) -> (Point, Vec2) {
+        let (i, u) = self.locate(t);
+        let (p0, p1) = (self.points[i], self.points[i + 1]);
+        let lines = (self.points.len() - 1) as f64;
+        (p0.lerp(p1, u), (p1 - p0) * lines)
+    }
+
+    fn sample_pt_tangent(&self, t: f64, _sign: f64) -> CurveFitSample {
+        let (i, u) = self.locate(t);
+        CurveFitSample {
+            p: self.points[i].lerp(self.points[i + 1], u),
+            tangent: self.tangents[i].lerp(self.tangents[i + 1], u),
+        }
+    }
+
+    fn break_cusp(&self, _range: Range<f64>) -> Option<f64> {
+        None
+    }
+}
+
+/// Signed area of a polygon, positive when it runs counter-clockwise
+fn polygon_area(points: &[Point]) -> f64 {
+    let count = points.len();
+    (0..count)
+        .map(|i| points[i].to_vec2().cross(points[(i + 1) % count].to_vec2()))
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Wind a contour counter-clockwise (or clockwise) in y-up coordinates
+fn orient(contour: BezPath, counter_clockwise: bool) -> BezPath {
+    if (kurbo::Shape::area(&contour) > 0.0) == counter_clockwise {
+        contour
+    } else {
+        contour.reverse_subpaths()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kurbo::{ParamCurve, Shape};
+
+    /// A square of isoline points one pixel apart, counter-clockwise
+    fn square_ring(min: f64, max: f64) -> Vec<Point> {
+        let steps = (max - min) as usize;
+        let mut ring = Vec::new();
+        for i in 0..steps {
+            ring.push(Point::new(min + i as f64, min));
+        }
+        for i in 0..steps {
+            ring.push(Point::new(max, min + i as f64));
+        }
+        for i in 0..steps {
+            ring.push(Point::new(max - i as f64, max));
+        }
+        for i in 0..steps {
+            ring.push(Point::new(min, max - i as f64));
+        }
+        ring.push(ring[0]);
+        ring
+    }
+
+    /// A circle of isoline points, counter-clockwise
+    fn circle_ring(center: Point, radius: f64, sides: usize) -> Vec<Point> {
+        (0..=sides)
+            .map(|i| {
+                let angle = i as f64 / sides as f64 * std::f64::consts::TAU;
+                center + radius * Vec2::new(angle.cos(), angle.sin())
+            })
+            .collect()
+    }
+
+    fn on_curve_points(path: &BezPath) -> Vec<Point> {
+        path.segments().map(|seg| seg.start()).collect()
+    }
+
+    #[test]
+    fn test_sharpen_corners_finds_square_corners() {
+        let (points, corners) = sharpen_corners(
+            distinct_points(&square_ring(0.0, 20.0)),
+            TraceParams::default().corner_angle,
+        );
+        assert_eq!(corners.len(), 4);
+        let mut corner_points: Vec<(i64, i64)> = corners
+            .iter()
+            .map(|&i| (points[i].x.round() as i64, points[i].y.round() as i64))
+            .collect();
+        corner_points.sort_unstable();
+        assert_eq!(corner_points, vec![(0, 0), (0, 20), (20, 0), (20, 20)]);
+    }
+
+    #[test]
+    fn test_trace_square_with_counter() {
+        let polygons =
+            vec![(square_ring(0.0, 40.0), vec![square_ring(10.0, 30.0)])];
+        let transform = Affine::scale(10.0);
+        let contours =
+            trace_polygons(&polygons, transform, &TraceParams::default());
+        assert_eq!(contours.len(), 2);
+
+        // The outer square is counter-clockwise and the counter clockwise,
+        // each made of four lines between its corners
+        assert!(contours[0].area() > 0.0);
+        assert!(contours[1].area() < 0.0);
+        assert!((contours[0].area() - 160_000.0).abs() < 1.0);
+        assert!((contours[1].area() + 40_000.0).abs() < 1.0);
+        for contour in &contours {
+            assert_eq!(on_curve_points(contour).len(), 4);
+            assert!(contour
+                .segments()
+                .all(|seg| matches!(seg, PathSeg::Line(_))));
+        }
+    }
+
+    #[test]
+    fn test_trace_circle_fits_few_curves() {
+        let center = Point::new(50.0, 50.0);
+        let ring = circle_ring(center, 40.0, 240);
+        let contours = trace_polygons(
+            &[(ring, Vec::new())],
+            Affine::IDENTITY,
+            &TraceParams {
+                tolerance: 0.5,
+                ..Default::default()
+            },
+        );
+        assert_eq!(contours.len(), 1);
+        let count = contours[0].segments().count();
+        assert!(count <= 8, "expected few curves, got {count}");
+        for seg in contours[0].segments() {
+            for i in 0..=8 {
+                let p = seg.eval(i as f64 / 8.0);
+                let radius = p.distance(center);
+                assert!((radius - 40.0).abs() < 1.0, "radius {radius}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_trace_leaves_out_specks_and_mirrors() {
+        let polygons = vec![
+            (square_ring(0.0, 20.0), Vec::new()),
+            (square_ring(30.0, 31.0), Vec::new()),
+        ];
+        // A mirrored placement still gives a counter-clockwise outline
+        let transform = Affine::FLIP_X;
+        let contours =
+            trace_polygons(&polygons, transform, &TraceParams::default());
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].area() > 0.0);
+        assert!(contours[0].bounding_box().x1 <= 0.0);
+    }
+
+    #[test]
+    fn test_trace_bitmap() {
+        // A dark ring on white, 12 pixels wide, with a 4 pixel hole
+        let (width, height) = (16, 16);
+        let mut pixels = vec![255u8; width * height];
+        for row in 2..14 {
+            for column in 2..14 {
+                let in_hole =
+                    (6..10).contains(&row) && (6..10).contains(&column);
+                if !in_hole {
+                    pixels[row * width + column] = 0;
+                }
+            }
+        }
+        let contours = trace_bitmap(
+            &pixels,
+            width,
+            height,
+            Affine::IDENTITY,
+            &TraceParams {
+                tolerance: 0.1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(contours.len(), 2);
+        let outer = contours[0].bounding_box();
+        assert!((outer.x0 - 2.0).abs() < 0.6 && (outer.x1 - 14.0).abs() < 0.6);
+        assert!((outer.y0 - 2.0).abs() < 0.6 && (outer.y1 - 14.0).abs() < 0.6);
+        assert!(contours[0].area() > 0.0);
+        assert!(contours[1].area() < 0.0);
+    }
+}
@@ -190,6 +190,42 @@ pub fn find_nearest_point(
     nearest
 }
 
+/// Map a flat point index to the index of its on-curve node
+///
+/// Flat indices count every point in `extract_editable_points` order (the
+/// same numbering point entities use). Node 0 is the start point and node `n`
+/// ends the `n`th segment, so a run of selected nodes `a..=b` covers segments
+/// `a..b`. Returns `None` for off-curve points.
+pub fn on_curve_node_index(
+    path: &BezPath,
+    point_index: usize,
+) -> Option<usize> {
+    let points = extract_editable_points(path);
+    let point = points.get(point_index)?;
+    if point.point_type != PathPointType::OnCurve {
+        return None;
+    }
+    let on_curve_before = points[..point_index]
+        .iter()
+        .filter(|p| p.point_type == PathPointType::OnCurve)
+        .count();
+    Some(on_curve_before)
+}
+
+/// Number of on-curve nodes in a path, numbered as `on_curve_node_index`
+/// numbers them
+pub fn on_curve_node_count(path: &BezPath) -> usize {
+    extract_editable_points(path)
+        .iter()
+        .filter(|p| p.point_type == PathPointType::OnCurve)
+        .count()
+}
+
+/// Whether a contour is closed, ending with `ClosePath`
+pub fn is_closed(path: &BezPath) -> bool {
+    matches!(path.elements().last(), Some(PathEl::ClosePath))
+}
+
 /// Convert multiple BezPaths to a single path with multiple contours
 pub fn paths_to_multi_contour(paths: &[BezPath]) -> BezPath {
     let mut result = BezPath::new();
@@ -239,4 +275,28 @@ mod tests {
         let points = extract_editable_points(&path);
         assert_eq!(points[1].position, Point::new(200.0, 50.0));
     }
+
+    #[test]
+    fn test_on_curve_node_index() {
+        let mut path = BezPath::new();
+        path.move_to(Point::new(0.0, 0.0));
+        path.curve_to(
+            Point::new(50.0, 0.0),
+            Point::new(100.0, 50.0),
+            Point::new(100.0, 100.0),
+        );
+        path.line_to(Point::new(0.0, 100.0));
+        path.close_path();
+
+        assert_eq!(on_curve_node_index(&path, 0), Some(0));
+        assert_eq!(on_curve_node_index(&path, 1), None);
+        assert_eq!(on_curve_node_index(&path, 2), None);
+        assert_eq!(on_curve_node_index(&path, 3), Some(1));
+        assert_eq!(on_curve_node_index(&path, 4), Some(2));
+        assert_eq!(on_curve_node_index(&path, 5), None);
+        assert_eq!(on_curve_node_count(&path), 3);
+        assert!(is_closed(&path));
+        path.pop();
+        assert!(!is_closed(&path));
+    }
 }
@@ -1,9 +1,15 @@
 //! Geometric Primitives and Operations
 
+pub mod autotrace;
 pub mod bezpath_editing;
 pub mod design_space;
+pub mod offset;
+pub mod outline_check;
 pub mod point;
 pub mod quadrant;
+pub mod quadratic;
+pub mod simplify;
+pub mod snapping;
 
 // Re-export commonly used items
 pub use design_space::{DPoint, DVec2};
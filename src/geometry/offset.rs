@@ -0,0 +1,552 @@
+//! Contour offsetting and stroke expansion
+//!
+//! Offsetting grows or shrinks closed contours by a fixed distance, while
+//! stroke expansion turns a centerline into a closed outline of a given
+//! width. Both are built on kurbo's stroker: a closed contour stroked at twice
+//! the offset distance yields one contour on each side of the original, and
+//! the side we want is picked by comparing areas.
+//!
+//! The results only contain lines and cubic curves, wound in the usual
+//! PostScript direction (outer contours counter-clockwise). The stroker
+//! leaves small loops on the inside of sharp corners; these are trimmed back
+//! to the point where neighbouring segments cross. Overlaps between distant
+//! parts of a contour are left for the remove-overlap step.
+
+use crate::geometry::simplify::{simplify_path, SimplifyParams};
+use kurbo::{
+    BezPath, Line, ParamCurve, ParamCurveNearest, PathEl, PathSeg, Point, Rect,
+    Shape, Stroke, StrokeOpts,
+};
+
+pub use kurbo::{Cap, Join};
+
+/// Distances and areas below this are treated as zero
+const EPSILON: f64 = 1e-6;
+
+/// How many segments ahead to look for a crossing when trimming corner loops
+const LOOP_WINDOW: usize = 8;
+
+/// Intersections this close to a segment end are not treated as crossings
+const T_EPSILON: f64 = 1e-6;
+
+/// Subdivision depth after which a curve/curve crossing is considered found
+const MAX_INTERSECT_DEPTH: usize = 40;
+
+/// User-adjustable parameters shared by offsetting and stroke expansion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetParams {
+    /// How the offset outline is connected at corners
+    pub join: Join,
+    /// Ratio of miter length to offset width beyond which miter joins are
+    /// beveled
+    pub miter_limit: f64,
+    /// Maximum deviation from the exact offset curve, in font units
+    pub tolerance: f64,
+    /// Whether the result is refitted to reduce the number of points
+    pub simplify: bool,
+}
+
+impl Default for OffsetParams {
+    fn default() -> Self {
+        Self {
+            join: Join::Miter,
+            miter_limit: 4.0,
+            tolerance: 0.5,
+            simplify: true,
+        }
+    }
+}
+
+/// Offset every closed contour in a path
+///
+/// Positive distances make the shape bolder (outer contours grow, counters
+/// shrink); negative distances make it lighter. Open contours, and contours
+/// that would collapse at this distance, are left unchanged.
+pub fn offset_path(
+    path: &BezPath,
+    distance: f64,
+    params: &OffsetParams,
+) -> BezPath {
+    let mut result = BezPath::new();
+    for (contour, closed) in split_contours(path) {
+        let offset = closed
+            .then(|| offset_contour(&contour, distance, params))
+            .flatten();
+        result.extend(offset.unwrap_or(contour));
+    }
+    result
+}
+
+/// Offset a single closed contour
+///
+/// Returns `None` if the contour is degenerate, or if it would collapse (even
+/// partly) at this distance. The result keeps the winding direction of the input.
+pub fn offset_contour(
+    contour: &BezPath,
+    distance: f64,
+    params: &OffsetParams,
+) -> Option<BezPath> {
+    let area = contour.area();
+    if area.abs() < EPSILON {
+        return None;
+    }
+    if distance.abs() < EPSILON {
+        return Some(contour.clone());
+    }
+
+    let style = Stroke::new(2.0 * distance.abs())
+        .with_join(params.join)
+        .with_miter_limit(params.miter_limit);
+    let stroked = kurbo::stroke(
+        contour,
+        &style,
+        &StrokeOpts::default(),
+        params.tolerance,
+    );
+
+    // Ink lies to the left of counter-clockwise contours, so making a
+    // counter-clockwise contour bolder means taking the larger side
+    let grow = (distance > 0.0) == (area > 0.0);
+    let sides = split_contours(&stroked)
+        .into_iter()
+        .filter(|(_, closed)| *closed)
+        .map(|(side, _)| {
+            let side_area = side.area();
+            (side, side_area)
+        });
+    let (side, _) = if grow {
+        sides.max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))?
+    } else {
+        sides.min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))?
+    };
+
+    // A shrunk side that turned inside out, or that still comes closer to
+    // the original than the offset distance after trimming, means the offset
+    // ran past the middle of the shape
+    let side = trim_corner_loops(&orient(side, area > 0.0));
+    let side_area = side.area();
+    if !grow
+        && (side_area.abs() < EPSILON
+            || (side_area > 0.0) != (area > 0.0)
+            || !keeps_distance(&side, contour, distance.abs(), params))
+    {
+        return None;
+    }
+
+    Some(finish(&side, params))
+}
+
+/// Expand contours into closed outlines of the given stroke width
+///
+/// Open contours become a single outline capped at both ends. Closed contours
+/// become a ring: an outer contour and a counter wound the opposite way.
+/// Returns one path per resulting contour.
+pub fn expand_stroke(
+    path: &BezPath,
+    width: f64,
+    cap: Cap,
+    params: &OffsetParams,
+) -> Vec<BezPath> {
+    let mut result = Vec::new();
+    if width.abs() < EPSILON {
+        return result;
+    }
+    let style = Stroke::new(width.abs())
+        .with_join(params.join)
+        .with_miter_limit(params.miter_limit)
+        .with_caps(cap);
+
+    for (contour, closed) in split_contours(path) {
+        let stroked = kurbo::stroke(
+            &contour,
+            &style,
+            &StrokeOpts::default(),
+            params.tolerance,
+        );
+        let outlines: Vec<BezPath> = split_contours(&stroked)
+            .into_iter()
+            .map(|(outline, _)| trim_corner_loops(&outline))
+            .filter(|outline| outline.area().abs() > EPSILON)
+            .collect();
+        let outer = outlines
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.area().abs().total_cmp(&b.1.area().abs()))
+            .map(|(index, _)| index);
+
+        for (index, outline) in outlines.into_iter().enumerate() {
+            let counter_clockwise = !closed || Some(index) == outer;
+            result.push(finish(&orient(outline, counter_clockwise), params));
+        }
+    }
+    result
+}
+
+/// Wind a contour counter-clockwise (or clockwise) in y-up coordinates
+fn orient(contour: BezPath, counter_clockwise: bool) -> BezPath {
+    if (contour.area() > 0.0) == counter_clockwise {
+        contour
+    } else {
+        contour.reverse_subpaths()
+    }
+}
+
+/// Optionally refit the offset outline and drop any leftover degenerate
+/// segments
+fn finish(contour: &BezPath, params: &OffsetParams) -> BezPath {
+    let contour = if params.simplify {
+        let simplify_params = SimplifyParams {
+            tolerance: params.tolerance,
+            ..SimplifyParams::default()
+        };
+        simplify_path(contour, &simplify_params)
+    } else {
+        contour.clone()
+    };
+    split_contours(&contour)
+        .into_iter()
+        .flat_map(|(contour, _)| contour)
+        .collect()
+}
+
+/// Whether every part of an offset outline is at least the offset distance
+/// away from the contour it was made from
+fn keeps_distance(
+    offset: &BezPath,
+    original: &BezPath,
+    distance: f64,
+    params: &OffsetParams,
+) -> bool {
+    let min_distance = distance - params.tolerance - EPSILON;
+    let original_segments: Vec<PathSeg> = original.segments().collect();
+    offset
+        .segments()
+        .flat_map(|segment| [segment.eval(0.0), segment.eval(0.5)])
+        .all(|sample| {
+            original_segments.iter().all(|segment| {
+                segment.nearest(sample, EPSILON).distance_sq
+                    >= min_distance * min_distance
+            })
+        })
+}
+
+/// Cut away small loops where nearby segments of a closed contour cross
+///
+/// Each crossing between a segment and one of the next few segments is
+/// resolved by ending the first segment at the crossing, starting the second
+/// one there, and dropping everything in between.
+fn trim_corner_loops(contour: &BezPath) -> BezPath {
+    let mut segments: Vec<PathSeg> = contour.segments().collect();
+    'search: loop {
+        let count = segments.len();
+        if count < 4 {
+            break;
+        }
+        let window = LOOP_WINDOW.min(count / 2);
+        for first in 0..count {
+            for step in 2..=window {
+                let second = (first + step) % count;
+                let Some((t_first, t_second)) =
+                    intersect_segments(segments[first], segments[second])
+                else {
+                    continue;
+                };
+                let crossing = segments[first].eval(t_first);
+                let head = with_end(
+                    segments[first].subsegment(0.0..t_first),
+                    crossing,
+                );
+                let tail = with_start(
+                    segments[second].subsegment(t_second..1.0),
+                    crossing,
+                );
+                segments.rotate_left(first);
+                segments[0] = head;
+                segments[step] = tail;
+                segments.drain(1..step);
+                continue 'search;
+            }
+        }
+        break;
+    }
+
+    let Some(first) = segments.first() else {
+        return BezPath::new();
+    };
+    let mut result = BezPath::new();
+    result.move_to(first.start());
+    for segment in &segments {
+        match *segment {
+            PathSeg::Line(line) => result.line_to(line.p1),
+            PathSeg::Quad(quad) => result.quad_to(quad.p1, quad.p2),
+            PathSeg::Cubic(cubic) => {
+                result.curve_to(cubic.p1, cubic.p2, cubic.p3)
+            }
+        }
+    }
+    result.close_path();
+    result
+}
+
+/// Find where two segments cross, as parameters on each
+///
+/// If there are several crossings, the one latest on the first segment is
+/// returned, since that one encloses the smallest loop.
+fn intersect_segments(a: PathSeg, b: PathSeg) -> Option<(f64, f64)> {
+    let inside = |t: f64| t > T_EPSILON && t < 1.0 - T_EPSILON;
+    let crossings: Vec<(f64, f64)> = match (a, b) {
+        (_, PathSeg::Line(line)) => a
+            .intersect_line(line)
+            .iter()
+            .map(|hit| (hit.segment_t, hit.line_t))
+            .collect(),
+        (PathSeg::Line(line), _) => b
+            .intersect_line(line)
+            .iter()
+            .map(|hit| (hit.line_t, hit.segment_t))
+            .collect(),
+        _ => {
+            let mut found = Vec::new();
+            intersect_curves(a, 0.0..1.0, b, 0.0..1.0, 0, &mut found);
+            found
+        }
+    };
+    crossings
+        .into_iter()
+        .filter(|(ta, tb)| inside(*ta) && inside(*tb))
+        .max_by(|x, y| x.0.total_cmp(&y.0))
+}
+
+/// Find curve/curve crossings by subdividing until the bounding boxes of
+/// both pieces are tiny
+fn intersect_curves(
+    a: PathSeg,
+    range_a: std::ops::Range<f64>,
+    b: PathSeg,
+    range_b: std::ops::Range<f64>,
+    depth: usize,
+    found: &mut Vec<(f64, f64)>,
+) {
+    let box_a = a.subsegment(range_a.clone()).bounding_box();
+    let box_b = b.subsegment(range_b.clone()).bounding_box();
+    if !boxes_overlap(box_a, box_b) {
+        return;
+    }
+    let mid_a = (range_a.start + range_a.end) / 2.0;
+    let mid_b = (range_b.start + range_b.end) / 2.0;
+    let tiny = |r: Rect| r.width().max(r.height()) < EPSILON;
+    if depth >= MAX_INTERSECT_DEPTH || (tiny(box_a) && tiny(box_b)) {
+        // Neighbouring pieces report the same crossing; keep just one
+        let is_new = found.iter().all(|(ta, tb)| {
+            (ta - mid_a).abs() > 1e-4 || (tb - mid_b).abs() > 1e-4
+        });
+        if is_new {
+            found.push((mid_a, mid_b));
+        }
+        return;
+    }
+    for sub_a in [range_a.start..mid_a, mid_a..range_a.end] {
+        for sub_b in [range_b.start..mid_b, mid_b..range_b.end] {
+            intersect_curves(a, sub_a.clone(), b, sub_b, depth + 1, found);
+        }
+    }
+}
+
+/// Whether two bounding boxes touch (boxes of straight pieces can be flat)
+fn boxes_overlap(a: Rect, b: Rect) -> bool {
+    a.x0 <= b.x1 + EPSILON
+        && b.x0 <= a.x1 + EPSILON
+        && a.y0 <= b.y1 + EPSILON
+        && b.y0 <= a.y1 + EPSILON
+}
+
+/// Replace the start point of a segment
+fn with_start(segment: PathSeg, start: Point) -> PathSeg {
+    match segment {
+        PathSeg::Line(line) => PathSeg::Line(Line::new(start, line.p1)),
+        PathSeg::Quad(mut quad) => {
+            quad.p0 = start;
+            PathSeg::Quad(quad)
+        }
+        PathSeg::Cubic(mut cubic) => {
+            cubic.p0 = start;
+            PathSeg::Cubic(cubic)
+        }
+    }
+}
+
+/// Replace the end point of a segment
+fn with_end(segment: PathSeg, end: Point) -> PathSeg {
+    match segment {
+        PathSeg::Line(line) => PathSeg::Line(Line::new(line.p0, end)),
+        PathSeg::Quad(mut quad) => {
+            quad.p2 = end;
+            PathSeg::Quad(quad)
+        }
+        PathSeg::Cubic(mut cubic) => {
+            cubic.p3 = end;
+            PathSeg::Cubic(cubic)
+        }
+    }
+}
+
+/// Split a path into its contours, with degenerate segments removed
+///
+/// Each contour is returned together with whether it is closed. Subpaths that
+/// end where they started (as the stroker produces for round caps) are closed.
+fn split_contours(path: &BezPath) -> Vec<(BezPath, bool)> {
+    let mut contours = Vec::new();
+    let mut current: Option<(Point, Vec<PathEl>)> = None;
+
+    let mut flush = |current: &mut Option<(Point, Vec<PathEl>)>,
+                     explicit_close: bool| {
+        if let Some((start, mut elements)) = current.take() {
+            let last = elements
+                .last()
+                .and_then(|el| el.end_point())
+                .unwrap_or(start);
+            let closed =
+                explicit_close || (elements.len() > 2 && last == start);
+            if closed
+                && matches!(elements.last(), Some(PathEl::LineTo(p)) if *p == start)
+            {
+                elements.pop();
+            }
+            if elements.len() < 2 {
+                return;
+            }
+            if closed {
+                elements.push(PathEl::ClosePath);
+            }
+            contours.push((BezPath::from_vec(elements), closed));
+        }
+    };
+
+    for el in path.elements() {
+        match *el {
+            PathEl::MoveTo(p) => {
+                flush(&mut current, false);
+                current = Some((p, vec![PathEl::MoveTo(p)]));
+            }
+            PathEl::ClosePath => flush(&mut current, true),
+            el => {
+                let Some((_, elements)) = current.as_mut() else {
+                    continue;
+                };
+                let from = elements
+                    .last()
+                    .and_then(|last| last.end_point())
+                    .unwrap_or_default();
+                if !is_degenerate(from, el) {
+                    elements.push(el);
+                }
+            }
+        }
+    }
+    flush(&mut current, false);
+    contours
+}
+
+/// Whether a segment starting at `from` has (almost) no extent
+fn is_degenerate(from: Point, el: PathEl) -> bool {
+    let near = |p: Point| (p - from).hypot() < EPSILON;
+    match el {
+        PathEl::LineTo(p) => near(p),
+        PathEl::QuadTo(p1, p2) => near(p1) && near(p2),
+        PathEl::CurveTo(p1, p2, p3) => near(p1) && near(p2) && near(p3),
+        PathEl::MoveTo(_) | PathEl::ClosePath => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kurbo::Rect;
+
+    fn square(size: f64) -> BezPath {
+        Rect::new(0.0, 0.0, size, size).to_path(0.1)
+    }
+
+    fn assert_bounds(path: &BezPath, expected: Rect) {
+        let bounds = path.bounding_box();
+        for (actual, wanted) in [
+            (bounds.x0, expected.x0),
+            (bounds.y0, expected.y0),
+            (bounds.x1, expected.x1),
+            (bounds.y1, expected.y1),
+        ] {
+            assert!(
+                (actual - wanted).abs() < 0.5,
+                "bounds {bounds:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_offset_grows_and_shrinks_outer_contour() {
+        let params = OffsetParams::default();
+        let grown = offset_contour(&square(100.0), 10.0, &params).unwrap();
+        assert_bounds(&grown, Rect::new(-10.0, -10.0, 110.0, 110.0));
+        assert!(grown.area() > 0.0);
+
+        let shrunk = offset_contour(&square(100.0), -10.0, &params).unwrap();
+        assert_bounds(&shrunk, Rect::new(10.0, 10.0, 90.0, 90.0));
+        assert!(shrunk.area() > 0.0);
+    }
+
+    #[test]
+    fn test_offset_circle() {
+        let circle = kurbo::Circle::new((0.0, 0.0), 50.0).to_path(0.1);
+        let params = OffsetParams::default();
+        let shrunk = offset_contour(&circle, -10.0, &params).unwrap();
+        assert_bounds(&shrunk, Rect::new(-40.0, -40.0, 40.0, 40.0));
+        let grown = offset_contour(&circle, 10.0, &params).unwrap();
+        assert_bounds(&grown, Rect::new(-60.0, -60.0, 60.0, 60.0));
+    }
+
+    #[test]
+    fn test_offset_bolder_shrinks_counter() {
+        let counter = square(100.0).reverse_subpaths();
+        let params = OffsetParams::default();
+        let offset = offset_contour(&counter, 10.0, &params).unwrap();
+        assert_bounds(&offset, Rect::new(10.0, 10.0, 90.0, 90.0));
+        assert!(offset.area() < 0.0);
+    }
+
+    #[test]
+    fn test_offset_collapse_keeps_contour() {
+        let params = OffsetParams::default();
+        assert!(offset_contour(&square(20.0), -15.0, &params).is_none());
+        let unchanged = offset_path(&square(20.0), -15.0, &params);
+        assert_bounds(&unchanged, Rect::new(0.0, 0.0, 20.0, 20.0));
+    }
+
+    #[test]
+    fn test_expand_open_line() {
+        let mut line = BezPath::new();
+        line.move_to((0.0, 0.0));
+        line.line_to((100.0, 0.0));
+
+        let params = OffsetParams::default();
+        let outlines = expand_stroke(&line, 20.0, Cap::Butt, &params);
+        assert_eq!(outlines.len(), 1);
+        assert!(split_contours(&outlines[0])[0].1);
+        assert_bounds(&outlines[0], Rect::new(0.0, -10.0, 100.0, 10.0));
+        assert!(outlines[0].area() > 0.0);
+
+        let capped = expand_stroke(&line, 20.0, Cap::Square, &params);
+        assert_bounds(&capped[0], Rect::new(-10.0, -10.0, 110.0, 10.0));
+    }
+
+    #[test]
+    fn test_expand_closed_contour_makes_ring() {
+        let params = OffsetParams::default();
+        let ring = expand_stroke(&square(100.0), 20.0, Cap::Butt, &params);
+        assert_eq!(ring.len(), 2);
+        let areas: Vec<f64> = ring.iter().map(|c| c.area()).collect();
+        assert!(areas.iter().any(|a| *a > 0.0));
+        assert!(areas.iter().any(|a| *a < 0.0));
+        let outer = ring.iter().find(|c| c.area() > 0.0).unwrap();
+        assert_bounds(outer, Rect::new(-10.0, -10.0, 110.0, 110.0));
+    }
+}
@@ -0,0 +1,474 @@
+//! Outline checks and quick fixes
+//!
+//! Finds common problems in a glyph's contours: open contours, contours
+//! wound the wrong way, curves without points at their extremes, curves
+//! that are almost straight lines, tiny segments, overlapping points, stray
+//! points and coordinates that are not whole numbers. Every problem comes
+//! with a quick fix.
+//!
+//! Contour direction follows the PostScript convention used by UFOs: outer
+//! contours run counter-clockwise and counters clockwise. A contour counts
+//! as a counter when it lies completely inside another contour, so
+//! overlapping shapes are not mistaken for counters.
+
+use crate::geometry::simplify::{
+    append_segments, distance_to_line, split_at_extrema, split_subpaths,
+    with_start, Subpath,
+};
+use kurbo::{
+    BezPath, Line, ParamCurve, ParamCurveArclen, ParamCurveExtrema, PathEl,
+    PathSeg, Point, Rect, Shape,
+};
+
+/// Segments shorter than this, in font units, are reported as tiny
+const TINY_SEGMENT_LENGTH: f64 = 2.0;
+
+/// Curves whose control points are this close to the straight line between
+/// their ends are reported as nearly straight
+const STRAIGHT_TOLERANCE: f64 = 1.0;
+
+/// Curves that bulge past their end points by less than this at an extreme
+/// are not reported as missing it
+const EXTREMUM_TOLERANCE: f64 = 0.5;
+
+/// Parameter distance from a segment end inside which extrema are ignored
+const EXTREMA_T_EPSILON: f64 = 0.01;
+
+/// Distances below this are treated as zero
+const EPSILON: f64 = 1e-6;
+
+/// Upper bound on the fixes applied by `fix_all`, in case fixes keep
+/// producing new problems
+const MAX_FIXES: usize = 1000;
+
+/// Kinds of outline problems, in the order `fix_all` fixes them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProblemKind {
+    StrayPoint,
+    OpenContour,
+    OverlappingPoints,
+    TinySegment,
+    NearlyStraightCurve,
+    MissingExtremum,
+    WrongDirection,
+    NonIntegerCoordinates,
+}
+
+impl ProblemKind {
+    pub fn description(&self) -> &'static str {
+        match self {
+            ProblemKind::StrayPoint => "Stray point",
+            ProblemKind::OpenContour => "Open contour",
+            ProblemKind::OverlappingPoints => "Overlapping points",
+            ProblemKind::TinySegment => "Tiny segment",
+            ProblemKind::NearlyStraightCurve => "Nearly straight curve",
+            ProblemKind::MissingExtremum => "Missing extreme point",
+            ProblemKind::WrongDirection => "Wrong contour direction",
+            ProblemKind::NonIntegerCoordinates => "Non-integer coordinates",
+        }
+    }
+
+    /// Short name of the quick fix
+    pub fn fix_label(&self) -> &'static str {
+        match self {
+            ProblemKind::StrayPoint => "Delete",
+            ProblemKind::OpenContour => "Close",
+            ProblemKind::OverlappingPoints | ProblemKind::TinySegment => {
+                "Remove"
+            }
+            ProblemKind::NearlyStraightCurve => "Make line",
+            ProblemKind::MissingExtremum => "Add point",
+            ProblemKind::WrongDirection => "Reverse",
+            ProblemKind::NonIntegerCoordinates => "Round",
+        }
+    }
+}
+
+/// A problem found in a glyph's contours
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineProblem {
+    pub kind: ProblemKind,
+    pub contour_index: usize,
+    /// Segment of the contour the problem is on, counting the closing line
+    /// of closed contours
+    pub segment_index: Option<usize>,
+    /// Where to mark the problem, in glyph coordinates
+    pub position: Point,
+}
+
+/// Every problem in a glyph's contours, by contour
+pub fn check_contours(contours: &[BezPath]) -> Vec<OutlineProblem> {
+    let subpaths: Vec<Option<Subpath>> = contours
+        .iter()
+        .map(|contour| split_subpaths(contour).into_iter().next())
+        .collect();
+
+    let mut problems = Vec::new();
+    for (contour_index, contour) in contours.iter().enumerate() {
+        let Some(start) = start_point(contour) else {
+            continue;
+        };
+        let problem = |kind, segment_index, position| OutlineProblem {
+            kind,
+            contour_index,
+            segment_index,
+            position,
+        };
+        let Some(subpath) = &subpaths[contour_index] else {
+            problems.push(problem(ProblemKind::StrayPoint, None, start));
+            continue;
+        };
+
+        if !subpath.closed {
+            let end = subpath.segments.last().map_or(start, PathSeg::end);
+            problems.push(problem(ProblemKind::OpenContour, None, end));
+        }
+        for (index, seg) in subpath.segments.iter().enumerate() {
+            if let Some((kind, position)) = check_segment(seg) {
+                problems.push(problem(kind, Some(index), position));
+            }
+            for position in missing_extrema(seg) {
+                problems.push(problem(
+                    ProblemKind::MissingExtremum,
+                    Some(index),
+                    position,
+                ));
+            }
+        }
+        if is_wrong_direction(contours, &subpaths, contour_index) {
+            problems.push(problem(ProblemKind::WrongDirection, None, start));
+        }
+        if let Some(position) = non_integer_point(contour) {
+            problems.push(problem(
+                ProblemKind::NonIntegerCoordinates,
+                None,
+                position,
+            ));
+        }
+    }
+    problems
+}
+
+/// Apply the quick fix for a problem
+///
+/// Returns false, leaving the contours alone, if the problem is no longer
+/// in them.
+pub fn fix_problem(
+    contours: &mut Vec<BezPath>,
+    problem: &OutlineProblem,
+) -> bool {
+    if !check_contours(contours).contains(problem) {
+        return false;
+    }
+    let index = problem.contour_index;
+    if problem.kind == ProblemKind::StrayPoint {
+        contours.remove(index);
+        return true;
+    }
+
+    let contour = &mut contours[index];
+    match problem.kind {
+        ProblemKind::WrongDirection => {
+            *contour = contour.reverse_subpaths();
+            return true;
+        }
+        ProblemKind::NonIntegerCoordinates => {
+            *contour = round_contour(contour);
+            return true;
+        }
+        _ => {}
+    }
+
+    let Some(mut subpath) = split_subpaths(contour).into_iter().next() else {
+        return false;
+    };
+    let segments = &mut subpath.segments;
+    match (problem.kind, problem.segment_index) {
+        (ProblemKind::OpenContour, _) => subpath.closed = true,
+        (ProblemKind::MissingExtremum, Some(i)) => {
+            let pieces = split_at_extrema(segments[i]);
+            segments.splice(i..=i, pieces);
+        }
+        (ProblemKind::NearlyStraightCurve, Some(i)) => {
+            segments[i] = PathSeg::Line(Line::new(
+                segments[i].start(),
+                segments[i].end(),
+            ));
+        }
+        (
+            ProblemKind::OverlappingPoints | ProblemKind::TinySegment,
+            Some(i),
+        ) => {
+            // Merge the segment's end point into its start point
+            let removed = segments.remove(i);
+            if i < segments.len() {
+                segments[i] = with_start(segments[i], removed.start());
+            } else if subpath.closed && !segments.is_empty() {
+                segments[0] = with_start(segments[0], removed.start());
+            }
+        }
+        _ => return false,
+    }
+
+    let mut fixed = BezPath::new();
+    append_segments(&mut fixed, &subpath.segments, subpath.closed);
+    *contour = fixed;
+    true
+}
+
+/// Apply every quick fix, returning how many were applied
+///
+/// Problems are fixed one at a time in `ProblemKind` order, checking the
+/// contours again after each fix.
+pub fn fix_all(contours: &mut Vec<BezPath>) -> usize {
+    let mut fixed = 0;
+    while fixed < MAX_FIXES {
+        let Some(problem) = check_contours(contours)
+            .into_iter()
+            .min_by_key(|problem| problem.kind)
+        else {
+            break;
+        };
+        if !fix_problem(contours, &problem) {
+            break;
+        }
+        fixed += 1;
+    }
+    fixed
+}
+
+fn start_point(contour: &BezPath) -> Option<Point> {
+    match contour.elements().first() {
+        Some(PathEl::MoveTo(p)) => Some(*p),
+        _ => None,
+    }
+}
+
+/// Overlapping points, tiny segments and nearly straight curves
+fn check_segment(seg: &PathSeg) -> Option<(ProblemKind, Point)> {
+    let (start, end) = (seg.start(), seg.end());
+    if start.distance(end) < EPSILON {
+        let collapsed = match seg {
+            PathSeg::Line(_) => true,
+            PathSeg::Quad(quad) => quad.p1.distance(start) < EPSILON,
+            PathSeg::Cubic(cubic) => {
+                cubic.p1.distance(start) < EPSILON
+                    && cubic.p2.distance(start) < EPSILON
+            }
+        };
+        return collapsed.then_some((ProblemKind::OverlappingPoints, start));
+    }
+    if seg.arclen(0.01) < TINY_SEGMENT_LENGTH {
+        return Some((ProblemKind::TinySegment, seg.eval(0.5)));
+    }
+    let controls = match seg {
+        PathSeg::Line(_) => return None,
+        PathSeg::Quad(quad) => vec![quad.p1],
+        PathSeg::Cubic(cubic) => vec![cubic.p1, cubic.p2],
+    };
+    let chord = Line::new(start, end);
+    let straight = controls.iter().all(|&p| {
+        // Control points past the ends make the curve double back
+        let along = (p - start).dot(end - start) / (end - start).hypot2();
+        (0.0..=1.0).contains(&along)
+            && distance_to_line(p, chord) <= STRAIGHT_TOLERANCE
+    });
+    straight.then_some((ProblemKind::NearlyStraightCurve, seg.eval(0.5)))
+}
+
+/// Extremes of a curve that bulge past its end points
+fn missing_extrema(seg: &PathSeg) -> Vec<Point> {
+    if matches!(seg, PathSeg::Line(_)) {
+        return Vec::new();
+    }
+    let ends = Rect::from_points(seg.start(), seg.end())
+        .inflate(EXTREMUM_TOLERANCE, EXTREMUM_TOLERANCE);
+    seg.extrema()
+        .into_iter()
+        .filter(|t| *t > EXTREMA_T_EPSILON && *t < 1.0 - EXTREMA_T_EPSILON)
+        .map(|t| seg.eval(t))
+        .filter(|p| !ends.contains(*p))
+        .collect()
+}
+
+/// Whether a closed contour runs against the PostScript convention
+fn is_wrong_direction(
+    contours: &[BezPath],
+    subpaths: &[Option<Subpath>],
+    index: usize,
+) -> bool {
+    let Some(subpath) = subpaths[index].as_ref().filter(|s| s.closed) else {
+        return false;
+    };
+    let area = contours[index].area();
+    if area.abs() < EPSILON {
+        return false;
+    }
+
+    let points: Vec<Point> =
+        subpath.segments.iter().map(PathSeg::end).collect();
+    let depth = contours
+        .iter()
+        .zip(subpaths)
+        .enumerate()
+        .filter(|(other, (contour, subpath))| {
+            *other != index
+                && subpath.as_ref().is_some_and(|s| s.closed)
+                && contour.area().abs() > area.abs()
+                && points.iter().all(|&p| contour.winding(p) != 0)
+        })
+        .count();
+    let counter_clockwise = area > 0.0;
+    counter_clockwise != (depth % 2 == 0)
+}
+
+/// First point of a contour with a coordinate that is not a whole number
+fn non_integer_point(contour: &BezPath) -> Option<Point> {
+    contour
+        .elements()
+        .iter()
+        .flat_map(element_points)
+        .find(|p| {
+            (p.x - p.x.round()).abs() > EPSILON
+                || (p.y - p.y.round()).abs() > EPSILON
+        })
+}
+
+fn element_points(el: &PathEl) -> Vec<Point> {
+    match *el {
+        PathEl::MoveTo(p) | PathEl::LineTo(p) => vec![p],
+        PathEl::QuadTo(p1, p2) => vec![p1, p2],
+        PathEl::CurveTo(p1, p2, p3) => vec![p1, p2, p3],
+        PathEl::ClosePath => Vec::new(),
+    }
+}
+
+fn round_contour(contour: &BezPath) -> BezPath {
+    let round = |p: Point| p.round();
+    contour
+        .elements()
+        .iter()
+        .map(|el| match *el {
+            PathEl::MoveTo(p) => PathEl::MoveTo(round(p)),
+            PathEl::LineTo(p) => PathEl::LineTo(round(p)),
+            PathEl::QuadTo(p1, p2) => PathEl::QuadTo(round(p1), round(p2)),
+            PathEl::CurveTo(p1, p2, p3) => {
+                PathEl::CurveTo(round(p1), round(p2), round(p3))
+            }
+            PathEl::ClosePath => PathEl::ClosePath,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((x0, y0));
+        path.line_to((x1, y0));
+        path.line_to((x1, y1));
+        path.line_to((x0, y1));
+        path.close_path();
+        path
+    }
+
+    fn kinds(contours: &[BezPath]) -> Vec<ProblemKind> {
+        check_contours(contours)
+            .iter()
+            .map(|problem| problem.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_contours() {
+        let outer = rect(0.0, 0.0, 500.0, 700.0);
+        let counter = rect(100.0, 100.0, 400.0, 600.0).reverse_subpaths();
+        assert!(check_contours(&[outer.clone(), counter]).is_empty());
+
+        // Overlapping shapes both run counter-clockwise
+        let overlap = rect(400.0, 0.0, 800.0, 700.0);
+        assert!(check_contours(&[outer, overlap]).is_empty());
+    }
+
+    #[test]
+    fn test_contour_direction() {
+        let outer = rect(0.0, 0.0, 500.0, 700.0);
+        let counter = rect(100.0, 100.0, 400.0, 600.0);
+        let mut contours = vec![outer.reverse_subpaths(), counter];
+        assert_eq!(
+            kinds(&contours),
+            vec![ProblemKind::WrongDirection, ProblemKind::WrongDirection]
+        );
+        assert_eq!(fix_all(&mut contours), 2);
+        assert!(contours[0].area() > 0.0 && contours[1].area() < 0.0);
+    }
+
+    #[test]
+    fn test_segment_problems() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((300.0, 0.0));
+        path.line_to((300.0, 0.0));
+        path.line_to((301.0, 0.0));
+        path.curve_to((350.0, 1.0), (450.0, -1.0), (500.0, 0.0));
+        path.curve_to((500.0, 400.0), (0.0, 400.0), (0.0, 0.0));
+        path.close_path();
+        let mut contours = vec![path];
+
+        let problems = check_contours(&contours);
+        let kinds: Vec<_> = problems.iter().map(|p| p.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ProblemKind::OverlappingPoints,
+                ProblemKind::TinySegment,
+                ProblemKind::NearlyStraightCurve,
+                ProblemKind::MissingExtremum,
+            ]
+        );
+        assert_eq!(problems[0].segment_index, Some(1));
+        assert_eq!(problems[3].segment_index, Some(4));
+        assert_eq!(problems[3].position, Point::new(250.0, 300.0));
+
+        assert_eq!(fix_all(&mut contours), 4);
+        assert!(check_contours(&contours).is_empty());
+        let lines = contours[0]
+            .elements()
+            .iter()
+            .filter(|el| matches!(el, PathEl::LineTo(_)))
+            .count();
+        assert_eq!(lines, 2);
+    }
+
+    #[test]
+    fn test_open_stray_and_rounding() {
+        let mut open = BezPath::new();
+        open.move_to((0.0, 0.0));
+        open.line_to((100.0, 0.5));
+        open.line_to((100.0, 100.0));
+        let mut stray = BezPath::new();
+        stray.move_to((50.0, 50.0));
+        let mut contours = vec![stray, open];
+
+        assert_eq!(
+            kinds(&contours),
+            vec![
+                ProblemKind::StrayPoint,
+                ProblemKind::OpenContour,
+                ProblemKind::NonIntegerCoordinates,
+            ]
+        );
+
+        // Fixing a problem that is no longer there does nothing
+        let stray_problem = check_contours(&contours)[0].clone();
+        assert!(fix_problem(&mut contours, &stray_problem));
+        assert!(!fix_problem(&mut contours, &stray_problem));
+
+        assert_eq!(fix_all(&mut contours), 2);
+        assert_eq!(contours.len(), 1);
+        assert!(check_contours(&contours).is_empty());
+        assert_eq!(contours[0].elements().last(), Some(&PathEl::ClosePath));
+    }
+}
@@ -0,0 +1,631 @@
+//! Quadratic (TrueType) outline support
+//!
+//! TrueType outlines are chains of quadratic curves where an on-curve point
+//! between two off-curve points may be omitted: it is then *implied* to sit
+//! exactly halfway between them. Once loaded into a `BezPath` those implied
+//! points become explicit `QuadTo` end points, so this module records which
+//! of them the UFO left implied, carries that over edits, and converts whole
+//! contours between cubic and quadratic curves.
+//!
+//! Conversion to quadratics works on several paths at once (one per master)
+//! and picks the same number of quadratic pieces for each segment in every
+//! path, so converted masters stay interpolation compatible.
+
+use kurbo::{BezPath, CubicBez, ParamCurve, PathEl, Point, QuadBez};
+
+/// Distance below which a point sits at an implied point's position
+const IMPLIED_EPSILON: f64 = 1e-6;
+
+/// Largest number of quadratic pieces used to approximate one cubic
+const MAX_QUADS_PER_CUBIC: usize = 16;
+
+/// Number of samples used when measuring approximation error
+const ERROR_SAMPLES: usize = 16;
+
+/// Which kind of curves a contour is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveType {
+    Cubic,
+    Quadratic,
+}
+
+/// Whether a path contains any quadratic segments
+pub fn has_quadratics(path: &BezPath) -> bool {
+    path.elements()
+        .iter()
+        .any(|el| matches!(el, PathEl::QuadTo(..)))
+}
+
+/// Whether a path contains any cubic segments
+pub fn has_cubics(path: &BezPath) -> bool {
+    path.elements()
+        .iter()
+        .any(|el| matches!(el, PathEl::CurveTo(..)))
+}
+
+/// Positions of the on-curve points a UFO contour leaves implied
+///
+/// Two off-curve points in a row within a `qcurve` segment imply an
+/// on-curve point halfway between them; a closed contour of off-curve
+/// points only is quadratic all the way round.
+pub fn ufo_implied_on_curves(contour: &norad::Contour) -> Vec<Point> {
+    use norad::PointType;
+
+    let points = &contour.points;
+    let count = points.len();
+    let closed = !matches!(points.first(), Some(p) if p.typ == PointType::Move);
+    let mut implied = Vec::new();
+
+    for i in 0..count {
+        let next = i + 1;
+        if next == count && !closed {
+            break;
+        }
+        let (a, b) = (&points[i], &points[next % count]);
+        if a.typ != PointType::OffCurve || b.typ != PointType::OffCurve {
+            continue;
+        }
+        // The off-curves belong to the segment of the next on-curve point
+        let segment_end = if closed {
+            (next..next + count)
+                .map(|j| &points[j % count])
+                .find(|p| p.typ != PointType::OffCurve)
+        } else {
+            points[next..].iter().find(|p| p.typ != PointType::OffCurve)
+        };
+        let quadratic = match segment_end {
+            Some(end) => end.typ == PointType::QCurve,
+            None => closed,
+        };
+        if quadratic {
+            implied.push(Point::new(a.x, a.y).midpoint(Point::new(b.x, b.y)));
+        }
+    }
+    implied
+}
+
+/// Flags marking which points of a path are implied on-curve points
+///
+/// The result is indexed by flat point index (every point of every element,
+/// in order, as used by point entities). `implied` holds the positions of
+/// the implied points as recorded when the outline was loaded: a `QuadTo`
+/// end point at one of them is implied if another `QuadTo` follows it, and
+/// the start of a closed contour if quadratics lead into and out of it.
+pub fn implied_flags_at(path: &BezPath, implied: &[Point]) -> Vec<bool> {
+    let elements = path.elements();
+    let is_recorded =
+        |p: Point| implied.iter().any(|q| q.distance(p) < IMPLIED_EPSILON);
+    let mut flags = Vec::new();
+    let mut last_quad_end = None;
+
+    for (i, el) in elements.iter().enumerate() {
+        match *el {
+            PathEl::MoveTo(_) | PathEl::LineTo(_) => flags.push(false),
+            PathEl::CurveTo(..) => flags.extend([false; 3]),
+            PathEl::QuadTo(_, p) => {
+                let followed_by_quad =
+                    matches!(elements.get(i + 1), Some(PathEl::QuadTo(..)));
+                flags.push(false);
+                flags.push(followed_by_quad && is_recorded(p));
+                last_quad_end = Some(flags.len() - 1);
+            }
+            PathEl::ClosePath => {}
+        }
+    }
+
+    // The start of a closed contour can itself be implied when the contour
+    // returns to it with a quadratic and leaves it with another one
+    if let (Some((_, last_end)), Some(_)) =
+        (closing_quad(elements), opening_quad_control(elements))
+    {
+        if let Some(PathEl::MoveTo(start)) = elements.first() {
+            if last_end.distance(*start) < IMPLIED_EPSILON
+                && is_recorded(*start)
+            {
+                flags[0] = true;
+                if let Some(end_index) = last_quad_end {
+                    flags[end_index] = true;
+                }
+            }
+        }
+    }
+
+    flags
+}
+
+/// Carry implied flags over an edit of a path
+///
+/// Points that were only moved keep their flags. When points were added,
+/// removed or reordered, the points still at the position of a flagged one
+/// stay implied.
+pub fn remap_implied_flags(
+    before: &BezPath,
+    after: &BezPath,
+    flags: &[bool],
+) -> Vec<bool> {
+    let same_layout = before.elements().len() == after.elements().len()
+        && before
+            .elements()
+            .iter()
+            .zip(after.elements())
+            .all(|(a, b)| {
+                std::mem::discriminant(a) == std::mem::discriminant(b)
+            });
+    if same_layout {
+        return flags.to_vec();
+    }
+    implied_flags_at(after, &flagged_points(before, flags))
+}
+
+/// Implied flags of a path converted by [`cubic_to_quadratic_compatible`]
+///
+/// Points kept from `before` keep their flags, and the on-curve points
+/// joining the quadratic pieces a cubic was split into are implied, so
+/// every master converted together gets the same flags.
+pub fn converted_implied_flags(
+    before: &BezPath,
+    flags: &[bool],
+    after: &BezPath,
+) -> Vec<bool> {
+    let converted = after.elements();
+    let mut result = Vec::new();
+    let mut flat_index = 0;
+    let mut after_index = 0;
+
+    for el in before.elements() {
+        let point_count = element_point_count(el);
+        match *el {
+            PathEl::CurveTo(_, _, end)
+                if matches!(
+                    converted.get(after_index),
+                    Some(PathEl::QuadTo(..))
+                ) =>
+            {
+                while let Some(PathEl::QuadTo(_, p)) =
+                    converted.get(after_index)
+                {
+                    after_index += 1;
+                    let is_last = *p == end;
+                    result.push(false);
+                    result.push(!is_last);
+                    if is_last {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                result.extend(
+                    (flat_index..flat_index + point_count).map(|index| {
+                        flags.get(index).copied().unwrap_or(false)
+                    }),
+                );
+                after_index += 1;
+            }
+        }
+        flat_index += point_count;
+    }
+    result
+}
+
+/// Positions of the points flagged in `flags`
+pub fn flagged_points(path: &BezPath, flags: &[bool]) -> Vec<Point> {
+    path.elements()
+        .iter()
+        .flat_map(|el| match *el {
+            PathEl::MoveTo(p) | PathEl::LineTo(p) => vec![p],
+            PathEl::QuadTo(c, p) => vec![c, p],
+            PathEl::CurveTo(c1, c2, p) => vec![c1, c2, p],
+            PathEl::ClosePath => Vec::new(),
+        })
+        .zip(flags)
+        .filter(|(_, flagged)| **flagged)
+        .map(|(p, _)| p)
+        .collect()
+}
+
+fn element_point_count(el: &PathEl) -> usize {
+    match el {
+        PathEl::MoveTo(_) | PathEl::LineTo(_) => 1,
+        PathEl::QuadTo(..) => 2,
+        PathEl::CurveTo(..) => 3,
+        PathEl::ClosePath => 0,
+    }
+}
+
+/// Move implied on-curve points back to the middle of their control points
+///
+/// `flags` are the implied flags from before the edit; points that were
+/// implied then are re-centred, so dragging a control point carries its
+/// implied neighbours along.
+pub fn recenter_implied_on_curves(path: &mut BezPath, flags: &[bool]) {
+    let mut elements = path.elements().to_vec();
+    let closing = closing_quad(&elements).map(|(c, _)| c);
+    let opening = opening_quad_control(&elements);
+    let count = elements.len();
+    let mut flat_index = 0;
+
+    for i in 0..count {
+        match elements[i] {
+            PathEl::MoveTo(_) => {
+                if flags.get(flat_index).copied().unwrap_or(false) {
+                    if let (Some(a), Some(b)) = (closing, opening) {
+                        let mid = a.midpoint(b);
+                        elements[i] = PathEl::MoveTo(mid);
+                        if let Some(last) = last_quad_index(&elements) {
+                            if let PathEl::QuadTo(c, _) = elements[last] {
+                                elements[last] = PathEl::QuadTo(c, mid);
+                            }
+                        }
+                    }
+                }
+                flat_index += 1;
+            }
+            PathEl::LineTo(_) => flat_index += 1,
+            PathEl::CurveTo(..) => flat_index += 3,
+            PathEl::QuadTo(c, _) => {
+                let end_implied =
+                    flags.get(flat_index + 1).copied().unwrap_or(false);
+                if end_implied {
+                    if let Some(PathEl::QuadTo(next_c, _)) = elements.get(i + 1)
+                    {
+                        elements[i] = PathEl::QuadTo(c, c.midpoint(*next_c));
+                    }
+                }
+                flat_index += 2;
+            }
+            PathEl::ClosePath => {}
+        }
+    }
+
+    *path = BezPath::from_vec(elements);
+}
+
+/// Convert every quadratic segment to an exactly equivalent cubic
+pub fn quadratic_to_cubic(path: &BezPath) -> BezPath {
+    let mut result = BezPath::new();
+    let mut current = Point::ZERO;
+    for el in path.elements() {
+        match *el {
+            PathEl::QuadTo(c, p) => {
+                let cubic = QuadBez::new(current, c, p).raise();
+                result.curve_to(cubic.p1, cubic.p2, cubic.p3);
+                current = p;
+            }
+            other => {
+                result.push(other);
+                current =
+                    end_point(&other).unwrap_or_else(|| subpath_start(&result));
+            }
+        }
+    }
+    result
+}
+
+/// Convert every cubic segment of a single path to quadratics
+pub fn cubic_to_quadratic(path: &BezPath, tolerance: f64) -> BezPath {
+    cubic_to_quadratic_compatible(std::slice::from_ref(path), tolerance)
+        .and_then(|mut paths| paths.pop())
+        .unwrap_or_else(|| path.clone())
+}
+
+/// Convert matching paths (one per master) to quadratics compatibly
+///
+/// Each cubic segment is split into the smallest number of quadratic
+/// pieces that keeps *every* path within `tolerance`; the on-curve points
+/// between those pieces are implied. Returns `None` if the paths don't share
+/// the same element structure.
+pub fn cubic_to_quadratic_compatible(
+    paths: &[BezPath],
+    tolerance: f64,
+) -> Option<Vec<BezPath>> {
+    let first = paths.first()?;
+    let structure_matches = paths.iter().all(|path| {
+        path.elements().len() == first.elements().len()
+            && path.elements().iter().zip(first.elements()).all(|(a, b)| {
+                std::mem::discriminant(a) == std::mem::discriminant(b)
+            })
+    });
+    if !structure_matches {
+        return None;
+    }
+
+    let mut results = vec![BezPath::new(); paths.len()];
+    let mut currents = vec![Point::ZERO; paths.len()];
+
+    for el_index in 0..first.elements().len() {
+        if let PathEl::CurveTo(..) = first.elements()[el_index] {
+            let cubics: Vec<CubicBez> = paths
+                .iter()
+                .zip(&currents)
+                .map(|(path, &current)| match path.elements()[el_index] {
+                    PathEl::CurveTo(c1, c2, p) => {
+                        CubicBez::new(current, c1, c2, p)
+                    }
+                    _ => unreachable!("structure checked above"),
+                })
+                .collect();
+
+            let pieces = (1..=MAX_QUADS_PER_CUBIC)
+                .find(|&n| {
+                    cubics
+                        .iter()
+                        .all(|cubic| quad_spline_error(cubic, n) <= tolerance)
+                })
+                .unwrap_or(MAX_QUADS_PER_CUBIC);
+
+            for (i, cubic) in cubics.iter().enumerate() {
+                for quad in quad_spline(cubic, pieces) {
+                    results[i].quad_to(quad.p1, quad.p2);
+                }
+                currents[i] = cubic.p3;
+            }
+        } else {
+            for (i, path) in paths.iter().enumerate() {
+                let el = path.elements()[el_index];
+                if let Some(p) = end_point(&el) {
+                    currents[i] = p;
+                }
+                if let PathEl::ClosePath = el {
+                    currents[i] = subpath_start(&results[i]);
+                }
+                results[i].push(el);
+            }
+        }
+    }
+
+    Some(results)
+}
+
+/// Approximate a cubic with `n` quadratics joined at implied on-curves
+fn quad_spline(cubic: &CubicBez, n: usize) -> Vec<QuadBez> {
+    let controls: Vec<Point> = (0..n)
+        .map(|i| {
+            let t0 = i as f64 / n as f64;
+            let t1 = (i + 1) as f64 / n as f64;
+            let piece = cubic.subsegment(t0..t1);
+            // Control point of the quadratic that best matches the piece
+            // (the average of the two degree-reduction estimates)
+            let estimate = (piece.p1.to_vec2() * 3.0 - piece.p0.to_vec2()
+                + piece.p2.to_vec2() * 3.0
+                - piece.p3.to_vec2())
+                / 4.0;
+            estimate.to_point()
+        })
+        .collect();
+
+    (0..n)
+        .map(|i| {
+            let start = if i == 0 {
+                cubic.p0
+            } else {
+                controls[i - 1].midpoint(controls[i])
+            };
+            let end = if i == n - 1 {
+                cubic.p3
+            } else {
+                controls[i].midpoint(controls[i + 1])
+            };
+            QuadBez::new(start, controls[i], end)
+        })
+        .collect()
+}
+
+/// Largest sampled distance between a cubic and its `n`-piece quad spline
+fn quad_spline_error(cubic: &CubicBez, n: usize) -> f64 {
+    quad_spline(cubic, n)
+        .iter()
+        .enumerate()
+        .map(|(i, quad)| {
+            let t0 = i as f64 / n as f64;
+            let t1 = (i + 1) as f64 / n as f64;
+            let piece = cubic.subsegment(t0..t1);
+            (0..=ERROR_SAMPLES)
+                .map(|s| {
+                    let t = s as f64 / ERROR_SAMPLES as f64;
+                    piece.eval(t).distance(quad.eval(t))
+                })
+                .fold(0.0, f64::max)
+        })
+        .fold(0.0, f64::max)
+}
+
+fn end_point(el: &PathEl) -> Option<Point> {
+    match *el {
+        PathEl::MoveTo(p)
+        | PathEl::LineTo(p)
+        | PathEl::QuadTo(_, p)
+        | PathEl::CurveTo(_, _, p) => Some(p),
+        PathEl::ClosePath => None,
+    }
+}
+
+fn subpath_start(path: &BezPath) -> Point {
+    path.elements()
+        .iter()
+        .rev()
+        .find_map(|el| match el {
+            PathEl::MoveTo(p) => Some(*p),
+            _ => None,
+        })
+        .unwrap_or(Point::ZERO)
+}
+
+/// Index of the last `QuadTo` element
+fn last_quad_index(elements: &[PathEl]) -> Option<usize> {
+    elements
+        .iter()
+        .rposition(|el| matches!(el, PathEl::QuadTo(..)))
+}
+
+/// Control and end point of the quadratic that closes a closed contour
+fn closing_quad(elements: &[PathEl]) -> Option<(Point, Point)> {
+    if !matches!(elements.last(), Some(PathEl::ClosePath)) {
+        return None;
+    }
+    match elements.get(elements.len().checked_sub(2)?) {
+        Some(PathEl::QuadTo(c, p)) => Some((*c, *p)),
+        _ => None,
+    }
+}
+
+/// Control point of the quadratic leaving the start of a contour
+fn opening_quad_control(elements: &[PathEl]) -> Option<Point> {
+    match elements.get(1) {
+        Some(PathEl::QuadTo(c, _)) => Some(*c),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kurbo::ParamCurveNearest;
+
+    fn quarter_circle() -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((100.0, 0.0));
+        path.curve_to((100.0, 55.0), (55.0, 100.0), (0.0, 100.0));
+        path
+    }
+
+    #[test]
+    fn test_implied_flags() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.quad_to((0.0, 100.0), (50.0, 100.0));
+        path.quad_to((100.0, 100.0), (100.0, 0.0));
+        path.close_path();
+
+        let flags = implied_flags_at(&path, &[Point::new(50.0, 100.0)]);
+        assert_eq!(flags, vec![false, false, true, false, false]);
+
+        // An explicit point halfway between two controls stays explicit
+        let flags = implied_flags_at(&path, &[]);
+        assert_eq!(flags, vec![false; 5]);
+    }
+
+    #[test]
+    fn test_ufo_implied_on_curves() {
+        use norad::{Contour, ContourPoint, PointType};
+
+        let point = |x, y, typ| ContourPoint::new(x, y, typ, false, None, None);
+        let contour = Contour::new(
+            vec![
+                point(0.0, 0.0, PointType::QCurve),
+                point(0.0, 100.0, PointType::OffCurve),
+                point(100.0, 100.0, PointType::OffCurve),
+                point(100.0, 0.0, PointType::QCurve),
+                point(100.0, -50.0, PointType::OffCurve),
+                point(0.0, -50.0, PointType::OffCurve),
+                point(0.0, -10.0, PointType::Curve),
+            ],
+            None,
+        );
+
+        assert_eq!(
+            ufo_implied_on_curves(&contour),
+            vec![Point::new(50.0, 100.0)]
+        );
+    }
+
+    #[test]
+    fn test_remap_implied_flags() {
+        let mut before = BezPath::new();
+        before.move_to((0.0, 0.0));
+        before.quad_to((0.0, 100.0), (50.0, 100.0));
+        before.quad_to((100.0, 100.0), (100.0, 0.0));
+        let flags = vec![false, false, true, false, false];
+
+        // A new point in front of the contour shifts the implied one along
+        let mut after = BezPath::new();
+        after.move_to((-50.0, 0.0));
+        after.line_to((0.0, 0.0));
+        after.quad_to((0.0, 100.0), (50.0, 100.0));
+        after.quad_to((100.0, 100.0), (100.0, 0.0));
+
+        assert_eq!(
+            remap_implied_flags(&before, &after, &flags),
+            vec![false, false, false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_converted_implied_flags() {
+        let cubic = quarter_circle();
+        let quads = cubic_to_quadratic(&cubic, 0.1);
+        let flags = converted_implied_flags(&cubic, &[], &quads);
+
+        let pieces = quads.elements().len() - 1;
+        assert!(pieces > 1);
+        let implied = flags.iter().filter(|flag| **flag).count();
+        assert_eq!(implied, pieces - 1);
+        assert_eq!(flags.last(), Some(&false));
+    }
+
+    #[test]
+    fn test_recenter_follows_control() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.quad_to((0.0, 100.0), (50.0, 100.0));
+        path.quad_to((100.0, 100.0), (100.0, 0.0));
+
+        let flags = implied_flags_at(&path, &[Point::new(50.0, 100.0)]);
+        let mut elements = path.elements().to_vec();
+        elements[2] =
+            PathEl::QuadTo(Point::new(100.0, 200.0), (100.0, 0.0).into());
+        let mut edited = BezPath::from_vec(elements);
+        recenter_implied_on_curves(&mut edited, &flags);
+
+        match edited.elements()[1] {
+            PathEl::QuadTo(_, p) => assert_eq!(p, Point::new(50.0, 150.0)),
+            _ => panic!("expected a quadratic"),
+        }
+    }
+
+    #[test]
+    fn test_cubic_to_quadratic_within_tolerance() {
+        let cubic = quarter_circle();
+        let quads = cubic_to_quadratic(&cubic, 0.5);
+        assert!(!has_cubics(&quads));
+        assert!(has_quadratics(&quads));
+
+        let original = CubicBez::new(
+            (100.0, 0.0),
+            (100.0, 55.0),
+            (55.0, 100.0),
+            (0.0, 100.0),
+        );
+        for seg in quads.segments() {
+            let mid = seg.eval(0.5);
+            let nearest = original.nearest(mid, 1e-6);
+            assert!(nearest.distance_sq.sqrt() < 0.5);
+        }
+    }
+
+    #[test]
+    fn test_compatible_conversion_matches_structure() {
+        let thin = quarter_circle();
+        let mut bold = BezPath::new();
+        bold.move_to((300.0, 0.0));
+        bold.curve_to((300.0, 165.0), (165.0, 300.0), (0.0, 300.0));
+
+        let converted =
+            cubic_to_quadratic_compatible(&[thin, bold], 0.1).unwrap();
+        assert_eq!(
+            converted[0].elements().len(),
+            converted[1].elements().len()
+        );
+    }
+
+    #[test]
+    fn test_quadratic_to_cubic_is_exact() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.quad_to((50.0, 100.0), (100.0, 0.0));
+
+        let cubic = quadratic_to_cubic(&path);
+        assert!(has_cubics(&cubic));
+        let seg = cubic.segments().next().unwrap();
+        assert!(seg.eval(0.5).distance(Point::new(50.0, 50.0)) < 1e-9);
+    }
+}
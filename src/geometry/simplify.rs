@@ -0,0 +1,674 @@
+//! Curve simplification and refitting
+//!
+//! This module refits runs of path segments with as few cubic curves as
+//! possible while staying inside a user-set error tolerance. Corners (joins
+//! where the tangent turns by more than a threshold angle) are never smoothed
+//! away, and extrema can optionally be restored as on-curve points after the
+//! fit so the result stays friendly for hinting and interpolation.
+//!
+//! The actual curve fitting is delegated to kurbo's simplifier; this module is
+//! responsible for splitting contours into smooth runs and reassembling them.
+
+use kurbo::simplify::{simplify_bezpath, SimplifyOptLevel, SimplifyOptions};
+use kurbo::{
+    BezPath, CubicBez, Line, ParamCurve, ParamCurveExtrema, PathEl, PathSeg,
+    Point, Vec2,
+};
+
+/// Smallest parameter distance from a segment end at which an extremum is
+/// still inserted as a new point (avoids creating tiny slivers)
+const EXTREMA_T_EPSILON: f64 = 0.01;
+
+/// User-adjustable parameters for curve simplification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimplifyParams {
+    /// Maximum allowed distance between the original and refitted outline,
+    /// in font units
+    pub tolerance: f64,
+    /// Joins whose tangents differ by more than this angle (in degrees) are
+    /// treated as corners and kept
+    pub corner_angle: f64,
+    /// Whether on-curve points are inserted at horizontal/vertical extrema
+    pub keep_extrema: bool,
+}
+
+impl Default for SimplifyParams {
+    fn default() -> Self {
+        Self {
+            tolerance: 1.0,
+            corner_angle: 30.0,
+            keep_extrema: true,
+        }
+    }
+}
+
+/// A single contour split into its segments
+#[derive(Debug, Clone)]
+pub(crate) struct Subpath {
+    pub(crate) segments: Vec<PathSeg>,
+    pub(crate) closed: bool,
+}
+
+/// Simplify every contour in a path
+pub fn simplify_path(path: &BezPath, params: &SimplifyParams) -> BezPath {
+    let mut result = BezPath::new();
+    for subpath in split_subpaths(path) {
+        let segments = simplify_subpath(&subpath, params);
+        append_segments(&mut result, &segments, subpath.closed);
+    }
+    result
+}
+
+/// Simplify a range of segments within a single contour
+///
+/// `first_segment` and `last_segment` are inclusive indices into the
+/// contour's segments (as produced by `BezPath::segments`, including the
+/// implicit closing line of a closed contour). Segments outside the range
+/// are left untouched. If the range wraps past the end of a closed contour
+/// (`last_segment < first_segment`), it continues from the start.
+pub fn simplify_segment_range(
+    path: &BezPath,
+    first_segment: usize,
+    last_segment: usize,
+    params: &SimplifyParams,
+) -> BezPath {
+    let Some(subpath) = split_subpaths(path).into_iter().next() else {
+        return path.clone();
+    };
+    let count = subpath.segments.len();
+    if count == 0 || first_segment >= count || last_segment >= count {
+        return path.clone();
+    }
+
+    if last_segment < first_segment && !subpath.closed {
+        return path.clone();
+    }
+
+    // Rotate closed contours so the selected range starts at index 0; this
+    // keeps the bookkeeping below identical for wrapping and plain ranges.
+    let (segments, offset) = if subpath.closed {
+        let mut rotated = subpath.segments[first_segment..].to_vec();
+        rotated.extend_from_slice(&subpath.segments[..first_segment]);
+        (rotated, first_segment)
+    } else {
+        (subpath.segments.clone(), 0)
+    };
+    let start = first_segment - offset;
+    let end = (last_segment + count - offset) % count;
+
+    let run = &segments[start..=end];
+    let mut refit = Vec::new();
+    for run in split_at_corners(run, params.corner_angle) {
+        refit.extend(fit_run(&run, params));
+    }
+
+    let mut new_segments = segments[..start].to_vec();
+    new_segments.extend(refit);
+    new_segments.extend_from_slice(&segments[end + 1..]);
+
+    // Restore the original start point of closed contours
+    if subpath.closed && offset != 0 {
+        let original_start = subpath.segments[0].start();
+        if let Some(idx) = new_segments
+            .iter()
+            .position(|seg| seg.start().distance(original_start) < 1e-9)
+        {
+            new_segments.rotate_left(idx);
+        }
+    }
+
+    let mut result = BezPath::new();
+    append_segments(&mut result, &new_segments, subpath.closed);
+    result
+}
+
+/// Simplify the segments between runs of selected nodes of a contour
+///
+/// Nodes are numbered as `bezpath_editing::on_curve_node_index` numbers
+/// them. Each run of consecutive nodes is refitted on its own, leaving the
+/// segments between runs alone, and on a closed contour a run may continue
+/// from the last node to the first. Returns `None` when no two selected
+/// nodes are next to each other.
+pub fn simplify_node_runs(
+    path: &BezPath,
+    nodes: &[usize],
+    params: &SimplifyParams,
+) -> Option<BezPath> {
+    let subpath = split_subpaths(path).into_iter().next()?;
+    let count = subpath.segments.len();
+    if count == 0 {
+        return None;
+    }
+    let node_count = if subpath.closed { count } else { count + 1 };
+    let node_point = |node: usize| match subpath.segments.get(node) {
+        Some(segment) => segment.start(),
+        None => subpath.segments[count - 1].end(),
+    };
+    let runs: Vec<(Point, Point)> =
+        node_runs(nodes, node_count, subpath.closed)
+            .into_iter()
+            .map(|(first, last)| (node_point(first), node_point(last)))
+            .collect();
+    if runs.is_empty() {
+        return None;
+    }
+
+    // Refitting changes the number of segments, so each run is found again
+    // by its end points, which refitting keeps
+    let mut result = path.clone();
+    for (start, end) in runs {
+        let Some(subpath) = split_subpaths(&result).into_iter().next() else {
+            break;
+        };
+        let segments = &subpath.segments;
+        let Some(first) = segments
+            .iter()
+            .position(|segment| segment.start().distance(start) < 1e-9)
+        else {
+            continue;
+        };
+        let Some(last) = (0..segments.len())
+            .map(|offset| (first + offset) % segments.len())
+            .find(|&index| segments[index].end().distance(end) < 1e-9)
+        else {
+            continue;
+        };
+        result = simplify_segment_range(&result, first, last, params);
+    }
+    Some(result)
+}
+
+/// Runs of consecutive nodes among the selected ones, as their first and
+/// last node, leaving out single nodes
+///
+/// On a closed contour a run ending at the last node and one starting at
+/// the first are joined into one that wraps, ending before it starts.
+fn node_runs(
+    nodes: &[usize],
+    node_count: usize,
+    closed: bool,
+) -> Vec<(usize, usize)> {
+    let mut nodes: Vec<usize> = nodes
+        .iter()
+        .copied()
+        .filter(|&node| node < node_count)
+        .collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for node in nodes {
+        match runs.last_mut() {
+            Some((_, last)) if *last + 1 == node => *last = node,
+            _ => runs.push((node, node)),
+        }
+    }
+    if closed && runs.len() > 1 {
+        let (first_start, first_end) = runs[0];
+        let (last_start, last_end) = runs[runs.len() - 1];
+        if first_start == 0 && last_end == node_count - 1 {
+            runs.remove(0);
+            if let Some(run) = runs.last_mut() {
+                *run = (last_start, first_end);
+            }
+        }
+    }
+    runs.retain(|(first, last)| first != last);
+    runs
+}
+
+/// Number of segments in the first contour of a path
+pub fn segment_count(path: &BezPath) -> usize {
+    split_subpaths(path)
+        .first()
+        .map_or(0, |subpath| subpath.segments.len())
+}
+
+/// Simplify one contour, returning its new segments
+fn simplify_subpath(
+    subpath: &Subpath,
+    params: &SimplifyParams,
+) -> Vec<PathSeg> {
+    if subpath.segments.len() < 2 {
+        return subpath.segments.clone();
+    }
+
+    let mut segments = subpath.segments.clone();
+    let original_start = segments[0].start();
+
+    // For closed contours, start at a corner so no run spans the seam. If
+    // there is no corner the whole loop is fitted as a single run.
+    if subpath.closed {
+        if let Some(corner) = (0..segments.len()).find(|&i| {
+            let prev = &segments[(i + segments.len() - 1) % segments.len()];
+            is_corner(prev, &segments[i], params.corner_angle)
+        }) {
+            segments.rotate_left(corner);
+        }
+    }
+
+    let mut result = Vec::new();
+    for run in split_at_corners(&segments, params.corner_angle) {
+        result.extend(fit_run(&run, params));
+    }
+
+    // Keep the original start point if it survived the refit
+    if subpath.closed {
+        if let Some(idx) = result
+            .iter()
+            .position(|seg| seg.start().distance(original_start) < 1e-9)
+        {
+            result.rotate_left(idx);
+        }
+    }
+
+    result
+}
+
+/// Split a sequence of segments at corner joins
+fn split_at_corners(
+    segments: &[PathSeg],
+    corner_angle: f64,
+) -> Vec<Vec<PathSeg>> {
+    let mut runs = Vec::new();
+    let mut current: Vec<PathSeg> = Vec::new();
+    for seg in segments {
+        if let Some(prev) = current.last() {
+            if is_corner(prev, seg, corner_angle) {
+                runs.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(*seg);
+    }
+    if !current.is_empty() {
+        runs.push(current);
+    }
+    runs
+}
+
+/// Refit a smooth run of segments
+fn fit_run(run: &[PathSeg], params: &SimplifyParams) -> Vec<PathSeg> {
+    let fitted = if run.len() == 1 {
+        run.to_vec()
+    } else if run.iter().all(|seg| matches!(seg, PathSeg::Line(_)))
+        && is_collinear(run, params.tolerance)
+    {
+        vec![PathSeg::Line(Line::new(
+            run[0].start(),
+            run[run.len() - 1].end(),
+        ))]
+    } else {
+        let mut run_path = BezPath::new();
+        append_segments(&mut run_path, run, false);
+        // Corners were already split off, so never break the run again
+        let options = SimplifyOptions::default()
+            .angle_thresh(f64::INFINITY)
+            .opt_level(SimplifyOptLevel::Optimize);
+        let simplified =
+            simplify_bezpath(run_path, params.tolerance.max(1e-3), &options);
+        let segments: Vec<PathSeg> = simplified
+            .segments()
+            .map(|seg| straighten(seg, params))
+            .collect();
+        if segments.is_empty() {
+            run.to_vec()
+        } else {
+            snap_endpoints(segments, run[0].start(), run[run.len() - 1].end())
+        }
+    };
+
+    if params.keep_extrema {
+        fitted.into_iter().flat_map(split_at_extrema).collect()
+    } else {
+        fitted
+    }
+}
+
+/// Replace nearly-flat cubics with lines
+fn straighten(seg: PathSeg, params: &SimplifyParams) -> PathSeg {
+    if let PathSeg::Cubic(cubic) = seg {
+        let chord = Line::new(cubic.p0, cubic.p3);
+        let flat = distance_to_line(cubic.p1, chord) <= params.tolerance * 0.1
+            && distance_to_line(cubic.p2, chord) <= params.tolerance * 0.1;
+        if flat {
+            return PathSeg::Line(chord);
+        }
+    }
+    seg
+}
+
+/// Make sure a refit run starts and ends exactly where the original did
+fn snap_endpoints(
+    mut segments: Vec<PathSeg>,
+    start: Point,
+    end: Point,
+) -> Vec<PathSeg> {
+    if let Some(first) = segments.first_mut() {
+        *first = with_start(*first, start);
+    }
+    if let Some(last) = segments.last_mut() {
+        *last = with_end(*last, end);
+    }
+    segments
+}
+
+/// Split a segment at its interior horizontal/vertical extrema
+pub(crate) fn split_at_extrema(seg: PathSeg) -> Vec<PathSeg> {
+    let ts: Vec<f64> = seg
+        .extrema()
+        .into_iter()
+        .filter(|t| *t > EXTREMA_T_EPSILON && *t < 1.0 - EXTREMA_T_EPSILON)
+        .collect();
+    if ts.is_empty() {
+        return vec![seg];
+    }
+
+    let mut pieces = Vec::with_capacity(ts.len() + 1);
+    let mut t0 = 0.0;
+    for t in ts.into_iter().chain(std::iter::once(1.0)) {
+        pieces.push(seg.subsegment(t0..t));
+        t0 = t;
+    }
+    pieces
+}
+
+/// Whether the join between two segments should be kept as a corner
+fn is_corner(prev: &PathSeg, next: &PathSeg, corner_angle: f64) -> bool {
+    let incoming = end_tangent(prev);
+    let outgoing = start_tangent(next);
+    if incoming.hypot2() < 1e-12 || outgoing.hypot2() < 1e-12 {
+        return true;
+    }
+    let angle = incoming.cross(outgoing).atan2(incoming.dot(outgoing)).abs();
+    angle > corner_angle.to_radians()
+}
+
+/// Direction of a segment where it leaves its start point
+fn start_tangent(seg: &PathSeg) -> Vec2 {
+    match seg {
+        PathSeg::Line(line) => line.p1 - line.p0,
+        PathSeg::Quad(quad) => first_distinct(quad.p0, &[quad.p1, quad.p2]),
+        PathSeg::Cubic(cubic) => {
+            first_distinct(cubic.p0, &[cubic.p1, cubic.p2, cubic.p3])
+        }
+    }
+}
+
+/// Direction of a segment where it arrives at its end point
+fn end_tangent(seg: &PathSeg) -> Vec2 {
+    match seg {
+        PathSeg::Line(line) => line.p1 - line.p0,
+        PathSeg::Quad(quad) => -first_distinct(quad.p2, &[quad.p1, quad.p0]),
+        PathSeg::Cubic(cubic) => {
+            -first_distinct(cubic.p3, &[cubic.p2, cubic.p1, cubic.p0])
+        }
+    }
+}
+
+/// Vector from `from` to the first point in `candidates` that differs from it
+fn first_distinct(from: Point, candidates: &[Point]) -> Vec2 {
+    candidates
+        .iter()
+        .map(|p| *p - from)
+        .find(|v| v.hypot2() > 1e-12)
+        .unwrap_or(Vec2::ZERO)
+}
+
+/// Whether every point of a run of lines lies within `tolerance` of the chord
+fn is_collinear(run: &[PathSeg], tolerance: f64) -> bool {
+    let chord = Line::new(run[0].start(), run[run.len() - 1].end());
+    run.iter()
+        .all(|seg| distance_to_line(seg.end(), chord) <= tolerance)
+}
+
+/// Perpendicular distance from a point to an infinite line
+pub(crate) fn distance_to_line(point: Point, line: Line) -> f64 {
+    let dir = line.p1 - line.p0;
+    let len = dir.hypot();
+    if len < 1e-12 {
+        return point.distance(line.p0);
+    }
+    (dir.cross(point - line.p0) / len).abs()
+}
+
+pub(crate) fn with_start(seg: PathSeg, start: Point) -> PathSeg {
+    match seg {
+        PathSeg::Line(line) => PathSeg::Line(Line::new(start, line.p1)),
+        PathSeg::Quad(mut quad) => {
+            quad.p0 = start;
+            PathSeg::Quad(quad)
+        }
+        PathSeg::Cubic(cubic) => {
+            PathSeg::Cubic(CubicBez::new(start, cubic.p1, cubic.p2, cubic.p3))
+        }
+    }
+}
+
+fn with_end(seg: PathSeg, end: Point) -> PathSeg {
+    match seg {
+        PathSeg::Line(line) => PathSeg::Line(Line::new(line.p0, end)),
+        PathSeg::Quad(mut quad) => {
+            quad.p2 = end;
+            PathSeg::Quad(quad)
+        }
+        PathSeg::Cubic(cubic) => {
+            PathSeg::Cubic(CubicBez::new(cubic.p0, cubic.p1, cubic.p2, end))
+        }
+    }
+}
+
+/// Break a path into contours, making implicit closing lines explicit
+pub(crate) fn split_subpaths(path: &BezPath) -> Vec<Subpath> {
+    let mut subpaths = Vec::new();
+    let mut segments = Vec::new();
+    let mut start = Point::ZERO;
+    let mut last = Point::ZERO;
+
+    for el in path.elements() {
+        match *el {
+            PathEl::MoveTo(p) => {
+                if !segments.is_empty() {
+                    subpaths.push(Subpath {
+                        segments: std::mem::take(&mut segments),
+                        closed: false,
+                    });
+                }
+                start = p;
+                last = p;
+            }
+            PathEl::LineTo(p) => {
+                segments.push(PathSeg::Line(Line::new(last, p)));
+                last = p;
+            }
+            PathEl::QuadTo(p1, p2) => {
+                segments.push(PathSeg::Quad(kurbo::QuadBez::new(last, p1, p2)));
+                last = p2;
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                segments.push(PathSeg::Cubic(CubicBez::new(last, p1, p2, p3)));
+                last = p3;
+            }
+            PathEl::ClosePath => {
+                if last.distance(start) > 1e-9 {
+                    segments.push(PathSeg::Line(Line::new(last, start)));
+                }
+                subpaths.push(Subpath {
+                    segments: std::mem::take(&mut segments),
+                    closed: true,
+                });
+                last = start;
+            }
+        }
+    }
+    if !segments.is_empty() {
+        subpaths.push(Subpath {
+            segments,
+            closed: false,
+        });
+    }
+    subpaths
+}
+
+/// Append segments to a path as one contour
+///
+/// For closed contours a trailing line back to the start point is folded
+/// into the `ClosePath`, matching how UFO contours are stored.
+pub(crate) fn append_segments(
+    path: &mut BezPath,
+    segments: &[PathSeg],
+    closed: bool,
+) {
+    let Some(first) = segments.first() else {
+        return;
+    };
+    let start = first.start();
+    path.move_to(start);
+
+    let count = segments.len();
+    for (i, seg) in segments.iter().enumerate() {
+        let is_closing_line = closed
+            && i == count - 1
+            && matches!(seg, PathSeg::Line(line) if line.p1.distance(start) < 1e-9);
+        if is_closing_line {
+            break;
+        }
+        match seg {
+            PathSeg::Line(line) => path.line_to(line.p1),
+            PathSeg::Quad(quad) => path.quad_to(quad.p1, quad.p2),
+            PathSeg::Cubic(cubic) => {
+                path.curve_to(cubic.p1, cubic.p2, cubic.p3)
+            }
+        }
+    }
+    if closed {
+        path.close_path();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A closed polyline approximating a circle
+    fn polygon_circle(radius: f64, sides: usize) -> BezPath {
+        let mut path = BezPath::new();
+        for i in 0..sides {
+            let angle = i as f64 / sides as f64 * std::f64::consts::TAU;
+            let p = Point::new(radius * angle.cos(), radius * angle.sin());
+            if i == 0 {
+                path.move_to(p);
+            } else {
+                path.line_to(p);
+            }
+        }
+        path.close_path();
+        path
+    }
+
+    #[test]
+    fn test_simplify_reduces_polyline_circle() {
+        let path = polygon_circle(200.0, 120);
+        let params = SimplifyParams {
+            tolerance: 2.0,
+            ..Default::default()
+        };
+        let simplified = simplify_path(&path, &params);
+        let count = simplified.segments().count();
+        assert!(count < 20, "expected few segments, got {count}");
+        // Every output point stays near the circle
+        for seg in simplified.segments() {
+            for i in 0..=8 {
+                let p = seg.eval(i as f64 / 8.0);
+                let r = p.to_vec2().hypot();
+                assert!((r - 200.0).abs() < 4.0, "radius {r} off circle");
+            }
+        }
+    }
+
+    #[test]
+    fn test_simplify_keeps_corners() {
+        // A square made of many collinear points per side
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        for i in 1..=10 {
+            path.line_to((i as f64 * 10.0, 0.0));
+        }
+        for i in 1..=10 {
+            path.line_to((100.0, i as f64 * 10.0));
+        }
+        for i in 1..=10 {
+            path.line_to((100.0 - i as f64 * 10.0, 100.0));
+        }
+        for i in 1..10 {
+            path.line_to((0.0, 100.0 - i as f64 * 10.0));
+        }
+        path.close_path();
+
+        let simplified = simplify_path(&path, &SimplifyParams::default());
+        let segments: Vec<_> = simplified.segments().collect();
+        assert_eq!(segments.len(), 4);
+        assert!(segments.iter().all(|seg| matches!(seg, PathSeg::Line(_))));
+        assert_eq!(segments[0].start(), Point::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_simplify_inserts_extrema() {
+        let path = polygon_circle(100.0, 90);
+        let simplified = simplify_path(&path, &SimplifyParams::default());
+        let has_top = simplified
+            .segments()
+            .any(|seg| seg.end().distance(Point::new(0.0, 100.0)) < 2.0);
+        assert!(has_top, "expected an on-curve point at the top extremum");
+    }
+
+    #[test]
+    fn test_simplify_segment_range_leaves_rest_untouched() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((50.0, 0.0));
+        path.line_to((100.0, 0.0));
+        path.line_to((100.0, 100.0));
+        path.line_to((0.0, 100.0));
+        path.close_path();
+
+        let simplified =
+            simplify_segment_range(&path, 0, 1, &SimplifyParams::default());
+        let segments: Vec<_> = simplified.segments().collect();
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[0].end(), Point::new(100.0, 0.0));
+        assert_eq!(segments[1].end(), Point::new(100.0, 100.0));
+    }
+
+    #[test]
+    fn test_node_runs() {
+        assert_eq!(node_runs(&[5, 1, 2, 3, 7], 8, false), vec![(1, 3)]);
+        assert_eq!(node_runs(&[7, 0, 3, 4], 8, true), vec![(3, 4), (7, 0)]);
+        assert_eq!(node_runs(&[6, 7, 0, 1], 8, false), vec![(0, 1), (6, 7)]);
+        assert!(node_runs(&[1, 3, 5], 8, true).is_empty());
+    }
+
+    #[test]
+    fn test_simplify_node_runs_wrapping() {
+        // Two collinear lines meet at the start point of a square
+        let mut path = BezPath::new();
+        path.move_to((50.0, 0.0));
+        path.line_to((100.0, 0.0));
+        path.line_to((100.0, 100.0));
+        path.line_to((0.0, 100.0));
+        path.line_to((0.0, 0.0));
+        path.close_path();
+
+        let params = SimplifyParams::default();
+        let simplified =
+            simplify_node_runs(&path, &[4, 0, 1], &params).unwrap();
+        let segments: Vec<_> = simplified.segments().collect();
+        assert_eq!(segments.len(), 4);
+        assert!(
+            segments
+                .iter()
+                .all(|segment| segment.end().distance(Point::new(50.0, 0.0))
+                    > 1.0)
+        );
+        assert!(simplify_node_runs(&path, &[0, 2], &params).is_none());
+    }
+}
@@ -0,0 +1,303 @@
+//! Snapping of positions to nearby targets
+//!
+//! Snap targets are either points, which a position snaps onto completely,
+//! or infinite lines (metrics, guidelines, alignment with other points),
+//! which a position is projected onto. Every kind of target has a rule with
+//! its reach, and rules are listed in priority order: the highest-priority
+//! target in reach wins, with ties going to the closest one. When the winner
+//! is a line and a second, non-parallel line is also in reach, the position
+//! snaps to their crossing, so for example a point can sit on both the
+//! x-height and the vertical of another point.
+
+use kurbo::{BezPath, ParamCurve, ParamCurveExtrema, Point, Vec2};
+
+/// Smallest distance from a segment end at which an extremum counts as a
+/// separate snap target (ends are on-curve points already)
+const EXTREMA_T_EPSILON: f64 = 0.01;
+
+/// What a snap target comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SnapKind {
+    /// On-curve point of the outline
+    Point,
+    /// Horizontal or vertical extremum of a curve
+    Extremum,
+    /// Font metrics line (baseline, x-height, cap height, ...)
+    Metric,
+    /// Font-level or glyph-level guideline
+    Guideline,
+    /// Horizontal or vertical alignment with another point
+    Alignment,
+}
+
+/// Shape of a snap target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnapGeometry {
+    /// Snap both coordinates to a point
+    Point(Point),
+    /// Snap onto the infinite line through `origin`
+    Line { origin: Point, direction: Vec2 },
+}
+
+/// Something positions can snap to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapTarget {
+    pub kind: SnapKind,
+    pub geometry: SnapGeometry,
+}
+
+impl SnapTarget {
+    pub fn point(kind: SnapKind, position: Point) -> Self {
+        Self {
+            kind,
+            geometry: SnapGeometry::Point(position),
+        }
+    }
+
+    /// Line through `origin` at an angle in degrees
+    pub fn line(kind: SnapKind, origin: Point, degrees: f64) -> Self {
+        let radians = degrees.to_radians();
+        Self {
+            kind,
+            geometry: SnapGeometry::Line {
+                origin,
+                direction: Vec2::new(radians.cos(), radians.sin()),
+            },
+        }
+    }
+
+    /// Horizontal line through `origin`
+    pub fn horizontal(kind: SnapKind, origin: Point) -> Self {
+        Self::line(kind, origin, 0.0)
+    }
+
+    /// Vertical line through `origin`
+    pub fn vertical(kind: SnapKind, origin: Point) -> Self {
+        Self {
+            kind,
+            geometry: SnapGeometry::Line {
+                origin,
+                direction: Vec2::new(0.0, 1.0),
+            },
+        }
+    }
+
+    /// Closest position on the target
+    pub fn closest(&self, position: Point) -> Point {
+        match self.geometry {
+            SnapGeometry::Point(point) => point,
+            SnapGeometry::Line { origin, direction } => {
+                origin + direction * (position - origin).dot(direction)
+            }
+        }
+    }
+
+    /// Crossing with another target, if both are non-parallel lines
+    fn intersection(&self, other: &SnapTarget) -> Option<Point> {
+        let (
+            SnapGeometry::Line {
+                origin: o1,
+                direction: d1,
+            },
+            SnapGeometry::Line {
+                origin: o2,
+                direction: d2,
+            },
+        ) = (self.geometry, other.geometry)
+        else {
+            return None;
+        };
+        let denominator = d1.cross(d2);
+        if denominator.abs() < 1e-9 {
+            return None;
+        }
+        Some(o1 + d1 * ((o2 - o1).cross(d2) / denominator))
+    }
+}
+
+/// Reach of one kind of snap target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapRule {
+    pub kind: SnapKind,
+    /// Largest distance that still snaps, before threshold scaling
+    pub threshold: f64,
+}
+
+/// Where a position snapped to and which targets it snapped to
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapResult {
+    pub position: Point,
+    /// The winning target, plus a second line when snapped to a crossing
+    pub targets: Vec<SnapTarget>,
+}
+
+/// Snap a position to the best target in reach
+///
+/// `rules` are in priority order, highest first; targets whose kind has no
+/// rule are ignored. Thresholds are multiplied by `threshold_scale`, which
+/// lets callers keep the reach constant on screen at any zoom level.
+pub fn snap(
+    position: Point,
+    targets: &[SnapTarget],
+    rules: &[SnapRule],
+    threshold_scale: f64,
+) -> Option<SnapResult> {
+    // (priority, distance, threshold, target)
+    let mut candidates: Vec<(usize, f64, f64, &SnapTarget)> = targets
+        .iter()
+        .filter_map(|target| {
+            let priority =
+                rules.iter().position(|rule| rule.kind == target.kind)?;
+            let threshold = rules[priority].threshold * threshold_scale;
+            let distance = (target.closest(position) - position).hypot();
+            (distance <= threshold)
+                .then_some((priority, distance, threshold, target))
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let &(_, _, threshold, best) = candidates.first()?;
+    if matches!(best.geometry, SnapGeometry::Point(_)) {
+        return Some(SnapResult {
+            position: best.closest(position),
+            targets: vec![*best],
+        });
+    }
+
+    // Prefer the crossing with another line in reach, as long as it is not
+    // far away along nearly parallel lines
+    let crossing = candidates.iter().skip(1).find_map(
+        |&(_, _, other_threshold, other)| {
+            let crossing = best.intersection(other)?;
+            let reach = 2.0 * threshold.max(other_threshold);
+            ((crossing - position).hypot() <= reach)
+                .then_some((crossing, other))
+        },
+    );
+    Some(match crossing {
+        Some((crossing, other)) => SnapResult {
+            position: crossing,
+            targets: vec![*best, *other],
+        },
+        None => SnapResult {
+            position: best.closest(position),
+            targets: vec![*best],
+        },
+    })
+}
+
+/// Interior horizontal and vertical extrema of a contour
+pub fn contour_extrema(contour: &BezPath) -> Vec<Point> {
+    contour
+        .segments()
+        .flat_map(|segment| {
+            segment
+                .extrema()
+                .into_iter()
+                .filter(|t| {
+                    *t > EXTREMA_T_EPSILON && *t < 1.0 - EXTREMA_T_EPSILON
+                })
+                .map(move |t| segment.eval(t))
+        })
+        .collect()
+}
+
+/// Default snap rules, highest priority first
+pub fn default_snap_rules() -> Vec<SnapRule> {
+    vec![
+        SnapRule {
+            kind: SnapKind::Point,
+            threshold: 8.0,
+        },
+        SnapRule {
+            kind: SnapKind::Extremum,
+            threshold: 8.0,
+        },
+        SnapRule {
+            kind: SnapKind::Guideline,
+            threshold: 6.0,
+        },
+        SnapRule {
+            kind: SnapKind::Metric,
+            threshold: 6.0,
+        },
+        SnapRule {
+            kind: SnapKind::Alignment,
+            threshold: 4.0,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Point, b: Point) {
+        assert!((a - b).hypot() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn test_snap_priority_and_reach() {
+        let targets = vec![
+            SnapTarget::horizontal(SnapKind::Metric, Point::new(0.0, 500.0)),
+            SnapTarget::point(SnapKind::Point, Point::new(103.0, 503.0)),
+        ];
+        let rules = default_snap_rules();
+
+        // Nothing in reach
+        assert!(snap(Point::new(300.0, 300.0), &targets, &rules, 1.0).is_none());
+
+        // The point outranks the closer metrics line
+        let result =
+            snap(Point::new(100.0, 500.0), &targets, &rules, 1.0).unwrap();
+        assert_near(result.position, Point::new(103.0, 503.0));
+        assert_eq!(result.targets[0].kind, SnapKind::Point);
+
+        // Out of the point's reach, the line still snaps
+        let result =
+            snap(Point::new(200.0, 504.0), &targets, &rules, 1.0).unwrap();
+        assert_near(result.position, Point::new(200.0, 500.0));
+
+        // A larger scale widens the reach
+        assert!(snap(Point::new(200.0, 510.0), &targets, &rules, 1.0).is_none());
+        assert!(snap(Point::new(200.0, 510.0), &targets, &rules, 2.0).is_some());
+
+        // Kinds without a rule are ignored
+        let rules = vec![SnapRule {
+            kind: SnapKind::Metric,
+            threshold: 6.0,
+        }];
+        let result =
+            snap(Point::new(100.0, 500.0), &targets, &rules, 1.0).unwrap();
+        assert_eq!(result.targets[0].kind, SnapKind::Metric);
+    }
+
+    #[test]
+    fn test_snap_to_crossing() {
+        let targets = vec![
+            SnapTarget::horizontal(SnapKind::Metric, Point::new(0.0, 500.0)),
+            SnapTarget::vertical(SnapKind::Alignment, Point::new(120.0, 0.0)),
+        ];
+        let result = snap(
+            Point::new(122.0, 497.0),
+            &targets,
+            &default_snap_rules(),
+            1.0,
+        )
+        .unwrap();
+        assert_near(result.position, Point::new(120.0, 500.0));
+        assert_eq!(result.targets.len(), 2);
+    }
+
+    #[test]
+    fn test_contour_extrema() {
+        let mut contour = BezPath::new();
+        contour.move_to((0.0, 0.0));
+        contour.curve_to((0.0, 100.0), (100.0, 100.0), (100.0, 0.0));
+        contour.close_path();
+
+        let extrema = contour_extrema(&contour);
+        assert_eq!(extrema.len(), 1);
+        assert_near(extrema[0], Point::new(50.0, 75.0));
+    }
+}
@@ -52,6 +52,8 @@ pub fn setup_camera(mut commands: Commands) {
             ..default()
         },
         DesignCamera,
+        // Keep the UI on this camera when other cameras are added
+        IsDefaultUiCamera,
     ));
 }
 
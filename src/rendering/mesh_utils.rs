@@ -1,10 +1,15 @@
 //! Mesh utility functions for rendering
 //!
 //! This module contains utility functions for creating common mesh shapes
-//! used throughout the rendering system.
+//! used throughout the rendering system, and filled glyph meshes.
 
 use bevy::prelude::*;
 use bevy::render::mesh::{Indices, Mesh, PrimitiveTopology};
+use lyon::path::Path;
+use lyon::tessellation::{
+    BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex,
+    VertexBuffers,
+};
 
 /// Create a line mesh between two points with the specified width
 /// Returns a mesh centered at the midpoint between start and end
@@ -51,4 +56,70 @@ pub fn create_line_mesh(start: Vec2, end: Vec2, width: f32) -> Mesh {
     mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
     mesh.insert_indices(Indices::U32(indices));
     mesh
-}
\ No newline at end of file
+}
+/// Create a filled mesh from glyph contours, in font units
+///
+/// Contours are filled with the even-odd rule. Returns None when the
+/// contours are empty or can't be tessellated.
+pub fn create_filled_glyph_mesh(paths: &[kurbo::BezPath]) -> Option<Mesh> {
+    let mut builder = Path::builder();
+    for path in paths {
+        for element in path.elements() {
+            match *element {
+                kurbo::PathEl::MoveTo(pt) => {
+                    builder.begin(lyon_point(pt));
+                }
+                kurbo::PathEl::LineTo(pt) => {
+                    builder.line_to(lyon_point(pt));
+                }
+                kurbo::PathEl::QuadTo(c, pt) => {
+                    builder.quadratic_bezier_to(lyon_point(c), lyon_point(pt));
+                }
+                kurbo::PathEl::CurveTo(c1, c2, pt) => {
+                    builder.cubic_bezier_to(
+                        lyon_point(c1),
+                        lyon_point(c2),
+                        lyon_point(pt),
+                    );
+                }
+                kurbo::PathEl::ClosePath => builder.close(),
+            }
+        }
+    }
+    let path = builder.build();
+
+    let mut geometry: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+    let result = FillTessellator::new().tessellate_path(
+        &path,
+        &FillOptions::default().with_fill_rule(FillRule::EvenOdd),
+        &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
+            vertex.position().to_array()
+        }),
+    );
+    if let Err(e) = result {
+        warn!("Glyph tessellation failed: {:?}", e);
+        return None;
+    }
+    if geometry.vertices.is_empty() {
+        return None;
+    }
+
+    let vertices: Vec<[f32; 3]> = geometry
+        .vertices
+        .iter()
+        .map(|&[x, y]| [x, y, 0.0])
+        .collect();
+    let normals = vec![[0.0, 0.0, 1.0]; vertices.len()];
+    let uvs = vec![[0.0, 0.0]; vertices.len()];
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(geometry.indices));
+    Some(mesh)
+}
+
+fn lyon_point(point: kurbo::Point) -> lyon::geom::Point<f32> {
+    lyon::geom::point(point.x as f32, point.y as f32)
+}
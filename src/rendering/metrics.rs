@@ -8,9 +8,14 @@
 
 use crate::core::state::font_metrics::FontMetrics;
 use crate::core::state::fontir_app_state::FontIRMetrics;
+use crate::editing::guidelines::{sort_guidelines, GuidelineReference};
+use crate::editing::selection::components::Selected;
 use crate::rendering::camera_responsive::CameraResponsiveScale;
 use crate::rendering::entity_pools::{update_metrics_entity, EntityPools};
-use crate::ui::theme::METRICS_GUIDE_COLOR;
+use crate::ui::theme::{
+    FONT_GUIDELINE_COLOR, GLYPH_GUIDELINE_COLOR, LOCKED_GUIDELINE_ALPHA,
+    METRICS_GUIDE_COLOR, SELECTED_PRIMARY_COLOR,
+};
 use bevy::prelude::*;
 use bevy::render::mesh::Mesh2d;
 use bevy::sprite::{ColorMaterial, MeshMaterial2d};
@@ -37,6 +42,7 @@ pub enum MetricsLineType {
     Descender,
     AdvanceWidth,
     BoundingBox,
+    Guideline,
 }
 
 /// Resource to track metrics line entities
@@ -78,11 +84,12 @@ impl GlyphMetricsCache {
         &mut self,
         fontir_state: &crate::core::state::FontIRAppState,
     ) -> &FontIRMetrics {
-        // For now, skip generation tracking since FontIRAppState doesn't expose it
-        // TODO: Add generation tracking when FontIRAppState API supports it
-
-        if self.font_metrics.is_none() {
+        // Refresh when the font info was edited or reloaded
+        if self.font_metrics.is_none()
+            || self.last_font_generation != fontir_state.font_info_generation
+        {
             self.font_metrics = Some(fontir_state.get_font_metrics());
+            self.last_font_generation = fontir_state.font_info_generation;
             debug!(
                 "Cached font metrics: UPM={}",
                 self.font_metrics.as_ref().unwrap().units_per_em
@@ -128,6 +135,12 @@ pub struct PreviewMetricsState {
 /// Z-levels for metrics lines
 const METRICS_LINE_Z: f32 = 5.0; // Behind glyph editing elements
 
+/// Half length of drawn guidelines, enough to cross the viewport
+const GUIDELINE_HALF_LENGTH: f32 = 20_000.0;
+
+/// Length of the knob marking a guideline's origin (before camera scaling)
+const GUIDELINE_KNOB_LENGTH: f32 = 24.0;
+
 /// Helper to spawn a mesh-based metrics line
 fn spawn_metrics_line(
     commands: &mut Commands,
@@ -166,6 +179,77 @@ fn spawn_metrics_line(
         .id()
 }
 
+/// Spawn the font-level and glyph-level guidelines shown with an active sort
+///
+/// Each guideline is a long line through its origin plus a short knob across
+/// the origin, where its handle sits.
+fn spawn_guideline_lines(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    fontir_state: &crate::core::state::FontIRAppState,
+    sort: &crate::editing::sort::Sort,
+    sort_entity: Entity,
+    position: Vec2,
+    guideline_handles: &Query<(&GuidelineReference, Has<Selected>)>,
+    camera_scale: &CameraResponsiveScale,
+) -> Vec<Entity> {
+    let knob_half_length =
+        camera_scale.adjusted_point_size(GUIDELINE_KNOB_LENGTH) * 0.5;
+    let mut entities = Vec::new();
+
+    for (target, guideline) in sort_guidelines(fontir_state, &sort.glyph_name) {
+        let selected = guideline_handles.iter().any(|(reference, selected)| {
+            selected
+                && reference.target == target
+                && reference.glyph_name == sort.glyph_name
+        });
+        let color = if selected {
+            SELECTED_PRIMARY_COLOR
+        } else if target.is_font() {
+            FONT_GUIDELINE_COLOR
+        } else {
+            GLYPH_GUIDELINE_COLOR
+        };
+        let color = if guideline.locked {
+            color.with_alpha(LOCKED_GUIDELINE_ALPHA)
+        } else {
+            color
+        };
+
+        let origin =
+            position + Vec2::new(guideline.x as f32, guideline.y as f32);
+        let direction = guideline.direction();
+        let direction = Vec2::new(direction.x as f32, direction.y as f32);
+        let normal = direction.perp();
+
+        entities.push(spawn_metrics_line(
+            commands,
+            meshes,
+            materials,
+            origin - direction * GUIDELINE_HALF_LENGTH,
+            origin + direction * GUIDELINE_HALF_LENGTH,
+            color,
+            sort_entity,
+            MetricsLineType::Guideline,
+            camera_scale,
+        ));
+        entities.push(spawn_metrics_line(
+            commands,
+            meshes,
+            materials,
+            origin - normal * knob_half_length,
+            origin + normal * knob_half_length,
+            color,
+            sort_entity,
+            MetricsLineType::Guideline,
+            camera_scale,
+        ));
+    }
+
+    entities
+}
+
 /// ENTITY POOLING: Get or update a metrics line entity from the pool
 #[allow(dead_code)]
 fn get_or_update_metrics_line(
@@ -260,6 +344,7 @@ pub fn render_mesh_metrics_lines(
         ),
     >,
     _existing_metrics: Query<Entity, With<MetricsLine>>,
+    guideline_handles: Query<(&GuidelineReference, Has<Selected>)>,
     fontir_app_state: Option<Res<crate::core::state::FontIRAppState>>,
     camera_scale: Res<CameraResponsiveScale>,
     presentation_mode: Option<Res<crate::ui::toolbars::edit_mode_toolbar::PresentationMode>>,
@@ -604,6 +689,19 @@ pub fn render_mesh_metrics_lines(
             );
             line_entities.push(left_entity);
 
+            // Guidelines
+            line_entities.extend(spawn_guideline_lines(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &fontir_state,
+                sort,
+                sort_entity,
+                position,
+                &guideline_handles,
+                &camera_scale,
+            ));
+
             metrics_entities.lines.insert(sort_entity, line_entities);
         }
 
@@ -778,6 +876,19 @@ pub fn render_mesh_metrics_lines(
             );
             line_entities.push(left_entity);
 
+            // Guidelines
+            line_entities.extend(spawn_guideline_lines(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &fontir_state,
+                sort,
+                sort_entity,
+                position,
+                &guideline_handles,
+                &camera_scale,
+            ));
+
             info!(
                 "🟢 METRICS STORED: {} metrics entities for active buffer sort {:?}", 
                 line_entities.len(), sort_entity
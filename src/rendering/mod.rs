@@ -4,6 +4,7 @@
 //! - Drawing systems for glyphs, paths, and UI elements
 //! - Camera management for viewport control
 //! - Background patterns and visual aids
+//! - Reference images placed in glyphs
 //! - Debug visualization tools
 //! - Selection visualization (marquee, selected points, handles)
 
@@ -20,6 +21,7 @@ pub mod mesh_utils;
 pub mod metrics;
 pub mod outline_elements;
 pub mod points;
+pub mod reference_images;
 pub mod selection;
 pub mod sort_renderer;
 pub mod sort_visuals;
@@ -33,6 +35,7 @@ pub use mesh_cache::MeshCachingPlugin;
 pub use metrics::MetricsRenderingPlugin;
 pub use outline_elements::OutlineElementsPlugin;
 pub use points::PointRenderingPlugin;
+pub use reference_images::ReferenceImagePlugin;
 pub use selection::{
     render_all_point_entities, render_selected_entities,
     render_selection_marquee,
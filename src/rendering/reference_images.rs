@@ -0,0 +1,144 @@
+//! Reference image rendering
+//!
+//! Draws the reference image of each active sort's glyph behind its
+//! outline, placed, tinted and faded the way the glyph's image says;
+//! skewed images are drawn without their skew. Images are decoded into
+//! textures once, the first time they are shown.
+
+use crate::core::state::{FontIRAppState, ImageData};
+use crate::data::images::decode_image;
+use crate::editing::sort::{ActiveSort, Sort};
+use crate::rendering::unified_glyph_editing::render_unified_glyph_editing;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::sprite::Anchor;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Z-level of reference images, behind the background layer and outlines
+const REFERENCE_IMAGE_Z: f32 = 5.0;
+
+/// Component to mark the sprites of reference images
+#[derive(Component)]
+pub struct ReferenceImageSprite;
+
+/// Textures of the reference images shown so far, with their size in
+/// pixels, keyed by UFO path and file name; `None` for images that could
+/// not be decoded
+#[derive(Resource, Default)]
+pub struct ReferenceImageTextures {
+    textures: HashMap<(PathBuf, String), Option<(Handle<Image>, Vec2)>>,
+}
+
+/// Reference images drawn, as (image, UFO path, sort position)
+type DrawnImages = Vec<(ImageData, PathBuf, Vec2)>;
+
+pub struct ReferenceImagePlugin;
+
+impl Plugin for ReferenceImagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReferenceImageTextures>().add_systems(
+            Update,
+            render_reference_images.after(render_unified_glyph_editing),
+        );
+    }
+}
+
+/// Draws the reference images of the active sorts' glyphs
+///
+/// Sprites are rebuilt only when an image, its placement or its sort
+/// changes.
+fn render_reference_images(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut textures: ResMut<ReferenceImageTextures>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    active_sort_query: Query<(&Sort, &Transform), With<ActiveSort>>,
+    sprites: Query<Entity, With<ReferenceImageSprite>>,
+    presentation_mode: Option<
+        Res<crate::ui::toolbars::edit_mode_toolbar::PresentationMode>,
+    >,
+    mut drawn: Local<DrawnImages>,
+) {
+    let presentation_active =
+        presentation_mode.as_ref().is_some_and(|pm| pm.active);
+    let mut files = HashMap::new();
+    let shown: DrawnImages = match fontir_state.as_ref() {
+        Some(fontir_state) if !presentation_active => active_sort_query
+            .iter()
+            .filter_map(|(sort, transform)| {
+                let (image, ufo_path, data) =
+                    fontir_state.glyph_image_file(&sort.glyph_name)?;
+                files.insert((ufo_path.clone(), image.file_name.clone()), data);
+                Some((image, ufo_path, transform.translation.truncate()))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if *drawn == shown {
+        return;
+    }
+
+    for sprite in sprites.iter() {
+        commands.entity(sprite).despawn();
+    }
+    for (image, ufo_path, sort_position) in &shown {
+        let key = (ufo_path.clone(), image.file_name.clone());
+        let texture =
+            textures.textures.entry(key.clone()).or_insert_with(|| {
+                let data = files.get(&key)?;
+                match decode_image(data) {
+                    Ok(decoded) => {
+                        let size = Vec2::new(
+                            decoded.width() as f32,
+                            decoded.height() as f32,
+                        );
+                        let texture = Image::from_dynamic(
+                            decoded,
+                            true,
+                            RenderAssetUsages::RENDER_WORLD,
+                        );
+                        Some((images.add(texture), size))
+                    }
+                    Err(e) => {
+                        warn!("Cannot show image {}: {}", image.file_name, e);
+                        None
+                    }
+                }
+            });
+        let Some((handle, size)) = texture.clone() else {
+            continue;
+        };
+
+        let (red, green, blue) = image.color.unwrap_or((1.0, 1.0, 1.0));
+        let position =
+            *sort_position + Vec2::new(image.x as f32, image.y as f32);
+        commands.spawn((
+            ReferenceImageSprite,
+            Sprite {
+                image: handle,
+                color: Color::srgba(
+                    red as f32,
+                    green as f32,
+                    blue as f32,
+                    image.opacity as f32,
+                ),
+                custom_size: Some(size),
+                anchor: Anchor::BottomLeft,
+                ..default()
+            },
+            Transform {
+                translation: position.extend(REFERENCE_IMAGE_Z),
+                rotation: Quat::from_rotation_z(
+                    (image.rotation as f32).to_radians(),
+                ),
+                scale: Vec3::new(
+                    image.x_scale as f32,
+                    image.y_scale as f32,
+                    1.0,
+                ),
+            },
+        ));
+    }
+    *drawn = shown;
+}
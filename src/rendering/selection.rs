@@ -202,7 +202,7 @@ pub fn render_all_point_entities(
     _nudge_state: Res<NudgeState>,
     camera_query: Query<
         (&Camera, &GlobalTransform, &Projection),
-        With<Camera2d>,
+        With<crate::rendering::cameras::DesignCamera>,
     >,
 ) {
     // Always render all points - no dual-mode rendering
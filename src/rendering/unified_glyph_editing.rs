@@ -5,11 +5,14 @@
 
 #![allow(clippy::too_many_arguments)]
 
+use crate::core::state::{CompatibilityState, OutlineCheckState};
+use crate::editing::anchors::AnchorReference;
 use crate::editing::selection::components::{
     GlyphPointReference, PointType, Selected,
 };
 use crate::editing::sort::{ActiveSort, Sort};
 use crate::rendering::camera_responsive::CameraResponsiveScale;
+use crate::systems::harfbuzz_shaping::VariableFontGlyph;
 use crate::systems::sort_manager::SortPointEntity;
 use crate::ui::theme::*;
 use crate::ui::themes::CurrentTheme;
@@ -18,14 +21,6 @@ use bevy::render::mesh::Mesh2d;
 use bevy::sprite::{ColorMaterial, MeshMaterial2d};
 use std::collections::{HashMap, HashSet};
 
-// Lyon imports for filled glyph tessellation
-use lyon::geom::point;
-use lyon::path::Path;
-use lyon::tessellation::{
-    BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex,
-    VertexBuffers,
-};
-
 /// Component to mark entities as unified glyph editing elements
 #[derive(Component)]
 pub struct UnifiedGlyphElement {
@@ -43,6 +38,7 @@ pub enum UnifiedElementType {
     OutlineSegment,
     Handle,
     ContourStartArrow,
+    Anchor,
 }
 
 /// Resource to track unified editing entities
@@ -51,6 +47,15 @@ pub struct UnifiedGlyphEntities {
     pub elements: HashMap<Entity, Vec<Entity>>, // sort_entity -> element entities
 }
 
+/// Component to mark the problems found by the outline check and the
+/// compatibility check on the canvas
+#[derive(Component)]
+pub struct OutlineProblemMarker;
+
+/// Component to mark the background layer drawn behind active sorts
+#[derive(Component)]
+pub struct BackgroundLayerGhost;
+
 /// Resource to track when sorts need visual updates (prevents unnecessary rebuilding)
 #[derive(Resource, Default)]
 pub struct SortVisualUpdateTracker {
@@ -62,6 +67,9 @@ const UNIFIED_HANDLE_Z: f32 = 7.0; // Behind outlines
 const UNIFIED_OUTLINE_Z: f32 = 8.0; // Above handles, behind points
 const UNIFIED_POINT_Z: f32 = 10.0; // Unselected points
 const UNIFIED_SELECTED_POINT_Z: f32 = 15.0; // Selected points - always above unselected
+const UNIFIED_ANCHOR_Z: f32 = 12.0; // Above points, below selected points
+const UNIFIED_PROBLEM_Z: f32 = 14.0; // Outline check markers, below selected points
+const UNIFIED_BACKGROUND_LAYER_Z: f32 = 6.0; // Background layer, behind everything
 
 /// Unified system that renders all sorts - both active (with points/handles) and inactive (filled outlines)
 /// This eliminates the need for the separate mesh_glyph_outline system and coordination complexity
@@ -79,7 +87,12 @@ pub fn render_unified_glyph_editing(
         With<ActiveSort>,
     >,
     inactive_sort_query: Query<
-        (Entity, &crate::editing::sort::Sort, &Transform),
+        (
+            Entity,
+            &crate::editing::sort::Sort,
+            &Transform,
+            Option<&VariableFontGlyph>,
+        ),
         (With<crate::editing::sort::InactiveSort>, Without<ActiveSort>),
     >,
     point_query: Query<
@@ -93,6 +106,12 @@ pub fn render_unified_glyph_editing(
         ),
         With<SortPointEntity>,
     >,
+    anchor_query: Query<(
+        &Transform,
+        &AnchorReference,
+        Option<&Selected>,
+        &SortPointEntity,
+    )>,
     app_state: Option<Res<crate::core::state::AppState>>,
     fontir_app_state: Option<Res<crate::core::state::FontIRAppState>>,
     existing_elements: Query<(Entity, &UnifiedGlyphElement)>,
@@ -156,7 +175,7 @@ pub fn render_unified_glyph_editing(
         current_active_sorts.insert(sort_entity);
     }
     
-    for (sort_entity, _, _) in inactive_sort_query.iter() {
+    for (sort_entity, _, _, _) in inactive_sort_query.iter() {
         current_inactive_sorts.insert(sort_entity);
     }
     
@@ -177,7 +196,7 @@ pub fn render_unified_glyph_editing(
     for (sort_entity, _, _) in active_sort_query.iter() {
         sorts_to_clear.insert(sort_entity);
     }
-    for (sort_entity, _, _) in inactive_sort_query.iter() {
+    for (sort_entity, _, _, _) in inactive_sort_query.iter() {
         sorts_to_clear.insert(sort_entity);
     }
     
@@ -242,6 +261,18 @@ pub fn render_unified_glyph_editing(
                 &camera_scale,
                 &theme,
             );
+            if !presentation_active {
+                render_unified_anchors(
+                    &mut commands,
+                    &mut element_entities,
+                    sort_entity,
+                    &sort.glyph_name,
+                    &anchor_query,
+                    fontir_app_state.as_deref(),
+                    &camera_scale,
+                    &theme,
+                );
+            }
             unified_entities.elements.insert(sort_entity, element_entities);
             continue;
         }
@@ -332,13 +363,25 @@ pub fn render_unified_glyph_editing(
             );
         }
 
+        // 4. Render anchors on top of the outline
+        render_unified_anchors(
+            &mut commands,
+            &mut element_entities,
+            sort_entity,
+            &sort.glyph_name,
+            &anchor_query,
+            fontir_app_state.as_deref(),
+            &camera_scale,
+            &theme,
+        );
+
         unified_entities
             .elements
             .insert(sort_entity, element_entities);
     }
 
     // Process INACTIVE sorts (filled outlines only, no points/handles) - only those that changed
-    for (sort_entity, sort, sort_transform) in inactive_sort_query.iter() {
+    for (sort_entity, sort, sort_transform, variable_font_glyph) in inactive_sort_query.iter() {
         // Skip sorts that don't need re-rendering (selective update)
         if !sorts_to_clear.contains(&sort_entity) && !sorts_to_clear.is_empty() {
             continue;
@@ -347,6 +390,27 @@ pub fn render_unified_glyph_editing(
         let sort_position = sort_transform.translation.truncate();
         let mut element_entities = Vec::new();
 
+        // Sorts of text buffers shown through the compiled variable font
+        // draw its outlines instead of the masters'
+        if let Some(VariableFontGlyph(preview_glyph)) = variable_font_glyph {
+            if !preview_glyph.paths.is_empty() {
+                spawn_filled_paths(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    &mut element_entities,
+                    sort_entity,
+                    &sort.glyph_name,
+                    &preview_glyph.paths,
+                    sort_position,
+                );
+            }
+            unified_entities
+                .elements
+                .insert(sort_entity, element_entities);
+            continue;
+        }
+
         // Render filled outline for inactive sorts
         render_filled_outline(
             &mut commands,
@@ -385,110 +449,65 @@ fn render_filled_outline(
     if let Some(fontir_state) = fontir_state {
         if let Some(paths) = fontir_state.get_glyph_paths_with_components(glyph_name) {
             info!("🎨 Rendering filled outline for '{}' with {} paths (includes components)", glyph_name, paths.len());
-            
-            // Check if we actually have path data
-            let total_elements: usize = paths.iter().map(|p| p.elements().len()).sum();
-            if total_elements == 0 {
-                warn!("⚠️ Glyph '{}' has {} paths but 0 total elements - skipping fill", glyph_name, paths.len());
-                return;
-            }
-            
-            // Combine all contours into a single Lyon path for proper winding rule handling
-            let mut lyon_path_builder = Path::builder();
-            
-            // Convert all kurbo paths (contours) to a single Lyon path
-            for (path_idx, kurbo_path) in paths.iter().enumerate() {
-                let elements_count = kurbo_path.elements().len();
-                info!("🎨 Processing path {}/{}: {} elements", path_idx + 1, paths.len(), elements_count);
-                
-                for element in kurbo_path.elements().iter() {
-                    match element {
-                        kurbo::PathEl::MoveTo(pt) => {
-                            lyon_path_builder.begin(point(pt.x as f32, pt.y as f32));
-                        },
-                        kurbo::PathEl::LineTo(pt) => {
-                            lyon_path_builder.line_to(point(pt.x as f32, pt.y as f32));
-                        },
-                        kurbo::PathEl::CurveTo(c1, c2, pt) => {
-                            lyon_path_builder.cubic_bezier_to(
-                                point(c1.x as f32, c1.y as f32),
-                                point(c2.x as f32, c2.y as f32),
-                                point(pt.x as f32, pt.y as f32),
-                            );
-                        },
-                        kurbo::PathEl::QuadTo(c, pt) => {
-                            lyon_path_builder.quadratic_bezier_to(
-                                point(c.x as f32, c.y as f32),
-                                point(pt.x as f32, pt.y as f32),
-                            );
-                        },
-                        kurbo::PathEl::ClosePath => {
-                            lyon_path_builder.close();
-                        }
-                    }
-                }
-            }
-            
-            let lyon_path = lyon_path_builder.build();
-            
-            // Tessellate the combined path for filled rendering with proper winding rules
-            let mut tessellator = FillTessellator::new();
-            let mut geometry: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
-            
-            let tessellation_result = tessellator.tessellate_path(
-                &lyon_path,
-                &FillOptions::default().with_fill_rule(FillRule::EvenOdd),
-                &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
-                    [vertex.position().x, vertex.position().y]
-                }),
+            spawn_filled_paths(
+                commands,
+                meshes,
+                materials,
+                element_entities,
+                sort_entity,
+                glyph_name,
+                &paths,
+                position,
             );
-            
-            if tessellation_result.is_ok() && !geometry.vertices.is_empty() {
-                info!("🎨 Tessellation successful: {} vertices, {} indices for '{}'", 
-                      geometry.vertices.len(), geometry.indices.len(), glyph_name);
-                
-                // Convert tessellated geometry to Bevy mesh
-                let vertices: Vec<[f32; 3]> = geometry.vertices
-                    .iter()
-                    .map(|&[x, y]| [x + position.x, y + position.y, 0.0])
-                    .collect();
-                
-                let normals = vec![[0.0, 0.0, 1.0]; vertices.len()];
-                let uvs = vec![[0.0, 0.0]; vertices.len()]; // Simple UV mapping
-                
-                let mut mesh = Mesh::new(bevy::render::mesh::PrimitiveTopology::TriangleList, default());
-                mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
-                mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-                mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-                mesh.insert_indices(bevy::render::mesh::Indices::U32(geometry.indices));
-                
-                // Create filled mesh entity
-                let entity = commands.spawn((
-                    UnifiedGlyphElement { 
-                        element_type: UnifiedElementType::OutlineSegment,
-                        sort_entity,
-                    },
-                    Mesh2d(meshes.add(mesh)),
-                    MeshMaterial2d(materials.add(ColorMaterial::from_color(FILLED_GLYPH_COLOR))),
-                    Transform::from_translation(Vec3::new(0.0, 0.0, UNIFIED_OUTLINE_Z)),
-                    GlobalTransform::default(),
-                    Visibility::Visible,
-                    InheritedVisibility::default(),
-                    ViewVisibility::default(),
-                )).id();
-                
-                element_entities.push(entity);
-            } else {
-                if tessellation_result.is_err() {
-                    warn!("🎨 Tessellation FAILED for glyph '{}': {:?}", glyph_name, tessellation_result.err());
-                } else {
-                    warn!("🎨 Tessellation produced EMPTY geometry for glyph '{}'", glyph_name);
-                }
-            }
         }
     }
 }
 
+/// Spawn the filled mesh of a glyph's paths at a sort's position
+fn spawn_filled_paths(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    element_entities: &mut Vec<Entity>,
+    sort_entity: Entity,
+    glyph_name: &str,
+    paths: &[kurbo::BezPath],
+    position: Vec2,
+) {
+    // Check if we actually have path data
+    let total_elements: usize = paths.iter().map(|p| p.elements().len()).sum();
+    if total_elements == 0 {
+        warn!("⚠️ Glyph '{}' has {} paths but 0 total elements - skipping fill", glyph_name, paths.len());
+        return;
+    }
+
+    // Tessellate all contours together so the even-odd fill handles
+    // counters
+    let Some(mesh) =
+        crate::rendering::mesh_utils::create_filled_glyph_mesh(paths)
+    else {
+        warn!("🎨 Tessellation produced no geometry for glyph '{}'", glyph_name);
+        return;
+    };
+
+    // Create filled mesh entity
+    let entity = commands.spawn((
+        UnifiedGlyphElement {
+            element_type: UnifiedElementType::OutlineSegment,
+            sort_entity,
+        },
+        Mesh2d(meshes.add(mesh)),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(FILLED_GLYPH_COLOR))),
+        Transform::from_translation(position.extend(UNIFIED_OUTLINE_Z)),
+        GlobalTransform::default(),
+        Visibility::Visible,
+        InheritedVisibility::default(),
+        ViewVisibility::default(),
+    )).id();
+
+    element_entities.push(entity);
+}
+
 /// Render outline using live Transform positions from points
 fn render_unified_outline(
     commands: &mut Commands,
@@ -1296,6 +1315,90 @@ fn render_fontir_outline_unified(
 }
 
 /// Helper to spawn a line mesh entity for unified rendering
+/// Render anchors as labelled diamond markers using live Transform positions
+#[allow(clippy::type_complexity)]
+fn render_unified_anchors(
+    commands: &mut Commands,
+    element_entities: &mut Vec<Entity>,
+    sort_entity: Entity,
+    glyph_name: &str,
+    anchor_query: &Query<(
+        &Transform,
+        &AnchorReference,
+        Option<&Selected>,
+        &SortPointEntity,
+    )>,
+    fontir_state: Option<&crate::core::state::FontIRAppState>,
+    camera_scale: &CameraResponsiveScale,
+    theme: &CurrentTheme,
+) {
+    let Some(fontir_state) = fontir_state else {
+        return;
+    };
+    let anchors = fontir_state.glyph_anchors(glyph_name);
+    let size = camera_scale.adjusted_point_size(ON_CURVE_POINT_RADIUS * 2.5);
+    let font_size = 12.0 * camera_scale.scale_factor;
+
+    for (transform, anchor_ref, selected, sort_point) in anchor_query.iter() {
+        if sort_point.sort_entity != sort_entity {
+            continue;
+        }
+        let Some(anchor) = anchors.get(anchor_ref.anchor_index) else {
+            continue;
+        };
+        let position = transform.translation.truncate();
+        let color = if selected.is_some() {
+            theme.theme().selected_primary_color()
+        } else {
+            theme.theme().special_color()
+        };
+
+        // Diamond marker: a square rotated by 45 degrees
+        let marker = commands
+            .spawn((
+                UnifiedGlyphElement {
+                    element_type: UnifiedElementType::Anchor,
+                    sort_entity,
+                },
+                Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+                Transform::from_translation(position.extend(UNIFIED_ANCHOR_Z))
+                    .with_rotation(Quat::from_rotation_z(
+                        std::f32::consts::FRAC_PI_4,
+                    )),
+                GlobalTransform::default(),
+                Visibility::Visible,
+                InheritedVisibility::default(),
+                ViewVisibility::default(),
+            ))
+            .id();
+        element_entities.push(marker);
+
+        let label = commands
+            .spawn((
+                UnifiedGlyphElement {
+                    element_type: UnifiedElementType::Anchor,
+                    sort_entity,
+                },
+                Text2d::new(anchor.name.clone()),
+                TextFont {
+                    font_size,
+                    ..default()
+                },
+                TextColor(color),
+                bevy::sprite::Anchor::BottomLeft,
+                Transform::from_translation(
+                    (position + Vec2::splat(size)).extend(UNIFIED_ANCHOR_Z),
+                ),
+            ))
+            .id();
+        element_entities.push(label);
+    }
+}
+
 fn spawn_unified_line_mesh(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -1381,6 +1484,184 @@ fn detect_sort_changes(
     }
 }
 
+type ActiveSortMovedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (),
+    (
+        With<ActiveSort>,
+        Or<(Added<ActiveSort>, Changed<Sort>, Changed<Transform>)>,
+    ),
+>;
+
+/// Marks the problems found by the outline check and the compatibility
+/// check on the active sorts
+///
+/// Markers are rebuilt when the check results change, and when active sorts
+/// move, change or are deactivated. Outline problems are marked while the
+/// outline check pane is open; compatibility issues whenever the glyph has
+/// them, only in the masters they concern.
+fn render_outline_problems(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    outline_check: Res<OutlineCheckState>,
+    compatibility: Res<CompatibilityState>,
+    fontir_state: Option<Res<crate::core::state::FontIRAppState>>,
+    camera_scale: Res<CameraResponsiveScale>,
+    active_sort_query: Query<(&Sort, &Transform), With<ActiveSort>>,
+    changed_sorts: ActiveSortMovedQuery,
+    mut removed_active: RemovedComponents<ActiveSort>,
+    markers: Query<Entity, With<OutlineProblemMarker>>,
+    presentation_mode: Option<
+        Res<crate::ui::toolbars::edit_mode_toolbar::PresentationMode>,
+    >,
+) {
+    let removed = removed_active.read().count() > 0;
+    let presentation_changed =
+        presentation_mode.as_ref().is_some_and(|pm| pm.is_changed());
+    if !outline_check.is_changed()
+        && !compatibility.is_changed()
+        && !camera_scale.is_changed()
+        && changed_sorts.is_empty()
+        && !removed
+        && !presentation_changed
+    {
+        return;
+    }
+
+    for marker in markers.iter() {
+        commands.entity(marker).despawn();
+    }
+    let presentation_active =
+        presentation_mode.as_ref().is_some_and(|pm| pm.active);
+    if presentation_active {
+        return;
+    }
+
+    let size = camera_scale.adjusted_point_size(ON_CURVE_POINT_RADIUS * 3.0);
+    let font_size = 12.0 * camera_scale.scale_factor;
+    let mesh = meshes.add(Circle::new(size * 0.5));
+    let problem_material =
+        materials.add(ColorMaterial::from_color(OUTLINE_PROBLEM_COLOR));
+    let compatibility_material =
+        materials.add(ColorMaterial::from_color(COMPATIBILITY_ISSUE_COLOR));
+
+    for (sort, sort_transform) in active_sort_query.iter() {
+        let mut markers: Vec<_> = outline_check
+            .glyph_problems(&sort.glyph_name)
+            .map(|problem| {
+                let label = problem.kind.description().to_string();
+                (problem.position, label, false)
+            })
+            .collect();
+        if let Some(fontir_state) = fontir_state.as_ref() {
+            markers.extend(
+                compatibility
+                    .glyph_markers(
+                        &sort.glyph_name,
+                        &fontir_state.current_location,
+                    )
+                    .into_iter()
+                    .map(|(position, label)| (position, label, true)),
+            );
+        }
+
+        let sort_position = sort_transform.translation.truncate();
+        for (point, label, is_compatibility_issue) in markers {
+            let (material, color) = if is_compatibility_issue {
+                (compatibility_material.clone(), COMPATIBILITY_ISSUE_COLOR)
+            } else {
+                (problem_material.clone(), OUTLINE_PROBLEM_COLOR)
+            };
+            let position =
+                sort_position + Vec2::new(point.x as f32, point.y as f32);
+            commands.spawn((
+                OutlineProblemMarker,
+                Mesh2d(mesh.clone()),
+                MeshMaterial2d(material),
+                Transform::from_translation(position.extend(UNIFIED_PROBLEM_Z)),
+            ));
+            commands.spawn((
+                OutlineProblemMarker,
+                Text2d::new(label),
+                TextFont {
+                    font_size,
+                    ..default()
+                },
+                TextColor(color),
+                bevy::sprite::Anchor::TopLeft,
+                Transform::from_translation(
+                    (position + Vec2::new(size, -size))
+                        .extend(UNIFIED_PROBLEM_Z),
+                ),
+            ));
+        }
+    }
+}
+
+/// Background layers drawn behind active sorts, as (paths, sort position)
+type DrawnBackgrounds = Vec<(Vec<kurbo::BezPath>, Vec2)>;
+
+/// Draws the background layer of each active sort's glyph behind it, or its
+/// foreground while the background is edited
+///
+/// The ghost is a locked fill with no points. It is rebuilt only when what
+/// it shows or where it sits changes.
+fn render_background_layer(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    fontir_state: Option<Res<crate::core::state::FontIRAppState>>,
+    active_sort_query: Query<(&Sort, &Transform), With<ActiveSort>>,
+    ghosts: Query<Entity, With<BackgroundLayerGhost>>,
+    presentation_mode: Option<
+        Res<crate::ui::toolbars::edit_mode_toolbar::PresentationMode>,
+    >,
+    mut drawn: Local<DrawnBackgrounds>,
+) {
+    let presentation_active =
+        presentation_mode.as_ref().is_some_and(|pm| pm.active);
+    let backgrounds: DrawnBackgrounds = match fontir_state.as_ref() {
+        Some(fontir_state) if !presentation_active => active_sort_query
+            .iter()
+            .map(|(sort, transform)| {
+                (
+                    fontir_state.background_glyph_paths(&sort.glyph_name),
+                    transform.translation.truncate(),
+                )
+            })
+            .filter(|(paths, _)| !paths.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    };
+    if *drawn == backgrounds {
+        return;
+    }
+
+    for ghost in ghosts.iter() {
+        commands.entity(ghost).despawn();
+    }
+    let material =
+        materials.add(ColorMaterial::from_color(BACKGROUND_LAYER_COLOR));
+    for (paths, position) in &backgrounds {
+        let Some(mesh) =
+            crate::rendering::mesh_utils::create_filled_glyph_mesh(paths)
+        else {
+            continue;
+        };
+        commands.spawn((
+            BackgroundLayerGhost,
+            Mesh2d(meshes.add(mesh)),
+            MeshMaterial2d(material.clone()),
+            Transform::from_translation(
+                position.extend(UNIFIED_BACKGROUND_LAYER_Z),
+            ),
+        ));
+    }
+    *drawn = backgrounds;
+}
+
 /// Spawn an arrow indicator at the contour start point
 fn spawn_contour_start_arrow(
     commands: &mut Commands,
@@ -1461,25 +1742,22 @@ impl Plugin for UnifiedGlyphEditingPlugin {
                render_unified_glyph_editing,
            ).chain()
                .after(crate::systems::text_editor_sorts::spawn_active_sort_points_optimized)
-               .after(crate::editing::selection::nudge::handle_nudge_input));
+               .after(crate::editing::selection::nudge::handle_nudge_input))
+           .add_systems(Update, render_outline_problems.after(render_unified_glyph_editing))
+           .add_systems(Update, render_background_layer.after(render_unified_glyph_editing));
     }
 }
 
-/// Check if a glyph has components by loading the UFO data directly
+/// Check if a glyph is built only from components (no contours to edit)
+///
+/// Glyphs with their own contours stay editable; their components are drawn
+/// as part of the outline.
 fn glyph_has_components(glyph_name: &str, fontir_state: Option<&crate::core::state::FontIRAppState>) -> bool {
-    if let Some(fontir_state) = fontir_state {
-        // Check if the source is a UFO file
-        let source_path = &fontir_state.source_path;
-        if source_path.extension().map_or(false, |ext| ext == "ufo") {
-            // Load UFO directly to check for components
-            if let Ok(font) = norad::Font::load(source_path) {
-                let layer = font.default_layer();
-                if let Some(glyph) = layer.get_glyph(glyph_name) {
-                    return !glyph.components.is_empty();
-                }
-            }
-        }
-    }
-    false
+    fontir_state.is_some_and(|fontir_state| {
+        !fontir_state.glyph_components(glyph_name).is_empty()
+            && fontir_state
+                .get_glyph_paths_with_edits(glyph_name)
+                .is_none_or(|paths| paths.is_empty())
+    })
 }
 
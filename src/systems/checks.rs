@@ -0,0 +1,149 @@
+//! Outline and interpolation compatibility checks
+//!
+//! Keeps `OutlineCheckState` and `CompatibilityState` up to date as glyphs
+//! are edited. The outline check only runs while its pane is open; the
+//! compatibility of the current glyph is always checked, so mismatching
+//! masters are marked on the canvas without opening the compatibility pane.
+
+use crate::core::state::{
+    CheckScope, CompatibilityState, FontIRAppState, OutlineCheckState,
+};
+use bevy::prelude::*;
+
+pub struct ChecksPlugin;
+
+impl Plugin for ChecksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OutlineCheckState>()
+            .init_resource::<CompatibilityState>()
+            // Checks once the edits and fixes of the frame are applied
+            .add_systems(
+                PostUpdate,
+                (run_outline_check, run_compatibility_check),
+            );
+    }
+}
+
+/// Checks the glyphs in scope when the pane opens or the scope changes,
+/// and the current glyph and fixed glyphs again after edits
+pub fn run_outline_check(
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut check_state: ResMut<OutlineCheckState>,
+    mut last_check: Local<Option<(CheckScope, Option<String>)>>,
+) {
+    if !check_state.visible {
+        *last_check = None;
+        return;
+    }
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    let current_glyph = fontir_state.current_glyph.clone();
+    // Switching glyphs only calls for a new check in glyph scope
+    let key = match check_state.scope {
+        CheckScope::Glyph => (CheckScope::Glyph, current_glyph.clone()),
+        CheckScope::Font => (CheckScope::Font, None),
+    };
+
+    if last_check.as_ref() != Some(&key) {
+        *last_check = Some(key);
+        check_state.problems.clear();
+        check_state.stale.clear();
+        let glyph_names = match check_state.scope {
+            CheckScope::Glyph => current_glyph.into_iter().collect(),
+            CheckScope::Font => {
+                let mut glyph_names = fontir_state.get_glyph_names();
+                glyph_names.sort();
+                glyph_names
+            }
+        };
+        for glyph_name in glyph_names {
+            check_glyph(fontir_state, &mut check_state, &glyph_name);
+        }
+        info!(
+            "Outline check: {} problem(s) found",
+            check_state.problems.len()
+        );
+        return;
+    }
+
+    if !fontir_state.is_changed() {
+        return;
+    }
+    let mut glyph_names: Vec<String> = check_state.stale.drain().collect();
+    glyph_names.extend(current_glyph);
+    glyph_names.sort();
+    glyph_names.dedup();
+    for glyph_name in glyph_names {
+        check_glyph(fontir_state, &mut check_state, &glyph_name);
+    }
+}
+
+/// Checks a glyph's contours at the current location
+fn check_glyph(
+    fontir_state: &FontIRAppState,
+    check_state: &mut OutlineCheckState,
+    glyph_name: &str,
+) {
+    let contours = if fontir_state.has_glyph(glyph_name) {
+        fontir_state
+            .get_glyph_paths_with_edits(glyph_name)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    check_state.set_glyph_problems(glyph_name, &contours);
+}
+
+/// Checks the glyphs in scope when the pane opens or the scope changes,
+/// and the current glyph again after edits
+///
+/// While the pane is closed the current glyph is still checked, so the
+/// masters that don't match can be marked when it is opened.
+pub fn run_compatibility_check(
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut compatibility: ResMut<CompatibilityState>,
+    mut last_check: Local<Option<(CheckScope, Option<String>)>>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    let current_glyph = fontir_state.current_glyph.clone();
+    let scope = if compatibility.visible {
+        compatibility.scope
+    } else {
+        CheckScope::Glyph
+    };
+    // Switching glyphs only calls for a new check in glyph scope
+    let key = match scope {
+        CheckScope::Glyph => (CheckScope::Glyph, current_glyph.clone()),
+        CheckScope::Font => (CheckScope::Font, None),
+    };
+
+    if last_check.as_ref() != Some(&key) {
+        *last_check = Some(key);
+        compatibility.issues.clear();
+        let glyph_names = match scope {
+            CheckScope::Glyph => current_glyph.into_iter().collect(),
+            CheckScope::Font => {
+                let mut glyph_names = fontir_state.get_glyph_names();
+                glyph_names.sort();
+                glyph_names
+            }
+        };
+        for glyph_name in glyph_names {
+            compatibility.set_glyph_issues(fontir_state, &glyph_name);
+        }
+        info!(
+            "Compatibility check: {} issue(s) found",
+            compatibility.issues.len()
+        );
+        return;
+    }
+
+    if fontir_state.is_changed() {
+        if let Some(glyph_name) = current_glyph {
+            compatibility.set_glyph_issues(fontir_state, &glyph_name);
+        }
+    }
+}
@@ -11,13 +11,38 @@
 #![allow(deprecated)]
 #![allow(unused_mut)]
 
-use crate::core::state::{AppState, FontIRAppState, GlyphNavigation};
+use crate::core::settings::BezySettings;
+use crate::core::state::{
+    AppState, EditableGlyphInstance, FontIRAppState, GlyphNavigation,
+    TextEditorState,
+};
+use crate::data::glyph_construction::{
+    build_composite, parse_recipes, unicode_recipes, GlyphRecipe,
+};
+use crate::data::glyph_set::validate_glyph_name;
+use crate::data::images::{decode_image, grayscale_image, image_size};
+use crate::data::ufo::duplicate_codepoints;
+use crate::editing::selection::components::{GlyphPointReference, Selected};
+use crate::editing::selection::events::AppStateChanged;
 use crate::editing::sort::{ActiveSort, Sort};
+use crate::rendering::cameras::DesignCamera;
 use crate::rendering::checkerboard::CheckerboardEnabled;
+use crate::rendering::unified_glyph_editing::SortVisualUpdateTracker;
 // BezyResult not used in current implementation
+use crate::geometry::autotrace::{trace_bitmap, TraceParams};
+use crate::geometry::bezpath_editing::{
+    is_closed, on_curve_node_count, on_curve_node_index,
+};
+use crate::geometry::offset::{expand_stroke, offset_contour};
+use crate::geometry::outline_check::{fix_all, fix_problem, OutlineProblem};
+use crate::geometry::quadratic::{self, CurveType};
+use crate::geometry::simplify::{simplify_node_runs, simplify_path};
 use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
 // Using String for glyph names in current norad version
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Event)]
 pub struct OpenFileEvent {
@@ -32,20 +57,33 @@ pub struct SaveFileAsEvent {
     pub path: PathBuf,
 }
 
+/// Add an empty glyph to every master
 #[derive(Event)]
-pub struct NewGlyphEvent;
+pub struct NewGlyphEvent {
+    pub glyph_name: String,
+    pub codepoints: Vec<char>,
+}
 
+/// Delete a glyph from every master
 #[derive(Event)]
 pub struct DeleteGlyphEvent {
     pub glyph_name: String,
 }
 
+/// Rename a glyph in every master, along with everything that refers to it
 #[derive(Event)]
 pub struct RenameGlyphEvent {
     pub old_name: String,
     pub new_name: String,
 }
 
+/// Replace the codepoints of a glyph
+#[derive(Event)]
+pub struct SetGlyphCodepointsEvent {
+    pub glyph_name: String,
+    pub codepoints: Vec<char>,
+}
+
 #[derive(Event)]
 pub struct OpenGlyphEditorEvent {
     pub glyph_name: String,
@@ -68,6 +106,151 @@ pub struct CreateContourEvent {
     pub contour: norad::Contour,
 }
 
+/// Refit contours with fewer curves
+///
+/// Uses the current point selection: each run of neighbouring selected
+/// on-curve points simplifies just the segments between them, selecting
+/// every on-curve point of a contour simplifies all of it, and no selection
+/// simplifies every contour in the glyph. Contours without two neighbouring
+/// selected on-curve points are left alone.
+#[derive(Event)]
+pub struct SimplifyContoursEvent;
+
+/// Which contours a curve conversion applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionScope {
+    /// Contours with selected points, or the whole current glyph
+    Selection,
+    /// Every glyph in the font
+    Font,
+}
+
+/// Convert contours between cubic and quadratic curves in every master
+#[derive(Event)]
+pub struct ConvertCurvesEvent {
+    pub target: CurveType,
+    pub scope: ConversionScope,
+}
+
+/// Grow or shrink closed contours by a distance in font units
+///
+/// Positive distances make the shape bolder, negative ones lighter. Applies
+/// to closed contours with selected points, or every closed contour of the
+/// current glyph; open contours are left to `ExpandStrokeEvent`.
+#[derive(Event)]
+pub struct OffsetContoursEvent {
+    pub distance: f64,
+}
+
+/// Expand contours into closed outlines of the configured stroke width
+///
+/// Applies to contours with selected points, or every open contour of the
+/// current glyph.
+#[derive(Event)]
+pub struct ExpandStrokeEvent;
+
+/// Where composite glyph recipes come from
+#[derive(Debug, Clone)]
+pub enum RecipeSource {
+    /// A recipe file, one `name = base + mark@anchor | XXXX` per line
+    File(PathBuf),
+    /// Canonical decompositions of accented Latin letters
+    UnicodeDecomposition,
+}
+
+/// Build composite glyphs from recipes in every master
+///
+/// Components are placed by their anchors, the advance width comes from the
+/// bases and new glyphs get the recipe's codepoints. Glyphs named in a recipe
+/// file that already exist get their components and width rebuilt; Unicode
+/// recipes only add glyphs that are missing.
+#[derive(Event)]
+pub struct BuildCompositesEvent {
+    pub source: RecipeSource,
+}
+
+/// Which outline check problems to fix
+#[derive(Debug, Clone)]
+pub enum OutlineFix {
+    /// A single problem found by the outline check
+    Problem(OutlineProblem),
+    /// Every problem in the glyph
+    All,
+}
+
+/// Apply outline check quick fixes to a glyph at the current location
+#[derive(Event)]
+pub struct FixOutlineEvent {
+    pub glyph_name: String,
+    pub fix: OutlineFix,
+}
+
+/// Move the editing location in the designspace
+///
+/// Between masters, glyphs show interpolated and can't be edited.
+#[derive(Event)]
+pub struct SetLocationEvent {
+    /// Normalized value by axis tag; axes left out keep their value
+    pub axis_values: Vec<(String, f64)>,
+}
+
+/// What to do with the current glyph's background layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundAction {
+    /// Replace the background with the foreground
+    CopyToBackground,
+    /// Swap the foreground and the background
+    Swap,
+    /// Start or stop editing the background in place of the foreground
+    ToggleEdit,
+}
+
+/// Act on the background layer of the current glyph at the current master
+#[derive(Event)]
+pub struct BackgroundLayerEvent {
+    pub action: BackgroundAction,
+}
+
+/// Where the bitmap an autotrace reads comes from
+#[derive(Debug, Clone)]
+pub enum TraceSource {
+    /// The glyph's reference image at the current master, traced where it
+    /// is placed
+    ReferenceImage,
+    /// A PNG or JPEG file, traced as if added as the reference image
+    File(PathBuf),
+}
+
+/// Trace a bitmap into contours added to a glyph at the current master
+///
+/// Dark parts of the bitmap become outer contours and light parts inside
+/// them counters; the glyph's existing contours are kept.
+#[derive(Event)]
+pub struct AutotraceEvent {
+    pub glyph_name: String,
+    pub source: TraceSource,
+}
+
+/// Autotraces running in the background, by the glyph they trace into
+///
+/// Tracing a large scan takes a while, so the bitmap is decoded and traced
+/// on the async compute pool and the contours are added once it is done,
+/// at the master current then.
+#[derive(Resource, Default)]
+pub struct AutotraceTasks {
+    tasks: Vec<(String, Task<anyhow::Result<Vec<kurbo::BezPath>>>)>,
+}
+
+impl AutotraceTasks {
+    /// Whether a glyph is being traced into
+    pub fn is_tracing(&self, glyph_name: &str) -> bool {
+        self.tasks.iter().any(|(name, _)| name == glyph_name)
+    }
+}
+
+/// Recipe file looked up next to the font source
+const COMPOSITE_RECIPES_FILE: &str = "composites.txt";
+
 pub struct CommandsPlugin;
 
 impl Plugin for CommandsPlugin {
@@ -84,9 +267,20 @@ fn register_event_handlers(app: &mut App) {
         .add_event::<NewGlyphEvent>()
         .add_event::<DeleteGlyphEvent>()
         .add_event::<RenameGlyphEvent>()
+        .add_event::<SetGlyphCodepointsEvent>()
         .add_event::<OpenGlyphEditorEvent>()
         .add_event::<CycleCodepointEvent>()
         .add_event::<CreateContourEvent>()
+        .add_event::<SimplifyContoursEvent>()
+        .add_event::<ConvertCurvesEvent>()
+        .add_event::<OffsetContoursEvent>()
+        .add_event::<ExpandStrokeEvent>()
+        .add_event::<BuildCompositesEvent>()
+        .add_event::<FixOutlineEvent>()
+        .add_event::<SetLocationEvent>()
+        .add_event::<BackgroundLayerEvent>()
+        .add_event::<AutotraceEvent>()
+        .init_resource::<AutotraceTasks>()
         .add_systems(
             Update,
             (
@@ -96,12 +290,31 @@ fn register_event_handlers(app: &mut App) {
                 handle_new_glyph,
                 handle_delete_glyph,
                 handle_rename_glyph,
+                handle_set_glyph_codepoints,
                 handle_open_glyph_editor,
                 handle_cycle_codepoint,
                 handle_create_contour,
                 handle_codepoint_cycling,
                 handle_save_shortcuts,
                 handle_checkerboard_toggle,
+                (
+                    handle_simplify_contours,
+                    handle_simplify_shortcut,
+                    handle_convert_curves,
+                    handle_convert_curves_shortcuts,
+                    handle_offset_contours,
+                    handle_expand_stroke,
+                    handle_offset_shortcuts,
+                    handle_build_composites,
+                    handle_build_composites_shortcut,
+                    handle_fix_outline,
+                    handle_set_location,
+                    handle_background_layer_shortcuts,
+                    handle_background_layer,
+                    stop_editing_background_on_change,
+                    handle_autotrace,
+                    apply_finished_autotraces,
+                ),
             ),
         );
 }
@@ -170,46 +383,207 @@ fn handle_save_file_as(
     }
 }
 
+/// Handler for adding glyphs, half an em wide and without outlines
 fn handle_new_glyph(
     mut event_reader: EventReader<NewGlyphEvent>,
-    _app_state: Option<ResMut<AppState>>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
 ) {
-    for _event in event_reader.read() {
-        debug!("New glyph creation requested");
-        // Implementation here when needed
+    for event in event_reader.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("New glyph requested but no font is loaded");
+            continue;
+        };
+        if let Err(e) = validate_glyph_name(&event.glyph_name) {
+            warn!("Cannot add glyph: {}", e);
+            continue;
+        }
+        if fontir_state.has_glyph(&event.glyph_name) {
+            warn!("Cannot add glyph '{}': it already exists", event.glyph_name);
+            continue;
+        }
+
+        let width = (fontir_state.get_font_metrics().units_per_em / 2.0) as f64;
+        let instances = fontir_state
+            .font_master_locations()
+            .into_iter()
+            .map(|location| {
+                let instance = EditableGlyphInstance {
+                    width,
+                    height: None,
+                    vertical_origin: None,
+                    contours: Vec::new(),
+                    implied_on_curves: Vec::new(),
+                    components: Vec::new(),
+                    anchors: Vec::new(),
+                    guidelines: Vec::new(),
+                    image: None,
+                    is_dirty: true,
+                };
+                (location, instance)
+            })
+            .collect();
+        fontir_state.add_glyph(
+            &event.glyph_name,
+            event.codepoints.clone(),
+            instances,
+        );
+        app_state_changed.write(AppStateChanged);
     }
 }
 
+/// Handler for deleting glyphs
+///
+/// Composites that use the glyph are left with a missing component, and
+/// are listed in a warning.
 fn handle_delete_glyph(
     mut event_reader: EventReader<DeleteGlyphEvent>,
-    _app_state: Option<ResMut<AppState>>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
 ) {
     for event in event_reader.read() {
-        debug!("Delete glyph requested for {:?}", event.glyph_name);
-        // Implementation here when needed
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Delete glyph requested but no font is loaded");
+            continue;
+        };
+        let composites = fontir_state.composites_using(&event.glyph_name);
+        if let Err(e) = fontir_state.delete_glyph(&event.glyph_name) {
+            warn!("Cannot delete glyph '{}': {}", event.glyph_name, e);
+            continue;
+        }
+        if !composites.is_empty() {
+            warn!(
+                "Deleted glyph '{}' is still used as a component by: {}",
+                event.glyph_name,
+                composites.join(", ")
+            );
+        }
+        app_state_changed.write(AppStateChanged);
     }
 }
 
+/// Handler for renaming glyphs, including the sorts that show them
 fn handle_rename_glyph(
     mut event_reader: EventReader<RenameGlyphEvent>,
-    _app_state: Option<ResMut<AppState>>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut text_editor_state: ResMut<TextEditorState>,
+    mut sorts: Query<&mut Sort>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
 ) {
     for event in event_reader.read() {
-        debug!(
-            "Rename glyph requested: {:?} -> {:?}",
-            event.old_name, event.new_name
-        );
-        // Implementation here when needed
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Rename glyph requested but no font is loaded");
+            continue;
+        };
+        if let Err(e) =
+            fontir_state.rename_glyph(&event.old_name, &event.new_name)
+        {
+            warn!("Cannot rename glyph '{}': {}", event.old_name, e);
+            continue;
+        }
+
+        text_editor_state.rename_glyph(&event.old_name, &event.new_name);
+        for mut sort in &mut sorts {
+            if sort.glyph_name == event.old_name {
+                sort.glyph_name = event.new_name.clone();
+            }
+        }
+        app_state_changed.write(AppStateChanged);
+    }
+}
+
+/// Handler for replacing the codepoints of glyphs
+///
+/// Codepoints that other glyphs already have are assigned anyway, with a
+/// warning naming those glyphs.
+fn handle_set_glyph_codepoints(
+    mut event_reader: EventReader<SetGlyphCodepointsEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    for event in event_reader.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Set codepoints requested but no font is loaded");
+            continue;
+        };
+        if let Err(e) = fontir_state
+            .set_glyph_codepoints(&event.glyph_name, event.codepoints.clone())
+        {
+            warn!("Cannot set codepoints of '{}': {}", event.glyph_name, e);
+            continue;
+        }
+
+        let codepoint_glyphs = fontir_state.codepoint_glyphs_map();
+        for (codepoint, glyph_names) in duplicate_codepoints(&codepoint_glyphs)
+        {
+            if glyph_names.contains(&event.glyph_name) {
+                warn!(
+                    "U+{} is assigned to more than one glyph: {}",
+                    codepoint,
+                    glyph_names.join(", ")
+                );
+            }
+        }
+        app_state_changed.write(AppStateChanged);
     }
 }
 
+/// Gap between an opened glyph and the sorts to its left
+const OPENED_SORT_GAP: f32 = 100.0;
+
+/// Opens a glyph as a new freeform sort, right of the existing sorts, and
+/// centers the design view on it
 fn handle_open_glyph_editor(
     mut event_reader: EventReader<OpenGlyphEditorEvent>,
-    _app_state: Option<ResMut<AppState>>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut text_editor_state: ResMut<TextEditorState>,
+    sorts: Query<(&Sort, &Transform), Without<DesignCamera>>,
+    mut cameras: Query<&mut Transform, With<DesignCamera>>,
 ) {
     for event in event_reader.read() {
-        debug!("Open glyph editor requested for {:?}", event.glyph_name);
-        // Implementation here when needed
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Open glyph requested but no font is loaded");
+            continue;
+        };
+        if !fontir_state.has_glyph(&event.glyph_name) {
+            warn!("Cannot open glyph '{}': not in the font", event.glyph_name);
+            continue;
+        }
+
+        let position = sorts
+            .iter()
+            .map(|(sort, transform)| {
+                transform.translation.truncate()
+                    + Vec2::new(
+                        fontir_state.get_glyph_advance_width(&sort.glyph_name)
+                            + OPENED_SORT_GAP,
+                        0.0,
+                    )
+            })
+            .max_by(|a, b| a.x.total_cmp(&b.x))
+            .unwrap_or_default();
+        let advance_width =
+            fontir_state.get_glyph_advance_width(&event.glyph_name);
+        let codepoint = fontir_state
+            .glyph_codepoints(&event.glyph_name)
+            .first()
+            .copied();
+
+        text_editor_state.add_freeform_sort(
+            event.glyph_name.clone(),
+            position,
+            advance_width,
+            codepoint,
+        );
+        fontir_state.set_current_glyph(Some(event.glyph_name.clone()));
+
+        let metrics = fontir_state.get_font_metrics();
+        let height = metrics.ascender.unwrap_or(metrics.units_per_em * 0.8);
+        for mut camera_transform in cameras.iter_mut() {
+            camera_transform.translation.x = position.x + advance_width / 2.0;
+            camera_transform.translation.y = position.y + height / 2.0;
+        }
+        info!("Opened glyph '{}'", event.glyph_name);
     }
 }
 
@@ -412,3 +786,1001 @@ pub fn handle_checkerboard_toggle(
         debug!("Detected Command+G / Ctrl+G key combination, toggling checkerboard to: {}", status);
     }
 }
+
+/// System to handle the keyboard shortcut for simplifying contours
+///
+/// This system watches for Command+Shift+M (macOS) or Ctrl+Shift+M
+/// (Windows/Linux)
+pub fn handle_simplify_shortcut(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut simplify_event: EventWriter<SimplifyContoursEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+
+    if modifier_pressed && shift_pressed && keyboard.just_pressed(KeyCode::KeyM)
+    {
+        debug!("Detected Command+Shift+M / Ctrl+Shift+M, simplifying contours");
+        simplify_event.write(SimplifyContoursEvent);
+    }
+}
+
+/// Handler for simplifying contours in FontIR working copies
+fn handle_simplify_contours(
+    mut events: EventReader<SimplifyContoursEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    settings: Res<BezySettings>,
+    selected_points: Query<&GlyphPointReference, With<Selected>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    let Some(fontir_state) = fontir_state.as_mut() else {
+        warn!("Simplify requested but FontIR state is not available");
+        return;
+    };
+    let params = settings.simplify.params();
+
+    // Group selected points by glyph and contour
+    let mut selection: HashMap<(String, usize), Vec<usize>> = HashMap::new();
+    for point_ref in selected_points.iter() {
+        selection
+            .entry((point_ref.glyph_name.clone(), point_ref.contour_index))
+            .or_default()
+            .push(point_ref.point_index);
+    }
+
+    // With nothing selected, simplify every contour of the current glyph
+    if selection.is_empty() {
+        let Some(glyph_name) = fontir_state.current_glyph.clone() else {
+            debug!("Simplify: no selection and no current glyph");
+            return;
+        };
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&glyph_name)
+        else {
+            return;
+        };
+        for contour in working_copy.contours.iter_mut() {
+            *contour = simplify_path(contour, &params);
+        }
+        working_copy.is_dirty = true;
+        info!("Simplified all contours of glyph '{}'", glyph_name);
+        app_state_changed.write(AppStateChanged);
+        return;
+    }
+
+    let mut changed = false;
+    for ((glyph_name, contour_index), point_indices) in selection {
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&glyph_name)
+        else {
+            continue;
+        };
+        let Some(contour) = working_copy.contours.get_mut(contour_index) else {
+            continue;
+        };
+
+        let mut nodes: Vec<usize> = point_indices
+            .iter()
+            .filter_map(|&index| on_curve_node_index(contour, index))
+            .collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+
+        // Each run of neighbouring nodes is refitted on its own
+        let simplified = if nodes.len() == on_curve_node_count(contour) {
+            Some(simplify_path(contour, &params))
+        } else {
+            simplify_node_runs(contour, &nodes, &params)
+        };
+        let Some(simplified) = simplified else {
+            debug!(
+                "Simplify: select neighbouring points of contour {} in '{}'",
+                contour_index, glyph_name
+            );
+            continue;
+        };
+        *contour = simplified;
+        working_copy.is_dirty = true;
+        changed = true;
+        info!(
+            "Simplified contour {} of glyph '{}'",
+            contour_index, glyph_name
+        );
+    }
+
+    if changed {
+        app_state_changed.write(AppStateChanged);
+    }
+}
+
+/// System to handle keyboard shortcuts for cubic/quadratic conversion
+///
+/// Command+Shift+Period (macOS) or Ctrl+Shift+Period (Windows/Linux)
+/// converts to quadratic curves and Command+Shift+B / Ctrl+Shift+B back to
+/// cubic ones. Holding Alt as well converts the whole font instead of the
+/// selection. Q is avoided since Command+Shift+Q logs out on macOS.
+pub fn handle_convert_curves_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut convert_event: EventWriter<ConvertCurvesEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if !modifier_pressed || !shift_pressed {
+        return;
+    }
+
+    let scope = if keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight)
+    {
+        ConversionScope::Font
+    } else {
+        ConversionScope::Selection
+    };
+
+    if keyboard.just_pressed(KeyCode::Period) {
+        debug!("Converting {:?} to quadratic curves", scope);
+        convert_event.write(ConvertCurvesEvent {
+            target: CurveType::Quadratic,
+            scope,
+        });
+    } else if keyboard.just_pressed(KeyCode::KeyB) {
+        debug!("Converting {:?} to cubic curves", scope);
+        convert_event.write(ConvertCurvesEvent {
+            target: CurveType::Cubic,
+            scope,
+        });
+    }
+}
+
+/// Handler for converting contours between cubic and quadratic curves
+fn handle_convert_curves(
+    mut events: EventReader<ConvertCurvesEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    settings: Res<BezySettings>,
+    selected_points: Query<&GlyphPointReference, With<Selected>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!(
+                "Curve conversion requested but FontIR state is not available"
+            );
+            return;
+        };
+
+        // Glyphs to convert, with the contours to convert in each
+        // (`None` meaning every contour)
+        let targets: Vec<(String, Option<HashSet<usize>>)> = match event.scope {
+            ConversionScope::Font => fontir_state
+                .glyph_cache
+                .keys()
+                .map(|glyph_name| (glyph_name.clone(), None))
+                .collect(),
+            ConversionScope::Selection => {
+                let targets = contour_targets(fontir_state, &selected_points);
+                if targets.is_empty() {
+                    continue;
+                }
+                targets
+            }
+        };
+
+        let tolerance = settings.curve_conversion.tolerance;
+        let converted = targets
+            .iter()
+            .filter(|(glyph_name, contours)| {
+                convert_glyph_curves(
+                    fontir_state,
+                    glyph_name,
+                    contours.as_ref(),
+                    event.target,
+                    tolerance,
+                )
+            })
+            .count();
+
+        info!(
+            "Converted {} glyph(s) to {:?} curves",
+            converted, event.target
+        );
+        if converted > 0 {
+            app_state_changed.write(AppStateChanged);
+        }
+    }
+}
+
+/// Convert a glyph's contours in every master, keeping them compatible
+///
+/// Returns whether anything changed.
+fn convert_glyph_curves(
+    fontir_state: &mut FontIRAppState,
+    glyph_name: &str,
+    contours: Option<&HashSet<usize>>,
+    target: CurveType,
+    tolerance: f64,
+) -> bool {
+    let locations = fontir_state.master_locations(glyph_name);
+    let mut masters: Vec<Vec<kurbo::BezPath>> = Vec::new();
+    let mut implied: Vec<Vec<Vec<bool>>> = Vec::new();
+    for location in &locations {
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy_at(glyph_name, location)
+        else {
+            return false;
+        };
+        masters.push(working_copy.contours.clone());
+        let mut flags = working_copy.implied_on_curves.clone();
+        flags.resize(working_copy.contours.len(), Vec::new());
+        implied.push(flags);
+    }
+    let Some(contour_count) = masters.iter().map(Vec::len).min() else {
+        return false;
+    };
+
+    let mut changed = false;
+    for contour_index in 0..contour_count {
+        if contours.is_some_and(|selected| !selected.contains(&contour_index)) {
+            continue;
+        }
+        let paths: Vec<kurbo::BezPath> = masters
+            .iter()
+            .map(|contours| contours[contour_index].clone())
+            .collect();
+
+        let converted = match target {
+            CurveType::Quadratic => {
+                if !paths.iter().any(quadratic::has_cubics) {
+                    continue;
+                }
+                quadratic::cubic_to_quadratic_compatible(&paths, tolerance)
+                    .unwrap_or_else(|| {
+                        warn!(
+                            "Contour {} of '{}' is not compatible across masters; converting each master separately",
+                            contour_index, glyph_name
+                        );
+                        paths
+                            .iter()
+                            .map(|path| {
+                                quadratic::cubic_to_quadratic(path, tolerance)
+                            })
+                            .collect()
+                    })
+            }
+            CurveType::Cubic => {
+                if !paths.iter().any(quadratic::has_quadratics) {
+                    continue;
+                }
+                paths.iter().map(quadratic::quadratic_to_cubic).collect()
+            }
+        };
+
+        for ((contours, flags), path) in
+            masters.iter_mut().zip(&mut implied).zip(converted)
+        {
+            // Quadratic pieces of a cubic are joined at implied on-curves
+            flags[contour_index] = match target {
+                CurveType::Quadratic => quadratic::converted_implied_flags(
+                    &contours[contour_index],
+                    &flags[contour_index],
+                    &path,
+                ),
+                CurveType::Cubic => Vec::new(),
+            };
+            contours[contour_index] = path;
+        }
+        changed = true;
+    }
+
+    if changed {
+        for ((location, contours), flags) in
+            locations.iter().zip(masters).zip(implied)
+        {
+            if let Some(working_copy) =
+                fontir_state.get_or_create_working_copy_at(glyph_name, location)
+            {
+                working_copy.contours = contours;
+                working_copy.implied_on_curves = flags;
+                working_copy.is_dirty = true;
+            }
+        }
+    }
+    changed
+}
+
+/// Glyphs an outline command applies to, with the contours to change in each
+/// (`None` meaning every contour)
+///
+/// Contours with selected points are used if there are any, otherwise the
+/// whole current glyph.
+fn contour_targets(
+    fontir_state: &FontIRAppState,
+    selected_points: &Query<&GlyphPointReference, With<Selected>>,
+) -> Vec<(String, Option<HashSet<usize>>)> {
+    let mut selection: HashMap<String, HashSet<usize>> = HashMap::new();
+    for point_ref in selected_points.iter() {
+        selection
+            .entry(point_ref.glyph_name.clone())
+            .or_default()
+            .insert(point_ref.contour_index);
+    }
+    if selection.is_empty() {
+        return fontir_state
+            .current_glyph
+            .clone()
+            .map(|glyph_name| vec![(glyph_name, None)])
+            .unwrap_or_default();
+    }
+    selection
+        .into_iter()
+        .map(|(glyph_name, contours)| (glyph_name, Some(contours)))
+        .collect()
+}
+
+/// System to handle keyboard shortcuts for offsetting and stroke expansion
+///
+/// Command+Shift+O (macOS) or Ctrl+Shift+O (Windows/Linux) offsets contours
+/// outwards by the configured distance, and inwards when Alt is held too.
+/// Command+Shift+X / Ctrl+Shift+X expands strokes.
+pub fn handle_offset_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<BezySettings>,
+    mut offset_event: EventWriter<OffsetContoursEvent>,
+    mut expand_event: EventWriter<ExpandStrokeEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if !modifier_pressed || !shift_pressed {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyO) {
+        let inwards = keyboard.pressed(KeyCode::AltLeft)
+            || keyboard.pressed(KeyCode::AltRight);
+        let distance = if inwards {
+            -settings.offset.distance
+        } else {
+            settings.offset.distance
+        };
+        debug!("Offsetting contours by {}", distance);
+        offset_event.write(OffsetContoursEvent { distance });
+    } else if keyboard.just_pressed(KeyCode::KeyX) {
+        debug!("Expanding strokes");
+        expand_event.write(ExpandStrokeEvent);
+    }
+}
+
+/// Handler for offsetting contours in FontIR working copies
+fn handle_offset_contours(
+    mut events: EventReader<OffsetContoursEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    settings: Res<BezySettings>,
+    selected_points: Query<&GlyphPointReference, With<Selected>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Offset requested but FontIR state is not available");
+            return;
+        };
+        let params = settings.offset.params();
+
+        let mut changed = false;
+        for (glyph_name, contours) in
+            contour_targets(fontir_state, &selected_points)
+        {
+            let Some(working_copy) =
+                fontir_state.get_or_create_working_copy(&glyph_name)
+            else {
+                continue;
+            };
+            for (contour_index, contour) in
+                working_copy.contours.iter_mut().enumerate()
+            {
+                if contours
+                    .as_ref()
+                    .is_some_and(|selected| !selected.contains(&contour_index))
+                {
+                    continue;
+                }
+                // Open contours are centerlines; expanding strokes turns
+                // them into outlines
+                if !is_closed(contour) {
+                    debug!(
+                        "Offset: skipping open contour {} of '{}'",
+                        contour_index, glyph_name
+                    );
+                    continue;
+                }
+                match offset_contour(contour, event.distance, &params) {
+                    Some(offset) => {
+                        *contour = offset;
+                        working_copy.is_dirty = true;
+                        changed = true;
+                    }
+                    None => warn!(
+                        "Contour {} of '{}' cannot be offset by {}",
+                        contour_index, glyph_name, event.distance
+                    ),
+                }
+            }
+            info!(
+                "Offset contours of glyph '{}' by {}",
+                glyph_name, event.distance
+            );
+        }
+
+        if changed {
+            app_state_changed.write(AppStateChanged);
+        }
+    }
+}
+
+/// Handler for expanding contours into stroked outlines
+fn handle_expand_stroke(
+    mut events: EventReader<ExpandStrokeEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    settings: Res<BezySettings>,
+    selected_points: Query<&GlyphPointReference, With<Selected>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+    let Some(fontir_state) = fontir_state.as_mut() else {
+        warn!("Stroke expansion requested but FontIR state is not available");
+        return;
+    };
+    let params = settings.offset.params();
+    let width = settings.offset.stroke_width;
+    let cap = settings.offset.cap;
+
+    let mut changed = false;
+    for (glyph_name, contours) in
+        contour_targets(fontir_state, &selected_points)
+    {
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&glyph_name)
+        else {
+            continue;
+        };
+
+        let mut expanded_any = false;
+        let mut result = Vec::with_capacity(working_copy.contours.len());
+        for (contour_index, contour) in working_copy.contours.iter().enumerate()
+        {
+            // Without a selection only open contours (centerlines) are
+            // expanded; closed ones are already outlines
+            let expand = match &contours {
+                Some(selected) => selected.contains(&contour_index),
+                None => !is_closed(contour),
+            };
+            if !expand {
+                result.push(contour.clone());
+                continue;
+            }
+            result.extend(expand_stroke(contour, width, cap, &params));
+            expanded_any = true;
+        }
+
+        if expanded_any {
+            working_copy.contours = result;
+            working_copy.is_dirty = true;
+            changed = true;
+            info!(
+                "Expanded strokes of glyph '{}' to width {}",
+                glyph_name, width
+            );
+        }
+    }
+
+    if changed {
+        app_state_changed.write(AppStateChanged);
+    }
+}
+
+/// Cmd+Shift+R builds missing accented glyphs from Unicode, adding Alt
+/// builds the recipes in `composites.txt` next to the font source
+pub fn handle_build_composites_shortcut(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut build_event: EventWriter<BuildCompositesEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if !modifier_pressed
+        || !shift_pressed
+        || !keyboard.just_pressed(KeyCode::KeyR)
+    {
+        return;
+    }
+
+    let from_file = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+    let source = if from_file {
+        let Some(fontir_state) = fontir_state else {
+            return;
+        };
+        RecipeSource::File(
+            fontir_state
+                .source_path
+                .with_file_name(COMPOSITE_RECIPES_FILE),
+        )
+    } else {
+        RecipeSource::UnicodeDecomposition
+    };
+    debug!("Building composites from {:?}", source);
+    build_event.write(BuildCompositesEvent { source });
+}
+
+/// Handler for building composite glyphs from recipes
+fn handle_build_composites(
+    mut events: EventReader<BuildCompositesEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!(
+                "Composite build requested but FontIR state is not available"
+            );
+            return;
+        };
+
+        let recipes = match &event.source {
+            RecipeSource::File(path) => {
+                let recipes = std::fs::read_to_string(path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|text| parse_recipes(&text));
+                match recipes {
+                    Ok(recipes) => recipes,
+                    Err(e) => {
+                        error!(
+                            "Failed to read composite recipes from {}: {}",
+                            path.display(),
+                            e
+                        );
+                        continue;
+                    }
+                }
+            }
+            RecipeSource::UnicodeDecomposition => unicode_recipes()
+                .into_iter()
+                .filter(|recipe| {
+                    !fontir_state.has_glyph(&recipe.glyph_name)
+                        && recipe.components.iter().all(|component| {
+                            fontir_state.has_glyph(&component.glyph_name)
+                        })
+                })
+                .collect(),
+        };
+
+        let built = recipes
+            .iter()
+            .filter(|recipe| build_composite_glyph(fontir_state, recipe))
+            .count();
+        info!("Built {} composite glyph(s)", built);
+        if built > 0 {
+            app_state_changed.write(AppStateChanged);
+        }
+    }
+}
+
+/// Build a recipe in every master of its first base glyph
+///
+/// Returns whether the glyph was built.
+fn build_composite_glyph(
+    fontir_state: &mut FontIRAppState,
+    recipe: &GlyphRecipe,
+) -> bool {
+    let Some(first) = recipe.components.first() else {
+        return false;
+    };
+
+    let mut instances = Vec::new();
+    for location in fontir_state.master_locations(&first.glyph_name) {
+        let built = build_composite(recipe, |glyph_name| {
+            let width = fontir_state.glyph_width_at(glyph_name, &location)?;
+            Some((width, fontir_state.glyph_anchors_at(glyph_name, &location)))
+        });
+        match built {
+            Ok(built) => instances.push((location, built)),
+            Err(e) => {
+                warn!("Skipping composite '{}': {}", recipe.glyph_name, e);
+                return false;
+            }
+        }
+    }
+    if instances.is_empty() {
+        warn!(
+            "Skipping composite '{}': no masters found for '{}'",
+            recipe.glyph_name, first.glyph_name
+        );
+        return false;
+    }
+
+    if fontir_state.has_glyph(&recipe.glyph_name) {
+        for (location, built) in instances {
+            let Some(working_copy) = fontir_state
+                .get_or_create_working_copy_at(&recipe.glyph_name, &location)
+            else {
+                continue;
+            };
+            working_copy.components = built.components;
+            working_copy.width = built.width;
+            // Anchors the designer placed are kept
+            if working_copy.anchors.is_empty() {
+                working_copy.anchors = built.anchors;
+            }
+            working_copy.is_dirty = true;
+        }
+        // The recipe's codepoints are added to any the glyph already has
+        let mut codepoints = fontir_state.glyph_codepoints(&recipe.glyph_name);
+        if recipe.codepoints.iter().any(|c| !codepoints.contains(c)) {
+            codepoints.extend(&recipe.codepoints);
+            if let Err(e) = fontir_state
+                .set_glyph_codepoints(&recipe.glyph_name, codepoints)
+            {
+                warn!(
+                    "Cannot set codepoints of '{}': {}",
+                    recipe.glyph_name, e
+                );
+            }
+        }
+        info!("Rebuilt composite glyph '{}'", recipe.glyph_name);
+    } else {
+        let instances = instances
+            .into_iter()
+            .map(|(location, built)| {
+                let instance = EditableGlyphInstance {
+                    width: built.width,
+                    height: None,
+                    vertical_origin: None,
+                    contours: Vec::new(),
+                    implied_on_curves: Vec::new(),
+                    components: built.components,
+                    anchors: built.anchors,
+                    guidelines: Vec::new(),
+                    image: None,
+                    is_dirty: true,
+                };
+                (location, instance)
+            })
+            .collect();
+        fontir_state.add_glyph(
+            &recipe.glyph_name,
+            recipe.codepoints.clone(),
+            instances,
+        );
+    }
+    true
+}
+
+/// Handler for outline check quick fixes
+fn handle_fix_outline(
+    mut events: EventReader<FixOutlineEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    let mut changed = false;
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Outline fix requested but FontIR state is not available");
+            return;
+        };
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&event.glyph_name)
+        else {
+            continue;
+        };
+
+        let fixed = match &event.fix {
+            OutlineFix::Problem(problem) => {
+                usize::from(fix_problem(&mut working_copy.contours, problem))
+            }
+            OutlineFix::All => fix_all(&mut working_copy.contours),
+        };
+        if fixed == 0 {
+            debug!(
+                "Outline fix: nothing left to fix in glyph '{}'",
+                event.glyph_name
+            );
+            continue;
+        }
+        working_copy.is_dirty = true;
+        changed = true;
+        info!(
+            "Applied {} outline fix(es) to glyph '{}'",
+            fixed, event.glyph_name
+        );
+    }
+
+    if changed {
+        app_state_changed.write(AppStateChanged);
+    }
+}
+
+/// Handler for moving the editing location
+///
+/// Sorts take the advance widths of their glyphs at the new location and
+/// redraw.
+fn handle_set_location(
+    mut events: EventReader<SetLocationEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut text_editor_state: ResMut<TextEditorState>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    let Some(fontir_state) = fontir_state.as_mut() else {
+        events.clear();
+        return;
+    };
+    let mut moved = false;
+    for event in events.read() {
+        let previous = fontir_state.current_location.clone();
+        fontir_state.set_current_axis_values(&event.axis_values);
+        moved |= fontir_state.current_location != previous;
+    }
+    if !moved {
+        return;
+    }
+
+    debug!(
+        "Moved to designspace location {:?}",
+        fontir_state.current_location
+    );
+    text_editor_state.update_advance_widths(|glyph_name| {
+        fontir_state.get_glyph_advance_width(glyph_name)
+    });
+    visual_update.needs_update = true;
+    app_state_changed.write(AppStateChanged);
+}
+
+/// System to handle keyboard shortcuts for the background layer
+///
+/// Command+Alt+B (macOS) or Ctrl+Alt+B (Windows/Linux) copies the glyph
+/// into its background, Command+Alt+J / Ctrl+Alt+J swaps the two and
+/// Command+Alt+K / Ctrl+Alt+K starts or stops editing the background.
+pub fn handle_background_layer_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut background_event: EventWriter<BackgroundLayerEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+    if !modifier_pressed || !alt_pressed || shift_pressed {
+        return;
+    }
+
+    let action = if keyboard.just_pressed(KeyCode::KeyB) {
+        BackgroundAction::CopyToBackground
+    } else if keyboard.just_pressed(KeyCode::KeyJ) {
+        BackgroundAction::Swap
+    } else if keyboard.just_pressed(KeyCode::KeyK) {
+        BackgroundAction::ToggleEdit
+    } else {
+        return;
+    };
+    debug!("Background layer: {:?}", action);
+    background_event.write(BackgroundLayerEvent { action });
+}
+
+/// Handler for background layer commands on the current glyph
+fn handle_background_layer(
+    mut events: EventReader<BackgroundLayerEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Background layer command but FontIR state is not available");
+            return;
+        };
+        let Some(glyph_name) = fontir_state.current_glyph.clone() else {
+            continue;
+        };
+
+        let result = match event.action {
+            BackgroundAction::CopyToBackground => {
+                fontir_state.copy_to_background(&glyph_name)
+            }
+            BackgroundAction::Swap => fontir_state.swap_background(&glyph_name),
+            BackgroundAction::ToggleEdit => {
+                fontir_state.toggle_edit_background(&glyph_name)
+            }
+        };
+        if let Err(e) = result {
+            warn!("Background layer of '{}': {}", glyph_name, e);
+            continue;
+        }
+        info!(
+            "Background layer of '{}': {:?}{}",
+            glyph_name,
+            event.action,
+            if fontir_state.is_editing_background(&glyph_name) {
+                " (editing the background)"
+            } else {
+                ""
+            }
+        );
+        visual_update.needs_update = true;
+        app_state_changed.write(AppStateChanged);
+    }
+}
+
+/// Stops editing a background once another glyph or location is current,
+/// so the foreground is back in place before it is edited or saved
+fn stop_editing_background_on_change(
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    let Some(fontir_state) = fontir_state.as_mut() else {
+        return;
+    };
+    let Some((glyph_name, _)) = fontir_state.editing_background.as_ref() else {
+        return;
+    };
+    let still_current = fontir_state.current_glyph.as_ref() == Some(glyph_name)
+        && fontir_state.is_editing_background(glyph_name);
+    if still_current {
+        return;
+    }
+    if let Err(e) = fontir_state.stop_editing_background() {
+        warn!("Failed to stop editing the background: {}", e);
+    }
+    visual_update.needs_update = true;
+    app_state_changed.write(AppStateChanged);
+}
+
+/// Handler for tracing bitmaps into glyph contours, which starts the
+/// tracing in the background, see `AutotraceTasks`
+fn handle_autotrace(
+    mut events: EventReader<AutotraceEvent>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    settings: Res<BezySettings>,
+    mut autotrace_tasks: ResMut<AutotraceTasks>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_ref() else {
+            warn!("Autotrace requested but FontIR state is not available");
+            return;
+        };
+        let glyph_name = &event.glyph_name;
+        if autotrace_tasks.is_tracing(glyph_name) {
+            warn!("Already tracing into '{}'", glyph_name);
+            continue;
+        }
+        let (bytes, placement) =
+            match trace_source(fontir_state, glyph_name, &event.source) {
+                Ok(source) => source,
+                Err(e) => {
+                    warn!("Cannot autotrace for '{}': {}", glyph_name, e);
+                    continue;
+                }
+            };
+        let params = settings.autotrace.params();
+        let task = AsyncComputeTaskPool::get()
+            .spawn(async move { trace_image(&bytes, placement, &params) });
+        info!("Tracing into glyph '{}'...", glyph_name);
+        autotrace_tasks.tasks.push((glyph_name.clone(), task));
+    }
+}
+
+/// Adds the contours of finished autotraces to their glyphs
+fn apply_finished_autotraces(
+    mut autotrace_tasks: ResMut<AutotraceTasks>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+    mut app_state_changed: EventWriter<AppStateChanged>,
+) {
+    if autotrace_tasks.tasks.is_empty() {
+        return;
+    }
+    let mut finished = Vec::new();
+    autotrace_tasks.tasks.retain_mut(|(glyph_name, task)| {
+        match block_on(future::poll_once(task)) {
+            Some(result) => {
+                finished.push((std::mem::take(glyph_name), result));
+                false
+            }
+            None => true,
+        }
+    });
+
+    for (glyph_name, result) in finished {
+        let contours = match result {
+            Ok(contours) if contours.is_empty() => {
+                warn!("Autotrace found nothing to trace for '{}'", glyph_name);
+                continue;
+            }
+            Ok(contours) => contours,
+            Err(e) => {
+                warn!("Cannot autotrace for '{}': {}", glyph_name, e);
+                continue;
+            }
+        };
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        let Some(working_copy) =
+            fontir_state.get_or_create_working_copy(&glyph_name)
+        else {
+            warn!("'{}' can't be edited here", glyph_name);
+            continue;
+        };
+        let count = contours.len();
+        working_copy.contours.extend(contours);
+        working_copy.is_dirty = true;
+        info!("Traced {} contours into glyph '{}'", count, glyph_name);
+        visual_update.needs_update = true;
+        app_state_changed.write(AppStateChanged);
+    }
+}
+
+/// Read the image file an autotrace works on, with where it is placed in
+/// the glyph
+fn trace_source(
+    fontir_state: &FontIRAppState,
+    glyph_name: &str,
+    source: &TraceSource,
+) -> anyhow::Result<(Arc<Vec<u8>>, kurbo::Affine)> {
+    match source {
+        TraceSource::ReferenceImage => {
+            let (image, _, data) =
+                fontir_state.glyph_image_file(glyph_name).ok_or_else(|| {
+                    anyhow::anyhow!("no reference image at this master")
+                })?;
+            Ok((data, image.affine()))
+        }
+        TraceSource::File(path) => {
+            let bytes = std::fs::read(path).map_err(|e| {
+                anyhow::anyhow!("cannot read {}: {e}", path.display())
+            })?;
+            let (_, height) = image_size(&bytes)?;
+            let placement =
+                fontir_state.image_spanning_font(String::new(), height);
+            Ok((Arc::new(bytes), placement.affine()))
+        }
+    }
+}
+
+/// Decode an image file and trace it in the glyph's coordinates
+fn trace_image(
+    bytes: &[u8],
+    placement: kurbo::Affine,
+    params: &TraceParams,
+) -> anyhow::Result<Vec<kurbo::BezPath>> {
+    let bitmap = grayscale_image(&decode_image(bytes)?);
+    Ok(trace_bitmap(
+        bitmap.as_raw(),
+        bitmap.width() as usize,
+        bitmap.height() as usize,
+        placement,
+        params,
+    ))
+}
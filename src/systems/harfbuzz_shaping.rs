@@ -8,15 +8,36 @@
 //!    - TODO items for proper implementation  
 //!    - Testing procedures
 //!    - Integration notes
+//!
+//! Text buffers can also be shown through the variable font compiled from
+//! the saved sources, at axis coordinates of their own, see
+//! `SortBuffer::variations`. Their text is shaped at those coordinates,
+//! feature variations included, and their inactive sorts are drawn with the
+//! compiled outlines, so they show what the binary will do. The font is
+//! compiled in the background; until it is ready the buffers show the
+//! masters, or the font compiled before the last save.
 
 use crate::core::state::fontir_app_state::FontIRAppState;
+use crate::core::state::text_editor::buffer::{BufferId, SortKind};
 use crate::core::state::{SortLayoutMode, TextEditorState};
+use crate::rendering::unified_glyph_editing::SortVisualUpdateTracker;
+use crate::systems::text_editor_sorts::sort_entities::BufferSortIndex;
 use crate::systems::text_shaping::{ShapedGlyph, ShapedText, TextDirection};
+use crate::ui::panes::file_pane::FileInfo;
 use bevy::prelude::*;
-use harfrust::{Direction, FontRef, GlyphBuffer, Language, Script, ShaperData, ShaperInstance, UnicodeBuffer};
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use harfrust::{
+    Direction, FontRef, GlyphBuffer, Language, Script, ShaperData,
+    ShaperInstance, Tag, UnicodeBuffer, Variation,
+};
+use kurbo::BezPath;
+use skrifa::instance::Size;
+use skrifa::outline::{DrawSettings, OutlinePen};
+use skrifa::{GlyphId, MetadataProvider};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::SystemTime;
 use tempfile::TempDir;
 
 impl From<TextDirection> for Direction {
@@ -35,8 +56,6 @@ impl From<TextDirection> for Direction {
 pub struct HarfBuzzShapingCache {
     /// Temporary directory for compiled fonts
     temp_dir: Option<TempDir>,
-    /// Path to last compiled font binary
-    compiled_font_path: Option<PathBuf>,
     /// Last compilation timestamp for cache invalidation
     last_compiled: Option<std::time::Instant>,
     /// Whether HarfBuzz is available for use (future feature)
@@ -44,20 +63,82 @@ pub struct HarfBuzzShapingCache {
     harfbuzz_available: bool,
     /// Cache of shaped text results
     shaped_cache: HashMap<String, ShapedText>,
+    /// Variable font compiled from the saved sources for text buffers shown
+    /// through it
+    variable_font: Option<CompiledVariableFont>,
+    /// Source path and save time the variable font was compiled for, also
+    /// set when compiling failed so it isn't retried until the next save
+    variable_font_source: Option<(PathBuf, Option<SystemTime>)>,
+    /// Compilation running in the background, with the source path and
+    /// save time it compiles
+    variable_font_task: Option<(VariableFontSource, VariableFontTask)>,
+}
+
+/// Source path and save time of the sources a variable font is compiled
+/// from
+type VariableFontSource = (PathBuf, Option<SystemTime>);
+
+/// Background compilation of the variable font
+type VariableFontTask = Task<Result<Vec<u8>, String>>;
+
+/// A compiled variable font with the shaping data harfrust builds for it,
+/// which is kept rather than rebuilt for every run of text
+pub struct CompiledVariableFont {
+    bytes: Vec<u8>,
+    shaper_data: ShaperData,
+}
+
+impl CompiledVariableFont {
+    pub fn new(bytes: Vec<u8>) -> Result<Self, String> {
+        let font_ref = FontRef::from_index(&bytes, 0).map_err(|e| {
+            format!("Failed to create harfrust FontRef: {:?}", e)
+        })?;
+        let shaper_data = ShaperData::new(&font_ref);
+        Ok(Self { bytes, shaper_data })
+    }
 }
 
 impl Default for HarfBuzzShapingCache {
     fn default() -> Self {
         Self {
             temp_dir: None,
-            compiled_font_path: None,
             last_compiled: None,
             harfbuzz_available: false, // Will be set to true when HarfBuzz works
             shaped_cache: HashMap::new(),
+            variable_font: None,
+            variable_font_source: None,
+            variable_font_task: None,
         }
     }
 }
 
+/// Sorts of the text buffers shown through the compiled variable font, see
+/// `SortBuffer::variations`
+#[derive(Resource, Default)]
+pub struct VariableFontPreview {
+    /// What each previewed sort shows, by buffer index
+    pub glyphs: HashMap<usize, PreviewGlyph>,
+    /// Why the variable font couldn't be compiled, if it couldn't
+    pub error: Option<String>,
+    /// Whether the variable font is being compiled
+    pub compiling: bool,
+}
+
+/// What a sort shows through the compiled variable font: the glyphs shaped
+/// for its character, none when it was joined into a ligature
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreviewGlyph {
+    /// Outlines of the shaped glyphs, positioned from the sort's origin
+    pub paths: Vec<BezPath>,
+    /// Total advance of the shaped glyphs
+    pub advance_width: f32,
+}
+
+/// What a sort entity shows through the compiled variable font, kept in
+/// step with `VariableFontPreview`
+#[derive(Component, Clone, Debug)]
+pub struct VariableFontGlyph(pub PreviewGlyph);
+
 /// Get font bytes for HarfBuzz shaping (using existing TTF file for now)
 pub fn compile_font_for_shaping(
     _fontir_state: &FontIRAppState,
@@ -75,6 +156,154 @@ pub fn compile_font_for_shaping(
     Ok(font_bytes)
 }
 
+/// Compile the saved sources into a variable font with fontc, keeping the
+/// source glyph names
+///
+/// Takes a while for larger fonts, so it runs on the async compute pool,
+/// see `preview_variable_font_buffers`.
+pub fn compile_variable_font(
+    source_path: &Path,
+    build_dir: &Path,
+) -> Result<Vec<u8>, String> {
+    info!("🔨 Compiling variable font for text buffer previews...");
+    let input = fontc::Input::new(source_path)
+        .map_err(|e| format!("Failed to create fontc input: {}", e))?;
+    let mut flags = fontc::Flags::default();
+    flags.remove(fontc::Flags::PRODUCTION_NAMES);
+    let font_bytes =
+        fontc::generate_font(&input, build_dir, None, flags, false)
+            .map_err(|e| format!("Variable font compilation failed: {}", e))?;
+    info!("✅ Compiled variable font ({} bytes)", font_bytes.len());
+    Ok(font_bytes)
+}
+
+/// Pen collecting a compiled glyph outline into a path, offset by the
+/// glyph's position
+struct BezPathPen {
+    path: BezPath,
+    offset: kurbo::Vec2,
+}
+
+impl BezPathPen {
+    fn point(&self, x: f32, y: f32) -> kurbo::Point {
+        kurbo::Point::new(f64::from(x), f64::from(y)) + self.offset
+    }
+}
+
+impl OutlinePen for BezPathPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.path.move_to(self.point(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.path.line_to(self.point(x, y));
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        self.path.quad_to(self.point(cx0, cy0), self.point(x, y));
+    }
+
+    fn curve_to(
+        &mut self,
+        cx0: f32,
+        cy0: f32,
+        cx1: f32,
+        cy1: f32,
+        x: f32,
+        y: f32,
+    ) {
+        self.path.curve_to(
+            self.point(cx0, cy0),
+            self.point(cx1, cy1),
+            self.point(x, y),
+        );
+    }
+
+    fn close(&mut self) {
+        self.path.close_path();
+    }
+}
+
+/// Shape a run of characters through a font at user-space axis coordinates
+/// and draw what each character shows
+///
+/// Glyphs go to the character their cluster starts at; the other
+/// characters of a ligature show nothing.
+pub fn shape_run_with_variations(
+    text: &[char],
+    direction: TextDirection,
+    font: &CompiledVariableFont,
+    variations: &[(String, f32)],
+) -> Result<Vec<PreviewGlyph>, String> {
+    let font_bytes = font.bytes.as_slice();
+    let font_ref = FontRef::from_index(font_bytes, 0)
+        .map_err(|e| format!("Failed to create harfrust FontRef: {:?}", e))?;
+    let shaper_variations: Vec<Variation> = variations
+        .iter()
+        .filter_map(|(tag, value)| {
+            Some(Variation {
+                tag: Tag::new_checked(tag.as_bytes()).ok()?,
+                value: *value,
+            })
+        })
+        .collect();
+    let shaper_instance =
+        ShaperInstance::from_variations(&font_ref, &shaper_variations);
+    let shaper = font
+        .shaper_data
+        .shaper(&font_ref)
+        .instance(Some(&shaper_instance))
+        .build();
+
+    // Each character is its own cluster, so glyphs map back to sorts
+    let mut buffer = UnicodeBuffer::new();
+    for (cluster, ch) in text.iter().enumerate() {
+        buffer.add(*ch, cluster as u32);
+    }
+    buffer.set_direction(direction.into());
+    buffer.guess_segment_properties();
+    let glyph_buffer = shaper.shape(buffer, &[]);
+
+    let font = skrifa::FontRef::new(font_bytes)
+        .map_err(|e| format!("Failed to read compiled font: {:?}", e))?;
+    let location = font
+        .axes()
+        .location(variations.iter().map(|(tag, value)| (tag.as_str(), *value)));
+    let outlines = font.outline_glyphs();
+
+    let mut glyphs = vec![PreviewGlyph::default(); text.len()];
+    for (info, position) in glyph_buffer
+        .glyph_infos()
+        .iter()
+        .zip(glyph_buffer.glyph_positions())
+    {
+        let Some(glyph) = glyphs.get_mut(info.cluster as usize) else {
+            continue;
+        };
+        let mut pen = BezPathPen {
+            path: BezPath::new(),
+            offset: kurbo::Vec2::new(
+                f64::from(glyph.advance_width + position.x_offset as f32),
+                f64::from(position.y_offset),
+            ),
+        };
+        if let Some(outline) = outlines.get(GlyphId::new(info.glyph_id)) {
+            let settings = DrawSettings::unhinted(Size::unscaled(), &location);
+            if let Err(e) = outline.draw(settings, &mut pen) {
+                warn!(
+                    "Failed to draw compiled glyph {}: {:?}",
+                    info.glyph_id, e
+                );
+            }
+        }
+        if !pen.path.elements().is_empty() {
+            glyph.paths.push(pen.path);
+        }
+        glyph.advance_width += position.x_advance as f32;
+    }
+    Ok(glyphs)
+}
+
 /// Shape text using HarfBuzz with compiled font
 pub fn shape_text_with_harfbuzz(
     text: &str,
@@ -287,6 +516,11 @@ pub fn harfbuzz_shaping_system(
     let mut run_direction = TextDirection::LeftToRight;
     
     for (i, entry) in text_editor_state.buffer.iter().enumerate() {
+        // Buffers shown through the compiled variable font are shaped by
+        // `preview_variable_font_buffers`
+        if text_editor_state.buffer.is_variable_font_sort(i) {
+            continue;
+        }
         match &entry.kind {
             crate::core::state::text_editor::buffer::SortKind::Glyph { codepoint: Some(ch), .. } => {
                 if current_run.is_empty() {
@@ -402,6 +636,248 @@ pub fn harfbuzz_shaping_system(
     }
 }
 
+/// Shapes and draws the text buffers shown through the compiled variable
+/// font at their axis coordinates
+///
+/// The font is compiled from the saved sources in the background when a
+/// buffer is first shown through it, and again after each save; the task is
+/// polled every frame until it is done. Sorts typed by glyph name alone
+/// aren't in the font's character map, so they keep showing the masters.
+pub fn preview_variable_font_buffers(
+    mut text_editor_state: ResMut<TextEditorState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    file_info: Res<FileInfo>,
+    mut hb_cache: ResMut<HarfBuzzShapingCache>,
+    mut preview: ResMut<VariableFontPreview>,
+) {
+    let Some(fontir_state) = fontir_state else {
+        return;
+    };
+    let compiled = poll_variable_font_task(&mut hb_cache);
+    if !compiled
+        && !text_editor_state.is_changed()
+        && !fontir_state.is_changed()
+        && !file_info.is_changed()
+    {
+        return;
+    }
+
+    let buffers: Vec<(BufferId, Vec<(String, f32)>)> = text_editor_state
+        .buffer
+        .variable_font_buffers()
+        .map(|(buffer_id, variations)| (buffer_id, variations.to_vec()))
+        .collect();
+
+    let mut glyphs = HashMap::new();
+    let mut error = None;
+    if !buffers.is_empty() {
+        let source = (fontir_state.source_path.clone(), file_info.last_saved);
+        let compiling_source = hb_cache
+            .variable_font_task
+            .as_ref()
+            .is_some_and(|(task_source, _)| *task_source == source);
+        if hb_cache.variable_font_source.as_ref() != Some(&source)
+            && !compiling_source
+        {
+            spawn_variable_font_task(&mut hb_cache, source);
+        }
+
+        match hb_cache.variable_font.as_ref() {
+            Some(font) => {
+                for (buffer_id, variations) in &buffers {
+                    shape_buffer(
+                        &text_editor_state,
+                        *buffer_id,
+                        font,
+                        variations,
+                        &mut glyphs,
+                    );
+                }
+            }
+            None if hb_cache.variable_font_task.is_some() => {}
+            None => {
+                error = Some(
+                    "The variable font could not be compiled, see the log"
+                        .to_string(),
+                );
+            }
+        }
+    }
+    let compiling = hb_cache.variable_font_task.is_some();
+
+    // Sorts take the advance of what they show; sorts no longer shown
+    // through the variable font go back to their masters' advance
+    let previous: Vec<usize> = preview.glyphs.keys().copied().collect();
+    let mut widths: Vec<(usize, f32)> = glyphs
+        .iter()
+        .map(|(index, glyph)| (*index, glyph.advance_width))
+        .collect();
+    for index in previous {
+        if glyphs.contains_key(&index) {
+            continue;
+        }
+        if let Some(entry) = text_editor_state.buffer.get(index) {
+            if let SortKind::Glyph { glyph_name, .. } = &entry.kind {
+                widths.push((
+                    index,
+                    fontir_state.get_glyph_advance_width(glyph_name),
+                ));
+            }
+        }
+    }
+    for (index, width) in widths {
+        let unchanged = matches!(
+            text_editor_state.buffer.get(index).map(|entry| &entry.kind),
+            Some(SortKind::Glyph { advance_width, .. })
+                if *advance_width == width
+        );
+        if unchanged {
+            continue;
+        }
+        if let Some(entry) = text_editor_state.buffer.get_mut(index) {
+            if let SortKind::Glyph { advance_width, .. } = &mut entry.kind {
+                *advance_width = width;
+            }
+        }
+    }
+
+    if preview.glyphs != glyphs
+        || preview.error != error
+        || preview.compiling != compiling
+    {
+        preview.glyphs = glyphs;
+        preview.error = error;
+        preview.compiling = compiling;
+    }
+}
+
+/// Start compiling the variable font for a source in the background,
+/// replacing any compilation already running
+fn spawn_variable_font_task(
+    hb_cache: &mut HarfBuzzShapingCache,
+    source: VariableFontSource,
+) {
+    if hb_cache.temp_dir.is_none() {
+        match TempDir::new() {
+            Ok(temp_dir) => hb_cache.temp_dir = Some(temp_dir),
+            Err(e) => {
+                error!("Failed to create build directory: {}", e);
+                hb_cache.variable_font = None;
+                hb_cache.variable_font_source = Some(source);
+                return;
+            }
+        }
+    }
+    let Some(build_dir) =
+        hb_cache.temp_dir.as_ref().map(|dir| dir.path().to_path_buf())
+    else {
+        return;
+    };
+    let source_path = source.0.clone();
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { compile_variable_font(&source_path, &build_dir) });
+    hb_cache.variable_font_task = Some((source, task));
+}
+
+/// Take the variable font from the background compilation once it is done,
+/// returning whether it just finished
+fn poll_variable_font_task(hb_cache: &mut HarfBuzzShapingCache) -> bool {
+    let Some((_, task)) = hb_cache.variable_font_task.as_mut() else {
+        return false;
+    };
+    let Some(result) = block_on(future::poll_once(task)) else {
+        return false;
+    };
+    let Some((source, _)) = hb_cache.variable_font_task.take() else {
+        return false;
+    };
+    let variable_font = result.and_then(CompiledVariableFont::new);
+    hb_cache.variable_font = match variable_font {
+        Ok(font) => {
+            hb_cache.last_compiled = Some(std::time::Instant::now());
+            Some(font)
+        }
+        Err(e) => {
+            error!("{}", e);
+            None
+        }
+    };
+    hb_cache.variable_font_source = Some(source);
+    true
+}
+
+/// Gives the sort entities of previewed sorts what they show through the
+/// compiled variable font, and takes it from the others
+pub fn sync_variable_font_glyphs(
+    mut commands: Commands,
+    preview: Res<VariableFontPreview>,
+    sort_query: Query<(Entity, &BufferSortIndex, Option<&VariableFontGlyph>)>,
+    added_sorts: Query<(), Added<BufferSortIndex>>,
+    mut visual_update: ResMut<SortVisualUpdateTracker>,
+) {
+    if !preview.is_changed() && added_sorts.is_empty() {
+        return;
+    }
+    for (entity, index, shown) in sort_query.iter() {
+        match (preview.glyphs.get(&index.0), shown) {
+            (Some(glyph), Some(VariableFontGlyph(old))) if glyph == old => {}
+            (Some(glyph), _) => {
+                commands
+                    .entity(entity)
+                    .insert(VariableFontGlyph(glyph.clone()));
+                visual_update.needs_update = true;
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<VariableFontGlyph>();
+                visual_update.needs_update = true;
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Shape the runs of a text buffer between line breaks and sorts typed by
+/// glyph name
+fn shape_buffer(
+    text_editor_state: &TextEditorState,
+    buffer_id: BufferId,
+    font: &CompiledVariableFont,
+    variations: &[(String, f32)],
+    glyphs: &mut HashMap<usize, PreviewGlyph>,
+) {
+    let sorts = text_editor_state.get_sorts_for_buffer(buffer_id);
+    let direction = match sorts.first().map(|(_, entry)| &entry.layout_mode) {
+        Some(SortLayoutMode::RTLText) => TextDirection::RightToLeft,
+        _ => TextDirection::LeftToRight,
+    };
+
+    let mut runs: Vec<Vec<(usize, char)>> = vec![Vec::new()];
+    for (index, entry) in sorts {
+        match entry.kind.codepoint() {
+            Some(ch) => {
+                if let Some(run) = runs.last_mut() {
+                    run.push((index, ch));
+                }
+            }
+            None => runs.push(Vec::new()),
+        }
+    }
+
+    for run in runs.into_iter().filter(|run| !run.is_empty()) {
+        let text: Vec<char> = run.iter().map(|(_, ch)| *ch).collect();
+        let shaped =
+            shape_run_with_variations(&text, direction, font, variations);
+        match shaped {
+            Ok(shaped) => {
+                glyphs.extend(run.iter().map(|(index, _)| *index).zip(shaped));
+            }
+            Err(e) => {
+                error!("🔤 HarfBuzz: Variable font shaping failed: {}", e)
+            }
+        }
+    }
+}
+
 // HACK: Test function to figure out correct glyph IDs
 #[allow(dead_code)]
 fn test_arabic_shaping() {
@@ -416,6 +892,16 @@ pub struct HarfBuzzShapingPlugin;
 impl Plugin for HarfBuzzShapingPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<HarfBuzzShapingCache>()
-            .add_systems(Update, harfbuzz_shaping_system.in_set(crate::editing::FontEditorSets::TextBuffer));
+            .init_resource::<VariableFontPreview>()
+            .add_systems(
+                Update,
+                (
+                    harfbuzz_shaping_system,
+                    preview_variable_font_buffers,
+                    sync_variable_font_glyphs,
+                )
+                    .chain()
+                    .in_set(crate::editing::FontEditorSets::TextBuffer),
+            );
     }
 }
\ No newline at end of file
@@ -197,18 +197,8 @@ impl PenInputConsumer {
                 let location = fontir_state.current_location.clone();
                 let key = (current_glyph_name.clone(), location);
 
-                // Get or create a working copy
-                let working_copy_exists = fontir_state.working_copies.contains_key(&key);
-                
-                if !working_copy_exists {
-                    // Create working copy from original FontIR data
-                    if let Some(fontir_glyph) = fontir_state.glyph_cache.get(&current_glyph_name) {
-                        if let Some((_location, instance)) = fontir_glyph.sources().iter().next() {
-                            let working_copy = crate::core::state::fontir_app_state::EditableGlyphInstance::from(instance);
-                            fontir_state.working_copies.insert(key.clone(), working_copy);
-                        }
-                    }
-                }
+                // Get or create a working copy, unless between masters
+                fontir_state.get_or_create_working_copy(&current_glyph_name);
 
                 // Add the new contour to the working copy
                 if let Some(working_copy) = fontir_state.working_copies.get_mut(&key) {
@@ -9,6 +9,7 @@
 #![allow(unused_imports)]
 
 pub mod arabic_shaping;
+pub mod checks;
 pub mod commands;
 pub mod fontir_lifecycle;
 pub mod harfbuzz_shaping;
@@ -23,6 +24,7 @@ pub mod ui_interaction;
 
 // Re-export commonly used items
 pub use arabic_shaping::ArabicShapingPlugin;
+pub use checks::ChecksPlugin;
 pub use commands::CommandsPlugin;
 pub use fontir_lifecycle::load_fontir_font;
 pub use harfbuzz_shaping::HarfBuzzShapingPlugin;
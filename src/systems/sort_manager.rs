@@ -284,12 +284,7 @@ pub fn spawn_point_entities_for_sort(
             for (contour_idx, contour) in outline.contours.iter().enumerate() {
                 for (point_idx, point_data) in contour.points.iter().enumerate()
                 {
-                    let is_on_curve = matches!(
-                        point_data.point_type,
-                        crate::core::state::PointTypeData::Move
-                            | crate::core::state::PointTypeData::Line
-                            | crate::core::state::PointTypeData::Curve
-                    );
+                    let is_on_curve = point_data.point_type.is_on_curve();
 
                     // Calculate world position: sort position + point offset
                     let point_pos = transform.translation.truncate()
@@ -142,6 +142,12 @@ pub fn unicode_to_glyph_name_fontir(
     unicode_char: char,
     fontir_state: &FontIRAppState,
 ) -> Option<String> {
+    // Glyphs created in the editor carry their codepoints explicitly
+    if let Some(glyph_name) = fontir_state.new_glyph_for_codepoint(unicode_char)
+    {
+        return Some(glyph_name.to_string());
+    }
+
     // Get all available glyph names
     let glyph_names = fontir_state.get_glyph_names();
 
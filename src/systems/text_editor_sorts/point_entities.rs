@@ -3,9 +3,10 @@
 use crate::core::state::font_data::PointTypeData;
 use crate::core::state::{AppState, FontIRAppState};
 use crate::editing::selection::components::{
-    GlyphPointReference, PointType, Selectable,
+    GlyphPointReference, PointType, Selectable, Selected,
 };
 use crate::editing::sort::{ActiveSort, InactiveSort, Sort};
+use crate::geometry::bezpath_editing::extract_editable_points;
 use crate::geometry::design_space::DPoint;
 use crate::geometry::point::EditPoint;
 use crate::systems::sort_manager::SortPointEntity;
@@ -133,12 +134,7 @@ pub fn spawn_active_sort_points_optimized(
 
                             // Create PointType component
                             let point_type_component = PointType {
-                                is_on_curve: matches!(
-                                    point_type,
-                                    PointTypeData::Move
-                                        | PointTypeData::Line
-                                        | PointTypeData::Curve
-                                ),
+                                is_on_curve: point_type.is_on_curve(),
                             };
 
                             // Spawn the point entity
@@ -224,12 +220,7 @@ pub fn spawn_active_sort_points_optimized(
                             };
 
                             let point_type_component = PointType {
-                                is_on_curve: matches!(
-                                    point.point_type,
-                                    PointTypeData::Move
-                                        | PointTypeData::Line
-                                        | PointTypeData::Curve
-                                ),
+                                is_on_curve: point.point_type.is_on_curve(),
                             };
 
                             commands.spawn((
@@ -360,12 +351,7 @@ pub fn regenerate_points_on_fontir_change(
 
                             // Create PointType component
                             let point_type_component = PointType {
-                                is_on_curve: matches!(
-                                    point_type_data,
-                                    PointTypeData::Move
-                                        | PointTypeData::Line
-                                        | PointTypeData::Curve
-                                ),
+                                is_on_curve: point_type_data.is_on_curve(),
                             };
 
                             commands.spawn((
@@ -476,6 +462,62 @@ pub fn detect_sort_glyph_changes(
     }
 }
 
+/// System to keep implied on-curve points of quadratic contours in place
+///
+/// Implied on-curve points sit halfway between two quadratic control points.
+/// Dragging a control point re-centres them in the FontIR working copy, so
+/// this copies their new positions onto the point entities.
+#[allow(clippy::type_complexity)]
+pub fn sync_implied_on_curve_points(
+    fontir_app_state: Option<Res<FontIRAppState>>,
+    sort_query: Query<&Transform, (With<Sort>, Without<GlyphPointReference>)>,
+    mut point_query: Query<
+        (&GlyphPointReference, &SortPointEntity, &mut Transform),
+        (Without<Selected>, Without<Sort>),
+    >,
+) {
+    let Some(fontir_state) = fontir_app_state else {
+        return;
+    };
+    if !fontir_state.is_changed() {
+        return;
+    }
+
+    for (point_ref, sort_point, mut transform) in point_query.iter_mut() {
+        // Only working copies are edited, and they carry the implied flags
+        let key = (
+            point_ref.glyph_name.clone(),
+            fontir_state.current_location.clone(),
+        );
+        let Some(working_copy) = fontir_state.working_copies.get(&key) else {
+            continue;
+        };
+        let Some(path) = working_copy.contours.get(point_ref.contour_index)
+        else {
+            continue;
+        };
+        let implied = working_copy
+            .implied_on_curves
+            .get(point_ref.contour_index)
+            .and_then(|flags| flags.get(point_ref.point_index));
+        if implied != Some(&true) {
+            continue;
+        }
+        let Some(point) =
+            extract_editable_points(path).get(point_ref.point_index).cloned()
+        else {
+            continue;
+        };
+        let Ok(sort_transform) = sort_query.get(sort_point.sort_entity) else {
+            continue;
+        };
+
+        let sort_position = sort_transform.translation.truncate();
+        transform.translation.x = sort_position.x + point.position.x as f32;
+        transform.translation.y = sort_position.y + point.position.y as f32;
+    }
+}
+
 /// Extract all points (on-curve and off-curve) from a kurbo PathEl
 fn extract_points_from_path_element(
     element: &PathEl,
@@ -490,7 +532,7 @@ fn extract_points_from_path_element(
         ],
         PathEl::QuadTo(c, pt) => vec![
             (*c, PointTypeData::OffCurve), // Control point
-            (*pt, PointTypeData::Curve),   // End point
+            (*pt, PointTypeData::QCurve),  // End point
         ],
         PathEl::ClosePath => vec![], // ClosePath creates implicit line back to start - handled by unified rendering
     }
@@ -382,6 +382,73 @@ pub fn update_buffer_sort_positions(
     }
 }
 
+/// Position combining mark sorts on their base sorts using anchors
+///
+/// Runs after the text flow layout so that marks are placed relative to
+/// where their base ended up. Anchors are read from the working copies, so
+/// dragging an anchor in the active sort moves the attached marks live.
+/// The active sort keeps its flow position so that its editable points stay
+/// in sync with it.
+pub fn attach_mark_sorts(
+    text_editor_state: Res<TextEditorState>,
+    fontir_app_state: Option<Res<crate::core::state::FontIRAppState>>,
+    buffer_entities: Res<BufferSortEntities>,
+    added_sorts: Query<(), Added<BufferSortIndex>>,
+    mut sort_query: Query<
+        (&mut Transform, Has<ActiveSort>),
+        With<BufferSortIndex>,
+    >,
+    mut visual_update: ResMut<
+        crate::rendering::unified_glyph_editing::SortVisualUpdateTracker,
+    >,
+) {
+    let Some(fontir_state) = fontir_app_state else {
+        return;
+    };
+    if !text_editor_state.is_changed()
+        && !fontir_state.is_changed()
+        && added_sorts.is_empty()
+    {
+        return;
+    }
+
+    let attachments = text_editor_state
+        .mark_attachments(|glyph_name| fontir_state.glyph_anchors(glyph_name));
+
+    // Attachments are in buffer order, so a mark stacked on another mark is
+    // placed after the mark it sits on. Marks shown through the compiled
+    // variable font are positioned by shaping instead.
+    for attachment in attachments {
+        if text_editor_state
+            .buffer
+            .is_variable_font_sort(attachment.mark_index)
+        {
+            continue;
+        }
+        let (Some(&base_entity), Some(&mark_entity)) = (
+            buffer_entities.entities.get(&attachment.base_index),
+            buffer_entities.entities.get(&attachment.mark_index),
+        ) else {
+            continue;
+        };
+        let Ok((base_transform, _)) = sort_query.get(base_entity) else {
+            continue;
+        };
+        let target = base_transform.translation.truncate() + attachment.offset;
+
+        if let Ok((mut mark_transform, is_active)) =
+            sort_query.get_mut(mark_entity)
+        {
+            if is_active || mark_transform.translation.truncate() == target {
+                continue;
+            }
+            mark_transform.translation.x = target.x;
+            mark_transform.translation.y = target.y;
+            visual_update.needs_update = true;
+        }
+    }
+}
+
 /// System to auto-activate sorts when exactly one is selected
 pub fn auto_activate_selected_sorts(
     mut commands: Commands,
@@ -6,6 +6,12 @@ pub struct UiHoverState {
     pub is_hovering_ui: bool,
 }
 
+/// Marks a pane whose area counts as hovered UI, keeping clicks over it off
+/// the canvas
+#[derive(Component, Default)]
+#[require(Interaction)]
+pub struct BlocksCanvas;
+
 /// Plugin to add UI hover detection systems
 pub struct UiInteractionPlugin;
 
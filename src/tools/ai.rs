@@ -48,7 +48,7 @@ impl AiOperation {
     pub fn description(&self) -> &'static str {
         match self {
             AiOperation::Kerning => "Automatically adjust character spacing for optimal readability",
-            AiOperation::LanguageSupport => "Expand font support for additional languages and scripts", 
+            AiOperation::LanguageSupport => "Check language coverage and add missing glyphs", 
             AiOperation::OpticalAdjustment => "Make optical corrections to improve visual harmony",
             AiOperation::WeightFix => "Fix weight inconsistencies across characters",
             AiOperation::CurveSmoothing => "Optimize curve smoothness and mathematical precision",
@@ -74,6 +74,11 @@ pub struct CurrentAiOperation(pub AiOperation);
 #[derive(Resource, Default)]
 pub struct AiModeActive(pub bool);
 
+/// Event sent by the Language Support operation to show or hide the
+/// language support report
+#[derive(Event)]
+pub struct ToggleLanguageSupportEvent;
+
 /// The AI tool implementation
 pub struct AiTool;
 
@@ -110,6 +115,7 @@ impl Plugin for AiToolPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<AiModeActive>()
             .init_resource::<CurrentAiOperation>()
+            .add_event::<ToggleLanguageSupportEvent>()
             .add_systems(PostStartup, spawn_ai_submenu)
             .add_systems(
                 Update,
@@ -336,6 +342,7 @@ pub fn execute_ai_operations(
     current_tool: Res<crate::ui::toolbars::edit_mode_toolbar::CurrentTool>,
     current_operation: Res<CurrentAiOperation>,
     ai_mode_active: Res<AiModeActive>,
+    mut language_support_events: EventWriter<ToggleLanguageSupportEvent>,
 ) {
     if !ai_mode_active.0 || current_tool.get_current() != Some("ai") {
         return;
@@ -350,9 +357,10 @@ pub fn execute_ai_operations(
                 info!("   [PLACEHOLDER] This feature will be implemented later");
             }
             AiOperation::LanguageSupport => {
-                info!("🤖 Executing Language Support Expansion...");
-                info!("   Analyzing missing glyphs for target languages");
-                info!("   [PLACEHOLDER] This feature will be implemented later");
+                // Coverage is checked offline against the bundled
+                // character sets, see `data::charsets`
+                info!("🤖 Toggling language support report");
+                language_support_events.write(ToggleLanguageSupportEvent);
             }
             AiOperation::OpticalAdjustment => {
                 info!("🤖 Executing Optical Corrections...");
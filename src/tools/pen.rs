@@ -13,6 +13,7 @@ use crate::core::io::input::{helpers, InputEvent, InputMode, InputState};
 use crate::core::io::pointer::PointerInfo;
 use crate::core::state::{AppState, ContourData, PointData, PointTypeData};
 use crate::editing::selection::events::AppStateChanged;
+use crate::editing::snapping::SnapState;
 use crate::geometry::design_space::DPoint;
 use crate::rendering::camera_responsive::CameraResponsiveScale;
 use crate::systems::ui_interaction::UiHoverState;
@@ -185,6 +186,7 @@ pub fn handle_pen_mouse_events(
     pointer_info: Res<PointerInfo>,
     ui_hover_state: Res<UiHoverState>,
     settings: Res<crate::core::settings::BezySettings>,
+    mut snap_state: ResMut<SnapState>,
 ) {
     // Check if pen tool is active via multiple methods
     let pen_is_active = pen_mode_active.as_ref().is_some_and(|p| p.0) 
@@ -208,12 +210,13 @@ pub fn handle_pen_mouse_events(
     if mouse_button_input.just_pressed(MouseButton::Left) {
         let raw_position = pointer_info.design.to_raw();
         
-        // Calculate final position with grid snap and axis locking
+        // Calculate final position with snapping and axis locking
         let final_position = calculate_final_position(
             raw_position,
             &keyboard_input,
             &pen_state,
             &settings,
+            &mut snap_state,
         );
         let final_dpoint = DPoint::new(final_position.x, final_position.y);
         
@@ -295,6 +298,7 @@ pub fn render_pen_preview(
     pointer_info: Res<PointerInfo>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     settings: Res<crate::core::settings::BezySettings>,
+    mut snap_state: ResMut<SnapState>,
     camera_scale: Res<CameraResponsiveScale>,
     existing_preview_query: Query<Entity, With<PenPreviewElement>>,
     theme: Res<crate::ui::themes::CurrentTheme>,
@@ -326,12 +330,13 @@ pub fn render_pen_preview(
     // Use same method as click handler: to_raw()
     let cursor_pos = pointer_info.design.to_raw();
     
-    // Calculate final position with grid snap and axis locking for closure check
+    // Calculate final position with snapping and axis locking for closure check
     let final_position_for_closure = calculate_final_position(
         cursor_pos,
         &keyboard_input,
         &pen_state,
         &settings,
+        &mut snap_state,
     );
     
     let hovering_start_point = if pen_state.current_path.len() > 2 {
@@ -496,18 +501,8 @@ fn finalize_fontir_path(
             let location = fontir_state.current_location.clone();
             let key = (current_glyph_name.clone(), location);
 
-            // Get or create a working copy
-            let working_copy_exists = fontir_state.working_copies.contains_key(&key);
-            
-            if !working_copy_exists {
-                // Create working copy from original FontIR data
-                if let Some(fontir_glyph) = fontir_state.glyph_cache.get(&current_glyph_name) {
-                    if let Some((_location, instance)) = fontir_glyph.sources().iter().next() {
-                        let working_copy = crate::core::state::fontir_app_state::EditableGlyphInstance::from(instance);
-                        fontir_state.working_copies.insert(key.clone(), working_copy);
-                    }
-                }
-            }
+            // Get or create a working copy, unless between masters
+            fontir_state.get_or_create_working_copy(&current_glyph_name);
 
             // Add the new contour to the working copy
             if let Some(working_copy) = fontir_state.working_copies.get_mut(&key) {
@@ -761,28 +756,30 @@ fn spawn_pen_closure_indicator(
 // HELPER FUNCTIONS (ported from pen_full.rs)
 // ================================================================
 
-/// Calculate the final position after applying snap-to-grid and axis locking
+/// Calculate the final position after applying snapping and axis locking
+///
+/// Axis locking takes over from snapping to targets, which would pull the
+/// position off the locked axis.
 fn calculate_final_position(
     cursor_pos: Vec2,
     keyboard: &Res<ButtonInput<KeyCode>>,
     pen_state: &PenToolState,
     settings: &crate::core::settings::BezySettings,
+    snap_state: &mut SnapState,
 ) -> Vec2 {
-    // Apply snap to grid first
-    let snapped_pos = settings.apply_grid_snap(cursor_pos);
-
     // Apply axis locking if shift is held and we have points
     let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
         || keyboard.pressed(KeyCode::ShiftRight);
 
     if shift_pressed && !pen_state.current_path.is_empty() {
+        let snapped_pos = settings.apply_grid_snap(cursor_pos);
         if let Some(last_point) = pen_state.current_path.last() {
             axis_lock_position(snapped_pos, last_point.to_raw())
         } else {
             snapped_pos
         }
     } else {
-        snapped_pos
+        snap_state.snap_and_highlight(cursor_pos, settings)
     }
 }
 
@@ -5,10 +5,18 @@
 
 use bevy::prelude::*;
 use bevy::window::{PrimaryWindow, Window};
-use crate::core::state::fontir_app_state::FontIRAppState;
+use crate::core::state::fontir_app_state::{
+    EditableGlyphInstance, FontIRAppState,
+};
+use crate::core::state::{
+    AnchorData, ComponentData, FontInfo, GuidelineData, ImageData,
+};
+use crate::data::designspace::load_master_sources;
+use crate::data::glyph_set::{features_reference, GlyphSetChange};
 use crate::ui::panes::file_pane::FileInfo;
 // Note: Removed unused imports - we now preserve original glyph data
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use norad::{Font as NoradFont, designspace::DesignSpaceDocument};
 use kurbo::PathEl;
 
@@ -115,12 +123,17 @@ fn handle_keyboard_shortcuts(
 
 /// Handles save file events
 fn handle_save_file_events(
+    mut commands: Commands,
     mut save_events: EventReader<SaveFileEvent>,
-    fontir_state: Option<Res<FontIRAppState>>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
     mut file_info: ResMut<FileInfo>,
 ) {
     for _event in save_events.read() {
-        if let Some(state) = fontir_state.as_ref() {
+        if let Some(state) = fontir_state.as_mut() {
+            // The foreground goes back in place before it is written
+            if let Err(e) = state.stop_editing_background() {
+                warn!("Failed to stop editing the background: {}", e);
+            }
             match save_font_files(&state.source_path, state) {
                 Ok(saved_paths) => {
                     info!("Successfully saved {} files", saved_paths.len());
@@ -128,6 +141,25 @@ fn handle_save_file_events(
                         info!("  Saved: {}", path.display());
                     }
                     
+                    // Renames and deletions are in the UFOs now and must
+                    // not be replayed on the next save
+                    state.glyph_set_changes.clear();
+                    // Neither are glyphs, font info, guidelines and layers
+                    // written again until they are edited
+                    for working_copy in state.working_copies.values_mut() {
+                        working_copy.is_dirty = false;
+                    }
+                    state.font_info_dirty.clear();
+                    state.font_guidelines_dirty = false;
+                    state.glyph_layers_dirty.clear();
+                    
+                    // New axes and sources change where every master sits,
+                    // and new glyphs are in the sources now, so they are
+                    // loaded from there instead of kept in `new_glyphs`
+                    if state.designspace_dirty || !state.new_glyphs.is_empty() {
+                        reload_font(&mut commands, state);
+                    }
+                    
                     // Update the last saved time in file info
                     file_info.last_saved = Some(std::time::SystemTime::now());
                 }
@@ -169,11 +201,19 @@ fn save_font_files(
     if source_path.extension().and_then(|s| s.to_str()) == Some("designspace") {
         info!("💾 Saving changes to designspace UFO sources...");
         
-        // Parse designspace to get UFO source paths
-        let designspace = DesignSpaceDocument::load(source_path)?;
-        let designspace_dir = source_path.parent().unwrap_or(std::path::Path::new("."));
+        // Resolve each designspace source to its normalized location
+        let mut masters = load_master_sources(source_path)?;
+        
+        // Sparse sources added for intermediate masters aren't in the
+        // designspace on disk yet
+        for new_master in &fontir_state.new_intermediate_masters {
+            let source = &new_master.source;
+            if !masters.iter().any(|master| master.ufo_path == source.ufo_path && master.layer == source.layer) {
+                masters.push(source.clone());
+            }
+        }
         
-        info!("Found {} UFO sources in designspace", designspace.sources.len());
+        info!("Found {} UFO sources in designspace", masters.len());
         
         // Check for modified glyphs in working copies
         let modified_glyphs: Vec<_> = fontir_state.working_copies
@@ -181,63 +221,125 @@ fn save_font_files(
             .filter(|((_glyph_name, _location), working_copy)| working_copy.is_dirty)
             .collect();
             
-        if modified_glyphs.is_empty() {
+        if modified_glyphs.is_empty()
+            && !fontir_state.font_guidelines_dirty
+            && fontir_state.font_info_dirty.is_empty()
+            && fontir_state.glyph_set_changes.is_empty()
+            && !fontir_state.designspace_dirty
+            && fontir_state.glyph_layers_dirty.is_empty()
+        {
             info!("No modified glyphs found - nothing to save");
             return Ok(saved_paths);
         }
         
         info!("Found {} modified glyphs to save", modified_glyphs.len());
         
+        for ((glyph_name, location), _) in &modified_glyphs {
+            if !masters.iter().any(|master| master.matches(location)) {
+                warn!("No source found for edited glyph '{}' at {:?}, skipping", glyph_name, location);
+            }
+        }
+        
+        // Masters stored as layers of one UFO share its glyph set changes
+        // and its other layers
+        let mut changed_ufos = HashSet::new();
+        let mut layered_ufos = HashSet::new();
+        
         // Process each UFO source
-        for source in &designspace.sources {
-            let ufo_path = designspace_dir.join(&source.filename);
+        for master in &masters {
+            // Only the working copies edited at this source's location
+            let source_glyphs: Vec<_> = modified_glyphs
+                .iter()
+                .filter(|((_, location), _)| master.matches(location))
+                .collect();
             
-            // Check if any modified glyphs belong to this UFO source
-            let source_has_changes = modified_glyphs.iter().any(|((_, _location), _)| {
-                // For now, save to first UFO (regular). In a full implementation,
-                // you'd match the location to the correct UFO source
-                ufo_path.file_name() == Some(std::ffi::OsStr::new("bezy-grotesk-regular.ufo"))
-            });
+            let font_guidelines = edited_font_guidelines(fontir_state, &master.ufo_path);
+            let font_info = edited_font_info(fontir_state, &master.ufo_path);
+            let glyph_set_changed = !fontir_state.glyph_set_changes.is_empty()
+                && changed_ufos.insert(master.ufo_path.clone());
+            let layers_changed = has_dirty_layers(fontir_state, &master.ufo_path)
+                && layered_ufos.insert(master.ufo_path.clone());
             
-            if source_has_changes {
-                info!("Saving changes to UFO: {}", ufo_path.display());
-                
-                // Load the UFO
-                let mut ufo_font = NoradFont::load(&ufo_path)?;
+            if source_glyphs.is_empty() && font_guidelines.is_none() && font_info.is_none() && !glyph_set_changed && !layers_changed {
+                continue;
+            }
+            
+            let ufo_path = &master.ufo_path;
+            info!("Saving changes to UFO: {}", ufo_path.display());
+            
+            // Load the UFO
+            let mut ufo_font = NoradFont::load(ufo_path)?;
+            if glyph_set_changed {
+                apply_glyph_set_changes(&mut ufo_font, &fontir_state.glyph_set_changes);
+            }
+            if let Some(font_info) = font_info {
+                font_info.write_to_norad_font_info(&mut ufo_font.font_info);
+            }
+            if let Some(guidelines) = font_guidelines {
+                ufo_font.font_info.guidelines = guidelines;
+            }
+            if layers_changed {
+                write_glyph_layers(&mut ufo_font, ufo_path, fontir_state)?;
+            }
+            write_new_images(&mut ufo_font, ufo_path, fontir_state)?;
+            
+            // Sparse sources keep their outlines in a non-default layer,
+            // which is new for the first intermediate master at a location
+            if let Some(layer_name) = master.layer.as_deref() {
+                let is_new_layer = source_glyphs.iter().any(|((glyph_name, _), _)| {
+                    fontir_state.is_new_intermediate_master(glyph_name, master)
+                });
+                if is_new_layer && ufo_font.layers.get(layer_name).is_none() {
+                    ufo_font.layers.new_layer(layer_name)?;
+                }
+            }
+            let layer = match master.layer.as_deref() {
+                Some(layer_name) => match ufo_font.layers.get_mut(layer_name) {
+                    Some(layer) => layer,
+                    None => {
+                        warn!("Layer '{}' not found in {}, skipping", layer_name, ufo_path.display());
+                        continue;
+                    }
+                },
+                None => ufo_font.default_layer_mut(),
+            };
+            
+            // Update modified glyphs
+            for ((glyph_name, _location), working_copy) in source_glyphs {
+                info!("  Updating glyph: {}", glyph_name);
                 
-                // Update modified glyphs
-                for ((glyph_name, _location), working_copy) in &modified_glyphs {
-                    if working_copy.is_dirty {
-                        info!("  Updating glyph: {}", glyph_name);
-                        
-                        // Preserve original glyph and only update outline
-                        let layer = ufo_font.default_layer_mut();
-                        if let Some(existing_glyph) = layer.get_glyph_mut(glyph_name) {
-                            // Update only the outline and width, preserve everything else (anchors, unicode, etc.)
-                            existing_glyph.width = working_copy.width;
-                            if let Some(height) = working_copy.height {
-                                existing_glyph.height = height;
-                            }
-                            
-                            // Simple approach: recreate contours from BezPath
-                            // This will lose the original starting point, but it's reliable
-                            existing_glyph.contours.clear();
-                            for bez_path in &working_copy.contours {
-                                let contour = convert_bezpath_to_ufo_contour(bez_path);
-                                existing_glyph.contours.push(contour);
-                            }
-                        } else {
-                            warn!("Glyph {} not found in UFO, skipping update", glyph_name);
-                        }
+                // Preserve original glyph and only update outline
+                if let Some(existing_glyph) = layer.get_glyph_mut(glyph_name.as_str()) {
+                    write_working_copy(existing_glyph, working_copy);
+                } else if let Some(codepoints) = fontir_state.new_glyphs.get(glyph_name.as_str()) {
+                    // Glyphs created in the editor are added to the UFO
+                    let mut glyph = norad::Glyph::new(glyph_name.as_str());
+                    for &codepoint in codepoints {
+                        glyph.codepoints.insert(codepoint);
                     }
+                    write_working_copy(&mut glyph, working_copy);
+                    layer.insert_glyph(glyph);
+                } else if fontir_state.is_new_intermediate_master(glyph_name, master) {
+                    // Intermediate masters are added to their source's layer
+                    let mut glyph = norad::Glyph::new(glyph_name.as_str());
+                    write_working_copy(&mut glyph, working_copy);
+                    layer.insert_glyph(glyph);
+                } else {
+                    warn!("Glyph {} not found in UFO, skipping update", glyph_name);
                 }
-                
-                // Save the modified UFO
-                ufo_font.save(&ufo_path)?;
-                saved_paths.push(ufo_path.clone());
-                
-                info!("✅ Successfully saved UFO: {}", ufo_path.display());
             }
+            
+            // Save the modified UFO
+            ufo_font.save(ufo_path)?;
+            saved_paths.push(ufo_path.clone());
+            
+            info!("✅ Successfully saved UFO: {}", ufo_path.display());
+        }
+        
+        // Written after the UFOs, which were matched to their edits by the
+        // locations in the designspace as loaded
+        if fontir_state.designspace_dirty {
+            saved_paths.extend(write_designspace(source_path, fontir_state)?);
         }
         
     } else if source_path.extension().and_then(|s| s.to_str()) == Some("ufo") {
@@ -250,13 +352,30 @@ fn save_font_files(
             .filter(|((_glyph_name, _location), working_copy)| working_copy.is_dirty)
             .collect();
             
-        if modified_glyphs.is_empty() {
+        let font_guidelines = edited_font_guidelines(fontir_state, source_path);
+        let font_info = edited_font_info(fontir_state, source_path);
+        
+        if modified_glyphs.is_empty()
+            && font_guidelines.is_none()
+            && font_info.is_none()
+            && fontir_state.glyph_set_changes.is_empty()
+            && !has_dirty_layers(fontir_state, source_path)
+        {
             info!("No modified glyphs found - nothing to save");
             return Ok(saved_paths);
         }
         
         // Load the UFO
         let mut ufo_font = NoradFont::load(source_path)?;
+        apply_glyph_set_changes(&mut ufo_font, &fontir_state.glyph_set_changes);
+        if let Some(font_info) = font_info {
+            font_info.write_to_norad_font_info(&mut ufo_font.font_info);
+        }
+        if let Some(guidelines) = font_guidelines {
+            ufo_font.font_info.guidelines = guidelines;
+        }
+        write_glyph_layers(&mut ufo_font, source_path, fontir_state)?;
+        write_new_images(&mut ufo_font, source_path, fontir_state)?;
         
         // Update modified glyphs
         for ((glyph_name, _location), working_copy) in &modified_glyphs {
@@ -265,20 +384,16 @@ fn save_font_files(
                 
                 // Preserve original glyph and only update outline
                 let layer = ufo_font.default_layer_mut();
-                if let Some(existing_glyph) = layer.get_glyph_mut(glyph_name) {
-                    // Update only the outline and width, preserve everything else (anchors, unicode, etc.)
-                    existing_glyph.width = working_copy.width;
-                    if let Some(height) = working_copy.height {
-                        existing_glyph.height = height;
-                    }
-                    
-                    // Simple approach: recreate contours from BezPath
-                    // This will lose the original starting point, but it's reliable
-                    existing_glyph.contours.clear();
-                    for bez_path in &working_copy.contours {
-                        let contour = convert_bezpath_to_ufo_contour(bez_path);
-                        existing_glyph.contours.push(contour);
+                if let Some(existing_glyph) = layer.get_glyph_mut(glyph_name.as_str()) {
+                    write_working_copy(existing_glyph, working_copy);
+                } else if let Some(codepoints) = fontir_state.new_glyphs.get(glyph_name.as_str()) {
+                    // Glyphs created in the editor are added to the UFO
+                    let mut glyph = norad::Glyph::new(glyph_name.as_str());
+                    for &codepoint in codepoints {
+                        glyph.codepoints.insert(codepoint);
                     }
+                    write_working_copy(&mut glyph, working_copy);
+                    layer.insert_glyph(glyph);
                 } else {
                     warn!("Glyph {} not found in UFO, skipping update", glyph_name);
                 }
@@ -295,12 +410,255 @@ fn save_font_files(
     Ok(saved_paths)
 }
 
+/// Writes the designspace document edited in the designspace pane, after
+/// creating the UFOs of the sources added to it
+fn write_designspace(
+    source_path: &Path,
+    fontir_state: &FontIRAppState,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut saved_paths = Vec::new();
+    let Some(document) = fontir_state.designspace.as_ref() else {
+        return Ok(saved_paths);
+    };
+    let designspace_dir = source_path.parent().unwrap_or_else(|| Path::new("."));
+    
+    for source in &document.sources {
+        let ufo_path = designspace_dir.join(&source.filename);
+        let Some(seed) = fontir_state.new_source_seeds.get(&source.filename) else {
+            continue;
+        };
+        if ufo_path.exists() {
+            continue;
+        }
+        let mut ufo_font = NoradFont::load(seed)?;
+        ufo_font.font_info.style_name = source.stylename.clone();
+        ufo_font.save(&ufo_path)?;
+        info!("✅ Created UFO for new source: {}", ufo_path.display());
+        saved_paths.push(ufo_path);
+    }
+    
+    document.save(source_path)?;
+    saved_paths.push(source_path.to_path_buf());
+    info!("✅ Successfully saved designspace: {}", source_path.display());
+    
+    Ok(saved_paths)
+}
+
+/// Reloads the font after its designspace was written, so its new axes,
+/// sources and instances are picked up
+fn reload_font(commands: &mut Commands, fontir_state: &FontIRAppState) {
+    match FontIRAppState::from_path(fontir_state.source_path.clone()) {
+        Ok(mut reloaded) => {
+            reloaded.current_glyph = fontir_state.current_glyph.clone();
+            commands.insert_resource(reloaded);
+            info!("Reloaded {}", fontir_state.source_path.display());
+        }
+        Err(e) => error!("Failed to reload the font after saving: {}", e),
+    }
+}
+
+/// Replay the glyphs renamed and deleted in the editor on a UFO
+///
+/// Runs before the working copies are written, so edited glyphs are found
+/// under their new names.
+fn apply_glyph_set_changes(ufo_font: &mut NoradFont, changes: &[GlyphSetChange]) {
+    for change in changes {
+        if let Err(e) = change.apply_to_ufo(ufo_font) {
+            warn!("Skipping {:?}: {}", change, e);
+            continue;
+        }
+        if let GlyphSetChange::Delete { glyph_name } = change {
+            if features_reference(&ufo_font.features, glyph_name) {
+                warn!("Features still use deleted glyph '{}'", glyph_name);
+            }
+        }
+    }
+}
+
+/// Font info to write into a UFO's fontinfo, if it was edited
+fn edited_font_info<'a>(
+    fontir_state: &'a FontIRAppState,
+    ufo_path: &Path,
+) -> Option<&'a FontInfo> {
+    if !fontir_state.font_info_dirty.contains(ufo_path) {
+        return None;
+    }
+    fontir_state.font_infos.get(ufo_path)
+}
+
+/// Font-level guidelines to write into a UFO's fontinfo, if they were edited
+///
+/// An empty list is written as no guidelines at all.
+fn edited_font_guidelines(
+    fontir_state: &FontIRAppState,
+    ufo_path: &Path,
+) -> Option<Option<Vec<norad::Guideline>>> {
+    if !fontir_state.font_guidelines_dirty {
+        return None;
+    }
+    let guidelines = fontir_state.font_guidelines.get(ufo_path)?;
+    Some((!guidelines.is_empty()).then(|| {
+        guidelines
+            .iter()
+            .map(GuidelineData::to_norad_guideline)
+            .collect()
+    }))
+}
+
+/// Whether glyphs were edited in the non-master layers of a UFO
+fn has_dirty_layers(fontir_state: &FontIRAppState, ufo_path: &Path) -> bool {
+    fontir_state
+        .glyph_layers_dirty
+        .iter()
+        .any(|(path, _, _)| path == ufo_path)
+}
+
+/// Write the glyphs edited in the non-master layers of a UFO, such as
+/// backgrounds, creating layers and glyphs that are new
+///
+/// New glyphs take the default layer glyph's width. Everything else in the
+/// layers is left as loaded.
+fn write_glyph_layers(
+    ufo_font: &mut NoradFont,
+    ufo_path: &Path,
+    fontir_state: &FontIRAppState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(layers) = fontir_state.glyph_layers.get(ufo_path) else {
+        return Ok(());
+    };
+    for (path, layer_name, glyph_name) in &fontir_state.glyph_layers_dirty {
+        if path != ufo_path {
+            continue;
+        }
+        let Some(layer_glyph) = layers
+            .get(layer_name)
+            .and_then(|glyphs| glyphs.get(glyph_name))
+        else {
+            continue;
+        };
+        let width = ufo_font
+            .default_layer()
+            .get_glyph(glyph_name.as_str())
+            .map_or(0.0, |glyph| glyph.width);
+        
+        if ufo_font.layers.get(layer_name).is_none() {
+            if layer_glyph.is_empty() {
+                continue;
+            }
+            ufo_font.layers.new_layer(layer_name)?;
+        }
+        let Some(layer) = ufo_font.layers.get_mut(layer_name) else {
+            continue;
+        };
+        if !layer.contains_glyph(glyph_name) {
+            if layer_glyph.is_empty() {
+                continue;
+            }
+            let mut glyph = norad::Glyph::new(glyph_name.as_str());
+            glyph.width = width;
+            layer.insert_glyph(glyph);
+        }
+        let Some(glyph) = layer.get_glyph_mut(glyph_name.as_str()) else {
+            continue;
+        };
+        glyph.contours = ufo_contours(
+            &layer_glyph.contours,
+            &layer_glyph.implied_on_curves,
+        );
+        glyph.components = layer_glyph
+            .components
+            .iter()
+            .map(ComponentData::to_norad_component)
+            .collect();
+        info!("  Updating glyph: {} in layer {}", glyph_name, layer_name);
+    }
+    Ok(())
+}
+
+/// Copy the reference image files added in the editor into a UFO's
+/// `images` directory, skipping the ones already there
+fn write_new_images(
+    ufo_font: &mut NoradFont,
+    ufo_path: &Path,
+    fontir_state: &FontIRAppState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (path, file_name) in &fontir_state.new_image_files {
+        if path != ufo_path
+            || ufo_font.images.get(Path::new(file_name)).is_some()
+        {
+            continue;
+        }
+        let Some(data) = fontir_state
+            .image_files
+            .get(ufo_path)
+            .and_then(|files| files.get(file_name))
+        else {
+            continue;
+        };
+        ufo_font.images.insert(PathBuf::from(file_name), data.to_vec())?;
+        info!("  Adding image: {}", file_name);
+    }
+    Ok(())
+}
+
+/// Write a working copy's outline, components, anchors, guidelines,
+/// reference image and width into a UFO glyph, preserving everything else
+/// (unicode, lib, etc.)
+fn write_working_copy(
+    glyph: &mut norad::Glyph,
+    working_copy: &EditableGlyphInstance,
+) {
+    glyph.width = working_copy.width;
+    if let Some(height) = working_copy.height {
+        glyph.height = height;
+    }
+
+    // Simple approach: recreate contours from BezPath
+    // This will lose the original starting point, but it's reliable
+    glyph.contours = ufo_contours(
+        &working_copy.contours,
+        &working_copy.implied_on_curves,
+    );
+    glyph.components = working_copy
+        .components
+        .iter()
+        .map(ComponentData::to_norad_component)
+        .collect();
+    glyph.anchors = working_copy
+        .anchors
+        .iter()
+        .map(AnchorData::to_norad_anchor)
+        .collect();
+    glyph.guidelines = working_copy
+        .guidelines
+        .iter()
+        .map(GuidelineData::to_norad_guideline)
+        .collect();
+    glyph.image = working_copy.image.as_ref().map(ImageData::to_norad_image);
+}
+
+/// Convert contours to norad contours, leaving out the on-curve points
+/// flagged as implied
+fn ufo_contours(
+    contours: &[kurbo::BezPath],
+    implied_on_curves: &[Vec<bool>],
+) -> Vec<norad::Contour> {
+    contours
+        .iter()
+        .enumerate()
+        .map(|(index, contour)| {
+            let implied =
+                implied_on_curves.get(index).map_or(&[][..], Vec::as_slice);
+            convert_bezpath_to_ufo_contour(contour, implied)
+        })
+        .collect()
+}
 
 /// Convert BezPath to norad Contour, preserving the starting point
 /// 
 /// The BezPath MoveTo defines where the contour starts. We ensure the UFO contour
 /// starts at the same logical position by organizing points accordingly.
-fn convert_bezpath_to_ufo_contour(bez_path: &kurbo::BezPath) -> norad::Contour {
+fn convert_bezpath_to_ufo_contour(bez_path: &kurbo::BezPath, implied: &[bool]) -> norad::Contour {
     let mut all_points = Vec::new();
     let mut is_closed = false;
     let mut move_to_pos: Option<kurbo::Point> = None;
@@ -315,6 +673,9 @@ fn convert_bezpath_to_ufo_contour(bez_path: &kurbo::BezPath) -> norad::Contour {
         }
     }
     
+    // On-curve points that were implied in the UFO are left out again
+    let mut point_index = 0;
+    
     // Convert elements to points
     for element in elements {
         match element {
@@ -348,12 +709,20 @@ fn convert_bezpath_to_ufo_contour(bez_path: &kurbo::BezPath) -> norad::Contour {
                 all_points.push(norad::ContourPoint::new(
                     cp.x, cp.y, norad::PointType::OffCurve, false, None, None
                 ));
-                all_points.push(norad::ContourPoint::new(
-                    pt.x, pt.y, norad::PointType::QCurve, false, None, None
-                ));
+                if !implied.get(point_index + 1).copied().unwrap_or(false) {
+                    all_points.push(norad::ContourPoint::new(
+                        pt.x, pt.y, norad::PointType::QCurve, false, None, None
+                    ));
+                }
             }
             PathEl::ClosePath => {}
         }
+        point_index += match element {
+            PathEl::MoveTo(_) | PathEl::LineTo(_) => 1,
+            PathEl::QuadTo(..) => 2,
+            PathEl::CurveTo(..) => 3,
+            PathEl::ClosePath => 0,
+        };
     }
     
     // For closed contours, ensure the first point corresponds to the MoveTo position
@@ -424,7 +793,7 @@ mod tests {
         bez_path.line_to(Point::new(0.0, 0.0)); // Explicit line back to start
         bez_path.close_path();
         
-        let contour = convert_bezpath_to_ufo_contour(&bez_path);
+        let contour = convert_bezpath_to_ufo_contour(&bez_path, &[]);
         
         // Should have 4 points
         assert_eq!(contour.points.len(), 4, "Closed square should have 4 points");
@@ -444,7 +813,7 @@ mod tests {
         bez_path.line_to(Point::new(100.0, 100.0));
         // No close_path()
         
-        let contour = convert_bezpath_to_ufo_contour(&bez_path);
+        let contour = convert_bezpath_to_ufo_contour(&bez_path, &[]);
         
         // Should have 3 points (MoveTo + 2 LineTo)
         assert_eq!(contour.points.len(), 3, "Open contour should have 3 points");
@@ -464,7 +833,7 @@ mod tests {
         bez_path.line_to(Point::new(224.0, -16.0));
         bez_path.close_path();
         
-        let contour = convert_bezpath_to_ufo_contour(&bez_path);
+        let contour = convert_bezpath_to_ufo_contour(&bez_path, &[]);
         
         // Should have 4 points: 2 off-curves, 1 curve, 1 line
         assert_eq!(contour.points.len(), 4, "Curve contour should have 4 points");
@@ -495,7 +864,7 @@ mod tests {
         bez_path.line_to(Point::new(32.0, 160.0)); // Explicit line back to start
         bez_path.close_path();
         
-        let contour = convert_bezpath_to_ufo_contour(&bez_path);
+        let contour = convert_bezpath_to_ufo_contour(&bez_path, &[]);
         
         // Should find the point at (32, 160) and start there
         assert_eq!(contour.points.len(), 5); // 2 off-curve + 1 curve + 1 line + 1 line back
@@ -504,6 +873,28 @@ mod tests {
         assert_eq!((contour.points[0].x, contour.points[0].y), (32.0, 160.0));
         assert_eq!(contour.points[0].typ, norad::PointType::Line);
     }
+
+    #[test]
+    fn test_only_flagged_points_written_implied() {
+        // Both quadratic end points sit halfway between their controls
+        let mut bez_path = BezPath::new();
+        bez_path.move_to(Point::new(0.0, 0.0));
+        bez_path.quad_to(Point::new(0.0, 100.0), Point::new(50.0, 100.0));
+        bez_path.quad_to(Point::new(100.0, 100.0), Point::new(100.0, 0.0));
+        bez_path.line_to(Point::new(0.0, 0.0));
+        bez_path.close_path();
+
+        let explicit = convert_bezpath_to_ufo_contour(&bez_path, &[]);
+        assert_eq!(explicit.points.len(), 5, "Unflagged points stay explicit");
+
+        let implied = [false, false, true, false, false, false];
+        let contour = convert_bezpath_to_ufo_contour(&bez_path, &implied);
+        assert_eq!(contour.points.len(), 4, "Flagged point is left implied");
+        assert!(contour
+            .points
+            .iter()
+            .all(|point| (point.x, point.y) != (50.0, 100.0)));
+    }
 }
 
 /// Handles export to TTF events
@@ -0,0 +1,1230 @@
+//! Font overview
+//!
+//! A scrollable grid of every glyph in the font, drawn with filled outlines
+//! from the `GlyphMeshCache`. Command/Ctrl+Shift+F opens and closes it.
+//!
+//! Typing searches by glyph name, character or Unicode value (`U+00E9`,
+//! `uni00E9` or `00E9`); Escape clears the search, or closes the overview
+//! when the search is empty. The buttons in the header filter by script and
+//! category. Glyphs edited since the font was loaded are marked in the top
+//! right corner of their cell. Click a glyph to select it, move the
+//! selection with the arrow keys, and double-click or press Enter to open it
+//! as a new sort.
+//!
+//! Command/Ctrl+N adds a glyph named after the search (a single character
+//! or `U+XXXX` adds a `uniXXXX` glyph encoding it), Command/Ctrl+R renames
+//! the selected glyph, and Command/Ctrl+Backspace deletes it. Deleting a
+//! glyph that composites use as a component asks for a second press.
+//!
+//! The grid lives on its own render layer and is drawn by its own camera,
+//! on top of the design view, so the design camera keeps its position while
+//! the overview is open. Only the rows in view have cells.
+
+use crate::core::state::FontIRAppState;
+use crate::data::unicode::{self, Category, Script};
+use crate::rendering::cameras::DesignCamera;
+use crate::rendering::mesh_cache::GlyphMeshCache;
+use crate::rendering::mesh_utils::create_filled_glyph_mesh;
+use crate::systems::commands::{
+    DeleteGlyphEvent, NewGlyphEvent, OpenGlyphEditorEvent, RenameGlyphEvent,
+};
+use crate::ui::text_field::type_key;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::ecs::component::Tick;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::{ButtonState, InputSystem};
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use bevy::ui::{Display, FocusPolicy, UiTargetCamera};
+use bevy::window::PrimaryWindow;
+use bevy_pancam::PanCam;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Render layer of the overview grid
+const OVERVIEW_RENDER_LAYER: usize = 1;
+
+/// Size of a glyph cell, in pixels
+const CELL_SIZE: f32 = 96.0;
+
+/// Space between cells
+const CELL_GAP: f32 = 4.0;
+
+/// Space around the grid
+const GRID_MARGIN: f32 = 16.0;
+
+/// Height of the header with the search and filters
+const HEADER_HEIGHT: f32 = 56.0;
+
+/// Height of the glyph name at the bottom of a cell
+const LABEL_HEIGHT: f32 = 18.0;
+
+/// Space between a glyph and the edges of its cell
+const GLYPH_PADDING: f32 = 8.0;
+
+/// Size of the marker on edited glyphs
+const DIRTY_MARK_SIZE: f32 = 8.0;
+
+/// Font size of the glyph names
+const LABEL_FONT_SIZE: f32 = 11.0;
+
+/// Font size of the header
+const HEADER_FONT_SIZE: f32 = 16.0;
+
+/// Longest glyph name shown in full in a cell
+const LABEL_MAX_CHARS: usize = 13;
+
+/// Pixels scrolled per line of mouse wheel movement
+const SCROLL_LINE_HEIGHT: f32 = 48.0;
+
+/// Longest time between the clicks of a double click, in seconds
+const DOUBLE_CLICK_SECONDS: f64 = 0.4;
+
+// ============================================================================
+// COMPONENTS, RESOURCES & EVENTS
+// ============================================================================
+
+/// A glyph of the font with what the overview filters it by
+#[derive(Debug, Clone)]
+pub struct OverviewGlyph {
+    pub name: String,
+    pub codepoints: Vec<char>,
+    pub script: Option<Script>,
+    pub category: Option<Category>,
+}
+
+/// State of the font overview
+#[derive(Resource, Default)]
+pub struct FontOverviewState {
+    pub open: bool,
+    /// Search typed into the header
+    pub query: String,
+    pub script: Option<Script>,
+    pub category: Option<Category>,
+    /// Every glyph of the font, in glyph order
+    pub glyphs: Vec<OverviewGlyph>,
+    /// Indices into `glyphs` that pass the search and filters
+    pub shown: Vec<usize>,
+    /// Selected glyph name
+    pub selected: Option<String>,
+    /// Glyph being renamed and the name typed for it so far
+    renaming: Option<(String, String)>,
+    /// Glyph waiting for a second delete press, because composites use it
+    pending_delete: Option<String>,
+    /// Note shown in the header in place of the search, until the next key
+    message: Option<String>,
+    /// Distance scrolled down from the top of the grid, in pixels
+    scroll: f32,
+    /// Cells in view, by position in `shown`
+    cells: HashMap<usize, Entity>,
+    /// Layout the cells were spawned with
+    layout: Option<GridLayout>,
+    /// Whether the cells need respawning
+    cells_stale: bool,
+    /// Position in `shown` and time of the latest click
+    last_click: Option<(usize, f64)>,
+    /// Whether the design camera could pan before the overview opened
+    pancam_was_enabled: bool,
+    /// When the font state last changed as of the cached glyph meshes
+    meshes_built_at: Option<Tick>,
+    fill_material: Handle<ColorMaterial>,
+}
+
+impl FontOverviewState {
+    /// Whether a glyph passes the search and filters
+    fn passes(&self, glyph: &OverviewGlyph) -> bool {
+        let script = self.script.is_none_or(|s| glyph.script == Some(s));
+        let category = self.category.is_none_or(|c| glyph.category == Some(c));
+        script
+            && category
+            && matches_query(&self.query, &glyph.name, &glyph.codepoints)
+    }
+
+    /// Recompute which glyphs pass the search and filters
+    fn apply_filters(&mut self) {
+        self.shown = (0..self.glyphs.len())
+            .filter(|&index| self.passes(&self.glyphs[index]))
+            .collect();
+        self.scroll = 0.0;
+        self.cells_stale = true;
+    }
+
+    /// Position of the selected glyph in `shown`
+    fn selected_position(&self) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        self.shown
+            .iter()
+            .position(|&index| self.glyphs[index].name == *selected)
+    }
+
+    /// Name of the glyph at a position in `shown`
+    fn shown_name(&self, position: usize) -> Option<&str> {
+        let index = *self.shown.get(position)?;
+        Some(self.glyphs[index].name.as_str())
+    }
+}
+
+/// Camera that draws the overview grid and header
+#[derive(Component)]
+pub struct FontOverviewCamera;
+
+/// Root of the overview header, covering the window
+#[derive(Component)]
+pub struct FontOverviewRoot;
+
+/// Search text in the header
+#[derive(Component)]
+pub struct FontOverviewSearchText;
+
+/// Number of glyphs shown, in the header
+#[derive(Component)]
+pub struct FontOverviewCountText;
+
+/// Header button cycling through a filter
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum FontOverviewFilterButton {
+    Script,
+    Category,
+}
+
+/// Label of a filter button
+#[derive(Component)]
+pub struct FontOverviewFilterText(pub FontOverviewFilterButton);
+
+/// Cell of a glyph in the grid
+#[derive(Component)]
+pub struct FontOverviewCell {
+    /// Position in `FontOverviewState::shown`
+    pub position: usize,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct FontOverviewPlugin;
+
+impl Plugin for FontOverviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FontOverviewState>()
+            .add_systems(Startup, spawn_font_overview)
+            // Runs right after keyboard input is collected, so it can take
+            // the keys before any shortcut sees them
+            .add_systems(
+                PreUpdate,
+                handle_font_overview_keys.after(InputSystem),
+            )
+            .add_systems(
+                Update,
+                (
+                    sync_font_overview_open,
+                    handle_font_overview_filter_buttons,
+                    handle_font_overview_scroll,
+                    handle_font_overview_clicks,
+                    update_font_overview_cells,
+                    update_font_overview_header,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// ============================================================================
+// GRID LAYOUT
+// ============================================================================
+
+/// Placement of cells for a window size
+///
+/// Coordinates are in overview world space, with the origin at the window
+/// center when the grid is scrolled to the top.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GridLayout {
+    width: f32,
+    height: f32,
+    columns: usize,
+}
+
+impl GridLayout {
+    fn new(width: f32, height: f32) -> Self {
+        let pitch = CELL_SIZE + CELL_GAP;
+        let columns =
+            ((width - 2.0 * GRID_MARGIN + CELL_GAP) / pitch).floor() as usize;
+        Self {
+            width,
+            height,
+            columns: columns.max(1),
+        }
+    }
+
+    /// Top left corner of the grid
+    fn origin(&self) -> Vec2 {
+        Vec2::new(
+            -self.width / 2.0 + GRID_MARGIN,
+            self.height / 2.0 - HEADER_HEIGHT - GRID_MARGIN,
+        )
+    }
+
+    /// Center of the cell at a position
+    fn cell_center(&self, position: usize) -> Vec2 {
+        let pitch = CELL_SIZE + CELL_GAP;
+        let column = (position % self.columns) as f32;
+        let row = (position / self.columns) as f32;
+        self.origin()
+            + Vec2::new(
+                column * pitch + CELL_SIZE / 2.0,
+                -(row * pitch + CELL_SIZE / 2.0),
+            )
+    }
+
+    /// Position of the cell under a point, if any
+    fn position_at(&self, point: Vec2) -> Option<usize> {
+        let pitch = CELL_SIZE + CELL_GAP;
+        let offset = point - self.origin();
+        let (x, y) = (offset.x, -offset.y);
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let column = (x / pitch) as usize;
+        let row = (y / pitch) as usize;
+        let inside = x % pitch <= CELL_SIZE && y % pitch <= CELL_SIZE;
+        (inside && column < self.columns).then_some(row * self.columns + column)
+    }
+
+    /// Rows at least partly in view at a scroll distance
+    fn visible_rows(&self, scroll: f32) -> Range<usize> {
+        let pitch = CELL_SIZE + CELL_GAP;
+        let first = ((scroll - GRID_MARGIN) / pitch).floor().max(0.0);
+        let last = ((scroll + self.height - HEADER_HEIGHT) / pitch).ceil();
+        first as usize..last.max(0.0) as usize
+    }
+
+    /// Largest scroll distance for a number of cells
+    fn max_scroll(&self, count: usize) -> f32 {
+        let rows = count.div_ceil(self.columns) as f32;
+        let content = 2.0 * GRID_MARGIN + rows * (CELL_SIZE + CELL_GAP);
+        (content - (self.height - HEADER_HEIGHT)).max(0.0)
+    }
+}
+
+/// Whether a glyph matches a search
+///
+/// An empty search matches everything. A single character matches glyphs
+/// that encode it, a Unicode value (`U+00E9`, `uni00E9` or plain hex)
+/// matches glyphs that encode it, and any search matches glyph names that
+/// contain it, ignoring case.
+fn matches_query(query: &str, name: &str, codepoints: &[char]) -> bool {
+    let query = query.trim();
+    if query.is_empty() {
+        return true;
+    }
+
+    let mut chars = query.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return codepoints.contains(&c) || name == query;
+    }
+
+    let hex = query
+        .strip_prefix("U+")
+        .or_else(|| query.strip_prefix("u+"))
+        .or_else(|| query.strip_prefix("uni"))
+        .unwrap_or(query);
+    let encodes = u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .is_some_and(|c| codepoints.contains(&c));
+
+    encodes || name.to_lowercase().contains(&query.to_lowercase())
+}
+
+/// Glyph name shortened to fit in a cell
+fn cell_label(name: &str) -> String {
+    if name.chars().count() <= LABEL_MAX_CHARS {
+        return name.to_string();
+    }
+    let mut label: String = name.chars().take(LABEL_MAX_CHARS - 1).collect();
+    label.push('…');
+    label
+}
+
+/// Name and codepoints of a glyph added from a search
+///
+/// A single character or a `U+XXXX` value gives a glyph encoding it, named
+/// with `unicode::glyph_name_for_char`; other text is taken as the name.
+fn new_glyph_from_query(query: &str) -> (String, Vec<char>) {
+    let query = query.trim();
+    let mut chars = query.chars();
+    let codepoint = match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => query
+            .strip_prefix("U+")
+            .or_else(|| query.strip_prefix("u+"))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32),
+    };
+    match codepoint {
+        Some(c) => (unicode::glyph_name_for_char(c), vec![c]),
+        None if query.is_empty() => ("newGlyph".to_string(), Vec::new()),
+        None => (query.to_string(), Vec::new()),
+    }
+}
+
+/// Glyphs of the font with their scripts and categories
+///
+/// Glyphs without codepoints take the script and category of the glyph
+/// their name is based on, so `a.sc` is grouped with `a`.
+fn overview_glyphs(fontir_state: &FontIRAppState) -> Vec<OverviewGlyph> {
+    let names = fontir_state.get_glyph_names();
+    let codepoints: HashMap<&str, Vec<char>> = names
+        .iter()
+        .map(|name| (name.as_str(), fontir_state.glyph_codepoints(name)))
+        .collect();
+
+    names
+        .iter()
+        .map(|name| {
+            let own = &codepoints[name.as_str()];
+            let base = name.split('.').next().unwrap_or(name);
+            let classified = own
+                .first()
+                .or_else(|| codepoints.get(base).and_then(|base| base.first()));
+            OverviewGlyph {
+                name: name.clone(),
+                codepoints: own.clone(),
+                script: classified.map(|&c| unicode::script(c)),
+                category: classified.map(|&c| unicode::category(c)),
+            }
+        })
+        .collect()
+}
+
+/// Next value of a filter, going through the values present in the font
+/// and back to no filter
+fn next_filter<T: Copy + Ord>(
+    current: Option<T>,
+    values: impl Iterator<Item = T>,
+) -> Option<T> {
+    let mut present: Vec<T> = values.collect();
+    present.sort();
+    present.dedup();
+    match current {
+        None => present.first().copied(),
+        Some(current) => present.into_iter().find(|value| *value > current),
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the overview camera and header, both inactive
+pub fn spawn_font_overview(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut overview_state: ResMut<FontOverviewState>,
+) {
+    overview_state.fill_material =
+        materials.add(ColorMaterial::from_color(FILLED_GLYPH_COLOR));
+
+    let camera = commands
+        .spawn((
+            Camera2d,
+            Camera {
+                // Drawn over the design view while active
+                order: 1,
+                is_active: false,
+                ..default()
+            },
+            RenderLayers::layer(OVERVIEW_RENDER_LAYER),
+            FontOverviewCamera,
+            Name::new("FontOverviewCamera"),
+        ))
+        .id();
+
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: HEADER_FONT_SIZE,
+        ..default()
+    };
+    let theme = theme.theme();
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                display: Display::None,
+                ..default()
+            },
+            // Keeps clicks on the grid away from the design view
+            Interaction::default(),
+            FocusPolicy::Block,
+            UiTargetCamera(camera),
+            FontOverviewRoot,
+            Name::new("FontOverviewRoot"),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    height: Val::Px(HEADER_HEIGHT),
+                    padding: UiRect::horizontal(Val::Px(GRID_MARGIN)),
+                    border: UiRect::bottom(Val::Px(WIDGET_BORDER_WIDTH)),
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(GRID_MARGIN),
+                    ..default()
+                },
+                BackgroundColor(theme.widget_background_color()),
+                BorderColor(theme.widget_border_color()),
+            ))
+            .with_children(|header| {
+                header.spawn((
+                    Node {
+                        flex_grow: 1.0,
+                        ..default()
+                    },
+                    Text::new(""),
+                    text_font.clone(),
+                    TextColor(NORMAL_TEXT_COLOR),
+                    FontOverviewSearchText,
+                ));
+                for button in [
+                    FontOverviewFilterButton::Script,
+                    FontOverviewFilterButton::Category,
+                ] {
+                    header
+                        .spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(
+                                    Val::Px(8.0),
+                                    Val::Px(4.0),
+                                ),
+                                border: UiRect::all(Val::Px(
+                                    WIDGET_BORDER_WIDTH,
+                                )),
+                                ..default()
+                            },
+                            BackgroundColor(theme.normal_button_color()),
+                            BorderColor(theme.normal_button_outline_color()),
+                            button,
+                        ))
+                        .with_children(|button_node| {
+                            button_node.spawn((
+                                Text::new(""),
+                                text_font.clone(),
+                                TextColor(NORMAL_TEXT_COLOR),
+                                FontOverviewFilterText(button),
+                            ));
+                        });
+                }
+                header.spawn((
+                    Text::new(""),
+                    text_font.clone(),
+                    TextColor(SECONDARY_TEXT_COLOR),
+                    FontOverviewCountText,
+                ));
+            });
+        });
+}
+
+/// Spawns the cell of a glyph at a position in the grid
+#[allow(clippy::too_many_arguments)]
+fn spawn_cell(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    mesh_cache: &mut GlyphMeshCache,
+    fontir_state: &FontIRAppState,
+    overview_state: &FontOverviewState,
+    layout: &GridLayout,
+    position: usize,
+    label_font: &TextFont,
+    theme: &CurrentTheme,
+) -> Option<Entity> {
+    let glyph = &overview_state.glyphs[*overview_state.shown.get(position)?];
+    let layer = RenderLayers::layer(OVERVIEW_RENDER_LAYER);
+    let theme = theme.theme();
+
+    // Scale the font's vertical extent into the cell above the label
+    let metrics = fontir_state.get_font_metrics();
+    let ascender = metrics.ascender.unwrap_or(metrics.units_per_em * 0.8);
+    let descender = metrics.descender.unwrap_or(-metrics.units_per_em * 0.2);
+    let scale = (CELL_SIZE - LABEL_HEIGHT - 2.0 * GLYPH_PADDING)
+        / (ascender - descender).max(1.0);
+    let advance_width = fontir_state.get_glyph_advance_width(&glyph.name);
+    let glyph_offset = Vec2::new(
+        -advance_width * scale / 2.0,
+        -CELL_SIZE / 2.0 + LABEL_HEIGHT + GLYPH_PADDING - descender * scale,
+    );
+
+    let mesh = mesh_cache.get_filled_mesh(&glyph.name).or_else(|| {
+        let paths =
+            fontir_state.get_glyph_paths_with_components(&glyph.name)?;
+        let mesh = meshes.add(create_filled_glyph_mesh(&paths)?);
+        mesh_cache.cache_filled_mesh(glyph.name.clone(), mesh.clone());
+        Some(mesh)
+    });
+
+    let mut cell = commands.spawn((
+        Sprite::from_color(
+            theme.widget_background_color(),
+            Vec2::splat(CELL_SIZE),
+        ),
+        Transform::from_translation(layout.cell_center(position).extend(0.0)),
+        layer.clone(),
+        FontOverviewCell { position },
+    ));
+    cell.with_children(|cell| {
+        if let Some(mesh) = mesh {
+            cell.spawn((
+                Mesh2d(mesh),
+                MeshMaterial2d(overview_state.fill_material.clone()),
+                Transform::from_translation(glyph_offset.extend(1.0))
+                    .with_scale(Vec3::new(scale, scale, 1.0)),
+                layer.clone(),
+            ));
+        }
+        cell.spawn((
+            Text2d::new(cell_label(&glyph.name)),
+            label_font.clone(),
+            TextColor(SECONDARY_TEXT_COLOR),
+            Transform::from_xyz(0.0, (LABEL_HEIGHT - CELL_SIZE) / 2.0, 2.0),
+            layer.clone(),
+        ));
+        if fontir_state.is_glyph_dirty(&glyph.name) {
+            let corner = (CELL_SIZE - DIRTY_MARK_SIZE) / 2.0;
+            cell.spawn((
+                Sprite::from_color(
+                    theme.special_color(),
+                    Vec2::splat(DIRTY_MARK_SIZE),
+                ),
+                Transform::from_xyz(corner, corner, 2.0),
+                layer.clone(),
+            ));
+        }
+    });
+    Some(cell.id())
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Handles the overview shortcut, and the search and selection keys while
+/// the overview is open
+///
+/// Takes every key press while open, so that nothing else reacts to the
+/// typing.
+#[allow(clippy::too_many_arguments)]
+fn handle_font_overview_keys(
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut overview_state: ResMut<FontOverviewState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut open_events: EventWriter<OpenGlyphEditorEvent>,
+    mut new_events: EventWriter<NewGlyphEvent>,
+    mut delete_events: EventWriter<DeleteGlyphEvent>,
+    mut rename_events: EventWriter<RenameGlyphEvent>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if modifier_pressed && shift_pressed && keyboard.just_pressed(KeyCode::KeyF)
+    {
+        overview_state.open = !overview_state.open;
+        keyboard_events.clear();
+        keyboard.reset_all();
+        return;
+    }
+    if !overview_state.open {
+        return;
+    }
+
+    let columns = overview_state.layout.map_or(1, |layout| layout.columns);
+    for event in keyboard_events.drain() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        overview_state.message = None;
+        if overview_state.renaming.is_some() {
+            handle_rename_key(
+                &event,
+                modifier_pressed,
+                &mut overview_state,
+                &mut rename_events,
+            );
+            continue;
+        }
+        if modifier_pressed {
+            match event.key_code {
+                KeyCode::KeyN => {
+                    let Some(fontir_state) = fontir_state.as_ref() else {
+                        continue;
+                    };
+                    let (glyph_name, codepoints) =
+                        new_glyph_from_query(&overview_state.query);
+                    let glyph_name =
+                        fontir_state.unique_glyph_name(&glyph_name);
+                    overview_state.query.clear();
+                    overview_state.apply_filters();
+                    overview_state.selected = Some(glyph_name.clone());
+                    new_events.write(NewGlyphEvent {
+                        glyph_name,
+                        codepoints,
+                    });
+                }
+                KeyCode::KeyR => {
+                    if let Some(glyph_name) = overview_state.selected.clone() {
+                        overview_state.renaming =
+                            Some((glyph_name.clone(), glyph_name));
+                    }
+                }
+                KeyCode::Backspace | KeyCode::Delete => {
+                    let Some(glyph_name) = overview_state.selected.clone()
+                    else {
+                        continue;
+                    };
+                    let composites = fontir_state
+                        .as_ref()
+                        .map(|state| state.composites_using(&glyph_name))
+                        .unwrap_or_default();
+                    let confirmed = overview_state.pending_delete.take()
+                        == Some(glyph_name.clone());
+                    if composites.is_empty() || confirmed {
+                        overview_state.selected = None;
+                        delete_events.write(DeleteGlyphEvent { glyph_name });
+                    } else {
+                        overview_state.message = Some(format!(
+                            "{} is used by {}, press again to delete it",
+                            glyph_name,
+                            composites.join(", ")
+                        ));
+                        overview_state.pending_delete = Some(glyph_name);
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        overview_state.pending_delete = None;
+        let step: isize = match &event.logical_key {
+            Key::ArrowLeft => -1,
+            Key::ArrowRight => 1,
+            Key::ArrowUp => -(columns as isize),
+            Key::ArrowDown => columns as isize,
+            _ => 0,
+        };
+        if step != 0 {
+            let last = overview_state.shown.len().saturating_sub(1) as isize;
+            let position = match overview_state.selected_position() {
+                Some(position) => (position as isize + step).clamp(0, last),
+                None => 0,
+            };
+            overview_state.selected = overview_state
+                .shown_name(position as usize)
+                .map(String::from);
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                if let Some(glyph_name) = overview_state.selected.clone() {
+                    open_events.write(OpenGlyphEditorEvent { glyph_name });
+                    overview_state.open = false;
+                }
+            }
+            Key::Escape => {
+                if overview_state.query.is_empty() {
+                    overview_state.open = false;
+                } else {
+                    overview_state.query.clear();
+                    overview_state.apply_filters();
+                }
+            }
+            key => {
+                let query = &mut overview_state.query;
+                if type_key(query, key, modifier_pressed, true) {
+                    overview_state.apply_filters();
+                }
+            }
+        }
+    }
+
+    // Keep the typed keys away from shortcuts and tools
+    keyboard.reset_all();
+}
+
+/// Edits the new name of the glyph being renamed; Enter renames it and
+/// Escape cancels
+fn handle_rename_key(
+    event: &KeyboardInput,
+    modifier_pressed: bool,
+    overview_state: &mut FontOverviewState,
+    rename_events: &mut EventWriter<RenameGlyphEvent>,
+) {
+    let Some((old_name, new_name)) = overview_state.renaming.as_mut() else {
+        return;
+    };
+    match &event.logical_key {
+        Key::Enter => {
+            let (old_name, new_name) = (old_name.clone(), new_name.clone());
+            overview_state.renaming = None;
+            if new_name != old_name {
+                overview_state.selected = Some(new_name.clone());
+                rename_events.write(RenameGlyphEvent { old_name, new_name });
+            }
+        }
+        Key::Escape => overview_state.renaming = None,
+        key => {
+            type_key(new_name, key, modifier_pressed, false);
+        }
+    }
+}
+
+/// Opens or closes the overview to match its state
+#[allow(clippy::type_complexity)]
+fn sync_font_overview_open(
+    mut commands: Commands,
+    mut overview_state: ResMut<FontOverviewState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut mesh_cache: ResMut<GlyphMeshCache>,
+    mut camera_query: Query<
+        (&mut Camera, &mut Transform),
+        With<FontOverviewCamera>,
+    >,
+    mut root_query: Query<&mut Node, With<FontOverviewRoot>>,
+    mut pancam_query: Query<&mut PanCam, With<DesignCamera>>,
+) {
+    let Ok((mut camera, mut camera_transform)) = camera_query.single_mut()
+    else {
+        return;
+    };
+    if camera.is_active == overview_state.open {
+        // Glyphs added, renamed or deleted while open
+        if let Some(fontir_state) = fontir_state
+            .filter(|state| overview_state.open && state.is_changed())
+        {
+            let glyphs = overview_glyphs(&fontir_state);
+            let renamed = glyphs.len() != overview_state.glyphs.len()
+                || glyphs
+                    .iter()
+                    .zip(&overview_state.glyphs)
+                    .any(|(glyph, old)| glyph.name != old.name);
+            if renamed {
+                let scroll = overview_state.scroll;
+                overview_state.glyphs = glyphs;
+                overview_state.apply_filters();
+                overview_state.scroll = scroll;
+            }
+        }
+        return;
+    }
+    camera.is_active = overview_state.open;
+    for mut node in root_query.iter_mut() {
+        node.display = if overview_state.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    if !overview_state.open {
+        for (_, entity) in overview_state.cells.drain() {
+            commands.entity(entity).despawn();
+        }
+        overview_state.layout = None;
+        for mut pancam in pancam_query.iter_mut() {
+            pancam.enabled = overview_state.pancam_was_enabled;
+        }
+        info!("Closed font overview");
+        return;
+    }
+
+    // The design camera would zoom and pan along with the overview
+    for mut pancam in pancam_query.iter_mut() {
+        overview_state.pancam_was_enabled = pancam.enabled;
+        pancam.enabled = false;
+    }
+
+    // Glyph meshes are rebuilt when outlines may have changed since they
+    // were cached
+    if let Some(fontir_state) = fontir_state.as_ref() {
+        let changed_at = fontir_state.last_changed();
+        if overview_state.meshes_built_at != Some(changed_at) {
+            mesh_cache.invalidate_all();
+            overview_state.meshes_built_at = Some(changed_at);
+        }
+        overview_state.glyphs = overview_glyphs(fontir_state);
+    } else {
+        overview_state.glyphs.clear();
+    }
+    overview_state.apply_filters();
+    camera_transform.translation = Vec3::ZERO;
+    info!(
+        "Opened font overview with {} glyphs",
+        overview_state.glyphs.len()
+    );
+}
+
+/// Cycles the script and category filters when their buttons are clicked
+fn handle_font_overview_filter_buttons(
+    mut overview_state: ResMut<FontOverviewState>,
+    button_query: Query<
+        (&Interaction, &FontOverviewFilterButton),
+        Changed<Interaction>,
+    >,
+) {
+    if !overview_state.open {
+        return;
+    }
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            FontOverviewFilterButton::Script => {
+                let scripts = overview_state
+                    .glyphs
+                    .iter()
+                    .filter_map(|glyph| glyph.script)
+                    .collect::<Vec<_>>();
+                overview_state.script =
+                    next_filter(overview_state.script, scripts.into_iter());
+            }
+            FontOverviewFilterButton::Category => {
+                let categories = overview_state
+                    .glyphs
+                    .iter()
+                    .filter_map(|glyph| glyph.category)
+                    .collect::<Vec<_>>();
+                overview_state.category = next_filter(
+                    overview_state.category,
+                    categories.into_iter(),
+                );
+            }
+        }
+        overview_state.apply_filters();
+    }
+}
+
+/// Scrolls the grid with the mouse wheel
+fn handle_font_overview_scroll(
+    mut scroll_events: EventReader<MouseWheel>,
+    mut overview_state: ResMut<FontOverviewState>,
+    mut camera_query: Query<&mut Transform, With<FontOverviewCamera>>,
+) {
+    if !overview_state.open {
+        scroll_events.clear();
+        return;
+    }
+    let delta: f32 = scroll_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    let Some(layout) = overview_state.layout else {
+        return;
+    };
+
+    let max_scroll = layout.max_scroll(overview_state.shown.len());
+    let scroll = (overview_state.scroll - delta).clamp(0.0, max_scroll);
+    if scroll != overview_state.scroll {
+        overview_state.scroll = scroll;
+    }
+    for mut transform in camera_query.iter_mut() {
+        transform.translation.y = -overview_state.scroll;
+    }
+}
+
+/// Selects the clicked glyph, and opens it on a double click
+fn handle_font_overview_clicks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<FontOverviewCamera>>,
+    mut overview_state: ResMut<FontOverviewState>,
+    mut open_events: EventWriter<OpenGlyphEditorEvent>,
+) {
+    if !overview_state.open || !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(layout) = overview_state.layout else {
+        return;
+    };
+    let Some(cursor) = windows.single().ok().and_then(Window::cursor_position)
+    else {
+        return;
+    };
+    if cursor.y < HEADER_HEIGHT {
+        return;
+    }
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        return;
+    };
+    let Ok(point) = camera.viewport_to_world_2d(camera_transform, cursor)
+    else {
+        return;
+    };
+
+    let Some(position) = layout
+        .position_at(point)
+        .filter(|&position| position < overview_state.shown.len())
+    else {
+        overview_state.selected = None;
+        return;
+    };
+    let now = time.elapsed_secs_f64();
+    let double_click =
+        overview_state
+            .last_click
+            .is_some_and(|(last_position, last_time)| {
+                last_position == position
+                    && now - last_time <= DOUBLE_CLICK_SECONDS
+            });
+    overview_state.selected =
+        overview_state.shown_name(position).map(String::from);
+
+    if double_click {
+        overview_state.last_click = None;
+        if let Some(glyph_name) = overview_state.selected.clone() {
+            open_events.write(OpenGlyphEditorEvent { glyph_name });
+            overview_state.open = false;
+        }
+    } else {
+        overview_state.last_click = Some((position, now));
+    }
+}
+
+/// Spawns the cells coming into view and despawns the ones leaving it, and
+/// highlights the selected glyph
+#[allow(clippy::too_many_arguments)]
+fn update_font_overview_cells(
+    mut commands: Commands,
+    mut overview_state: ResMut<FontOverviewState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut mesh_cache: ResMut<GlyphMeshCache>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+    mut cell_query: Query<(&FontOverviewCell, &mut Sprite)>,
+) {
+    if !overview_state.open {
+        return;
+    }
+    let (Some(fontir_state), Ok(window)) = (fontir_state, windows.single())
+    else {
+        return;
+    };
+
+    let layout = GridLayout::new(window.width(), window.height());
+    if overview_state.layout != Some(layout) || overview_state.cells_stale {
+        for (_, entity) in overview_state.cells.drain() {
+            commands.entity(entity).despawn();
+        }
+        overview_state.layout = Some(layout);
+        overview_state.cells_stale = false;
+        let max_scroll = layout.max_scroll(overview_state.shown.len());
+        overview_state.scroll = overview_state.scroll.min(max_scroll);
+    }
+
+    let rows = layout.visible_rows(overview_state.scroll);
+    let in_view = rows.start * layout.columns
+        ..(rows.end * layout.columns).min(overview_state.shown.len());
+
+    let leaving: Vec<usize> = overview_state
+        .cells
+        .keys()
+        .copied()
+        .filter(|position| !in_view.contains(position))
+        .collect();
+    for position in leaving {
+        if let Some(entity) = overview_state.cells.remove(&position) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    let label_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: LABEL_FONT_SIZE,
+        ..default()
+    };
+    let spawned: HashSet<usize> =
+        overview_state.cells.keys().copied().collect();
+    for position in in_view.filter(|position| !spawned.contains(position)) {
+        if let Some(entity) = spawn_cell(
+            &mut commands,
+            &mut meshes,
+            &mut mesh_cache,
+            &fontir_state,
+            &overview_state,
+            &layout,
+            position,
+            &label_font,
+            &theme,
+        ) {
+            overview_state.cells.insert(position, entity);
+        }
+    }
+
+    let selected = overview_state.selected_position();
+    for (cell, mut sprite) in cell_query.iter_mut() {
+        let color = if Some(cell.position) == selected {
+            theme.theme().focus_background_color()
+        } else {
+            theme.theme().widget_background_color()
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
+/// Refreshes the search text, filter labels and glyph count
+#[allow(clippy::type_complexity)]
+fn update_font_overview_header(
+    overview_state: Res<FontOverviewState>,
+    mut search_query: Query<
+        &mut Text,
+        (
+            With<FontOverviewSearchText>,
+            Without<FontOverviewCountText>,
+            Without<FontOverviewFilterText>,
+        ),
+    >,
+    mut count_query: Query<
+        &mut Text,
+        (With<FontOverviewCountText>, Without<FontOverviewFilterText>),
+    >,
+    mut filter_query: Query<(&FontOverviewFilterText, &mut Text)>,
+) {
+    if !overview_state.open || !overview_state.is_changed() {
+        return;
+    }
+
+    let search = if let Some((old_name, new_name)) = &overview_state.renaming {
+        format!("Rename {old_name} to: {new_name}|")
+    } else if let Some(message) = &overview_state.message {
+        message.clone()
+    } else if overview_state.query.is_empty() {
+        "Search glyphs: type a name, character or U+XXXX".to_string()
+    } else {
+        format!("Search: {}|", overview_state.query)
+    };
+    for mut text in search_query.iter_mut() {
+        if text.0 != search {
+            text.0 = search.clone();
+        }
+    }
+
+    let count = format!(
+        "{} of {} glyphs",
+        overview_state.shown.len(),
+        overview_state.glyphs.len()
+    );
+    for mut text in count_query.iter_mut() {
+        if text.0 != count {
+            text.0 = count.clone();
+        }
+    }
+
+    for (filter, mut text) in filter_query.iter_mut() {
+        let label = match filter.0 {
+            FontOverviewFilterButton::Script => match overview_state.script {
+                Some(script) => format!("Script: {script}"),
+                None => "Script: All".to_string(),
+            },
+            FontOverviewFilterButton::Category => {
+                match overview_state.category {
+                    Some(category) => format!("Category: {category}"),
+                    None => "Category: All".to_string(),
+                }
+            }
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_query() {
+        let codepoints = ['\u{00E9}'];
+        assert!(matches_query("", "eacute", &codepoints));
+        assert!(matches_query("acu", "eacute", &codepoints));
+        assert!(matches_query("EACUTE", "eacute", &codepoints));
+        assert!(matches_query("\u{00E9}", "eacute", &codepoints));
+        assert!(matches_query("U+00E9", "eacute", &codepoints));
+        assert!(matches_query("uni00E9", "eacute", &codepoints));
+        assert!(matches_query("e9", "eacute", &codepoints));
+        assert!(!matches_query("e", "eacute", &codepoints));
+        assert!(!matches_query("U+00E8", "eacute", &codepoints));
+        assert!(matches_query("e", "e", &[]));
+    }
+
+    #[test]
+    fn test_new_glyph_from_query() {
+        assert_eq!(new_glyph_from_query(""), ("newGlyph".to_string(), vec![]));
+        assert_eq!(
+            new_glyph_from_query("\u{00E9}"),
+            ("eacute".to_string(), vec!['\u{00E9}'])
+        );
+        assert_eq!(
+            new_glyph_from_query("U+0628"),
+            ("uni0628".to_string(), vec!['\u{0628}'])
+        );
+        assert_eq!(
+            new_glyph_from_query("U+1F600"),
+            ("u1F600".to_string(), vec!['\u{1F600}'])
+        );
+        assert_eq!(
+            new_glyph_from_query(" a.sc "),
+            ("a.sc".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn test_grid_layout() {
+        let layout = GridLayout::new(1024.0, 768.0);
+        assert_eq!(layout.columns, 9);
+
+        // Cell centers map back to their positions, gaps to nothing
+        for position in [0, 1, 8, 9, 40] {
+            let center = layout.cell_center(position);
+            assert_eq!(layout.position_at(center), Some(position));
+        }
+        let gap = layout.cell_center(0) + Vec2::new(CELL_SIZE / 2.0 + 2.0, 0.0);
+        assert_eq!(layout.position_at(gap), None);
+
+        // Rows in view follow the scroll distance
+        assert_eq!(layout.visible_rows(0.0).start, 0);
+        assert!(layout.visible_rows(500.0).start > 0);
+        assert_eq!(layout.max_scroll(9), 0.0);
+        assert!(layout.max_scroll(900) > 0.0);
+    }
+
+    #[test]
+    fn test_next_filter() {
+        let latin = unicode::script('a');
+        let arabic = unicode::script('\u{0628}');
+        let values = [latin, arabic, latin];
+        assert_eq!(next_filter(None, values.into_iter()), Some(arabic));
+        assert_eq!(next_filter(Some(arabic), values.into_iter()), Some(latin));
+        assert_eq!(next_filter(Some(latin), values.into_iter()), None);
+    }
+}
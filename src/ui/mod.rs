@@ -1,9 +1,11 @@
 //! User interface modules for the Bezy font editor
 
 pub mod file_menu;
+pub mod font_overview;
 pub mod hud;
 pub mod panes;
 pub mod text_editor;
+pub mod text_field;
 pub mod theme;
 pub mod themes;
 pub mod toolbars;
@@ -0,0 +1,484 @@
+//! Axes pane
+//!
+//! A slider per designspace axis that moves the editing location anywhere
+//! in the designspace, see `SetLocationEvent`. Between masters, sorts show
+//! their glyphs interpolated at the location, and only masters can be
+//! edited; dragging a slider onto a master's value makes it editable again.
+//! There, a button adds an intermediate master of the current glyph,
+//! starting from its interpolated outline.
+//!
+//! The pane sits at the bottom center of the window while a designspace
+//! with axes is open, and tells when edits are repeated in all masters.
+
+use crate::core::state::FontIRAppState;
+use crate::data::designspace::{location_value, DesignAxis};
+use crate::editing::edit_all_masters::EditAllMasters;
+use crate::systems::commands::SetLocationEvent;
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::{Display, RelativeCursorPosition};
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const AXES_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the axis name column
+const AXIS_NAME_WIDTH: f32 = 110.0;
+
+/// Length of a slider track
+const SLIDER_WIDTH: f32 = 240.0;
+
+/// Height of a slider track
+const SLIDER_HEIGHT: f32 = 6.0;
+
+/// Size of a slider's thumb
+const THUMB_SIZE: f32 = 14.0;
+
+/// Width of the axis value column
+const AXIS_VALUE_WIDTH: f32 = 70.0;
+
+/// Width of the pane's content
+const AXES_PANE_WIDTH: f32 =
+    AXIS_NAME_WIDTH + SLIDER_WIDTH + AXIS_VALUE_WIDTH + 2.0 * WIDGET_PADDING;
+
+/// Smallest normalized change a drag sends on
+const SLIDER_STEP: f64 = 1e-3;
+
+// ============================================================================
+// COMPONENTS
+// ============================================================================
+
+/// Component marker for the axes pane
+#[derive(Component, Default)]
+pub struct AxesPane;
+
+/// Container the axis rows are spawned into
+#[derive(Component)]
+pub struct AxisRows;
+
+/// Tells whether the location is a master or between masters
+#[derive(Component)]
+pub struct AxesStatusText;
+
+/// Adds an intermediate master of the current glyph at the location
+#[derive(Component)]
+pub struct AddIntermediateMasterButton;
+
+/// Label of the intermediate master button
+#[derive(Component)]
+pub struct AddIntermediateMasterText;
+
+/// Slider track of an axis
+#[derive(Component)]
+pub struct AxisSlider {
+    pub index: usize,
+}
+
+/// Thumb of an axis slider
+#[derive(Component)]
+pub struct AxisSliderThumb {
+    pub index: usize,
+}
+
+/// User-space value of an axis at the current location
+#[derive(Component)]
+pub struct AxisValueText {
+    pub index: usize,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct AxesPanePlugin;
+
+impl Plugin for AxesPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_axes_pane).add_systems(
+            Update,
+            (
+                rebuild_axis_rows,
+                handle_axis_sliders,
+                handle_add_intermediate_master,
+                update_axes_pane,
+            )
+                .chain(),
+        );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the axes pane, hidden, at the bottom center of the window; its
+/// rows are added once the font's axes are known
+pub fn spawn_axes_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: AXES_TEXT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(WIDGET_MARGIN),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(
+                    -(AXES_PANE_WIDTH / 2.0
+                        + WIDGET_PADDING
+                        + WIDGET_BORDER_WIDTH),
+                )),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            AxesPane,
+            Name::new("AxesPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                AxesStatusText,
+            ));
+            pane.spawn((
+                Button,
+                Node {
+                    align_self: AlignSelf::FlexStart,
+                    padding: UiRect::horizontal(Val::Px(4.0)),
+                    border: UiRect::all(Val::Px(1.0)),
+                    display: Display::None,
+                    ..default()
+                },
+                BackgroundColor(NORMAL_BUTTON_COLOR),
+                BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+                AddIntermediateMasterButton,
+            ))
+            .with_children(|button| {
+                button.spawn((
+                    Text::new(""),
+                    text_font.clone(),
+                    TextColor(NORMAL_TEXT_COLOR),
+                    AddIntermediateMasterText,
+                ));
+            });
+            pane.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(WIDGET_ROW_LEADING),
+                    ..default()
+                },
+                AxisRows,
+            ));
+        });
+}
+
+/// Spawns the row of an axis: its name, slider and value
+fn spawn_axis_row(
+    rows: &mut ChildSpawnerCommands,
+    index: usize,
+    axis: &DesignAxis,
+    text_font: &TextFont,
+) {
+    rows.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(WIDGET_PADDING),
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            Node {
+                width: Val::Px(AXIS_NAME_WIDTH),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            Text::new(axis.name.clone()),
+            text_font.clone(),
+            TextLayout::new_with_no_wrap(),
+            TextColor(NORMAL_TEXT_COLOR),
+        ));
+        row.spawn((
+            Node {
+                width: Val::Px(SLIDER_WIDTH),
+                height: Val::Px(SLIDER_HEIGHT),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderRadius::all(Val::Px(SLIDER_HEIGHT / 2.0)),
+            Interaction::default(),
+            RelativeCursorPosition::default(),
+            AxisSlider { index },
+        ))
+        .with_children(|track| {
+            track.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(THUMB_SIZE),
+                    height: Val::Px(THUMB_SIZE),
+                    margin: UiRect::left(Val::Px(-THUMB_SIZE / 2.0)),
+                    ..default()
+                },
+                BackgroundColor(NORMAL_TEXT_COLOR),
+                BorderRadius::all(Val::Px(THUMB_SIZE / 2.0)),
+                AxisSliderThumb { index },
+            ));
+        });
+        row.spawn((
+            Node {
+                width: Val::Px(AXIS_VALUE_WIDTH),
+                ..default()
+            },
+            Text::new(""),
+            text_font.clone(),
+            TextColor(SECONDARY_TEXT_COLOR),
+            AxisValueText { index },
+        ));
+    });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Replaces the axis rows when a font with other axes is loaded, and shows
+/// the pane only while there are axes
+fn rebuild_axis_rows(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    rows_query: Query<Entity, With<AxisRows>>,
+    mut pane_query: Query<&mut Node, With<AxesPane>>,
+    mut shown_axes: Local<Vec<DesignAxis>>,
+) {
+    let axes = fontir_state
+        .as_ref()
+        .map(|state| state.axes.clone())
+        .unwrap_or_default();
+    if *shown_axes == axes {
+        return;
+    }
+
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: AXES_TEXT_SIZE,
+        ..default()
+    };
+    for rows in rows_query.iter() {
+        commands
+            .entity(rows)
+            .despawn_related::<Children>()
+            .with_children(|rows| {
+                for (index, axis) in axes.iter().enumerate() {
+                    spawn_axis_row(rows, index, axis, &text_font);
+                }
+            });
+    }
+    for mut node in pane_query.iter_mut() {
+        node.display = if axes.is_empty() {
+            Display::None
+        } else {
+            Display::Flex
+        };
+    }
+    *shown_axes = axes;
+}
+
+/// Moves the location while a slider is pressed or dragged
+fn handle_axis_sliders(
+    fontir_state: Option<Res<FontIRAppState>>,
+    slider_query: Query<(&Interaction, &RelativeCursorPosition, &AxisSlider)>,
+    mut location_events: EventWriter<SetLocationEvent>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    for (interaction, cursor, slider) in slider_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let (Some(axis), Some(cursor)) =
+            (fontir_state.axes.get(slider.index), cursor.normalized)
+        else {
+            continue;
+        };
+
+        // The cursor position runs from 0 at the left of the track to 1 at
+        // the right
+        let fraction = f64::from(cursor.x).clamp(0.0, 1.0);
+        let (minimum, maximum) = axis.normalized_range();
+        let value = minimum + fraction * (maximum - minimum);
+        let current = location_value(&fontir_state.current_location, &axis.tag);
+        if (value - current).abs() < SLIDER_STEP {
+            continue;
+        }
+        location_events.write(SetLocationEvent {
+            axis_values: vec![(axis.tag.clone(), value)],
+        });
+    }
+}
+
+/// Adds an intermediate master of the current glyph when its button is
+/// pressed
+fn handle_add_intermediate_master(
+    button_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<AddIntermediateMasterButton>),
+    >,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let Some(fontir_state) = fontir_state.as_mut() else {
+        return;
+    };
+    let Some(glyph_name) = fontir_state.current_glyph.clone() else {
+        return;
+    };
+    match fontir_state.add_intermediate_master(&glyph_name) {
+        Ok(master) => info!(
+            "Added an intermediate master of '{}' to {}, written on save",
+            glyph_name, master
+        ),
+        Err(e) => warn!("Intermediate master: {}", e),
+    }
+}
+
+/// Moves the thumbs and refreshes the values, status and intermediate
+/// master button after the location changes
+#[allow(clippy::type_complexity)]
+fn update_axes_pane(
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut status_query: Query<&mut Text, With<AxesStatusText>>,
+    mut value_query: Query<
+        (&AxisValueText, &mut Text),
+        (Without<AxesStatusText>, Without<AddIntermediateMasterText>),
+    >,
+    mut thumb_query: Query<(&AxisSliderThumb, &mut Node)>,
+    added_thumbs: Query<(), Added<AxisSliderThumb>>,
+    edit_all_masters: Res<EditAllMasters>,
+    mut button_query: Query<
+        &mut Node,
+        (With<AddIntermediateMasterButton>, Without<AxisSliderThumb>),
+    >,
+    mut button_text_query: Query<
+        &mut Text,
+        (With<AddIntermediateMasterText>, Without<AxesStatusText>),
+    >,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    if !fontir_state.is_changed()
+        && !edit_all_masters.is_changed()
+        && added_thumbs.is_empty()
+    {
+        return;
+    }
+    let location = &fontir_state.current_location;
+    let current_glyph = fontir_state.current_glyph.as_deref();
+    let glyph_master = current_glyph
+        .filter(|glyph| fontir_state.is_glyph_master_at(glyph, location));
+
+    let status = if let (false, Some(glyph)) =
+        (fontir_state.is_at_master(), glyph_master)
+    {
+        format!(
+            "{}, intermediate master of '{}'",
+            fontir_state.master_name_at(location),
+            glyph
+        )
+    } else if !fontir_state.is_at_master() {
+        "Interpolated preview, only masters can be edited".to_string()
+    } else if edit_all_masters.enabled {
+        let moving = if edit_all_masters.move_points {
+            ", moving points"
+        } else {
+            ""
+        };
+        format!(
+            "{}, editing all masters{}",
+            fontir_state.master_name_at(location),
+            moving
+        )
+    } else {
+        fontir_state.master_name_at(location)
+    };
+    for mut text in status_query.iter_mut() {
+        if text.0 != status {
+            text.0.clone_from(&status);
+        }
+    }
+
+    let add_master = current_glyph.filter(|_| {
+        !fontir_state.is_at_master()
+            && glyph_master.is_none()
+            && fontir_state.designspace.is_some()
+    });
+    for mut node in button_query.iter_mut() {
+        node.display = if add_master.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    if let Some(glyph) = add_master {
+        let label = format!("Add an intermediate master of '{glyph}'");
+        for mut text in button_text_query.iter_mut() {
+            if text.0 != label {
+                text.0.clone_from(&label);
+            }
+        }
+    }
+
+    for (value_text, mut text) in value_query.iter_mut() {
+        let Some(axis) = fontir_state.axes.get(value_text.index) else {
+            continue;
+        };
+        let value = axis.user_value(location_value(location, &axis.tag));
+        let value = format!("{} {}", axis.tag, value.round());
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+
+    for (thumb, mut node) in thumb_query.iter_mut() {
+        let Some(axis) = fontir_state.axes.get(thumb.index) else {
+            continue;
+        };
+        let (minimum, maximum) = axis.normalized_range();
+        let fraction = if maximum > minimum {
+            (location_value(location, &axis.tag) - minimum)
+                / (maximum - minimum)
+        } else {
+            0.0
+        };
+        node.left = Val::Percent(100.0 * fraction as f32);
+    }
+}
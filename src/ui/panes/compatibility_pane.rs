@@ -0,0 +1,301 @@
+//! Interpolation compatibility pane
+//!
+//! Lists where the masters of the current glyph, or of every glyph in the
+//! font, differ from the default master in ways that keep them from
+//! interpolating, see `data::compatibility`. Command/Ctrl+Shift+Y shows and
+//! hides the pane. The current glyph is checked again after every edit,
+//! open or not, see `systems::checks`, and the points that differ are
+//! marked on the canvas in the master they are in, along with the matching
+//! point of the default master.
+
+use crate::core::state::{CheckScope, CompatibilityState};
+use crate::systems::checks::run_compatibility_check;
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::Display;
+use std::collections::HashSet;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const COMPATIBILITY_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the issue descriptions
+const ISSUE_WIDTH: f32 = 420.0;
+
+/// Issues listed at once; the rest are counted under the list
+const MAX_ROWS: usize = 12;
+
+// ============================================================================
+// COMPONENTS & RESOURCES
+// ============================================================================
+
+/// Component marker for the compatibility pane
+#[derive(Component, Default)]
+pub struct CompatibilityPane;
+
+/// Component marker for the summary under the title
+#[derive(Component)]
+pub struct CompatibilitySummaryText;
+
+/// Scope buttons along the top of the pane
+#[derive(Component)]
+pub struct CompatibilityScopeButton(pub CheckScope);
+
+/// A line of the issue list
+#[derive(Component)]
+pub struct CompatibilityIssueText {
+    pub index: usize,
+}
+
+/// Component marker for the count of issues not listed
+#[derive(Component)]
+pub struct CompatibilityMoreText;
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct CompatibilityPanePlugin;
+
+impl Plugin for CompatibilityPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_compatibility_pane)
+            .add_systems(
+                Update,
+                (toggle_compatibility_pane, handle_compatibility_clicks)
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                update_compatibility_pane.after(run_compatibility_check),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the compatibility pane, hidden, on the left side of the window
+pub fn spawn_compatibility_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: COMPATIBILITY_TEXT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(25.0),
+                left: Val::Px(WIDGET_MARGIN),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            CompatibilityPane,
+            Name::new("CompatibilityPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Interpolation Compatibility"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+
+            pane.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(WIDGET_PADDING),
+                ..default()
+            })
+            .with_children(|buttons| {
+                for (scope, label) in
+                    [(CheckScope::Glyph, "Glyph"), (CheckScope::Font, "Font")]
+                {
+                    buttons
+                        .spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::horizontal(Val::Px(4.0)),
+                                border: UiRect::all(Val::Px(1.0)),
+                                ..default()
+                            },
+                            BackgroundColor(NORMAL_BUTTON_COLOR),
+                            BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+                            CompatibilityScopeButton(scope),
+                        ))
+                        .with_children(|button| {
+                            button.spawn((
+                                Text::new(label),
+                                text_font.clone(),
+                                TextColor(NORMAL_TEXT_COLOR),
+                            ));
+                        });
+                }
+            });
+
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                CompatibilitySummaryText,
+            ));
+
+            for index in 0..MAX_ROWS {
+                pane.spawn((
+                    Node {
+                        width: Val::Px(ISSUE_WIDTH),
+                        overflow: Overflow::clip(),
+                        display: Display::None,
+                        ..default()
+                    },
+                    Text::new(""),
+                    text_font.clone(),
+                    TextLayout::new_with_no_wrap(),
+                    TextColor(PRESSED_BUTTON_COLOR),
+                    CompatibilityIssueText { index },
+                ));
+            }
+
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                CompatibilityMoreText,
+            ));
+        });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane with Command/Ctrl+Shift+Y
+fn toggle_compatibility_pane(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut compatibility: ResMut<CompatibilityState>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if modifier_pressed && shift_pressed && keyboard.just_pressed(KeyCode::KeyY)
+    {
+        compatibility.visible = !compatibility.visible;
+    }
+}
+
+/// Switches the scope when a scope button is pressed
+fn handle_compatibility_clicks(
+    mut compatibility: ResMut<CompatibilityState>,
+    button_query: Query<
+        (&Interaction, &CompatibilityScopeButton),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction == Interaction::Pressed
+            && compatibility.scope != button.0
+        {
+            compatibility.scope = button.0;
+            compatibility.issues.clear();
+        }
+    }
+}
+
+/// Shows or hides the pane and refreshes the issue list
+#[allow(clippy::type_complexity)]
+fn update_compatibility_pane(
+    compatibility: Res<CompatibilityState>,
+    mut pane_query: Query<&mut Node, With<CompatibilityPane>>,
+    mut button_query: Query<(&CompatibilityScopeButton, &mut BackgroundColor)>,
+    mut issue_query: Query<
+        (&CompatibilityIssueText, &mut Text, &mut Node),
+        Without<CompatibilityPane>,
+    >,
+    mut text_query: ParamSet<(
+        Query<&mut Text, With<CompatibilitySummaryText>>,
+        Query<&mut Text, With<CompatibilityMoreText>>,
+    )>,
+) {
+    if !compatibility.is_changed() {
+        return;
+    }
+    let display = if compatibility.visible {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in pane_query.iter_mut() {
+        node.display = display;
+    }
+    if !compatibility.visible {
+        return;
+    }
+
+    for (button, mut background) in button_query.iter_mut() {
+        background.0 = if button.0 == compatibility.scope {
+            PRESSED_BUTTON_COLOR
+        } else {
+            NORMAL_BUTTON_COLOR
+        };
+    }
+
+    let issues = &compatibility.issues;
+    let glyph_count = issues
+        .iter()
+        .map(|issue| &issue.glyph_name)
+        .collect::<HashSet<_>>()
+        .len();
+    let summary = match (issues.len(), compatibility.scope) {
+        (0, _) => "All masters are compatible".to_string(),
+        (count, CheckScope::Glyph) => format!("{count} issue(s)"),
+        (count, CheckScope::Font) => {
+            format!("{count} issue(s) in {glyph_count} glyph(s)")
+        }
+    };
+    for mut text in text_query.p0().iter_mut() {
+        text.0.clone_from(&summary);
+    }
+    let more = match issues.len().saturating_sub(MAX_ROWS) {
+        0 => String::new(),
+        hidden => format!("and {hidden} more"),
+    };
+    for mut text in text_query.p1().iter_mut() {
+        text.0.clone_from(&more);
+    }
+
+    for (issue_text, mut text, mut node) in issue_query.iter_mut() {
+        let Some(issue) = issues.get(issue_text.index) else {
+            node.display = Display::None;
+            continue;
+        };
+        node.display = Display::Flex;
+        let description =
+            format!("{}: {}", issue.master, issue.issue.description());
+        text.0 = match compatibility.scope {
+            CheckScope::Glyph => description,
+            CheckScope::Font => format!("{}: {description}", issue.glyph_name),
+        };
+    }
+}
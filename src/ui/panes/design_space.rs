@@ -1,19 +1,599 @@
-//! Design space plugin for UI panes
+//! Designspace map
 //!
-//! This plugin provides the DesignSpacePlugin for UI functionality.
-//! The actual coordinate types (DPoint, DVec2) have been moved to geometry::design_space.
+//! Plots the designspace of the open font in the file pane: masters as
+//! filled dots, instances as rings and the current editing location as a
+//! marker. One axis is plotted as a line, two or more as a plane projected
+//! onto the two axes picked with the buttons above the map.
+//!
+//! Clicking a master or instance moves the editing location there, and
+//! pressing anywhere else on the map drags the location, snapping to
+//! masters close to the cursor. Locations move through `SetLocationEvent`,
+//! like the axis sliders.
+//!
+//! The coordinate types (DPoint, DVec2) that used to live here are in
+//! geometry::design_space.
 
+use crate::core::state::FontIRAppState;
+use crate::data::designspace::{location_value, DesignAxis};
+use crate::systems::commands::SetLocationEvent;
+use crate::ui::theme::*;
 use bevy::prelude::*;
+use bevy::ui::{Display, RelativeCursorPosition};
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the map's text
+const MAP_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the map, and height of a two-dimensional map
+const MAP_SIZE: f32 = 160.0;
+
+/// Height of a one-dimensional map
+const MAP_LINE_HEIGHT: f32 = 20.0;
+
+/// Size of master and instance dots
+const POINT_SIZE: f32 = 12.0;
+
+/// Size of the current location marker
+const MARKER_SIZE: f32 = 18.0;
+
+/// Distance in pixels under which a drag snaps onto a master
+const MASTER_SNAP_DISTANCE: f32 = 8.0;
+
+/// Smallest normalized change a drag sends on
+const DRAG_STEP: f64 = 1e-3;
+
+// ============================================================================
+// COMPONENTS & RESOURCES
+// ============================================================================
+
+/// Axes the map is projected onto, as indices into the font's axes
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct DesignSpaceMapState {
+    pub x_axis: usize,
+    /// `None` while the font has a single axis
+    pub y_axis: Option<usize>,
+}
+
+/// Component marker for the map, hidden for fonts without axes
+#[derive(Component)]
+pub struct DesignSpaceMap;
+
+/// The plotted area, pressed to drag the location
+#[derive(Component)]
+pub struct DesignSpacePlot;
+
+/// Marker of the current location
+#[derive(Component)]
+pub struct CurrentLocationMarker;
+
+/// Name of the hovered point, or of the current location
+#[derive(Component)]
+pub struct DesignSpaceLabelText;
+
+/// Picks the axis plotted horizontally or vertically
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum MapAxisButton {
+    X,
+    Y,
+}
 
-/// Plugin for design space UI functionality
-///
-/// This plugin is kept for compatibility with existing UI code.
-/// The coordinate types (DPoint, DVec2) are now available in geometry::design_space.
+/// A master or instance on the map
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct MapPoint {
+    pub name: String,
+    pub is_master: bool,
+    /// Normalized location as (axis tag, value) pairs
+    pub location: Vec<(String, f64)>,
+    /// Position on the map, 0..=1 from the left and from the bottom
+    pub position: Vec2,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+/// Plugin for the designspace map in the file pane
 pub struct DesignSpacePlugin;
 
 impl Plugin for DesignSpacePlugin {
-    fn build(&self, _app: &mut App) {
-        // The ViewPort resource is deprecated and no longer initialized here
-        // Coordinate types are now available via geometry::design_space
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DesignSpaceMapState>().add_systems(
+            Update,
+            (
+                update_map_projection,
+                handle_map_axis_buttons,
+                rebuild_map_points,
+                handle_map_point_clicks,
+                handle_map_drag,
+                update_map_display,
+            )
+                .chain(),
+        );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the map, hidden until a font with axes is loaded; the file pane
+/// spawns it where its master buttons used to be
+pub fn spawn_design_space_map(
+    parent: &mut ChildSpawnerCommands,
+    asset_server: &AssetServer,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: MAP_TEXT_SIZE,
+        ..default()
+    };
+
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                margin: UiRect::bottom(Val::Px(WIDGET_PADDING)),
+                display: Display::None,
+                ..default()
+            },
+            DesignSpaceMap,
+        ))
+        .with_children(|map| {
+            map.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(WIDGET_PADDING),
+                ..default()
+            })
+            .with_children(|buttons| {
+                for button in [MapAxisButton::X, MapAxisButton::Y] {
+                    spawn_axis_button(buttons, button, &text_font);
+                }
+            });
+
+            map.spawn((
+                Node {
+                    width: Val::Px(MAP_SIZE),
+                    height: Val::Px(MAP_SIZE),
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                BackgroundColor(NORMAL_BUTTON_COLOR),
+                BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+                Interaction::default(),
+                RelativeCursorPosition::default(),
+                DesignSpacePlot,
+            ))
+            .with_children(|plot| {
+                plot.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(MARKER_SIZE),
+                        height: Val::Px(MARKER_SIZE),
+                        margin: UiRect {
+                            left: Val::Px(-MARKER_SIZE / 2.0),
+                            top: Val::Px(-MARKER_SIZE / 2.0),
+                            ..default()
+                        },
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BorderColor(SELECTED_PRIMARY_COLOR),
+                    BorderRadius::all(Val::Px(MARKER_SIZE / 2.0)),
+                    ZIndex(1),
+                    CurrentLocationMarker,
+                ));
+            });
+
+            map.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                DesignSpaceLabelText,
+            ));
+        });
+}
+
+fn spawn_axis_button(
+    parent: &mut ChildSpawnerCommands,
+    button: MapAxisButton,
+    text_font: &TextFont,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::horizontal(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+            button,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+        });
+}
+
+// ============================================================================
+// PROJECTION
+// ============================================================================
+
+/// Where a normalized value lies along an axis, 0..=1
+fn axis_fraction(axis: &DesignAxis, value: f64) -> f32 {
+    let (minimum, maximum) = axis.normalized_range();
+    if maximum > minimum {
+        ((value - minimum) / (maximum - minimum)).clamp(0.0, 1.0) as f32
+    } else {
+        0.5
+    }
+}
+
+/// Normalized value at a fraction along an axis
+fn axis_value(axis: &DesignAxis, fraction: f32) -> f64 {
+    let (minimum, maximum) = axis.normalized_range();
+    minimum + f64::from(fraction.clamp(0.0, 1.0)) * (maximum - minimum)
+}
+
+/// Position of a location on the map
+fn map_position(
+    axes: &[DesignAxis],
+    projection: &DesignSpaceMapState,
+    value_of: impl Fn(&str) -> f64,
+) -> Vec2 {
+    let fraction = |index: Option<usize>| {
+        index
+            .and_then(|index| axes.get(index))
+            .map_or(0.5, |axis| axis_fraction(axis, value_of(&axis.tag)))
+    };
+    Vec2::new(
+        fraction(Some(projection.x_axis)),
+        fraction(projection.y_axis),
+    )
+}
+
+/// The masters and instances of a font, placed on the map
+fn map_points(
+    fontir_state: &FontIRAppState,
+    projection: &DesignSpaceMapState,
+) -> Vec<MapPoint> {
+    let point = |name: &str, is_master: bool, location: &[(String, f64)]| {
+        let position = map_position(&fontir_state.axes, projection, |tag| {
+            tagged_value(location, tag)
+        });
+        MapPoint {
+            name: name.to_string(),
+            is_master,
+            location: location.to_vec(),
+            position,
+        }
+    };
+    let masters = fontir_state
+        .master_glyphs
+        .iter()
+        .map(|(master, _)| point(&master.name, true, &master.location));
+    let instances = fontir_state
+        .instances
+        .iter()
+        .map(|instance| point(&instance.name, false, &instance.location));
+    masters.chain(instances).collect()
+}
+
+/// Value of a (tag, value) location on an axis, 0 when left out
+fn tagged_value(location: &[(String, f64)], tag: &str) -> f64 {
+    location
+        .iter()
+        .find(|(t, _)| t == tag)
+        .map_or(0.0, |(_, value)| *value)
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Plots the first two axes when a font with other axes is loaded, and
+/// shows the map only while there are axes
+fn update_map_projection(
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut projection: ResMut<DesignSpaceMapState>,
+    mut map_query: Query<&mut Node, With<DesignSpaceMap>>,
+    mut plot_query: Query<
+        &mut Node,
+        (With<DesignSpacePlot>, Without<DesignSpaceMap>),
+    >,
+    mut shown_axes: Local<Vec<DesignAxis>>,
+) {
+    let axes = fontir_state
+        .as_ref()
+        .map(|state| state.axes.clone())
+        .unwrap_or_default();
+    if *shown_axes == axes {
+        return;
+    }
+
+    *projection = DesignSpaceMapState {
+        x_axis: 0,
+        y_axis: (axes.len() > 1).then_some(1),
+    };
+    for mut node in map_query.iter_mut() {
+        node.display = if axes.is_empty() {
+            Display::None
+        } else {
+            Display::Flex
+        };
+    }
+    for mut node in plot_query.iter_mut() {
+        node.height = Val::Px(if axes.len() > 1 {
+            MAP_SIZE
+        } else {
+            MAP_LINE_HEIGHT
+        });
+    }
+    *shown_axes = axes;
+}
+
+/// Plots the next axis on a dimension, skipping the one on the other
+fn handle_map_axis_buttons(
+    fontir_state: Option<Res<FontIRAppState>>,
+    button_query: Query<(&Interaction, &MapAxisButton), Changed<Interaction>>,
+    mut projection: ResMut<DesignSpaceMapState>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    let axis_count = fontir_state.axes.len();
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed || axis_count < 3 {
+            continue;
+        }
+        let (current, other) = match button {
+            MapAxisButton::X => (projection.x_axis, projection.y_axis),
+            MapAxisButton::Y => {
+                let Some(y_axis) = projection.y_axis else {
+                    continue;
+                };
+                (y_axis, Some(projection.x_axis))
+            }
+        };
+        let next = (1..axis_count)
+            .map(|step| (current + step) % axis_count)
+            .find(|index| Some(*index) != other)
+            .unwrap_or(current);
+        match button {
+            MapAxisButton::X => projection.x_axis = next,
+            MapAxisButton::Y => projection.y_axis = Some(next),
+        }
+    }
+}
+
+/// Replaces the dots when the masters, instances or projection change
+fn rebuild_map_points(
+    mut commands: Commands,
+    fontir_state: Option<Res<FontIRAppState>>,
+    projection: Res<DesignSpaceMapState>,
+    plot_query: Query<Entity, With<DesignSpacePlot>>,
+    dot_query: Query<Entity, With<MapPoint>>,
+    mut shown_points: Local<Vec<MapPoint>>,
+) {
+    let points = fontir_state
+        .as_ref()
+        .map(|state| map_points(state, &projection))
+        .unwrap_or_default();
+    if *shown_points == points {
+        return;
+    }
+
+    for dot in dot_query.iter() {
+        commands.entity(dot).despawn();
+    }
+    for plot in plot_query.iter() {
+        commands.entity(plot).with_children(|plot| {
+            for point in &points {
+                plot.spawn((
+                    Button,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(100.0 * point.position.x),
+                        top: Val::Percent(100.0 * (1.0 - point.position.y)),
+                        width: Val::Px(POINT_SIZE),
+                        height: Val::Px(POINT_SIZE),
+                        margin: UiRect {
+                            left: Val::Px(-POINT_SIZE / 2.0),
+                            top: Val::Px(-POINT_SIZE / 2.0),
+                            ..default()
+                        },
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(if point.is_master {
+                        NORMAL_TEXT_COLOR
+                    } else {
+                        Color::NONE
+                    }),
+                    BorderColor(NORMAL_TEXT_COLOR),
+                    BorderRadius::all(Val::Px(POINT_SIZE / 2.0)),
+                    point.clone(),
+                ));
+            }
+        });
+    }
+    *shown_points = points;
+}
+
+/// Moves the location onto a clicked master or instance
+fn handle_map_point_clicks(
+    point_query: Query<(&Interaction, &MapPoint), Changed<Interaction>>,
+    mut location_events: EventWriter<SetLocationEvent>,
+) {
+    for (interaction, point) in point_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        info!("Moving to {} on the designspace map", point.name);
+        location_events.write(SetLocationEvent {
+            axis_values: point.location.clone(),
+        });
+    }
+}
+
+/// Drags the location while the map is pressed, snapping to masters
+fn handle_map_drag(
+    fontir_state: Option<Res<FontIRAppState>>,
+    projection: Res<DesignSpaceMapState>,
+    plot_query: Query<
+        (&Interaction, &RelativeCursorPosition, &ComputedNode),
+        With<DesignSpacePlot>,
+    >,
+    point_query: Query<&MapPoint>,
+    mut location_events: EventWriter<SetLocationEvent>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    for (interaction, cursor, computed) in plot_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(cursor) = cursor.normalized else {
+            continue;
+        };
+
+        // The cursor position is relative to the top left corner, y
+        // pointing down
+        let mut position =
+            Vec2::new(cursor.x, 1.0 - cursor.y).clamp(Vec2::ZERO, Vec2::ONE);
+        let size = computed.size() * computed.inverse_scale_factor();
+        if let Some(master) = point_query
+            .iter()
+            .filter(|point| point.is_master)
+            .map(|point| point.position)
+            .filter(|master| {
+                ((*master - position) * size).length() < MASTER_SNAP_DISTANCE
+            })
+            .min_by(|a, b| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            })
+        {
+            position = master;
+        }
+
+        let axes = &fontir_state.axes;
+        let axis_values: Vec<(String, f64)> = [
+            (Some(projection.x_axis), position.x),
+            (projection.y_axis, position.y),
+        ]
+        .into_iter()
+        .filter_map(|(index, fraction)| {
+            let axis = axes.get(index?)?;
+            Some((axis.tag.clone(), axis_value(axis, fraction)))
+        })
+        .collect();
+        let moved = axis_values.iter().any(|(tag, value)| {
+            (value - location_value(&fontir_state.current_location, tag)).abs()
+                >= DRAG_STEP
+        });
+        if moved {
+            location_events.write(SetLocationEvent { axis_values });
+        }
+    }
+}
+
+/// Moves the marker, highlights the master being edited and names the
+/// hovered point or the current location
+fn update_map_display(
+    fontir_state: Option<Res<FontIRAppState>>,
+    projection: Res<DesignSpaceMapState>,
+    mut marker_query: Query<&mut Node, With<CurrentLocationMarker>>,
+    mut point_query: Query<(&MapPoint, &Interaction, &mut BorderColor)>,
+    mut axis_button_query: Query<
+        (&MapAxisButton, &mut Node, &Children),
+        Without<CurrentLocationMarker>,
+    >,
+    mut text_query: Query<&mut Text, Without<DesignSpaceLabelText>>,
+    mut label_query: Query<&mut Text, With<DesignSpaceLabelText>>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    let axes = &fontir_state.axes;
+    let location = &fontir_state.current_location;
+
+    let position =
+        map_position(axes, &projection, |tag| location_value(location, tag));
+    for mut node in marker_query.iter_mut() {
+        node.left = Val::Percent(100.0 * position.x);
+        node.top = Val::Percent(100.0 * (1.0 - position.y));
+    }
+
+    let mut hovered = None;
+    let mut current = None;
+    for (point, interaction, mut border) in point_query.iter_mut() {
+        let is_current = axes.iter().all(|axis| {
+            (tagged_value(&point.location, &axis.tag)
+                - location_value(location, &axis.tag))
+            .abs()
+                < DRAG_STEP
+        });
+        let color = if is_current {
+            SELECTED_PRIMARY_COLOR
+        } else {
+            NORMAL_TEXT_COLOR
+        };
+        if border.0 != color {
+            border.0 = color;
+        }
+        if *interaction != Interaction::None {
+            hovered = Some(point.name.clone());
+        }
+        if is_current && (current.is_none() || point.is_master) {
+            current = Some(point.name.clone());
+        }
+    }
+    let label = hovered.or(current).unwrap_or_else(|| {
+        axes.iter()
+            .map(|axis| {
+                let value = location_value(location, &axis.tag);
+                format!("{} {}", axis.tag, axis.user_value(value).round())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    for mut text in label_query.iter_mut() {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+
+    for (button, mut node, children) in axis_button_query.iter_mut() {
+        let (prefix, index) = match button {
+            MapAxisButton::X => ("X", Some(projection.x_axis)),
+            MapAxisButton::Y => ("Y", projection.y_axis),
+        };
+        let axis = index.and_then(|index| axes.get(index));
+        node.display = if axis.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        let Some(axis) = axis else {
+            continue;
+        };
+        let caption = format!("{prefix}: {}", axis.name);
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                if text.0 != caption {
+                    text.0.clone_from(&caption);
+                }
+            }
+        }
     }
 }
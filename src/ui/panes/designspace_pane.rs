@@ -0,0 +1,810 @@
+//! Designspace pane
+//!
+//! Edits the `.designspace` document of the open font: its axes with their
+//! minimum, default, maximum and map, its sources, its named instances and
+//! its substitution rules. Command/Ctrl+Alt+D shows or hides the pane.
+//!
+//! Click a value to edit it, type, then press Enter to apply the change, Tab
+//! to apply it and move on to the next field, or Escape to cancel. See
+//! `data::designspace_editing` for how locations, maps, conditions and
+//! substitutions are typed. The + buttons add a source or instance at the
+//! current location; a new source is copied from the nearest master.
+//!
+//! Edits go into the FontIR state. On save the designspace is written,
+//! along with the UFOs of new sources, and the font is reloaded.
+
+use crate::core::state::FontIRAppState;
+use crate::data::designspace_editing::{
+    add_axis, add_rule, remove_axis, remove_instance, remove_rule,
+    remove_source, DesignspaceField,
+};
+use crate::systems::ui_interaction::{
+    detect_ui_hover, BlocksCanvas, UiHoverState,
+};
+use crate::ui::text_field::{TextField, TextFieldPlugin};
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::Display;
+use norad::designspace::DesignSpaceDocument;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const DESIGNSPACE_TEXT_SIZE: f32 = 14.0;
+
+/// Width of name columns
+const NAME_WIDTH: f32 = 130.0;
+
+/// Width of the axis tag column
+const TAG_WIDTH: f32 = 50.0;
+
+/// Width of the axis minimum, default and maximum columns
+const NUMBER_WIDTH: f32 = 70.0;
+
+/// Width of the axis map, source file and location columns
+const WIDE_WIDTH: f32 = 220.0;
+
+/// Width of the remove buttons
+const REMOVE_WIDTH: f32 = 20.0;
+
+/// Space above each section heading
+const SECTION_MARGIN: f32 = 8.0;
+
+// ============================================================================
+// COMPONENTS, RESOURCES & EVENTS
+// ============================================================================
+
+/// A part of the designspace document, listed as a section of the pane
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DesignspaceSection {
+    Axes,
+    Sources,
+    Instances,
+    Rules,
+}
+
+impl DesignspaceSection {
+    const ALL: [DesignspaceSection; 4] = [
+        DesignspaceSection::Axes,
+        DesignspaceSection::Sources,
+        DesignspaceSection::Instances,
+        DesignspaceSection::Rules,
+    ];
+
+    fn heading(self) -> &'static str {
+        match self {
+            DesignspaceSection::Axes => "Axes",
+            DesignspaceSection::Sources => "Sources",
+            DesignspaceSection::Instances => "Instances",
+            DesignspaceSection::Rules => "Rules",
+        }
+    }
+
+    /// Column labels and widths of the section's rows
+    fn columns(self) -> &'static [(&'static str, f32)] {
+        match self {
+            DesignspaceSection::Axes => &[
+                ("Name", NAME_WIDTH),
+                ("Tag", TAG_WIDTH),
+                ("Min", NUMBER_WIDTH),
+                ("Default", NUMBER_WIDTH),
+                ("Max", NUMBER_WIDTH),
+                ("Map", WIDE_WIDTH),
+            ],
+            DesignspaceSection::Sources => &[
+                ("Name", NAME_WIDTH),
+                ("File", WIDE_WIDTH),
+                ("Location", WIDE_WIDTH),
+            ],
+            DesignspaceSection::Instances => {
+                &[("Name", NAME_WIDTH), ("Location", WIDE_WIDTH)]
+            }
+            DesignspaceSection::Rules => &[
+                ("Name", NAME_WIDTH),
+                ("Conditions", WIDE_WIDTH),
+                ("Substitutions", WIDE_WIDTH),
+            ],
+        }
+    }
+
+    /// Number of rows of the section in a document
+    fn len(self, document: &DesignSpaceDocument) -> usize {
+        match self {
+            DesignspaceSection::Axes => document.axes.len(),
+            DesignspaceSection::Sources => document.sources.len(),
+            DesignspaceSection::Instances => document.instances.len(),
+            DesignspaceSection::Rules => document.rules.rules.len(),
+        }
+    }
+
+    /// Editable fields of a row, `None` for the source file, which can't
+    /// be edited
+    fn fields(self, index: usize) -> Vec<Option<DesignspaceField>> {
+        use DesignspaceField::*;
+        match self {
+            DesignspaceSection::Axes => vec![
+                Some(AxisName(index)),
+                Some(AxisTag(index)),
+                Some(AxisMinimum(index)),
+                Some(AxisDefault(index)),
+                Some(AxisMaximum(index)),
+                Some(AxisMap(index)),
+            ],
+            DesignspaceSection::Sources => {
+                vec![Some(SourceName(index)), None, Some(SourceLocation(index))]
+            }
+            DesignspaceSection::Instances => {
+                vec![Some(InstanceName(index)), Some(InstanceLocation(index))]
+            }
+            DesignspaceSection::Rules => vec![
+                Some(RuleName(index)),
+                Some(RuleConditions(index)),
+                Some(RuleSubstitutions(index)),
+            ],
+        }
+    }
+}
+
+/// Every editable field of a document, in the order Tab moves through them
+fn all_fields(document: &DesignSpaceDocument) -> Vec<DesignspaceField> {
+    DesignspaceSection::ALL
+        .iter()
+        .flat_map(|section| {
+            (0..section.len(document))
+                .flat_map(move |index| section.fields(index))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Visibility of the pane, the field being edited and the last error
+#[derive(Resource, Default)]
+pub struct DesignspacePaneState {
+    pub visible: bool,
+    /// Field being edited, if any
+    pub editing: Option<DesignspaceField>,
+    /// Text typed into the field being edited
+    pub input: String,
+    /// Why the last edit was refused
+    pub error: Option<String>,
+}
+
+impl TextField for DesignspacePaneState {
+    type Edit = EditDesignspaceEvent;
+
+    const TABS_BETWEEN_FIELDS: bool = true;
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn input_mut(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn finish_editing(&mut self) -> Option<EditDesignspaceEvent> {
+        let field = self.editing.take()?;
+        Some(EditDesignspaceEvent::SetField {
+            field,
+            text: std::mem::take(&mut self.input),
+        })
+    }
+
+    fn cancel_editing(&mut self) {
+        self.editing = None;
+        self.input.clear();
+    }
+
+    fn edit_next_field(
+        &mut self,
+        fontir_state: Option<&FontIRAppState>,
+    ) -> Option<EditDesignspaceEvent> {
+        let field = self.editing?;
+        let edit = self.finish_editing();
+        let Some(document) =
+            fontir_state.and_then(|state| state.designspace.as_ref())
+        else {
+            return edit;
+        };
+        let fields = all_fields(document);
+        if fields.is_empty() {
+            return edit;
+        }
+        let index = fields
+            .iter()
+            .position(|other| *other == field)
+            .unwrap_or_default();
+        let next = fields[(index + 1) % fields.len()];
+        // The edit isn't applied yet, so the next field shows the text it
+        // had before
+        self.input = next.text(document);
+        self.editing = Some(next);
+        edit
+    }
+}
+
+/// Edit the designspace document of the open font
+#[derive(Event, Debug, Clone)]
+pub enum EditDesignspaceEvent {
+    SetField {
+        field: DesignspaceField,
+        text: String,
+    },
+    /// Add an axis or rule, or a source or instance at the current location
+    Add(DesignspaceSection),
+    Remove(DesignspaceSection, usize),
+}
+
+/// Component marker for the designspace pane
+#[derive(Component, Default)]
+pub struct DesignspacePane;
+
+/// Tells about unsaved changes or the last refused edit
+#[derive(Component)]
+pub struct DesignspaceStatusText;
+
+/// Container the sections are spawned into
+#[derive(Component)]
+pub struct DesignspaceSections;
+
+/// Clickable value of a field
+#[derive(Component)]
+pub struct DesignspaceValue {
+    pub field: DesignspaceField,
+}
+
+/// Text showing the value of a field
+#[derive(Component)]
+pub struct DesignspaceValueText {
+    pub field: DesignspaceField,
+}
+
+/// Button adding or removing an axis, source, instance or rule
+#[derive(Component, Clone, Copy)]
+pub enum DesignspaceButton {
+    Add(DesignspaceSection),
+    Remove(DesignspaceSection, usize),
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct DesignspacePanePlugin;
+
+impl Plugin for DesignspacePanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DesignspacePaneState>()
+            .add_event::<EditDesignspaceEvent>()
+            .add_plugins(TextFieldPlugin::<DesignspacePaneState>::default())
+            .add_systems(Startup, spawn_designspace_pane)
+            .add_systems(
+                Update,
+                (
+                    toggle_designspace_pane,
+                    handle_designspace_clicks.after(detect_ui_hover),
+                    handle_edit_designspace,
+                    rebuild_designspace_sections,
+                    update_designspace_pane,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the designspace pane, hidden, at the top center of the window;
+/// its sections are added once the document is known
+pub fn spawn_designspace_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: DESIGNSPACE_TEXT_SIZE,
+        ..default()
+    };
+    let pane_width = NAME_WIDTH
+        + TAG_WIDTH
+        + 3.0 * NUMBER_WIDTH
+        + WIDE_WIDTH
+        + REMOVE_WIDTH
+        + 6.0 * WIDGET_PADDING;
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(TOOLBAR_CONTAINER_MARGIN + 4.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(
+                    -(pane_width / 2.0 + WIDGET_PADDING + WIDGET_BORDER_WIDTH),
+                )),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            DesignspacePane,
+            Name::new("DesignspacePane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Designspace"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                DesignspaceStatusText,
+            ));
+            pane.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                DesignspaceSections,
+            ));
+        });
+}
+
+/// Spawns a section heading with its add button, the column labels, and a
+/// row per axis, source, instance or rule
+fn spawn_section(
+    sections: &mut ChildSpawnerCommands,
+    section: DesignspaceSection,
+    document: &DesignSpaceDocument,
+    text_font: &TextFont,
+) {
+    sections
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(WIDGET_PADDING),
+            margin: UiRect::top(Val::Px(SECTION_MARGIN)),
+            ..default()
+        })
+        .with_children(|heading| {
+            heading.spawn((
+                Text::new(section.heading()),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+            spawn_button(
+                heading,
+                "+",
+                DesignspaceButton::Add(section),
+                text_font,
+            );
+        });
+
+    let row_node = Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(WIDGET_PADDING),
+        ..default()
+    };
+    sections.spawn(row_node.clone()).with_children(|labels| {
+        for &(label, width) in section.columns() {
+            labels.spawn((
+                Node {
+                    width: Val::Px(width),
+                    ..default()
+                },
+                Text::new(label),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+            ));
+        }
+    });
+
+    for index in 0..section.len(document) {
+        sections.spawn(row_node.clone()).with_children(|row| {
+            let fields = section.fields(index);
+            for (field, &(_, width)) in
+                fields.into_iter().zip(section.columns())
+            {
+                match field {
+                    Some(field) => spawn_value(row, field, width, text_font),
+                    None => {
+                        let source = &document.sources[index];
+                        let file = match &source.layer {
+                            Some(layer) => {
+                                format!("{} ({})", source.filename, layer)
+                            }
+                            None => source.filename.clone(),
+                        };
+                        row.spawn((
+                            Node {
+                                width: Val::Px(width),
+                                overflow: Overflow::clip(),
+                                ..default()
+                            },
+                            Text::new(file),
+                            text_font.clone(),
+                            TextLayout::new_with_no_wrap(),
+                            TextColor(SECONDARY_TEXT_COLOR),
+                        ));
+                    }
+                }
+            }
+            spawn_button(
+                row,
+                "x",
+                DesignspaceButton::Remove(section, index),
+                text_font,
+            );
+        });
+    }
+}
+
+/// Spawns the clickable value of a field
+fn spawn_value(
+    row: &mut ChildSpawnerCommands,
+    field: DesignspaceField,
+    width: f32,
+    text_font: &TextFont,
+) {
+    row.spawn((
+        Button,
+        Node {
+            width: Val::Px(width),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        DesignspaceValue { field },
+    ))
+    .with_children(|value| {
+        value.spawn((
+            Text::new(""),
+            text_font.clone(),
+            TextLayout::new_with_no_wrap(),
+            TextColor(ON_CURVE_PRIMARY_COLOR),
+            DesignspaceValueText { field },
+        ));
+    });
+}
+
+/// Spawns an add or remove button
+fn spawn_button(
+    parent: &mut ChildSpawnerCommands,
+    label: &str,
+    button: DesignspaceButton,
+    text_font: &TextFont,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(REMOVE_WIDTH),
+                justify_content: JustifyContent::Center,
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+            button,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+        });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane with Command/Ctrl+Alt+D
+fn toggle_designspace_pane(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut pane_state: ResMut<DesignspacePaneState>,
+    mut edit_events: EventWriter<EditDesignspaceEvent>,
+    mut pane_query: Query<&mut Node, With<DesignspacePane>>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if !(modifier_pressed
+        && alt_pressed
+        && !shift_pressed
+        && keyboard.just_pressed(KeyCode::KeyD))
+    {
+        return;
+    }
+
+    pane_state.visible = !pane_state.visible;
+    if !pane_state.visible {
+        if let Some(edit) = pane_state.finish_editing() {
+            edit_events.write(edit);
+        }
+    }
+    for mut node in pane_query.iter_mut() {
+        node.display = if pane_state.visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// Starts editing a clicked value, adds or removes rows, and applies the
+/// edit when clicking anywhere outside the pane
+fn handle_designspace_clicks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    ui_hover_state: Res<UiHoverState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    value_query: Query<(&Interaction, &DesignspaceValue), Changed<Interaction>>,
+    button_query: Query<
+        (&Interaction, &DesignspaceButton),
+        Changed<Interaction>,
+    >,
+    mut pane_state: ResMut<DesignspacePaneState>,
+    mut edit_events: EventWriter<EditDesignspaceEvent>,
+) {
+    if !pane_state.visible {
+        return;
+    }
+
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // The edit goes first, while its index still points at its row
+        if let Some(edit) = pane_state.finish_editing() {
+            edit_events.write(edit);
+        }
+        edit_events.write(match *button {
+            DesignspaceButton::Add(section) => {
+                EditDesignspaceEvent::Add(section)
+            }
+            DesignspaceButton::Remove(section, index) => {
+                EditDesignspaceEvent::Remove(section, index)
+            }
+        });
+        return;
+    }
+
+    for (interaction, value) in value_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(edit) = pane_state.finish_editing() {
+            edit_events.write(edit);
+        }
+        pane_state.input = fontir_state
+            .as_ref()
+            .and_then(|state| state.designspace.as_ref())
+            .map(|document| value.field.text(document))
+            .unwrap_or_default();
+        pane_state.editing = Some(value.field);
+        return;
+    }
+
+    if mouse.just_pressed(MouseButton::Left) && !ui_hover_state.is_hovering_ui {
+        if let Some(edit) = pane_state.finish_editing() {
+            edit_events.write(edit);
+        }
+    }
+}
+
+/// Applies designspace edits to the FontIR state
+fn handle_edit_designspace(
+    mut edit_events: EventReader<EditDesignspaceEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut pane_state: ResMut<DesignspacePaneState>,
+) {
+    for event in edit_events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Designspace: no font loaded");
+            continue;
+        };
+        let result = match event {
+            EditDesignspaceEvent::SetField { field, text } => {
+                let unchanged = fontir_state
+                    .designspace
+                    .as_ref()
+                    .is_some_and(|document| field.text(document) == *text);
+                if unchanged {
+                    continue;
+                }
+                fontir_state
+                    .edit_designspace(|document| field.set(document, text))
+                    .map(|()| format!("set {:?} to '{}'", field, text))
+            }
+            EditDesignspaceEvent::Add(section) => match section {
+                DesignspaceSection::Axes => fontir_state
+                    .edit_designspace(|document| Ok(add_axis(document)))
+                    .map(|_| "added an axis".to_string()),
+                DesignspaceSection::Sources => fontir_state
+                    .add_designspace_source()
+                    .map(|name| format!("added source '{name}'")),
+                DesignspaceSection::Instances => fontir_state
+                    .add_designspace_instance()
+                    .map(|name| format!("added instance '{name}'")),
+                DesignspaceSection::Rules => fontir_state
+                    .edit_designspace(|document| Ok(add_rule(document)))
+                    .map(|_| "added a rule".to_string()),
+            },
+            EditDesignspaceEvent::Remove(section, index) => {
+                let index = *index;
+                fontir_state
+                    .edit_designspace(|document| match section {
+                        DesignspaceSection::Axes => {
+                            remove_axis(document, index)
+                        }
+                        DesignspaceSection::Sources => {
+                            remove_source(document, index)
+                        }
+                        DesignspaceSection::Instances => {
+                            remove_instance(document, index)
+                        }
+                        DesignspaceSection::Rules => {
+                            remove_rule(document, index)
+                        }
+                    })
+                    .map(|()| {
+                        format!(
+                            "removed row {} of {}",
+                            index + 1,
+                            section.heading().to_lowercase()
+                        )
+                    })
+            }
+        };
+        match result {
+            Ok(change) => {
+                info!("Designspace: {}", change);
+                pane_state.error = None;
+            }
+            Err(e) => {
+                warn!("Designspace: {}", e);
+                pane_state.error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+/// Replaces the sections when rows are added or removed, or another font
+/// is loaded
+fn rebuild_designspace_sections(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pane_state: Res<DesignspacePaneState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    sections_query: Query<Entity, With<DesignspaceSections>>,
+    mut shown_rows: Local<Option<Vec<usize>>>,
+) {
+    if !pane_state.visible {
+        return;
+    }
+    let document = fontir_state
+        .as_ref()
+        .and_then(|state| state.designspace.as_ref());
+    let rows = document.map(|document| {
+        DesignspaceSection::ALL
+            .iter()
+            .map(|section| section.len(document))
+            .collect::<Vec<_>>()
+    });
+    // Values are refreshed in place, so only other rows or a reloaded font
+    // need the sections rebuilt
+    let reloaded = fontir_state.as_ref().is_some_and(|state| state.is_added());
+    if *shown_rows == rows && !reloaded {
+        return;
+    }
+
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: DESIGNSPACE_TEXT_SIZE,
+        ..default()
+    };
+    for sections in sections_query.iter() {
+        commands
+            .entity(sections)
+            .despawn_related::<Children>()
+            .with_children(|sections| {
+                let Some(document) = document else {
+                    return;
+                };
+                for section in DesignspaceSection::ALL {
+                    spawn_section(sections, section, document, &text_font);
+                }
+            });
+    }
+    *shown_rows = rows;
+}
+
+/// Refreshes the status and field values shown in the pane
+fn update_designspace_pane(
+    pane_state: Res<DesignspacePaneState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut status_query: Query<&mut Text, With<DesignspaceStatusText>>,
+    mut value_query: Query<
+        (&DesignspaceValueText, &mut Text, &mut TextColor),
+        Without<DesignspaceStatusText>,
+    >,
+    mut button_query: Query<(&DesignspaceValue, &mut BackgroundColor)>,
+) {
+    if !pane_state.visible {
+        return;
+    }
+    let fontir_state = fontir_state.as_deref();
+    let document = fontir_state.and_then(|state| state.designspace.as_ref());
+
+    let status = match (&pane_state.error, fontir_state) {
+        (Some(error), _) => error.clone(),
+        (None, None) => "No font loaded".to_string(),
+        (None, Some(_)) if document.is_none() => {
+            "Only .designspace sources have axes and sources".to_string()
+        }
+        (None, Some(state)) if state.designspace_dirty => {
+            "Unsaved changes, written on save".to_string()
+        }
+        (None, Some(_)) => String::new(),
+    };
+    for mut text in status_query.iter_mut() {
+        if text.0 != status {
+            text.0.clone_from(&status);
+        }
+    }
+
+    for (value, mut text, mut color) in value_query.iter_mut() {
+        let editing = pane_state.editing == Some(value.field);
+        let shown = if editing {
+            format!("{}|", pane_state.input)
+        } else {
+            document
+                .map(|document| value.field.text(document))
+                .filter(|value_text| !value_text.is_empty())
+                .unwrap_or_else(|| "--".to_string())
+        };
+        if text.0 != shown {
+            text.0 = shown;
+        }
+        let value_color = if editing {
+            NORMAL_TEXT_COLOR
+        } else {
+            ON_CURVE_PRIMARY_COLOR
+        };
+        if color.0 != value_color {
+            color.0 = value_color;
+        }
+    }
+
+    for (value, mut background) in button_query.iter_mut() {
+        let value_background = if pane_state.editing == Some(value.field) {
+            PANEL_BACKGROUND_COLOR
+        } else {
+            Color::NONE
+        };
+        if background.0 != value_background {
+            background.0 = value_background;
+        }
+    }
+}
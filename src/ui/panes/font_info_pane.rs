@@ -0,0 +1,505 @@
+//! Font info pane
+//!
+//! Shows the font info of the master at the current location: family and
+//! style names, version, vertical metrics, OS/2 and hhea values, the italic
+//! angle, and designer and license fields. Command/Ctrl+I shows or hides the
+//! pane.
+//!
+//! Click a value to edit it, type, then press Enter to apply the change, Tab
+//! to apply it and move on to the next field, or Escape to cancel. Clicking
+//! outside the pane applies the change too. While a field is being edited
+//! the pane takes all keyboard input, so tool and editing shortcuts don't
+//! fire.
+//!
+//! Edits go into the FontIR state and are written into the master's
+//! fontinfo.plist on save. Metrics lines follow vertical metrics edits right
+//! away. Masters stored as layers of one UFO share its font info.
+
+use crate::core::state::{FontIRAppState, FontInfoField};
+use crate::systems::ui_interaction::{
+    detect_ui_hover, BlocksCanvas, UiHoverState,
+};
+use crate::ui::text_field::{TextField, TextFieldPlugin};
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::Display;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's labels and values
+const FONT_INFO_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the label column
+const LABEL_WIDTH: f32 = 170.0;
+
+/// Width of the value column
+const VALUE_WIDTH: f32 = 180.0;
+
+/// Gap between the two columns of sections
+const COLUMN_GAP: f32 = 24.0;
+
+/// Space above each section heading
+const SECTION_MARGIN: f32 = 8.0;
+
+/// Sections of the pane, split over two columns
+const SECTIONS: [&[(&str, &[FontInfoField])]; 2] = [
+    &[
+        (
+            "Names",
+            &[FontInfoField::FamilyName, FontInfoField::StyleName],
+        ),
+        (
+            "Version",
+            &[FontInfoField::VersionMajor, FontInfoField::VersionMinor],
+        ),
+        (
+            "Metrics",
+            &[
+                FontInfoField::UnitsPerEm,
+                FontInfoField::Ascender,
+                FontInfoField::Descender,
+                FontInfoField::XHeight,
+                FontInfoField::CapHeight,
+                FontInfoField::ItalicAngle,
+            ],
+        ),
+    ],
+    &[
+        (
+            "OS/2",
+            &[
+                FontInfoField::Os2TypoAscender,
+                FontInfoField::Os2TypoDescender,
+                FontInfoField::Os2TypoLineGap,
+                FontInfoField::Os2WinAscent,
+                FontInfoField::Os2WinDescent,
+            ],
+        ),
+        (
+            "hhea",
+            &[
+                FontInfoField::HheaAscender,
+                FontInfoField::HheaDescender,
+                FontInfoField::HheaLineGap,
+            ],
+        ),
+        (
+            "Credits",
+            &[
+                FontInfoField::Designer,
+                FontInfoField::DesignerUrl,
+                FontInfoField::Manufacturer,
+                FontInfoField::Copyright,
+                FontInfoField::License,
+                FontInfoField::LicenseUrl,
+                FontInfoField::Trademark,
+            ],
+        ),
+    ],
+];
+
+// ============================================================================
+// COMPONENTS, RESOURCES & EVENTS
+// ============================================================================
+
+/// Visibility of the pane and the field being edited
+#[derive(Resource, Default)]
+pub struct FontInfoPaneState {
+    pub visible: bool,
+    /// Field being edited, if any
+    pub editing: Option<FontInfoField>,
+    /// Text typed into the field being edited
+    pub input: String,
+}
+
+impl TextField for FontInfoPaneState {
+    type Edit = EditFontInfoEvent;
+
+    const TABS_BETWEEN_FIELDS: bool = true;
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn input_mut(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn finish_editing(&mut self) -> Option<EditFontInfoEvent> {
+        let field = self.editing.take()?;
+        Some(EditFontInfoEvent {
+            field,
+            text: std::mem::take(&mut self.input),
+        })
+    }
+
+    fn cancel_editing(&mut self) {
+        self.editing = None;
+        self.input.clear();
+    }
+
+    fn edit_next_field(
+        &mut self,
+        fontir_state: Option<&FontIRAppState>,
+    ) -> Option<EditFontInfoEvent> {
+        let edit = self.finish_editing()?;
+        let index = FontInfoField::ALL
+            .iter()
+            .position(|field| *field == edit.field)
+            .unwrap_or_default();
+        let next = FontInfoField::ALL[(index + 1) % FontInfoField::ALL.len()];
+        self.input = fontir_state
+            .and_then(|state| state.current_font_info())
+            .map(|font_info| font_info.field_text(next))
+            .unwrap_or_default();
+        self.editing = Some(next);
+        Some(edit)
+    }
+}
+
+/// Set a font info field of the master at the current location
+#[derive(Event, Debug, Clone)]
+pub struct EditFontInfoEvent {
+    pub field: FontInfoField,
+    pub text: String,
+}
+
+/// Component marker for the font info pane
+#[derive(Component, Default)]
+pub struct FontInfoPane;
+
+/// Component marker for the master name at the top of the pane
+#[derive(Component)]
+pub struct FontInfoMasterText;
+
+/// Clickable value of a font info field
+#[derive(Component)]
+pub struct FontInfoValue {
+    pub field: FontInfoField,
+}
+
+/// Text showing the value of a font info field
+#[derive(Component)]
+pub struct FontInfoValueText {
+    pub field: FontInfoField,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct FontInfoPanePlugin;
+
+impl Plugin for FontInfoPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FontInfoPaneState>()
+            .add_event::<EditFontInfoEvent>()
+            .add_plugins(TextFieldPlugin::<FontInfoPaneState>::default())
+            .add_systems(Startup, spawn_font_info_pane)
+            .add_systems(
+                Update,
+                (
+                    toggle_font_info_pane,
+                    handle_font_info_clicks.after(detect_ui_hover),
+                    handle_edit_font_info,
+                    update_font_info_pane,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the font info pane, hidden, at the top center of the window
+pub fn spawn_font_info_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: FONT_INFO_TEXT_SIZE,
+        ..default()
+    };
+    let pane_width = 2.0 * (LABEL_WIDTH + VALUE_WIDTH) + COLUMN_GAP;
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(TOOLBAR_CONTAINER_MARGIN + 4.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(
+                    -(pane_width / 2.0 + WIDGET_PADDING + WIDGET_BORDER_WIDTH),
+                )),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            FontInfoPane,
+            Name::new("FontInfoPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Font Info"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+                FontInfoMasterText,
+            ));
+
+            pane.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(COLUMN_GAP),
+                ..default()
+            })
+            .with_children(|columns| {
+                for sections in SECTIONS {
+                    columns
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        })
+                        .with_children(|column| {
+                            for (heading, fields) in sections {
+                                spawn_section(
+                                    column, heading, fields, &text_font,
+                                );
+                            }
+                        });
+                }
+            });
+        });
+}
+
+/// Spawns a section heading and a label and value row per field
+fn spawn_section(
+    column: &mut ChildSpawnerCommands,
+    heading: &str,
+    fields: &[FontInfoField],
+    text_font: &TextFont,
+) {
+    column.spawn((
+        Node {
+            margin: UiRect::top(Val::Px(SECTION_MARGIN)),
+            ..default()
+        },
+        Text::new(heading),
+        text_font.clone(),
+        TextColor(NORMAL_TEXT_COLOR),
+    ));
+
+    for &field in fields {
+        column
+            .spawn(Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn((
+                    Node {
+                        width: Val::Px(LABEL_WIDTH),
+                        ..default()
+                    },
+                    Text::new(field.label()),
+                    text_font.clone(),
+                    TextColor(SECONDARY_TEXT_COLOR),
+                ));
+                row.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(VALUE_WIDTH),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    FontInfoValue { field },
+                ))
+                .with_children(|value| {
+                    value.spawn((
+                        Text::new(""),
+                        text_font.clone(),
+                        TextLayout::new_with_no_wrap(),
+                        TextColor(ON_CURVE_PRIMARY_COLOR),
+                        FontInfoValueText { field },
+                    ));
+                });
+            });
+    }
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane with Command/Ctrl+I
+fn toggle_font_info_pane(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut pane_state: ResMut<FontInfoPaneState>,
+    mut edit_events: EventWriter<EditFontInfoEvent>,
+    mut pane_query: Query<&mut Node, With<FontInfoPane>>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    if !(modifier_pressed && keyboard.just_pressed(KeyCode::KeyI)) {
+        return;
+    }
+
+    pane_state.visible = !pane_state.visible;
+    if !pane_state.visible {
+        if let Some(edit) = pane_state.finish_editing() {
+            edit_events.write(edit);
+        }
+    }
+    for mut node in pane_query.iter_mut() {
+        node.display = if pane_state.visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// Starts editing a clicked value, and applies the edit when clicking
+/// anywhere outside the pane
+fn handle_font_info_clicks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    ui_hover_state: Res<UiHoverState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    value_query: Query<(&Interaction, &FontInfoValue), Changed<Interaction>>,
+    mut pane_state: ResMut<FontInfoPaneState>,
+    mut edit_events: EventWriter<EditFontInfoEvent>,
+) {
+    if !pane_state.visible {
+        return;
+    }
+
+    for (interaction, value) in value_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(edit) = pane_state.finish_editing() {
+            edit_events.write(edit);
+        }
+        pane_state.input = fontir_state
+            .as_ref()
+            .and_then(|state| state.current_font_info())
+            .map(|font_info| font_info.field_text(value.field))
+            .unwrap_or_default();
+        pane_state.editing = Some(value.field);
+        return;
+    }
+
+    if mouse.just_pressed(MouseButton::Left) && !ui_hover_state.is_hovering_ui {
+        if let Some(edit) = pane_state.finish_editing() {
+            edit_events.write(edit);
+        }
+    }
+}
+
+/// Applies font info edits to the FontIR state
+fn handle_edit_font_info(
+    mut edit_events: EventReader<EditFontInfoEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+) {
+    for event in edit_events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            warn!("Font info: no font loaded");
+            continue;
+        };
+        let unchanged = fontir_state
+            .current_font_info()
+            .is_some_and(|info| info.field_text(event.field) == event.text);
+        if unchanged {
+            continue;
+        }
+        match fontir_state.set_current_font_info_field(event.field, &event.text)
+        {
+            Ok(()) => info!(
+                "Font info: set {} to '{}'",
+                event.field.label(),
+                event.text
+            ),
+            Err(e) => warn!("Font info: {}", e),
+        }
+    }
+}
+
+/// Refreshes the master name and field values shown in the pane
+fn update_font_info_pane(
+    pane_state: Res<FontInfoPaneState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut master_query: Query<&mut Text, With<FontInfoMasterText>>,
+    mut value_query: Query<
+        (&FontInfoValueText, &mut Text, &mut TextColor),
+        Without<FontInfoMasterText>,
+    >,
+    mut button_query: Query<(&FontInfoValue, &mut BackgroundColor)>,
+) {
+    if !pane_state.visible {
+        return;
+    }
+    let fontir_state = fontir_state.as_deref();
+    let font_info = fontir_state.and_then(FontIRAppState::current_font_info);
+
+    let title = match fontir_state.and_then(FontIRAppState::current_master_name)
+    {
+        Some(name) => format!("Font Info: {name}"),
+        None => "Font Info".to_string(),
+    };
+    for mut text in master_query.iter_mut() {
+        if text.0 != title {
+            text.0 = title.clone();
+        }
+    }
+
+    for (value, mut text, mut color) in value_query.iter_mut() {
+        let editing = pane_state.editing == Some(value.field);
+        let shown = if editing {
+            format!("{}|", pane_state.input)
+        } else {
+            font_info
+                .map(|font_info| font_info.field_text(value.field))
+                .filter(|value_text| !value_text.is_empty())
+                .unwrap_or_else(|| "--".to_string())
+        };
+        if text.0 != shown {
+            text.0 = shown;
+        }
+        let value_color = if editing {
+            NORMAL_TEXT_COLOR
+        } else {
+            ON_CURVE_PRIMARY_COLOR
+        };
+        if color.0 != value_color {
+            color.0 = value_color;
+        }
+    }
+
+    for (value, mut background) in button_query.iter_mut() {
+        let value_background = if pane_state.editing == Some(value.field) {
+            PANEL_BACKGROUND_COLOR
+        } else {
+            Color::NONE
+        };
+        if background.0 != value_background {
+            background.0 = value_background;
+        }
+    }
+}
@@ -2,13 +2,28 @@
 //!
 //! Shows glyph name, Unicode codepoint, advance width, side bearings,
 //! and side bearings in the lower left corner of the window.
+//!
+//! Each codepoint is listed with its Unicode name, category and script,
+//! and codepoints that other glyphs have too are flagged. Click the
+//! codepoints to edit them as hex values separated by spaces, then press
+//! Enter to apply or Escape to cancel. The suggested name button renames
+//! the glyph after its first codepoint, following the Adobe Glyph List.
 
 use crate::core::state::fontir_app_state::FontIRAppState;
 use crate::core::state::AppState;
+use crate::data::ufo::char_to_hex_codepoint;
+use crate::data::unicode::{
+    self, char_name, glyph_name_for_char, parse_codepoints,
+};
+use crate::systems::commands::{RenameGlyphEvent, SetGlyphCodepointsEvent};
+use crate::systems::ui_interaction::{detect_ui_hover, UiHoverState};
+use crate::ui::text_field::{TextField, TextFieldPlugin};
 use crate::ui::theme::*;
 use crate::ui::themes::CurrentTheme;
 use bevy::prelude::*;
+use bevy::ui::Display;
 use kurbo::{BezPath, PathEl};
+use std::collections::HashMap;
 
 /// Resource to store current glyph metrics for display
 #[derive(Resource, Default)]
@@ -20,6 +35,56 @@ pub struct CurrentGlyphMetrics {
     pub right_bearing: String,
     pub left_group: String,
     pub right_group: String,
+    /// Unicode name, category and script of each codepoint
+    pub codepoint_info: String,
+    /// Codepoints of the glyph that other glyphs have too
+    pub duplicates: String,
+    /// Glyph name suggested by the first codepoint, if it differs
+    pub suggested_name: String,
+    /// Whether another glyph already has the suggested name, which can't
+    /// be applied then
+    pub suggested_name_in_use: bool,
+}
+
+/// Codepoint editing state of the glyph pane
+#[derive(Resource, Default)]
+pub struct GlyphPaneState {
+    /// Glyph whose codepoints are being edited, if any
+    pub editing: Option<String>,
+    /// Text typed into the codepoint field
+    pub input: String,
+}
+
+impl TextField for GlyphPaneState {
+    type Edit = SetGlyphCodepointsEvent;
+
+    fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    fn input_mut(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn finish_editing(&mut self) -> Option<SetGlyphCodepointsEvent> {
+        let glyph_name = self.editing.take()?;
+        let input = std::mem::take(&mut self.input);
+        match parse_codepoints(&input) {
+            Ok(codepoints) => Some(SetGlyphCodepointsEvent {
+                glyph_name,
+                codepoints,
+            }),
+            Err(e) => {
+                warn!("Cannot set codepoints of '{}': {}", glyph_name, e);
+                None
+            }
+        }
+    }
+
+    fn cancel_editing(&mut self) {
+        self.editing = None;
+        self.input.clear();
+    }
 }
 
 /// Component marker for the glyph pane
@@ -36,6 +101,26 @@ pub struct GlyphNameText;
 #[derive(Component)]
 pub struct GlyphUnicodeText;
 
+/// Clickable codepoints of the glyph, edited in place
+#[derive(Component)]
+pub struct GlyphUnicodeButton;
+
+/// Component marker for the Unicode name, category and script text
+#[derive(Component)]
+pub struct GlyphCodepointInfoText;
+
+/// Component marker for the duplicate codepoints text
+#[derive(Component)]
+pub struct GlyphDuplicateText;
+
+/// Button renaming the glyph to the suggested name
+#[derive(Component)]
+pub struct GlyphSuggestNameButton;
+
+/// Component marker for the suggested name text
+#[derive(Component)]
+pub struct GlyphSuggestNameText;
+
 /// Component marker for the glyph advance text
 #[derive(Component)]
 pub struct GlyphAdvanceText;
@@ -61,14 +146,22 @@ pub struct GlyphPanePlugin;
 
 impl Plugin for GlyphPanePlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<CurrentGlyphMetrics>().add_systems(
-            Update,
-            (
-                update_glyph_pane,
-                update_glyph_metrics,
-                toggle_glyph_pane_visibility,
-            ),
-        );
+        app.init_resource::<CurrentGlyphMetrics>()
+            .init_resource::<GlyphPaneState>()
+            .add_plugins(TextFieldPlugin::<GlyphPaneState>::default())
+            .add_systems(
+                Update,
+                (
+                    (
+                        update_glyph_metrics,
+                        update_codepoint_info,
+                        update_glyph_pane,
+                    )
+                        .chain(),
+                    toggle_glyph_pane_visibility,
+                    handle_glyph_pane_clicks.after(detect_ui_hover),
+                ),
+            );
     }
 }
 
@@ -97,11 +190,18 @@ fn update_glyph_pane(world: &mut World) {
         metrics.glyph_name.clone()
     };
 
-    let unicode = if metrics.unicode.is_empty() {
+    let pane_state = world.resource::<GlyphPaneState>();
+    let unicode = if pane_state.editing.is_some() {
+        format!("{}|", pane_state.input)
+    } else if metrics.unicode.is_empty() {
         "None".to_string()
     } else {
         metrics.unicode.to_uppercase()
     };
+    let codepoint_info = metrics.codepoint_info.clone();
+    let duplicates = metrics.duplicates.clone();
+    let suggested_name = metrics.suggested_name.clone();
+    let suggested_name_in_use = metrics.suggested_name_in_use;
 
     let advance = if metrics.advance.is_empty() {
         "--".to_string()
@@ -190,6 +290,62 @@ fn update_glyph_pane(world: &mut World) {
     for mut text in right_group_query.iter_mut(world) {
         *text = Text::new(right_group.clone());
     }
+
+    set_row_text::<GlyphCodepointInfoText>(world, &codepoint_info);
+    set_row_text::<GlyphDuplicateText>(world, &duplicates);
+
+    let mut suggest_query =
+        world.query_filtered::<&mut Text, With<GlyphSuggestNameText>>();
+    for mut text in suggest_query.iter_mut(world) {
+        text.0 = if suggested_name_in_use {
+            format!("{suggested_name} (in use)")
+        } else {
+            format!("Rename to {suggested_name}")
+        };
+    }
+    let mut suggest_color_query =
+        world.query_filtered::<&mut TextColor, With<GlyphSuggestNameText>>();
+    let color = if suggested_name_in_use {
+        SECONDARY_TEXT_COLOR
+    } else {
+        NORMAL_TEXT_COLOR
+    };
+    for mut text_color in suggest_color_query.iter_mut(world) {
+        if text_color.0 != color {
+            text_color.0 = color;
+        }
+    }
+    let mut suggest_button_query =
+        world.query_filtered::<&mut Node, With<GlyphSuggestNameButton>>();
+    let display = display_unless_empty(&suggested_name);
+    for mut node in suggest_button_query.iter_mut(world) {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+/// Sets the text of a row, hiding the row while the text is empty
+fn set_row_text<M: Component>(world: &mut World, value: &str) {
+    let display = display_unless_empty(value);
+    let mut query = world.query_filtered::<(&mut Text, &mut Node), With<M>>();
+    for (mut text, mut node) in query.iter_mut(world) {
+        if text.0 != value {
+            text.0 = value.to_string();
+        }
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+/// Hides rows without text
+fn display_unless_empty(text: &str) -> Display {
+    if text.is_empty() {
+        Display::None
+    } else {
+        Display::Flex
+    }
 }
 
 /// System to toggle the visibility of the entire glyph pane based on active sort
@@ -301,16 +457,85 @@ pub fn spawn_glyph_pane(
                         TextColor(Color::srgba(0.7, 0.7, 0.7, 1.0)),
                     ));
 
-                    // Value
+                    // Value, click to edit
                     row.spawn((
-                        Text::new("Loading..."),
+                        Button,
+                        BackgroundColor(Color::NONE),
+                        GlyphUnicodeButton,
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            Text::new("Loading..."),
+                            TextFont {
+                                font: asset_server.load(MONO_FONT_PATH),
+                                font_size: WIDGET_TEXT_FONT_SIZE,
+                                ..default()
+                            },
+                            TextColor(Color::srgba(0.0, 1.0, 0.5, 1.0)),
+                            GlyphUnicodeText,
+                        ));
+                    });
+                });
+
+            // Unicode name, category and script of each codepoint
+            parent.spawn((
+                Node {
+                    margin: UiRect::bottom(Val::Px(WIDGET_ROW_LEADING)),
+                    display: Display::None,
+                    ..default()
+                },
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(MONO_FONT_PATH),
+                    font_size: WIDGET_TEXT_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(Color::srgba(0.7, 0.7, 0.7, 1.0)),
+                GlyphCodepointInfoText,
+            ));
+
+            // Codepoints other glyphs have too
+            parent.spawn((
+                Node {
+                    margin: UiRect::bottom(Val::Px(WIDGET_ROW_LEADING)),
+                    display: Display::None,
+                    ..default()
+                },
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(MONO_FONT_PATH),
+                    font_size: WIDGET_TEXT_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(PRESSED_BUTTON_COLOR),
+                GlyphDuplicateText,
+            ));
+
+            // Suggested name
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        margin: UiRect::bottom(Val::Px(WIDGET_ROW_LEADING)),
+                        padding: UiRect::horizontal(Val::Px(4.0)),
+                        border: UiRect::all(Val::Px(1.0)),
+                        display: Display::None,
+                        ..default()
+                    },
+                    BackgroundColor(NORMAL_BUTTON_COLOR),
+                    BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+                    GlyphSuggestNameButton,
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new(""),
                         TextFont {
                             font: asset_server.load(MONO_FONT_PATH),
                             font_size: WIDGET_TEXT_FONT_SIZE,
                             ..default()
                         },
-                        TextColor(Color::srgba(0.0, 1.0, 0.5, 1.0)),
-                        GlyphUnicodeText,
+                        TextColor(NORMAL_TEXT_COLOR),
+                        GlyphSuggestNameText,
                     ));
                 });
 
@@ -545,17 +770,12 @@ pub fn update_glyph_metrics(
         if let Some(fontir_state) = fontir_app_state.as_ref() {
             // Extract metrics from FontIR
 
-            // TODO: Get Unicode from FontIR glyph data when available
-            // For now, try to get it from glyph name
-            metrics.unicode = if glyph_name == "a" {
-                "0061".to_string() // Unicode for 'a'
-            } else if glyph_name.len() == 1
-                && glyph_name.chars().next().unwrap().is_ascii_lowercase()
-            {
-                format!("{:04X}", glyph_name.chars().next().unwrap() as u32)
-            } else {
-                String::new()
-            };
+            metrics.unicode = fontir_state
+                .glyph_codepoints(&glyph_name)
+                .into_iter()
+                .map(char_to_hex_codepoint)
+                .collect::<Vec<_>>()
+                .join(" ");
 
             // Get advance width from FontIR
             let advance_width =
@@ -799,6 +1019,149 @@ pub fn update_glyph_metrics(
     }
 }
 
+/// Updates the codepoint details and the suggested name of the current
+/// glyph
+///
+/// Finding duplicates looks at every glyph, so it only runs again when the
+/// glyph, its codepoints or the glyph set change.
+fn update_codepoint_info(
+    fontir_app_state: Option<Res<FontIRAppState>>,
+    mut metrics: ResMut<CurrentGlyphMetrics>,
+    mut last_update: Local<Option<(String, Vec<char>, u64)>>,
+) {
+    let Some(fontir_state) = fontir_app_state.as_ref() else {
+        return;
+    };
+    let glyph_name = metrics.glyph_name.clone();
+    let codepoints = fontir_state.glyph_codepoints(&glyph_name);
+    let key = (
+        glyph_name.clone(),
+        codepoints.clone(),
+        fontir_state.glyph_set_generation,
+    );
+    if last_update.as_ref() == Some(&key) {
+        return;
+    }
+    *last_update = Some(key);
+
+    metrics.codepoint_info = codepoints
+        .iter()
+        .map(|&c| {
+            let name = char_name(c).unwrap_or_else(|| "--".to_string());
+            format!(
+                "U+{} {}\n  {}, {}",
+                char_to_hex_codepoint(c),
+                name,
+                unicode::category(c),
+                unicode::script(c)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let codepoint_glyphs: HashMap<String, Vec<String>> =
+        if codepoints.is_empty() {
+            HashMap::new()
+        } else {
+            fontir_state.codepoint_glyphs_map()
+        };
+    metrics.duplicates = codepoints
+        .iter()
+        .filter_map(|&c| {
+            let codepoint = char_to_hex_codepoint(c);
+            let others: Vec<&str> = codepoint_glyphs
+                .get(&codepoint)?
+                .iter()
+                .filter(|other| **other != glyph_name)
+                .map(String::as_str)
+                .collect();
+            if others.is_empty() {
+                return None;
+            }
+            Some(format!("U+{codepoint} also in {}", others.join(", ")))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    metrics.suggested_name = codepoints
+        .first()
+        .map(|&c| glyph_name_for_char(c))
+        .filter(|suggested| *suggested != glyph_name)
+        .unwrap_or_default();
+    metrics.suggested_name_in_use = !metrics.suggested_name.is_empty()
+        && fontir_state.has_glyph(&metrics.suggested_name);
+}
+
+/// Starts editing the codepoints when they are clicked, applies the edit
+/// when clicking elsewhere, and renames the glyph with the suggest button
+fn handle_glyph_pane_clicks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    ui_hover_state: Res<UiHoverState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    metrics: Res<CurrentGlyphMetrics>,
+    unicode_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<GlyphUnicodeButton>),
+    >,
+    suggest_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<GlyphSuggestNameButton>),
+    >,
+    mut pane_state: ResMut<GlyphPaneState>,
+    mut codepoint_events: EventWriter<SetGlyphCodepointsEvent>,
+    mut rename_events: EventWriter<RenameGlyphEvent>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    let glyph_name = &metrics.glyph_name;
+
+    if unicode_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+        && fontir_state.has_glyph(glyph_name)
+    {
+        if let Some(edit) = pane_state.finish_editing() {
+            codepoint_events.write(edit);
+        }
+        pane_state.input = metrics.unicode.clone();
+        pane_state.editing = Some(glyph_name.clone());
+        return;
+    }
+
+    if suggest_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        if let Some(edit) = pane_state.finish_editing() {
+            codepoint_events.write(edit);
+        }
+        // A name in use is only shown, renaming to it would fail
+        if !metrics.suggested_name.is_empty() && !metrics.suggested_name_in_use
+        {
+            rename_events.write(RenameGlyphEvent {
+                old_name: glyph_name.clone(),
+                new_name: metrics.suggested_name.clone(),
+            });
+        }
+        return;
+    }
+
+    // Switching glyphs or clicking outside the pane applies the edit
+    let switched_glyph = pane_state
+        .editing
+        .as_ref()
+        .is_some_and(|editing| editing != glyph_name);
+    if switched_glyph
+        || (mouse.just_pressed(MouseButton::Left)
+            && !ui_hover_state.is_hovering_ui)
+    {
+        if let Some(edit) = pane_state.finish_editing() {
+            codepoint_events.write(edit);
+        }
+    }
+}
+
 /// Calculate the bounding box of FontIR BezPaths
 fn calculate_fontir_bounds(paths: &[BezPath]) -> Option<(f32, f32)> {
     let mut min_x = f64::MAX;
@@ -0,0 +1,419 @@
+//! Language support pane
+//!
+//! Reports how well the font covers the bundled character sets and
+//! languages, see `data::charsets`. Each row shows how many characters of
+//! a set the font has and the codepoints it is missing, with a button that
+//! adds an empty placeholder glyph for each missing character. Placeholders
+//! get the suggested name of their character; characters whose name is
+//! already taken by a glyph without that codepoint are skipped.
+//!
+//! The pane opens and closes by running the Language Support operation of
+//! the AI tool, see `ToggleLanguageSupportEvent`, and follows glyphs added,
+//! renamed or re-encoded while open.
+
+use crate::core::state::FontIRAppState;
+use crate::data::charsets::{coverage_report, Coverage, CHARACTER_SETS};
+use crate::data::unicode::glyph_name_for_char;
+use crate::systems::commands::NewGlyphEvent;
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::tools::ai::ToggleLanguageSupportEvent;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::Display;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const LANGUAGE_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the character set name column
+const NAME_WIDTH: f32 = 170.0;
+
+/// Width of the supported / total column
+const COUNT_WIDTH: f32 = 90.0;
+
+/// Width of the missing codepoints column
+const MISSING_WIDTH: f32 = 330.0;
+
+/// Missing codepoints listed in a row before summing up the rest
+const MISSING_SHOWN: usize = 5;
+
+/// Color of the counts of fully supported sets
+const COMPLETE_COLOR: Color = ON_CURVE_PRIMARY_COLOR;
+
+/// Color of the counts of sets with missing characters
+const INCOMPLETE_COLOR: Color = PRESSED_BUTTON_COLOR;
+
+// ============================================================================
+// COMPONENTS & RESOURCES
+// ============================================================================
+
+/// Visibility of the pane and the coverage it shows
+#[derive(Resource, Default)]
+pub struct LanguageSupportPaneState {
+    pub visible: bool,
+    /// Coverage of each bundled character set, in `CHARACTER_SETS` order
+    pub report: Vec<Coverage>,
+    /// Result of the last placeholder glyphs added
+    pub message: String,
+}
+
+/// Component marker for the language support pane
+#[derive(Component, Default)]
+pub struct LanguageSupportPane;
+
+/// Component marker for the message under the title
+#[derive(Component)]
+pub struct LanguageMessageText;
+
+/// Supported and total characters of a character set
+#[derive(Component)]
+pub struct LanguageCoverageText {
+    pub index: usize,
+}
+
+/// Missing codepoints of a character set
+#[derive(Component)]
+pub struct LanguageMissingText {
+    pub index: usize,
+}
+
+/// Button adding placeholders for the missing characters of a set
+#[derive(Component)]
+pub struct AddMissingGlyphsButton {
+    pub index: usize,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct LanguageSupportPanePlugin;
+
+impl Plugin for LanguageSupportPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LanguageSupportPaneState>()
+            .add_systems(Startup, spawn_language_support_pane)
+            .add_systems(
+                Update,
+                (
+                    toggle_language_support_pane,
+                    refresh_language_report,
+                    handle_add_missing_glyphs,
+                    update_language_support_pane,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the language support pane, hidden, at the top right of the
+/// window
+pub fn spawn_language_support_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: LANGUAGE_TEXT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(TOOLBAR_CONTAINER_MARGIN + 4.0),
+                right: Val::Px(WIDGET_MARGIN),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            LanguageSupportPane,
+            Name::new("LanguageSupportPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Language Support"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                LanguageMessageText,
+            ));
+
+            for (index, set) in CHARACTER_SETS.iter().enumerate() {
+                spawn_language_row(pane, index, set.name, &text_font);
+            }
+        });
+}
+
+/// Spawns the row of a character set
+fn spawn_language_row(
+    pane: &mut ChildSpawnerCommands,
+    index: usize,
+    name: &str,
+    text_font: &TextFont,
+) {
+    pane.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            Node {
+                width: Val::Px(NAME_WIDTH),
+                ..default()
+            },
+            Text::new(name),
+            text_font.clone(),
+            TextColor(NORMAL_TEXT_COLOR),
+        ));
+        row.spawn((
+            Node {
+                width: Val::Px(COUNT_WIDTH),
+                ..default()
+            },
+            Text::new(""),
+            text_font.clone(),
+            TextColor(INCOMPLETE_COLOR),
+            LanguageCoverageText { index },
+        ));
+        row.spawn((
+            Node {
+                width: Val::Px(MISSING_WIDTH),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            Text::new(""),
+            text_font.clone(),
+            TextLayout::new_with_no_wrap(),
+            TextColor(SECONDARY_TEXT_COLOR),
+            LanguageMissingText { index },
+        ));
+        row.spawn((
+            Button,
+            Node {
+                padding: UiRect::horizontal(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+            AddMissingGlyphsButton { index },
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new("Add"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+        });
+    });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane when the AI tool asks for it
+fn toggle_language_support_pane(
+    mut toggle_events: EventReader<ToggleLanguageSupportEvent>,
+    mut pane_state: ResMut<LanguageSupportPaneState>,
+) {
+    for _ in toggle_events.read() {
+        pane_state.visible = !pane_state.visible;
+        if pane_state.visible {
+            info!("Showing language support report");
+        } else {
+            info!("Hiding language support report");
+        }
+    }
+}
+
+/// Works out the coverage again when the pane opens and when glyphs are
+/// added, renamed, deleted or re-encoded while it is open
+fn refresh_language_report(
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut pane_state: ResMut<LanguageSupportPaneState>,
+    mut last_refresh: Local<Option<u64>>,
+) {
+    if !pane_state.visible {
+        *last_refresh = None;
+        return;
+    }
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    let generation = fontir_state.glyph_set_generation;
+    if *last_refresh == Some(generation) {
+        return;
+    }
+    *last_refresh = Some(generation);
+
+    pane_state.report = coverage_report(&fontir_state.font_codepoints());
+    let complete = pane_state
+        .report
+        .iter()
+        .filter(|coverage| coverage.is_complete())
+        .count();
+    info!(
+        "Language support: {} of {} character sets complete",
+        complete,
+        pane_state.report.len()
+    );
+}
+
+/// Adds placeholder glyphs for the missing characters of a set when its
+/// button is pressed
+fn handle_add_missing_glyphs(
+    fontir_state: Option<Res<FontIRAppState>>,
+    button_query: Query<
+        (&Interaction, &AddMissingGlyphsButton),
+        Changed<Interaction>,
+    >,
+    mut pane_state: ResMut<LanguageSupportPaneState>,
+    mut new_glyph_events: EventWriter<NewGlyphEvent>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(coverage) = pane_state.report.get(button.index) else {
+            continue;
+        };
+
+        let mut added = 0;
+        let mut skipped = Vec::new();
+        for &c in &coverage.missing {
+            let glyph_name = glyph_name_for_char(c);
+            if fontir_state.has_glyph(&glyph_name) {
+                skipped.push(glyph_name);
+                continue;
+            }
+            new_glyph_events.write(NewGlyphEvent {
+                glyph_name,
+                codepoints: vec![c],
+            });
+            added += 1;
+        }
+
+        let mut message =
+            format!("Added {} placeholder glyphs for {}", added, coverage.name);
+        if !skipped.is_empty() {
+            warn!(
+                "Language support: glyphs named {} exist without their \
+                 codepoints",
+                skipped.join(", ")
+            );
+            message.push_str(&format!(
+                ", skipped {} names already in use",
+                skipped.len()
+            ));
+        }
+        info!("Language support: {}", message);
+        pane_state.message = message;
+    }
+}
+
+/// Shows or hides the pane and refreshes the rows
+fn update_language_support_pane(
+    pane_state: Res<LanguageSupportPaneState>,
+    mut pane_query: Query<&mut Node, With<LanguageSupportPane>>,
+    mut message_query: Query<&mut Text, With<LanguageMessageText>>,
+    mut coverage_query: Query<
+        (&LanguageCoverageText, &mut Text, &mut TextColor),
+        Without<LanguageMessageText>,
+    >,
+    mut missing_query: Query<
+        (&LanguageMissingText, &mut Text),
+        (Without<LanguageMessageText>, Without<LanguageCoverageText>),
+    >,
+    mut button_query: Query<
+        (&AddMissingGlyphsButton, &mut Node),
+        Without<LanguageSupportPane>,
+    >,
+) {
+    let display = if pane_state.visible {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in pane_query.iter_mut() {
+        if node.display != display {
+            node.display = display;
+        }
+    }
+    if !pane_state.is_changed() {
+        return;
+    }
+
+    for mut text in message_query.iter_mut() {
+        text.0.clone_from(&pane_state.message);
+    }
+    for (coverage_text, mut text, mut color) in coverage_query.iter_mut() {
+        let Some(coverage) = pane_state.report.get(coverage_text.index) else {
+            continue;
+        };
+        text.0 = format!("{}/{}", coverage.supported(), coverage.total);
+        color.0 = if coverage.is_complete() {
+            COMPLETE_COLOR
+        } else {
+            INCOMPLETE_COLOR
+        };
+    }
+    for (missing_text, mut text) in missing_query.iter_mut() {
+        let Some(coverage) = pane_state.report.get(missing_text.index) else {
+            continue;
+        };
+        text.0 = missing_summary(&coverage.missing);
+    }
+    for (button, mut node) in button_query.iter_mut() {
+        let complete = pane_state
+            .report
+            .get(button.index)
+            .is_none_or(Coverage::is_complete);
+        node.display = if complete {
+            Display::None
+        } else {
+            Display::Flex
+        };
+    }
+}
+
+/// The first missing codepoints and how many more there are
+fn missing_summary(missing: &[char]) -> String {
+    let mut summary = missing
+        .iter()
+        .take(MISSING_SHOWN)
+        .map(|&c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if missing.len() > MISSING_SHOWN {
+        summary.push_str(&format!(" +{}", missing.len() - MISSING_SHOWN));
+    }
+    summary
+}
@@ -1,9 +1,29 @@
 #![allow(unused_imports)]
 
+pub mod axes_pane;
+pub mod compatibility_pane;
 pub mod coord_pane;
 pub mod design_space;
+pub mod designspace_pane;
 pub mod file_pane;
+pub mod font_info_pane;
 pub mod glyph_pane;
+pub mod language_support_pane;
+pub mod name_entry_pane;
+pub mod outline_check_pane;
+pub mod reference_image_pane;
+pub mod settings_pane;
+pub mod variable_font_pane;
 
+pub use axes_pane::AxesPanePlugin;
+pub use compatibility_pane::CompatibilityPanePlugin;
 pub use design_space::DesignSpacePlugin;
+pub use designspace_pane::DesignspacePanePlugin;
 pub use file_pane::FilePanePlugin;
+pub use font_info_pane::FontInfoPanePlugin;
+pub use language_support_pane::LanguageSupportPanePlugin;
+pub use name_entry_pane::NameEntryPanePlugin;
+pub use outline_check_pane::OutlineCheckPanePlugin;
+pub use reference_image_pane::ReferenceImagePanePlugin;
+pub use settings_pane::SettingsPanePlugin;
+pub use variable_font_pane::VariableFontPanePlugin;
@@ -0,0 +1,441 @@
+//! Name entry pane
+//!
+//! A one-line text field for commands that need a name typed in. It opens at
+//! the bottom of the window with a prompt, takes every key press while open,
+//! applies the command on Enter and closes on Escape.
+//!
+//! Keyboard shortcuts:
+//! - Command/Ctrl+Shift+C adds a component of the typed glyph to the current
+//!   glyph
+//! - Command/Ctrl+Alt+C makes the selected component use the typed glyph as
+//!   its base
+//! - Command/Ctrl+Alt+N renames the selected anchors
+
+use crate::core::state::FontIRAppState;
+use crate::editing::anchors::{
+    AnchorReference, AnchorRename, RenameAnchorsEvent,
+};
+use crate::editing::glyph_components::{
+    AddComponentEvent, ComponentSelection, SetComponentBaseEvent,
+};
+use crate::editing::selection::components::Selected;
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::ui::text_field::{
+    handle_text_field_input, TextField, TextFieldPlugin,
+};
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::Display;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const NAME_ENTRY_TEXT_SIZE: f32 = 16.0;
+
+/// Width of the pane's content
+const NAME_ENTRY_WIDTH: f32 = 420.0;
+
+// ============================================================================
+// COMPONENTS, RESOURCES & EVENTS
+// ============================================================================
+
+/// What a typed name is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameEntryPurpose {
+    /// Add a component of the named glyph to the current glyph
+    AddComponent,
+    /// Make the selected component use the named glyph as its base
+    ComponentBase,
+    /// Rename the selected anchors
+    AnchorName,
+}
+
+impl NameEntryPurpose {
+    fn prompt(self) -> &'static str {
+        match self {
+            NameEntryPurpose::AddComponent => "Add component",
+            NameEntryPurpose::ComponentBase => "Component base",
+            NameEntryPurpose::AnchorName => "Anchor name",
+        }
+    }
+}
+
+/// The open name entry, if any
+#[derive(Resource, Default)]
+pub struct NameEntryState {
+    pub purpose: Option<NameEntryPurpose>,
+    /// Text typed so far
+    pub input: String,
+}
+
+impl NameEntryState {
+    /// Open the entry with some text already typed
+    pub fn start(&mut self, purpose: NameEntryPurpose, input: &str) {
+        self.purpose = Some(purpose);
+        self.input = input.to_string();
+    }
+
+    /// Close the entry, returning its purpose and the typed name unless the
+    /// name is blank
+    pub fn finish(&mut self) -> Option<(NameEntryPurpose, String)> {
+        let purpose = self.purpose.take()?;
+        let input = std::mem::take(&mut self.input);
+        let name = input.trim();
+        if name.is_empty() {
+            return None;
+        }
+        Some((purpose, name.to_string()))
+    }
+
+    /// Close the entry without applying it
+    pub fn cancel(&mut self) {
+        self.purpose = None;
+        self.input.clear();
+    }
+}
+
+impl TextField for NameEntryState {
+    type Edit = NameEnteredEvent;
+
+    const ALLOWS_SPACES: bool = false;
+
+    fn is_editing(&self) -> bool {
+        self.purpose.is_some()
+    }
+
+    fn input_mut(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    fn finish_editing(&mut self) -> Option<NameEnteredEvent> {
+        let (purpose, name) = self.finish()?;
+        Some(NameEnteredEvent { purpose, name })
+    }
+
+    fn cancel_editing(&mut self) {
+        self.cancel();
+    }
+}
+
+/// A name typed into the entry, to apply as its purpose says
+#[derive(Event, Debug, Clone)]
+pub struct NameEnteredEvent {
+    pub purpose: NameEntryPurpose,
+    pub name: String,
+}
+
+/// Component marker for the name entry pane
+#[derive(Component, Default)]
+pub struct NameEntryPane;
+
+/// Prompt and typed text of the pane
+#[derive(Component)]
+pub struct NameEntryText;
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct NameEntryPanePlugin;
+
+impl Plugin for NameEntryPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NameEntryState>()
+            .add_event::<NameEnteredEvent>()
+            .add_plugins(TextFieldPlugin::<NameEntryState>::default())
+            .add_systems(Startup, spawn_name_entry_pane)
+            .add_systems(
+                PreUpdate,
+                apply_name_entry
+                    .after(handle_text_field_input::<NameEntryState>),
+            )
+            .add_systems(
+                Update,
+                (start_name_entry, update_name_entry_pane).chain(),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the name entry pane, hidden, at the bottom center of the window
+pub fn spawn_name_entry_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(WIDGET_MARGIN),
+                left: Val::Percent(50.0),
+                width: Val::Px(NAME_ENTRY_WIDTH),
+                margin: UiRect::left(Val::Px(
+                    -(NAME_ENTRY_WIDTH / 2.0
+                        + WIDGET_PADDING
+                        + WIDGET_BORDER_WIDTH),
+                )),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            NameEntryPane,
+            Name::new("NameEntryPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new(""),
+                TextFont {
+                    font: asset_server.load(MONO_FONT_PATH),
+                    font_size: NAME_ENTRY_TEXT_SIZE,
+                    ..default()
+                },
+                TextColor(NORMAL_TEXT_COLOR),
+                NameEntryText,
+            ));
+        });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Opens the entry for the shortcut pressed
+fn start_name_entry(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    component_selection: Res<ComponentSelection>,
+    selected_anchors: Query<&AnchorReference, With<Selected>>,
+    mut entry: ResMut<NameEntryState>,
+) {
+    if entry.purpose.is_some() {
+        return;
+    }
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+    if !modifier_pressed {
+        return;
+    }
+    let Some(fontir_state) = fontir_state else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::KeyC) {
+        if shift_pressed && fontir_state.current_glyph.is_some() {
+            entry.start(NameEntryPurpose::AddComponent, "");
+        } else if alt_pressed {
+            let Some((glyph_name, index)) = &component_selection.selected
+            else {
+                debug!("Component base: no component selected");
+                return;
+            };
+            let base = fontir_state
+                .glyph_components(glyph_name)
+                .get(*index)
+                .map(|component| component.base_glyph.clone())
+                .unwrap_or_default();
+            entry.start(NameEntryPurpose::ComponentBase, &base);
+        }
+    } else if alt_pressed
+        && !shift_pressed
+        && keyboard.just_pressed(KeyCode::KeyN)
+    {
+        let Some(anchor_ref) = selected_anchors.iter().next() else {
+            debug!("Anchor name: no anchor selected");
+            return;
+        };
+        let name = fontir_state
+            .glyph_anchors(&anchor_ref.glyph_name)
+            .get(anchor_ref.anchor_index)
+            .map(|anchor| anchor.name.clone())
+            .unwrap_or_default();
+        entry.start(NameEntryPurpose::AnchorName, &name);
+    }
+}
+
+/// Applies the names typed into the entry
+fn apply_name_entry(
+    mut name_events: EventReader<NameEnteredEvent>,
+    mut add_component_events: EventWriter<AddComponentEvent>,
+    mut component_base_events: EventWriter<SetComponentBaseEvent>,
+    mut rename_anchor_events: EventWriter<RenameAnchorsEvent>,
+) {
+    for event in name_events.read() {
+        let name = event.name.clone();
+        match event.purpose {
+            NameEntryPurpose::AddComponent => {
+                add_component_events
+                    .write(AddComponentEvent { base_glyph: name });
+            }
+            NameEntryPurpose::ComponentBase => {
+                component_base_events
+                    .write(SetComponentBaseEvent { base_glyph: name });
+            }
+            NameEntryPurpose::AnchorName => {
+                rename_anchor_events.write(RenameAnchorsEvent {
+                    rename: AnchorRename::Set(name),
+                });
+            }
+        }
+    }
+}
+
+/// Shows the pane with its prompt and typed text while the entry is open
+fn update_name_entry_pane(
+    entry: Res<NameEntryState>,
+    mut pane_query: Query<&mut Node, With<NameEntryPane>>,
+    mut text_query: Query<&mut Text, With<NameEntryText>>,
+) {
+    if !entry.is_changed() {
+        return;
+    }
+    let display = if entry.purpose.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in pane_query.iter_mut() {
+        node.display = display;
+    }
+    if let Some(purpose) = entry.purpose {
+        for mut text in text_query.iter_mut() {
+            text.0 = format!("{}: {}|", purpose.prompt(), entry.input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::input::keyboard::{Key, KeyboardInput};
+    use bevy::input::ButtonState;
+
+    fn key_press(logical_key: Key) -> KeyboardInput {
+        KeyboardInput {
+            key_code: KeyCode::KeyA,
+            logical_key,
+            state: ButtonState::Pressed,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        }
+    }
+
+    #[test]
+    fn test_finish_name_entry() {
+        let mut entry = NameEntryState::default();
+        assert_eq!(entry.finish(), None);
+
+        entry.start(NameEntryPurpose::AddComponent, "");
+        entry.input.push_str(" acutecomb ");
+        assert_eq!(
+            entry.finish(),
+            Some((NameEntryPurpose::AddComponent, "acutecomb".to_string()))
+        );
+        assert_eq!(entry.purpose, None);
+        assert!(entry.input.is_empty());
+
+        entry.start(NameEntryPurpose::ComponentBase, "a");
+        entry.input.pop();
+        assert_eq!(entry.finish(), None);
+        assert_eq!(entry.purpose, None);
+
+        entry.start(NameEntryPurpose::ComponentBase, "a");
+        entry.cancel();
+        assert_eq!(entry.finish(), None);
+    }
+
+    #[test]
+    fn test_name_entry_sends_events() {
+        let mut world = World::new();
+        world.init_resource::<Events<KeyboardInput>>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<Events<AddComponentEvent>>();
+        world.init_resource::<Events<SetComponentBaseEvent>>();
+        world.init_resource::<Events<RenameAnchorsEvent>>();
+        world.init_resource::<Events<NameEnteredEvent>>();
+        world.init_resource::<NameEntryState>();
+        world
+            .resource_mut::<NameEntryState>()
+            .start(NameEntryPurpose::AddComponent, "");
+
+        world
+            .run_system_once(|mut keys: EventWriter<KeyboardInput>| {
+                keys.write(key_press(Key::Character("o".into())));
+                keys.write(key_press(Key::Character("x".into())));
+                keys.write(key_press(Key::Backspace));
+                keys.write(key_press(Key::Character("k".into())));
+                keys.write(key_press(Key::Enter));
+            })
+            .unwrap();
+        world
+            .run_system_once(handle_text_field_input::<NameEntryState>)
+            .unwrap();
+        world.run_system_once(apply_name_entry).unwrap();
+
+        let added: Vec<String> = world
+            .resource_mut::<Events<AddComponentEvent>>()
+            .drain()
+            .map(|event| event.base_glyph)
+            .collect();
+        assert_eq!(added, vec!["ok".to_string()]);
+        assert!(world
+            .resource_mut::<Events<SetComponentBaseEvent>>()
+            .drain()
+            .next()
+            .is_none());
+        assert_eq!(world.resource::<NameEntryState>().purpose, None);
+    }
+
+    #[test]
+    fn test_name_entry_renames_anchors() {
+        let mut world = World::new();
+        world.init_resource::<Events<KeyboardInput>>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<Events<AddComponentEvent>>();
+        world.init_resource::<Events<SetComponentBaseEvent>>();
+        world.init_resource::<Events<RenameAnchorsEvent>>();
+        world.init_resource::<Events<NameEnteredEvent>>();
+        world.init_resource::<NameEntryState>();
+        world
+            .resource_mut::<NameEntryState>()
+            .start(NameEntryPurpose::AnchorName, "top");
+
+        world
+            .run_system_once(|mut keys: EventWriter<KeyboardInput>| {
+                keys.write(key_press(Key::Character("_".into())));
+                keys.write(key_press(Key::Enter));
+            })
+            .unwrap();
+        world
+            .run_system_once(handle_text_field_input::<NameEntryState>)
+            .unwrap();
+        world.run_system_once(apply_name_entry).unwrap();
+
+        let renames: Vec<AnchorRename> = world
+            .resource_mut::<Events<RenameAnchorsEvent>>()
+            .drain()
+            .map(|event| event.rename)
+            .collect();
+        assert_eq!(renames, vec![AnchorRename::Set("top_".to_string())]);
+    }
+}
@@ -0,0 +1,435 @@
+//! Outline check pane
+//!
+//! Lists the problems `geometry::outline_check` finds in the current glyph
+//! or in every glyph of the font, with a button per problem applying its
+//! quick fix and one fixing everything listed. Command/Ctrl+Shift+P shows
+//! and hides the pane. While it is open the current glyph is checked again
+//! after every edit, see `systems::checks`, and the problems in it are
+//! marked on the canvas.
+//!
+//! Glyphs are checked at the current location, and fixes change the master
+//! being edited.
+
+use crate::core::state::{CheckScope, OutlineCheckState};
+use crate::systems::checks::run_outline_check;
+use crate::systems::commands::{FixOutlineEvent, OutlineFix};
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::Display;
+use std::collections::HashSet;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const OUTLINE_CHECK_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the problem descriptions
+const PROBLEM_WIDTH: f32 = 340.0;
+
+/// Problems listed at once; the rest are counted under the list
+const MAX_ROWS: usize = 12;
+
+// ============================================================================
+// COMPONENTS & RESOURCES
+// ============================================================================
+
+/// Component marker for the outline check pane
+#[derive(Component, Default)]
+pub struct OutlineCheckPane;
+
+/// Component marker for the summary under the title
+#[derive(Component)]
+pub struct OutlineCheckSummaryText;
+
+/// Buttons along the top of the pane
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineCheckButton {
+    Scope(CheckScope),
+    FixAll,
+}
+
+/// A row of the problem list
+#[derive(Component)]
+pub struct OutlineProblemRow {
+    pub index: usize,
+}
+
+/// Description of the problem shown in a row
+#[derive(Component)]
+pub struct OutlineProblemText {
+    pub index: usize,
+}
+
+/// Button applying the quick fix of the problem in a row
+#[derive(Component)]
+pub struct OutlineFixButton {
+    pub index: usize,
+}
+
+/// Label of a row's quick fix button
+#[derive(Component)]
+pub struct OutlineFixText {
+    pub index: usize,
+}
+
+/// Component marker for the count of problems not listed
+#[derive(Component)]
+pub struct OutlineCheckMoreText;
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct OutlineCheckPanePlugin;
+
+impl Plugin for OutlineCheckPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_outline_check_pane)
+            .add_systems(
+                Update,
+                (toggle_outline_check_pane, handle_outline_check_clicks)
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                update_outline_check_pane.after(run_outline_check),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the outline check pane, hidden, on the right side of the window
+pub fn spawn_outline_check_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: OUTLINE_CHECK_TEXT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(25.0),
+                right: Val::Px(WIDGET_MARGIN),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            OutlineCheckPane,
+            Name::new("OutlineCheckPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Outline Check"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+
+            pane.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(WIDGET_PADDING),
+                ..default()
+            })
+            .with_children(|buttons| {
+                for (button, label) in [
+                    (OutlineCheckButton::Scope(CheckScope::Glyph), "Glyph"),
+                    (OutlineCheckButton::Scope(CheckScope::Font), "Font"),
+                    (OutlineCheckButton::FixAll, "Fix all"),
+                ] {
+                    spawn_button(buttons, button, label, &text_font);
+                }
+            });
+
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                OutlineCheckSummaryText,
+            ));
+
+            for index in 0..MAX_ROWS {
+                spawn_problem_row(pane, index, &text_font);
+            }
+
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                OutlineCheckMoreText,
+            ));
+        });
+}
+
+fn spawn_button(
+    parent: &mut ChildSpawnerCommands,
+    button: OutlineCheckButton,
+    label: &str,
+    text_font: &TextFont,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::horizontal(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+            button,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+        });
+}
+
+/// Spawns a hidden row of the problem list
+fn spawn_problem_row(
+    pane: &mut ChildSpawnerCommands,
+    index: usize,
+    text_font: &TextFont,
+) {
+    pane.spawn((
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            display: Display::None,
+            ..default()
+        },
+        OutlineProblemRow { index },
+    ))
+    .with_children(|row| {
+        row.spawn((
+            Node {
+                width: Val::Px(PROBLEM_WIDTH),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            Text::new(""),
+            text_font.clone(),
+            TextLayout::new_with_no_wrap(),
+            TextColor(PRESSED_BUTTON_COLOR),
+            OutlineProblemText { index },
+        ));
+        row.spawn((
+            Button,
+            Node {
+                padding: UiRect::horizontal(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+            OutlineFixButton { index },
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+                OutlineFixText { index },
+            ));
+        });
+    });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane with Command/Ctrl+Shift+P
+fn toggle_outline_check_pane(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut check_state: ResMut<OutlineCheckState>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if modifier_pressed && shift_pressed && keyboard.just_pressed(KeyCode::KeyP)
+    {
+        check_state.visible = !check_state.visible;
+        if !check_state.visible {
+            check_state.problems.clear();
+        }
+    }
+}
+
+/// Switches the scope and sends the quick fixes of pressed buttons
+fn handle_outline_check_clicks(
+    mut check_state: ResMut<OutlineCheckState>,
+    button_query: Query<
+        (&Interaction, &OutlineCheckButton),
+        Changed<Interaction>,
+    >,
+    fix_query: Query<(&Interaction, &OutlineFixButton), Changed<Interaction>>,
+    mut fix_events: EventWriter<FixOutlineEvent>,
+) {
+    if !check_state.visible {
+        return;
+    }
+
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            OutlineCheckButton::Scope(scope) => {
+                if check_state.scope != scope {
+                    check_state.scope = scope;
+                    check_state.problems.clear();
+                }
+            }
+            OutlineCheckButton::FixAll => {
+                let glyph_names: HashSet<String> = check_state
+                    .problems
+                    .iter()
+                    .map(|(glyph_name, _)| glyph_name.clone())
+                    .collect();
+                for glyph_name in glyph_names {
+                    fix_events.write(FixOutlineEvent {
+                        glyph_name: glyph_name.clone(),
+                        fix: OutlineFix::All,
+                    });
+                    check_state.stale.insert(glyph_name);
+                }
+            }
+        }
+    }
+
+    for (interaction, button) in fix_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some((glyph_name, problem)) =
+            check_state.problems.get(button.index).cloned()
+        else {
+            continue;
+        };
+        fix_events.write(FixOutlineEvent {
+            glyph_name: glyph_name.clone(),
+            fix: OutlineFix::Problem(problem),
+        });
+        check_state.stale.insert(glyph_name);
+    }
+}
+
+/// Shows or hides the pane and refreshes the problem list
+#[allow(clippy::type_complexity)]
+fn update_outline_check_pane(
+    check_state: Res<OutlineCheckState>,
+    mut pane_query: Query<&mut Node, With<OutlineCheckPane>>,
+    mut row_query: Query<
+        (&OutlineProblemRow, &mut Node),
+        Without<OutlineCheckPane>,
+    >,
+    mut button_query: Query<(&OutlineCheckButton, &mut BackgroundColor)>,
+    mut text_query: ParamSet<(
+        Query<&mut Text, With<OutlineCheckSummaryText>>,
+        Query<&mut Text, With<OutlineCheckMoreText>>,
+        Query<(&OutlineProblemText, &mut Text)>,
+        Query<(&OutlineFixText, &mut Text)>,
+    )>,
+) {
+    if !check_state.is_changed() {
+        return;
+    }
+    let display = if check_state.visible {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut node in pane_query.iter_mut() {
+        node.display = display;
+    }
+    if !check_state.visible {
+        return;
+    }
+
+    for (button, mut background) in button_query.iter_mut() {
+        let active = *button == OutlineCheckButton::Scope(check_state.scope);
+        background.0 = if active {
+            PRESSED_BUTTON_COLOR
+        } else {
+            NORMAL_BUTTON_COLOR
+        };
+    }
+
+    let problems = &check_state.problems;
+    let glyph_count = problems
+        .iter()
+        .map(|(glyph_name, _)| glyph_name)
+        .collect::<HashSet<_>>()
+        .len();
+    let summary = match (problems.len(), check_state.scope) {
+        (0, _) => "No problems found".to_string(),
+        (count, CheckScope::Glyph) => format!("{count} problem(s)"),
+        (count, CheckScope::Font) => {
+            format!("{count} problem(s) in {glyph_count} glyph(s)")
+        }
+    };
+    for mut text in text_query.p0().iter_mut() {
+        text.0.clone_from(&summary);
+    }
+    let more = match problems.len().saturating_sub(MAX_ROWS) {
+        0 => String::new(),
+        hidden => format!("and {hidden} more"),
+    };
+    for mut text in text_query.p1().iter_mut() {
+        text.0.clone_from(&more);
+    }
+
+    for (row, mut node) in row_query.iter_mut() {
+        node.display = if row.index < problems.len() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    for (problem_text, mut text) in text_query.p2().iter_mut() {
+        let Some((glyph_name, problem)) = problems.get(problem_text.index)
+        else {
+            continue;
+        };
+        let description = format!(
+            "{}, contour {}",
+            problem.kind.description(),
+            problem.contour_index + 1
+        );
+        text.0 = match check_state.scope {
+            CheckScope::Glyph => description,
+            CheckScope::Font => format!("{glyph_name}: {description}"),
+        };
+    }
+    for (fix_text, mut text) in text_query.p3().iter_mut() {
+        if let Some((_, problem)) = problems.get(fix_text.index) {
+            text.0 = problem.kind.fix_label().to_string();
+        }
+    }
+}
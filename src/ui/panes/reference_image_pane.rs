@@ -0,0 +1,790 @@
+//! Reference image pane
+//!
+//! Places a reference image, such as a sketch to trace, in the glyph being
+//! edited at the current master. Dropping a PNG or JPEG file on the window
+//! adds it, replacing the glyph's image; sliders then set its position,
+//! scale, rotation and opacity, scaling and rotating about the image's
+//! center. Command/Ctrl+Alt+P shows or hides the pane.
+//!
+//! The Trace button turns the image into contours of the glyph, in the
+//! background, and a file dropped with Alt held is traced the same way
+//! without being added. The threshold, tolerance and corner sliders set
+//! how images are traced.
+//!
+//! Images are stored the UFO way, in the glif `image` element and the
+//! UFO's `images` directory, and written on save like any other edit.
+
+use crate::core::settings::{AutotraceSettings, BezySettings};
+use crate::core::state::{FontIRAppState, ImageData};
+use crate::data::images::{is_image_file, png_size};
+use crate::systems::commands::{AutotraceEvent, AutotraceTasks, TraceSource};
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::{Display, RelativeCursorPosition};
+use kurbo::Point;
+use std::path::PathBuf;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const REFERENCE_IMAGE_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the parameter name column
+const PARAMETER_NAME_WIDTH: f32 = 80.0;
+
+/// Length of a slider track
+const SLIDER_WIDTH: f32 = 240.0;
+
+/// Height of a slider track
+const SLIDER_HEIGHT: f32 = 6.0;
+
+/// Size of a slider's thumb
+const THUMB_SIZE: f32 = 14.0;
+
+/// Width of the parameter value column
+const PARAMETER_VALUE_WIDTH: f32 = 70.0;
+
+/// Width of the pane's content
+const REFERENCE_IMAGE_PANE_WIDTH: f32 = PARAMETER_NAME_WIDTH
+    + SLIDER_WIDTH
+    + PARAMETER_VALUE_WIDTH
+    + 2.0 * WIDGET_PADDING;
+
+/// Smallest change a drag sends on, as a fraction of the slider
+const SLIDER_STEP: f64 = 1e-3;
+
+/// Scale range of the scale slider, in units per pixel; the slider is
+/// logarithmic
+const MIN_IMAGE_SCALE: f64 = 0.01;
+const MAX_IMAGE_SCALE: f64 = 10.0;
+
+/// Range of the tolerance slider, in font units
+const MIN_TRACE_TOLERANCE: f64 = 0.1;
+const MAX_TRACE_TOLERANCE: f64 = 20.0;
+
+// ============================================================================
+// COMPONENTS, RESOURCES & EVENTS
+// ============================================================================
+
+/// A setting of the reference image's placement, or of how images are
+/// traced, see `AutotraceSettings`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageParameter {
+    X,
+    Y,
+    Scale,
+    Rotation,
+    Opacity,
+    Threshold,
+    Tolerance,
+    CornerAngle,
+}
+
+impl ImageParameter {
+    const ALL: [ImageParameter; 8] = [
+        ImageParameter::X,
+        ImageParameter::Y,
+        ImageParameter::Scale,
+        ImageParameter::Rotation,
+        ImageParameter::Opacity,
+        ImageParameter::Threshold,
+        ImageParameter::Tolerance,
+        ImageParameter::CornerAngle,
+    ];
+
+    /// Whether the parameter sets how images are traced rather than where
+    /// the image is
+    fn is_trace_setting(self) -> bool {
+        matches!(
+            self,
+            ImageParameter::Threshold
+                | ImageParameter::Tolerance
+                | ImageParameter::CornerAngle
+        )
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ImageParameter::X => "X",
+            ImageParameter::Y => "Y",
+            ImageParameter::Scale => "Scale",
+            ImageParameter::Rotation => "Rotation",
+            ImageParameter::Opacity => "Opacity",
+            ImageParameter::Threshold => "Threshold",
+            ImageParameter::Tolerance => "Tolerance",
+            ImageParameter::CornerAngle => "Corners",
+        }
+    }
+
+    /// Slider range, positions covering a few ems around the glyph
+    fn range(self, units_per_em: f64) -> (f64, f64) {
+        match self {
+            ImageParameter::X | ImageParameter::Y => {
+                (-units_per_em, 2.0 * units_per_em)
+            }
+            ImageParameter::Scale => (MIN_IMAGE_SCALE, MAX_IMAGE_SCALE),
+            ImageParameter::Rotation => (-180.0, 180.0),
+            ImageParameter::Opacity | ImageParameter::Threshold => (0.0, 1.0),
+            ImageParameter::Tolerance => {
+                (MIN_TRACE_TOLERANCE, MAX_TRACE_TOLERANCE)
+            }
+            ImageParameter::CornerAngle => (0.0, 180.0),
+        }
+    }
+
+    fn value(self, image: &ImageData, autotrace: &AutotraceSettings) -> f64 {
+        match self {
+            ImageParameter::X => image.x,
+            ImageParameter::Y => image.y,
+            ImageParameter::Scale => image.x_scale,
+            ImageParameter::Rotation => image.rotation,
+            ImageParameter::Opacity => image.opacity,
+            ImageParameter::Threshold => autotrace.threshold,
+            ImageParameter::Tolerance => autotrace.tolerance,
+            ImageParameter::CornerAngle => autotrace.corner_angle,
+        }
+    }
+
+    /// Position of a value along the slider, from 0 to 1
+    fn fraction(self, value: f64, units_per_em: f64) -> f64 {
+        let (minimum, maximum) = self.range(units_per_em);
+        let fraction = match self {
+            ImageParameter::Scale => {
+                (value.max(minimum) / minimum).ln() / (maximum / minimum).ln()
+            }
+            _ => (value - minimum) / (maximum - minimum),
+        };
+        fraction.clamp(0.0, 1.0)
+    }
+
+    /// Value at a position along the slider, from 0 to 1
+    fn value_at(self, fraction: f64, units_per_em: f64) -> f64 {
+        let (minimum, maximum) = self.range(units_per_em);
+        match self {
+            ImageParameter::Scale => {
+                minimum * (maximum / minimum).powf(fraction)
+            }
+            _ => minimum + fraction * (maximum - minimum),
+        }
+    }
+
+    fn format(self, value: f64) -> String {
+        match self {
+            ImageParameter::X | ImageParameter::Y => format!("{value:.0}"),
+            ImageParameter::Scale => format!("{value:.3}"),
+            ImageParameter::Rotation => format!("{value:.1}°"),
+            ImageParameter::Opacity | ImageParameter::Threshold => {
+                format!("{:.0}%", value * 100.0)
+            }
+            ImageParameter::Tolerance => format!("{value:.1}"),
+            ImageParameter::CornerAngle => format!("{value:.0}°"),
+        }
+    }
+
+    /// Set the parameter; scaling and rotating keep `center` in place,
+    /// and scaling keeps the image's proportions and mirroring
+    fn apply(
+        self,
+        image: &mut ImageData,
+        autotrace: &mut AutotraceSettings,
+        value: f64,
+        center: Point,
+    ) {
+        match self {
+            ImageParameter::X => image.x = value,
+            ImageParameter::Y => image.y = value,
+            ImageParameter::Scale if image.x_scale > 0.0 => {
+                image.scale_about(value / image.x_scale, center);
+            }
+            ImageParameter::Scale => {}
+            ImageParameter::Rotation => {
+                image.rotate_about(value - image.rotation, center);
+            }
+            ImageParameter::Opacity => image.opacity = value.clamp(0.0, 1.0),
+            ImageParameter::Threshold => {
+                autotrace.threshold = value.clamp(0.0, 1.0);
+            }
+            ImageParameter::Tolerance => {
+                autotrace.tolerance = value.max(MIN_TRACE_TOLERANCE);
+            }
+            ImageParameter::CornerAngle => {
+                autotrace.corner_angle = value.clamp(0.0, 180.0);
+            }
+        }
+    }
+}
+
+/// Component marker for the reference image pane
+#[derive(Component, Default)]
+pub struct ReferenceImagePane;
+
+/// Tells which glyph the pane works on and its image's file
+#[derive(Component)]
+pub struct ReferenceImageStatusText;
+
+/// Container of the parameter rows and the remove button, shown while the
+/// glyph has an image
+#[derive(Component)]
+pub struct ReferenceImageRows;
+
+/// Slider track of a parameter
+#[derive(Component)]
+pub struct ReferenceImageSlider {
+    pub parameter: ImageParameter,
+}
+
+/// Thumb of a parameter slider
+#[derive(Component)]
+pub struct ReferenceImageSliderThumb {
+    pub parameter: ImageParameter,
+}
+
+/// Value of a parameter
+#[derive(Component)]
+pub struct ReferenceImageValueText {
+    pub parameter: ImageParameter,
+}
+
+/// Traces the glyph's image into contours
+#[derive(Component)]
+pub struct ReferenceImageTraceButton;
+
+/// Removes the glyph's image
+#[derive(Component)]
+pub struct ReferenceImageRemoveButton;
+
+/// Whether the pane is shown
+#[derive(Resource, Default)]
+pub struct ReferenceImagePaneState {
+    pub visible: bool,
+}
+
+/// Add an image file as the reference image of a glyph at the current
+/// master
+#[derive(Event, Debug, Clone)]
+pub struct AddReferenceImageEvent {
+    pub glyph_name: String,
+    pub path: PathBuf,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct ReferenceImagePanePlugin;
+
+impl Plugin for ReferenceImagePanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReferenceImagePaneState>()
+            .add_event::<AddReferenceImageEvent>()
+            .add_systems(Startup, spawn_reference_image_pane)
+            .add_systems(
+                Update,
+                (
+                    toggle_reference_image_pane,
+                    handle_dropped_image_files,
+                    handle_add_reference_image,
+                    handle_reference_image_sliders,
+                    handle_reference_image_trace,
+                    handle_reference_image_remove,
+                    update_reference_image_pane,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the reference image pane, hidden, at the top center of the window
+pub fn spawn_reference_image_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: REFERENCE_IMAGE_TEXT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(TOOLBAR_CONTAINER_MARGIN + 4.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(
+                    -(REFERENCE_IMAGE_PANE_WIDTH / 2.0
+                        + WIDGET_PADDING
+                        + WIDGET_BORDER_WIDTH),
+                )),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            ReferenceImagePane,
+            Name::new("ReferenceImagePane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Reference Image"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                ReferenceImageStatusText,
+            ));
+            pane.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(WIDGET_ROW_LEADING),
+                    display: Display::None,
+                    ..default()
+                },
+                ReferenceImageRows,
+            ))
+            .with_children(|rows| {
+                for parameter in ImageParameter::ALL {
+                    spawn_parameter_row(rows, parameter, &text_font);
+                }
+                rows.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(WIDGET_PADDING),
+                    ..default()
+                })
+                .with_children(|buttons| {
+                    spawn_button(
+                        buttons,
+                        "Trace",
+                        ReferenceImageTraceButton,
+                        &text_font,
+                    );
+                    spawn_button(
+                        buttons,
+                        "Remove image",
+                        ReferenceImageRemoveButton,
+                        &text_font,
+                    );
+                });
+            });
+        });
+}
+
+/// Spawns a text button with a marker component
+fn spawn_button(
+    parent: &mut ChildSpawnerCommands,
+    label: &str,
+    marker: impl Component,
+    text_font: &TextFont,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::horizontal(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+        });
+}
+
+/// Spawns the row of a parameter: its name, slider and value
+fn spawn_parameter_row(
+    rows: &mut ChildSpawnerCommands,
+    parameter: ImageParameter,
+    text_font: &TextFont,
+) {
+    rows.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(WIDGET_PADDING),
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            Node {
+                width: Val::Px(PARAMETER_NAME_WIDTH),
+                ..default()
+            },
+            Text::new(parameter.label()),
+            text_font.clone(),
+            TextColor(NORMAL_TEXT_COLOR),
+        ));
+        row.spawn((
+            Node {
+                width: Val::Px(SLIDER_WIDTH),
+                height: Val::Px(SLIDER_HEIGHT),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderRadius::all(Val::Px(SLIDER_HEIGHT / 2.0)),
+            Interaction::default(),
+            RelativeCursorPosition::default(),
+            ReferenceImageSlider { parameter },
+        ))
+        .with_children(|track| {
+            track.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(THUMB_SIZE),
+                    height: Val::Px(THUMB_SIZE),
+                    margin: UiRect::left(Val::Px(-THUMB_SIZE / 2.0)),
+                    ..default()
+                },
+                BackgroundColor(NORMAL_TEXT_COLOR),
+                BorderRadius::all(Val::Px(THUMB_SIZE / 2.0)),
+                ReferenceImageSliderThumb { parameter },
+            ));
+        });
+        row.spawn((
+            Node {
+                width: Val::Px(PARAMETER_VALUE_WIDTH),
+                ..default()
+            },
+            Text::new(""),
+            text_font.clone(),
+            TextColor(SECONDARY_TEXT_COLOR),
+            ReferenceImageValueText { parameter },
+        ));
+    });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane with Command/Ctrl+Alt+P
+fn toggle_reference_image_pane(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut pane_state: ResMut<ReferenceImagePaneState>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if modifier_pressed
+        && alt_pressed
+        && !shift_pressed
+        && keyboard.just_pressed(KeyCode::KeyP)
+    {
+        pane_state.visible = !pane_state.visible;
+    }
+}
+
+/// Adds PNG and JPEG files dropped on the window to the glyph being
+/// edited, or traces them into it when Alt is held
+fn handle_dropped_image_files(
+    mut drops: EventReader<FileDragAndDrop>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut add_events: EventWriter<AddReferenceImageEvent>,
+    mut trace_events: EventWriter<AutotraceEvent>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        if !is_image_file(path_buf) {
+            debug!("Ignoring dropped file {}", path_buf.display());
+            continue;
+        }
+        let Some(glyph_name) = fontir_state
+            .as_ref()
+            .and_then(|state| state.current_glyph.clone())
+        else {
+            warn!("No glyph to add {} to", path_buf.display());
+            continue;
+        };
+        if keyboard.pressed(KeyCode::AltLeft)
+            || keyboard.pressed(KeyCode::AltRight)
+        {
+            trace_events.write(AutotraceEvent {
+                glyph_name,
+                source: TraceSource::File(path_buf.clone()),
+            });
+            continue;
+        }
+        add_events.write(AddReferenceImageEvent {
+            glyph_name,
+            path: path_buf.clone(),
+        });
+    }
+}
+
+/// Adds image files to glyphs and shows the pane to place them
+fn handle_add_reference_image(
+    mut events: EventReader<AddReferenceImageEvent>,
+    mut fontir_state: Option<ResMut<FontIRAppState>>,
+    mut pane_state: ResMut<ReferenceImagePaneState>,
+) {
+    for event in events.read() {
+        let Some(fontir_state) = fontir_state.as_mut() else {
+            return;
+        };
+        match fontir_state.add_glyph_image(&event.glyph_name, &event.path) {
+            Ok(image) => {
+                info!(
+                    "Added {} to '{}' as {}",
+                    event.path.display(),
+                    event.glyph_name,
+                    image.file_name
+                );
+                pane_state.visible = true;
+            }
+            Err(e) => warn!(
+                "Cannot add {} to '{}': {}",
+                event.path.display(),
+                event.glyph_name,
+                e
+            ),
+        }
+    }
+}
+
+/// Sets the image's placement, or how images are traced, while a slider
+/// is pressed or dragged
+fn handle_reference_image_sliders(
+    pane_state: Res<ReferenceImagePaneState>,
+    fontir_state: Option<ResMut<FontIRAppState>>,
+    mut settings: ResMut<BezySettings>,
+    slider_query: Query<(
+        &Interaction,
+        &RelativeCursorPosition,
+        &ReferenceImageSlider,
+    )>,
+) {
+    let Some(mut fontir_state) = fontir_state else {
+        return;
+    };
+    if !pane_state.visible {
+        return;
+    }
+    for (interaction, cursor, slider) in slider_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(cursor) = cursor.normalized else {
+            continue;
+        };
+        let Some(glyph_name) = fontir_state.current_glyph.clone() else {
+            continue;
+        };
+        let Some((mut image, _, data)) =
+            fontir_state.glyph_image_file(&glyph_name)
+        else {
+            continue;
+        };
+
+        // The cursor position runs from 0 at the left of the track to 1 at
+        // the right
+        let units_per_em = fontir_state.get_font_metrics().units_per_em as f64;
+        let parameter = slider.parameter;
+        let fraction = (cursor.x as f64).clamp(0.0, 1.0);
+        let mut autotrace = settings.autotrace;
+        let current = parameter
+            .fraction(parameter.value(&image, &autotrace), units_per_em);
+        if (fraction - current).abs() < SLIDER_STEP {
+            continue;
+        }
+        let (width, height) = png_size(&data).unwrap_or_default();
+        let center = image.affine()
+            * Point::new(width as f64 / 2.0, height as f64 / 2.0);
+        parameter.apply(
+            &mut image,
+            &mut autotrace,
+            parameter.value_at(fraction, units_per_em),
+            center,
+        );
+        if parameter.is_trace_setting() {
+            settings.autotrace = autotrace;
+            continue;
+        }
+        if let Err(e) = fontir_state.set_glyph_image(&glyph_name, Some(image)) {
+            warn!("Cannot place the image of '{}': {}", glyph_name, e);
+        }
+    }
+}
+
+/// Traces the image of the glyph being edited into its contours
+fn handle_reference_image_trace(
+    button_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<ReferenceImageTraceButton>),
+    >,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut trace_events: EventWriter<AutotraceEvent>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let Some(glyph_name) = fontir_state
+        .as_ref()
+        .and_then(|state| state.current_glyph.clone())
+    else {
+        return;
+    };
+    trace_events.write(AutotraceEvent {
+        glyph_name,
+        source: TraceSource::ReferenceImage,
+    });
+}
+
+/// Removes the image of the glyph being edited
+fn handle_reference_image_remove(
+    button_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<ReferenceImageRemoveButton>),
+    >,
+    fontir_state: Option<ResMut<FontIRAppState>>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let Some(mut fontir_state) = fontir_state else {
+        return;
+    };
+    let Some(glyph_name) = fontir_state.current_glyph.clone() else {
+        return;
+    };
+    match fontir_state.set_glyph_image(&glyph_name, None) {
+        Ok(()) => info!("Removed the image of '{}'", glyph_name),
+        Err(e) => warn!("Cannot remove the image of '{}': {}", glyph_name, e),
+    }
+}
+
+/// Refreshes the pane's visibility, status, thumbs and values
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn update_reference_image_pane(
+    pane_state: Res<ReferenceImagePaneState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    settings: Res<BezySettings>,
+    autotrace_tasks: Res<AutotraceTasks>,
+    mut pane_query: Query<
+        &mut Node,
+        (
+            With<ReferenceImagePane>,
+            Without<ReferenceImageRows>,
+            Without<ReferenceImageSliderThumb>,
+        ),
+    >,
+    mut status_query: Query<&mut Text, With<ReferenceImageStatusText>>,
+    mut rows_query: Query<
+        &mut Node,
+        (With<ReferenceImageRows>, Without<ReferenceImageSliderThumb>),
+    >,
+    mut value_query: Query<
+        (&ReferenceImageValueText, &mut Text),
+        Without<ReferenceImageStatusText>,
+    >,
+    mut thumb_query: Query<(&ReferenceImageSliderThumb, &mut Node)>,
+) {
+    let fontir_changed = fontir_state
+        .as_ref()
+        .is_some_and(|fontir_state| fontir_state.is_changed());
+    if !pane_state.is_changed()
+        && !fontir_changed
+        && !settings.is_changed()
+        && !autotrace_tasks.is_changed()
+    {
+        return;
+    }
+    for mut node in pane_query.iter_mut() {
+        node.display = if pane_state.visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+
+    let glyph_name = fontir_state.current_glyph.clone();
+    let image = glyph_name
+        .as_deref()
+        .and_then(|glyph_name| fontir_state.glyph_image(glyph_name));
+    let status = match (&glyph_name, &image) {
+        (None, _) => "No glyph is being edited".to_string(),
+        (Some(glyph_name), _) if autotrace_tasks.is_tracing(glyph_name) => {
+            format!("Tracing into '{}'...", glyph_name)
+        }
+        (Some(glyph_name), Some(image)) => {
+            format!("{} in '{}'", image.file_name, glyph_name)
+        }
+        (Some(glyph_name), None) => format!(
+            "Drop a PNG or JPEG file on the window to add it to '{}'",
+            glyph_name
+        ),
+    };
+    for mut text in status_query.iter_mut() {
+        if text.0 != status {
+            text.0.clone_from(&status);
+        }
+    }
+    for mut node in rows_query.iter_mut() {
+        node.display = if image.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    let Some(image) = image else {
+        return;
+    };
+    let units_per_em = fontir_state.get_font_metrics().units_per_em as f64;
+    let autotrace = &settings.autotrace;
+    for (value_text, mut text) in value_query.iter_mut() {
+        let value = value_text
+            .parameter
+            .format(value_text.parameter.value(&image, autotrace));
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+    for (thumb, mut node) in thumb_query.iter_mut() {
+        let fraction = thumb
+            .parameter
+            .fraction(thumb.parameter.value(&image, autotrace), units_per_em);
+        node.left = Val::Percent(100.0 * fraction as f32);
+    }
+}
@@ -0,0 +1,475 @@
+//! Settings pane
+//!
+//! Shows the settings of the editing commands and lets the user change them
+//! while working: each row steps its value down or up, or switches it on
+//! and off. Snap target kinds have a reach, where zero turns them off, and a
+//! priority, where + moves them ahead of the others. Command/Ctrl+Comma
+//! shows or hides the pane. Changes take effect on the next command and
+//! last until the editor is closed.
+
+use crate::core::settings::BezySettings;
+use crate::geometry::offset::{Cap, Join};
+use crate::geometry::snapping::SnapKind;
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::Display;
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const SETTINGS_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the setting name column
+const SETTING_NAME_WIDTH: f32 = 200.0;
+
+/// Width of the setting value column
+const SETTING_VALUE_WIDTH: f32 = 90.0;
+
+/// Width of the pane's content
+const SETTINGS_PANE_WIDTH: f32 =
+    SETTING_NAME_WIDTH + SETTING_VALUE_WIDTH + 80.0;
+
+// ============================================================================
+// COMPONENTS & RESOURCES
+// ============================================================================
+
+/// A setting shown in the pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingField {
+    SimplifyTolerance,
+    SimplifyCornerAngle,
+    SimplifyKeepExtrema,
+    SimplifyToolOutput,
+    OffsetDistance,
+    StrokeWidth,
+    OffsetJoin,
+    StrokeCap,
+    OffsetMiterLimit,
+    SnapEnabled,
+    SnapThreshold(SnapKind),
+    SnapPriority(SnapKind),
+}
+
+/// The pane's sections, each a heading and its settings
+const SECTIONS: &[(&str, &[SettingField])] = &[
+    (
+        "Simplify",
+        &[
+            SettingField::SimplifyTolerance,
+            SettingField::SimplifyCornerAngle,
+            SettingField::SimplifyKeepExtrema,
+            SettingField::SimplifyToolOutput,
+        ],
+    ),
+    (
+        "Offset & Stroke",
+        &[
+            SettingField::OffsetDistance,
+            SettingField::StrokeWidth,
+            SettingField::OffsetJoin,
+            SettingField::StrokeCap,
+            SettingField::OffsetMiterLimit,
+        ],
+    ),
+    (
+        "Snapping",
+        &[
+            SettingField::SnapEnabled,
+            SettingField::SnapThreshold(SnapKind::Point),
+            SettingField::SnapPriority(SnapKind::Point),
+            SettingField::SnapThreshold(SnapKind::Extremum),
+            SettingField::SnapPriority(SnapKind::Extremum),
+            SettingField::SnapThreshold(SnapKind::Guideline),
+            SettingField::SnapPriority(SnapKind::Guideline),
+            SettingField::SnapThreshold(SnapKind::Metric),
+            SettingField::SnapPriority(SnapKind::Metric),
+            SettingField::SnapThreshold(SnapKind::Alignment),
+            SettingField::SnapPriority(SnapKind::Alignment),
+        ],
+    ),
+];
+
+impl SettingField {
+    fn label(self) -> String {
+        let label = match self {
+            SettingField::SimplifyTolerance => "Tolerance",
+            SettingField::SimplifyCornerAngle => "Corner angle",
+            SettingField::SimplifyKeepExtrema => "Keep extrema",
+            SettingField::SimplifyToolOutput => "Simplify tool output",
+            SettingField::OffsetDistance => "Offset distance",
+            SettingField::StrokeWidth => "Stroke width",
+            SettingField::OffsetJoin => "Join",
+            SettingField::StrokeCap => "Cap",
+            SettingField::OffsetMiterLimit => "Miter limit",
+            SettingField::SnapEnabled => "Snap",
+            SettingField::SnapThreshold(kind) => {
+                return format!("{} reach", snap_kind_name(kind));
+            }
+            SettingField::SnapPriority(kind) => {
+                return format!("{} priority", snap_kind_name(kind));
+            }
+        };
+        label.to_string()
+    }
+
+    /// The setting's current value as shown in the pane
+    fn display(self, settings: &BezySettings) -> String {
+        match self {
+            SettingField::SimplifyTolerance => {
+                format!("{:.2}", settings.simplify.tolerance)
+            }
+            SettingField::SimplifyCornerAngle => {
+                format!("{:.0}°", settings.simplify.corner_angle)
+            }
+            SettingField::SimplifyKeepExtrema => {
+                on_off(settings.simplify.keep_extrema)
+            }
+            SettingField::SimplifyToolOutput => {
+                on_off(settings.simplify.apply_to_tool_output)
+            }
+            SettingField::OffsetDistance => {
+                format!("{:.0}", settings.offset.distance)
+            }
+            SettingField::StrokeWidth => {
+                format!("{:.0}", settings.offset.stroke_width)
+            }
+            SettingField::OffsetJoin => format!("{:?}", settings.offset.join),
+            SettingField::StrokeCap => format!("{:?}", settings.offset.cap),
+            SettingField::OffsetMiterLimit => {
+                format!("{:.1}", settings.offset.miter_limit)
+            }
+            SettingField::SnapEnabled => on_off(settings.snap.enabled),
+            SettingField::SnapThreshold(kind) => settings
+                .snap
+                .threshold(kind)
+                .map_or("off".to_string(), |reach| format!("{reach:.0}")),
+            SettingField::SnapPriority(kind) => settings
+                .snap
+                .priority(kind)
+                .map_or("-".to_string(), |rank| rank.to_string()),
+        }
+    }
+
+    /// Step the setting down (negative) or up (positive); switches flip
+    /// either way and choices cycle through their options
+    fn step(self, settings: &mut BezySettings, steps: i32) {
+        let steps = steps as f64;
+        match self {
+            SettingField::SimplifyTolerance => {
+                let tolerance = &mut settings.simplify.tolerance;
+                *tolerance = (*tolerance + 0.25 * steps).clamp(0.25, 20.0);
+            }
+            SettingField::SimplifyCornerAngle => {
+                let angle = &mut settings.simplify.corner_angle;
+                *angle = (*angle + 5.0 * steps).clamp(5.0, 175.0);
+            }
+            SettingField::SimplifyKeepExtrema => {
+                settings.simplify.keep_extrema ^= true;
+            }
+            SettingField::SimplifyToolOutput => {
+                settings.simplify.apply_to_tool_output ^= true;
+            }
+            SettingField::OffsetDistance => {
+                let distance = &mut settings.offset.distance;
+                *distance = (*distance + steps).clamp(1.0, 200.0);
+            }
+            SettingField::StrokeWidth => {
+                let width = &mut settings.offset.stroke_width;
+                *width = (*width + 2.0 * steps).clamp(2.0, 400.0);
+            }
+            SettingField::OffsetJoin => {
+                settings.offset.join = cycle(
+                    &[Join::Miter, Join::Round, Join::Bevel],
+                    settings.offset.join,
+                    steps,
+                );
+            }
+            SettingField::StrokeCap => {
+                settings.offset.cap = cycle(
+                    &[Cap::Butt, Cap::Round, Cap::Square],
+                    settings.offset.cap,
+                    steps,
+                );
+            }
+            SettingField::OffsetMiterLimit => {
+                let limit = &mut settings.offset.miter_limit;
+                *limit = (*limit + 0.5 * steps).clamp(1.0, 20.0);
+            }
+            SettingField::SnapEnabled => settings.snap.enabled ^= true,
+            SettingField::SnapThreshold(kind) => {
+                let reach = settings.snap.threshold(kind).unwrap_or(0.0);
+                let reach = (reach + steps).clamp(0.0, 40.0);
+                settings.snap.set_threshold(kind, reach);
+            }
+            SettingField::SnapPriority(kind) => {
+                settings.snap.raise_priority(kind, steps as i32);
+            }
+        }
+    }
+}
+
+fn snap_kind_name(kind: SnapKind) -> &'static str {
+    match kind {
+        SnapKind::Point => "Point",
+        SnapKind::Extremum => "Extremum",
+        SnapKind::Guideline => "Guideline",
+        SnapKind::Metric => "Metric",
+        SnapKind::Alignment => "Alignment",
+    }
+}
+
+/// The option `steps` places after `current`, wrapping around
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, steps: f64) -> T {
+    let index = options.iter().position(|&o| o == current).unwrap_or(0);
+    let len = options.len() as i64;
+    options[(index as i64 + steps as i64).rem_euclid(len) as usize]
+}
+
+fn on_off(value: bool) -> String {
+    if value { "on" } else { "off" }.to_string()
+}
+
+/// Component marker for the settings pane
+#[derive(Component, Default)]
+pub struct SettingsPane;
+
+/// Steps a setting down or up when pressed
+#[derive(Component)]
+pub struct SettingStepButton {
+    pub field: SettingField,
+    pub steps: i32,
+}
+
+/// Value of a setting
+#[derive(Component)]
+pub struct SettingValueText {
+    pub field: SettingField,
+}
+
+/// Whether the pane is shown
+#[derive(Resource, Default)]
+pub struct SettingsPaneState {
+    pub visible: bool,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct SettingsPanePlugin;
+
+impl Plugin for SettingsPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsPaneState>()
+            .add_systems(Startup, spawn_settings_pane)
+            .add_systems(
+                Update,
+                (
+                    toggle_settings_pane,
+                    handle_setting_buttons,
+                    update_settings_pane,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the settings pane, hidden, at the top center of the window
+pub fn spawn_settings_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: SETTINGS_TEXT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(TOOLBAR_CONTAINER_MARGIN + 4.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(
+                    -(SETTINGS_PANE_WIDTH / 2.0
+                        + WIDGET_PADDING
+                        + WIDGET_BORDER_WIDTH),
+                )),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            SettingsPane,
+            Name::new("SettingsPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Settings"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+            for (heading, fields) in SECTIONS {
+                pane.spawn((
+                    Node {
+                        margin: UiRect::top(Val::Px(8.0)),
+                        ..default()
+                    },
+                    Text::new(*heading),
+                    text_font.clone(),
+                    TextColor(SECONDARY_TEXT_COLOR),
+                ));
+                for &field in *fields {
+                    spawn_setting_row(pane, field, &text_font);
+                }
+            }
+        });
+}
+
+/// Spawns the row of a setting: its name, buttons and value
+fn spawn_setting_row(
+    pane: &mut ChildSpawnerCommands,
+    field: SettingField,
+    text_font: &TextFont,
+) {
+    pane.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(8.0),
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            Node {
+                width: Val::Px(SETTING_NAME_WIDTH),
+                ..default()
+            },
+            Text::new(field.label()),
+            text_font.clone(),
+            TextColor(NORMAL_TEXT_COLOR),
+        ));
+        spawn_step_button(row, field, -1, text_font);
+        row.spawn((
+            Node {
+                width: Val::Px(SETTING_VALUE_WIDTH),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Text::new(""),
+            text_font.clone(),
+            TextColor(SECONDARY_TEXT_COLOR),
+            SettingValueText { field },
+        ));
+        spawn_step_button(row, field, 1, text_font);
+    });
+}
+
+/// Spawns a button stepping a setting down or up
+fn spawn_step_button(
+    row: &mut ChildSpawnerCommands,
+    field: SettingField,
+    steps: i32,
+    text_font: &TextFont,
+) {
+    row.spawn((
+        Button,
+        Node {
+            padding: UiRect::horizontal(Val::Px(6.0)),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BackgroundColor(NORMAL_BUTTON_COLOR),
+        BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+        SettingStepButton { field, steps },
+    ))
+    .with_children(|button| {
+        button.spawn((
+            Text::new(if steps < 0 { "-" } else { "+" }),
+            text_font.clone(),
+            TextColor(NORMAL_TEXT_COLOR),
+        ));
+    });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane with Command/Ctrl+Comma
+fn toggle_settings_pane(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut pane_state: ResMut<SettingsPaneState>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    if modifier_pressed && keyboard.just_pressed(KeyCode::Comma) {
+        pane_state.visible = !pane_state.visible;
+    }
+}
+
+/// Steps settings whose buttons were pressed
+fn handle_setting_buttons(
+    button_query: Query<
+        (&Interaction, &SettingStepButton),
+        Changed<Interaction>,
+    >,
+    mut settings: ResMut<BezySettings>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        button.field.step(&mut settings, button.steps);
+        debug!(
+            "Setting '{}' is now {}",
+            button.field.label(),
+            button.field.display(&settings)
+        );
+    }
+}
+
+/// Refreshes the pane's visibility and values
+fn update_settings_pane(
+    pane_state: Res<SettingsPaneState>,
+    settings: Res<BezySettings>,
+    mut pane_query: Query<&mut Node, With<SettingsPane>>,
+    mut value_query: Query<(&SettingValueText, &mut Text)>,
+) {
+    if pane_state.is_changed() {
+        for mut node in pane_query.iter_mut() {
+            node.display = if pane_state.visible {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
+    }
+    if !settings.is_changed() {
+        return;
+    }
+    for (value_text, mut text) in value_query.iter_mut() {
+        let value = value_text.field.display(&settings);
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
@@ -0,0 +1,607 @@
+//! Variable font pane
+//!
+//! Shows a text buffer through the variable font compiled from the saved
+//! sources, at axis coordinates of its own, apart from the location the
+//! masters are edited at. The buffer is shaped at those coordinates, feature
+//! variations included, and drawn with the compiled outlines, see
+//! `systems::harfbuzz_shaping`. Command/Ctrl+Alt+V shows or hides the pane.
+//!
+//! The pane works on the text buffer of the last active sort. A button
+//! switches the buffer between the compiled font and the masters, and a
+//! slider per axis sets its coordinates in user space.
+
+use crate::core::state::text_editor::buffer::BufferId;
+use crate::core::state::{FontIRAppState, TextEditorState};
+use crate::data::designspace::{location_value, DesignAxis};
+use crate::systems::harfbuzz_shaping::VariableFontPreview;
+use crate::systems::ui_interaction::BlocksCanvas;
+use crate::ui::theme::*;
+use crate::ui::themes::CurrentTheme;
+use bevy::prelude::*;
+use bevy::ui::{Display, RelativeCursorPosition};
+
+// ============================================================================
+// DESIGN CONSTANTS
+// ============================================================================
+
+/// Font size of the pane's text
+const VARIABLE_FONT_TEXT_SIZE: f32 = 14.0;
+
+/// Width of the axis name column
+const AXIS_NAME_WIDTH: f32 = 110.0;
+
+/// Length of a slider track
+const SLIDER_WIDTH: f32 = 240.0;
+
+/// Height of a slider track
+const SLIDER_HEIGHT: f32 = 6.0;
+
+/// Size of a slider's thumb
+const THUMB_SIZE: f32 = 14.0;
+
+/// Width of the axis value column
+const AXIS_VALUE_WIDTH: f32 = 70.0;
+
+/// Width of the pane's content
+const VARIABLE_FONT_PANE_WIDTH: f32 =
+    AXIS_NAME_WIDTH + SLIDER_WIDTH + AXIS_VALUE_WIDTH + 2.0 * WIDGET_PADDING;
+
+/// Smallest change a drag sends on, as a fraction of the axis range
+const SLIDER_STEP: f32 = 1e-3;
+
+// ============================================================================
+// COMPONENTS & RESOURCES
+// ============================================================================
+
+/// Component marker for the variable font pane
+#[derive(Component, Default)]
+pub struct VariableFontPane;
+
+/// Tells which buffer the pane works on and how it is shown
+#[derive(Component)]
+pub struct VariableFontStatusText;
+
+/// Switches the buffer between the compiled font and the masters
+#[derive(Component)]
+pub struct VariableFontToggleButton;
+
+/// Label of the switch button
+#[derive(Component)]
+pub struct VariableFontToggleText;
+
+/// Container the axis rows are spawned into
+#[derive(Component)]
+pub struct VariableFontAxisRows;
+
+/// Slider track of an axis
+#[derive(Component)]
+pub struct VariableFontSlider {
+    pub index: usize,
+}
+
+/// Thumb of an axis slider
+#[derive(Component)]
+pub struct VariableFontSliderThumb {
+    pub index: usize,
+}
+
+/// User-space coordinate of an axis in the buffer
+#[derive(Component)]
+pub struct VariableFontValueText {
+    pub index: usize,
+}
+
+/// Whether the pane is shown, and the text buffer it works on
+#[derive(Resource, Default)]
+pub struct VariableFontPaneState {
+    pub visible: bool,
+    pub buffer_id: Option<BufferId>,
+}
+
+// ============================================================================
+// PLUGIN
+// ============================================================================
+
+pub struct VariableFontPanePlugin;
+
+impl Plugin for VariableFontPanePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VariableFontPaneState>()
+            .add_systems(Startup, spawn_variable_font_pane)
+            .add_systems(
+                Update,
+                (
+                    toggle_variable_font_pane,
+                    track_variable_font_buffer,
+                    rebuild_variable_font_rows,
+                    handle_variable_font_toggle,
+                    handle_variable_font_sliders,
+                    update_variable_font_pane,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// ============================================================================
+// UI CREATION
+// ============================================================================
+
+/// Spawns the variable font pane, hidden, at the top center of the window;
+/// its rows are added once the font's axes are known
+pub fn spawn_variable_font_pane(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+) {
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: VARIABLE_FONT_TEXT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(TOOLBAR_CONTAINER_MARGIN + 4.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(
+                    -(VARIABLE_FONT_PANE_WIDTH / 2.0
+                        + WIDGET_PADDING
+                        + WIDGET_BORDER_WIDTH),
+                )),
+                padding: UiRect::all(Val::Px(WIDGET_PADDING)),
+                border: UiRect::all(Val::Px(WIDGET_BORDER_WIDTH)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(WIDGET_ROW_LEADING),
+                display: Display::None,
+                ..default()
+            },
+            BackgroundColor(theme.theme().widget_background_color()),
+            BorderColor(theme.theme().widget_border_color()),
+            BorderRadius::all(Val::Px(theme.theme().widget_border_radius())),
+            crate::ui::themes::WidgetBorderRadius,
+            BlocksCanvas,
+            VariableFontPane,
+            Name::new("VariableFontPane"),
+        ))
+        .with_children(|pane| {
+            pane.spawn((
+                Text::new("Variable Font"),
+                text_font.clone(),
+                TextColor(NORMAL_TEXT_COLOR),
+            ));
+            pane.spawn((
+                Text::new(""),
+                text_font.clone(),
+                TextColor(SECONDARY_TEXT_COLOR),
+                VariableFontStatusText,
+            ));
+            pane.spawn((
+                Button,
+                Node {
+                    align_self: AlignSelf::FlexStart,
+                    padding: UiRect::horizontal(Val::Px(4.0)),
+                    border: UiRect::all(Val::Px(1.0)),
+                    display: Display::None,
+                    ..default()
+                },
+                BackgroundColor(NORMAL_BUTTON_COLOR),
+                BorderColor(NORMAL_BUTTON_OUTLINE_COLOR),
+                VariableFontToggleButton,
+            ))
+            .with_children(|button| {
+                button.spawn((
+                    Text::new(""),
+                    text_font.clone(),
+                    TextColor(NORMAL_TEXT_COLOR),
+                    VariableFontToggleText,
+                ));
+            });
+            pane.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(WIDGET_ROW_LEADING),
+                    display: Display::None,
+                    ..default()
+                },
+                VariableFontAxisRows,
+            ));
+        });
+}
+
+/// Spawns the row of an axis: its name, slider and coordinate
+fn spawn_axis_row(
+    rows: &mut ChildSpawnerCommands,
+    index: usize,
+    axis: &DesignAxis,
+    text_font: &TextFont,
+) {
+    rows.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(WIDGET_PADDING),
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            Node {
+                width: Val::Px(AXIS_NAME_WIDTH),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            Text::new(axis.name.clone()),
+            text_font.clone(),
+            TextLayout::new_with_no_wrap(),
+            TextColor(NORMAL_TEXT_COLOR),
+        ));
+        row.spawn((
+            Node {
+                width: Val::Px(SLIDER_WIDTH),
+                height: Val::Px(SLIDER_HEIGHT),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON_COLOR),
+            BorderRadius::all(Val::Px(SLIDER_HEIGHT / 2.0)),
+            Interaction::default(),
+            RelativeCursorPosition::default(),
+            VariableFontSlider { index },
+        ))
+        .with_children(|track| {
+            track.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(THUMB_SIZE),
+                    height: Val::Px(THUMB_SIZE),
+                    margin: UiRect::left(Val::Px(-THUMB_SIZE / 2.0)),
+                    ..default()
+                },
+                BackgroundColor(NORMAL_TEXT_COLOR),
+                BorderRadius::all(Val::Px(THUMB_SIZE / 2.0)),
+                VariableFontSliderThumb { index },
+            ));
+        });
+        row.spawn((
+            Node {
+                width: Val::Px(AXIS_VALUE_WIDTH),
+                ..default()
+            },
+            Text::new(""),
+            text_font.clone(),
+            TextColor(SECONDARY_TEXT_COLOR),
+            VariableFontValueText { index },
+        ));
+    });
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+/// Shows or hides the pane with Command/Ctrl+Alt+V
+fn toggle_variable_font_pane(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut pane_state: ResMut<VariableFontPaneState>,
+    mut pane_query: Query<&mut Node, With<VariableFontPane>>,
+) {
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+    let alt_pressed = keyboard.pressed(KeyCode::AltLeft)
+        || keyboard.pressed(KeyCode::AltRight);
+    let shift_pressed = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
+    if !(modifier_pressed
+        && alt_pressed
+        && !shift_pressed
+        && keyboard.just_pressed(KeyCode::KeyV))
+    {
+        return;
+    }
+
+    pane_state.visible = !pane_state.visible;
+    for mut node in pane_query.iter_mut() {
+        node.display = if pane_state.visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// Follows the text buffer of the active sort, keeping the last one while
+/// no sort is active
+fn track_variable_font_buffer(
+    text_editor_state: Res<TextEditorState>,
+    mut pane_state: ResMut<VariableFontPaneState>,
+) {
+    if !text_editor_state.is_changed() {
+        return;
+    }
+    let active_buffer = text_editor_state
+        .get_active_sort()
+        .and_then(|(_, entry)| entry.buffer_id);
+    if let Some(buffer_id) = active_buffer {
+        if pane_state.buffer_id != Some(buffer_id) {
+            pane_state.buffer_id = Some(buffer_id);
+        }
+    } else if pane_state.buffer_id.is_some_and(|buffer_id| {
+        text_editor_state.find_buffer_root(buffer_id).is_none()
+    }) {
+        pane_state.buffer_id = None;
+    }
+}
+
+/// Replaces the axis rows when a font with other axes is loaded
+fn rebuild_variable_font_rows(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    rows_query: Query<Entity, With<VariableFontAxisRows>>,
+    mut shown_axes: Local<Vec<DesignAxis>>,
+) {
+    let axes = fontir_state
+        .as_ref()
+        .map(|state| state.axes.clone())
+        .unwrap_or_default();
+    if *shown_axes == axes {
+        return;
+    }
+
+    let text_font = TextFont {
+        font: asset_server.load(MONO_FONT_PATH),
+        font_size: VARIABLE_FONT_TEXT_SIZE,
+        ..default()
+    };
+    for rows in rows_query.iter() {
+        commands
+            .entity(rows)
+            .despawn_related::<Children>()
+            .with_children(|rows| {
+                for (index, axis) in axes.iter().enumerate() {
+                    spawn_axis_row(rows, index, axis, &text_font);
+                }
+            });
+    }
+    *shown_axes = axes;
+}
+
+/// Switches the buffer between the compiled font, starting at the location
+/// the masters are edited at, and the masters
+fn handle_variable_font_toggle(
+    button_query: Query<
+        &Interaction,
+        (Changed<Interaction>, With<VariableFontToggleButton>),
+    >,
+    pane_state: Res<VariableFontPaneState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut text_editor_state: ResMut<TextEditorState>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let (Some(buffer_id), Some(fontir_state)) =
+        (pane_state.buffer_id, fontir_state.as_ref())
+    else {
+        return;
+    };
+
+    if text_editor_state.buffer.variations(buffer_id).is_some() {
+        text_editor_state.buffer.set_variations(buffer_id, None);
+        info!("Text buffer shows the masters again");
+        return;
+    }
+    let variations = fontir_state
+        .axes
+        .iter()
+        .map(|axis| {
+            let normalized =
+                location_value(&fontir_state.current_location, &axis.tag);
+            (axis.tag.clone(), axis.user_value(normalized) as f32)
+        })
+        .collect();
+    text_editor_state
+        .buffer
+        .set_variations(buffer_id, Some(variations));
+    info!("Text buffer shown through the compiled variable font");
+}
+
+/// Sets the buffer's axis coordinates while a slider is pressed or dragged
+fn handle_variable_font_sliders(
+    pane_state: Res<VariableFontPaneState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    slider_query: Query<(
+        &Interaction,
+        &RelativeCursorPosition,
+        &VariableFontSlider,
+    )>,
+    mut text_editor_state: ResMut<TextEditorState>,
+) {
+    let (Some(buffer_id), Some(fontir_state)) =
+        (pane_state.buffer_id, fontir_state.as_ref())
+    else {
+        return;
+    };
+    for (interaction, cursor, slider) in slider_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let (Some(axis), Some(cursor)) =
+            (fontir_state.axes.get(slider.index), cursor.normalized)
+        else {
+            continue;
+        };
+        let Some(current) =
+            variation_value(&text_editor_state, buffer_id, axis)
+        else {
+            continue;
+        };
+
+        // The cursor position runs from 0 at the left of the track to 1 at
+        // the right
+        let fraction = cursor.x.clamp(0.0, 1.0);
+        let (minimum, maximum) = (axis.minimum as f32, axis.maximum as f32);
+        let value = minimum + fraction * (maximum - minimum);
+        if (value - current).abs() < SLIDER_STEP * (maximum - minimum) {
+            continue;
+        }
+        text_editor_state
+            .buffer
+            .set_variation(buffer_id, &axis.tag, value);
+    }
+}
+
+/// Refreshes the status, button, thumbs and coordinates after the buffer or
+/// its coordinates change
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn update_variable_font_pane(
+    pane_state: Res<VariableFontPaneState>,
+    text_editor_state: Res<TextEditorState>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    preview: Res<VariableFontPreview>,
+    mut status_query: Query<&mut Text, With<VariableFontStatusText>>,
+    mut button_query: Query<
+        &mut Node,
+        (
+            With<VariableFontToggleButton>,
+            Without<VariableFontAxisRows>,
+            Without<VariableFontSliderThumb>,
+        ),
+    >,
+    mut button_text_query: Query<
+        &mut Text,
+        (
+            With<VariableFontToggleText>,
+            Without<VariableFontStatusText>,
+        ),
+    >,
+    mut rows_query: Query<
+        &mut Node,
+        (With<VariableFontAxisRows>, Without<VariableFontSliderThumb>),
+    >,
+    mut value_query: Query<
+        (&VariableFontValueText, &mut Text),
+        (
+            Without<VariableFontStatusText>,
+            Without<VariableFontToggleText>,
+        ),
+    >,
+    mut thumb_query: Query<(&VariableFontSliderThumb, &mut Node)>,
+    added_thumbs: Query<(), Added<VariableFontSliderThumb>>,
+) {
+    let Some(fontir_state) = fontir_state.as_ref() else {
+        return;
+    };
+    if !pane_state.is_changed()
+        && !text_editor_state.is_changed()
+        && !preview.is_changed()
+        && added_thumbs.is_empty()
+    {
+        return;
+    }
+    let buffer_id = pane_state.buffer_id;
+    let previewed = buffer_id.is_some_and(|buffer_id| {
+        text_editor_state.buffer.variations(buffer_id).is_some()
+    });
+
+    let status = match (buffer_id, &preview.error) {
+        (None, _) => "Click a sort in a text buffer to preview it".to_string(),
+        (Some(_), Some(error)) if previewed => error.clone(),
+        (Some(_), _) if previewed && preview.compiling => {
+            "Compiling the variable font...".to_string()
+        }
+        (Some(_), _) if previewed => {
+            "Text buffer through the variable font compiled on save".to_string()
+        }
+        (Some(_), _) => {
+            "Text buffer shows the masters being edited".to_string()
+        }
+    };
+    for mut text in status_query.iter_mut() {
+        if text.0 != status {
+            text.0.clone_from(&status);
+        }
+    }
+
+    let show_button = buffer_id.is_some() && !fontir_state.axes.is_empty();
+    for mut node in button_query.iter_mut() {
+        node.display = if show_button {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    let label = if previewed {
+        "Show the masters"
+    } else {
+        "Preview through the compiled font"
+    };
+    for mut text in button_text_query.iter_mut() {
+        if text.0 != label {
+            text.0 = label.to_string();
+        }
+    }
+    for mut node in rows_query.iter_mut() {
+        node.display = if previewed {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    let Some(buffer_id) = buffer_id.filter(|_| previewed) else {
+        return;
+    };
+    for (value_text, mut text) in value_query.iter_mut() {
+        let Some(axis) = fontir_state.axes.get(value_text.index) else {
+            continue;
+        };
+        let Some(value) = variation_value(&text_editor_state, buffer_id, axis)
+        else {
+            continue;
+        };
+        let value = format!("{} {}", axis.tag, value.round());
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+    for (thumb, mut node) in thumb_query.iter_mut() {
+        let Some(axis) = fontir_state.axes.get(thumb.index) else {
+            continue;
+        };
+        let Some(value) = variation_value(&text_editor_state, buffer_id, axis)
+        else {
+            continue;
+        };
+        let (minimum, maximum) = (axis.minimum as f32, axis.maximum as f32);
+        let fraction = if maximum > minimum {
+            (value - minimum) / (maximum - minimum)
+        } else {
+            0.0
+        };
+        node.left = Val::Percent(100.0 * fraction);
+    }
+}
+
+/// User-space coordinate of an axis in a buffer shown through the compiled
+/// font, the axis default when it wasn't set
+fn variation_value(
+    text_editor_state: &TextEditorState,
+    buffer_id: BufferId,
+    axis: &DesignAxis,
+) -> Option<f32> {
+    let variations = text_editor_state.buffer.variations(buffer_id)?;
+    Some(
+        variations
+            .iter()
+            .find(|(tag, _)| *tag == axis.tag)
+            .map(|(_, value)| *value)
+            .unwrap_or(axis.default as f32),
+    )
+}
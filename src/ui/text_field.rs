@@ -0,0 +1,168 @@
+//! Text fields of panes
+//!
+//! A pane with a text field keeps which field is edited and the typed text
+//! in its state, and implements [`TextField`] for it. Adding a
+//! [`TextFieldPlugin`] for the state then types into the field while it is
+//! edited: Enter applies the edit, Tab applies it and moves on to the next
+//! field where the pane has several, and Escape cancels it.
+
+use crate::core::state::FontIRAppState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::{ButtonState, InputSystem};
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+/// State of a pane with a text field
+pub trait TextField: Resource {
+    /// Event written when an edit is applied
+    type Edit: Event;
+
+    /// Whether spaces can be typed into the field
+    const ALLOWS_SPACES: bool = true;
+
+    /// Whether Tab moves on to the next field
+    const TABS_BETWEEN_FIELDS: bool = false;
+
+    /// Whether a field is being edited
+    fn is_editing(&self) -> bool;
+
+    /// Text typed so far
+    fn input_mut(&mut self) -> &mut String;
+
+    /// Stop editing, returning the edit to apply
+    fn finish_editing(&mut self) -> Option<Self::Edit>;
+
+    /// Stop editing without applying the edit
+    fn cancel_editing(&mut self);
+
+    /// Stop editing and start editing the next field, returning the edit to
+    /// apply, for panes that tab between fields
+    fn edit_next_field(
+        &mut self,
+        _fontir_state: Option<&FontIRAppState>,
+    ) -> Option<Self::Edit> {
+        self.finish_editing()
+    }
+}
+
+/// Types into the text field of the pane state `T`
+pub struct TextFieldPlugin<T>(PhantomData<T>);
+
+impl<T> Default for TextFieldPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: TextField> Plugin for TextFieldPlugin<T> {
+    fn build(&self, app: &mut App) {
+        // Runs right after keyboard input is collected, so it can take the
+        // keys before any shortcut sees them
+        app.add_systems(
+            PreUpdate,
+            handle_text_field_input::<T>.after(InputSystem),
+        );
+    }
+}
+
+/// Types into a text field while it is edited
+///
+/// Takes every key press while editing, so that nothing else reacts to the
+/// typing.
+pub fn handle_text_field_input<T: TextField>(
+    mut keyboard_events: ResMut<Events<KeyboardInput>>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    fontir_state: Option<Res<FontIRAppState>>,
+    mut field: ResMut<T>,
+    mut edit_events: EventWriter<T::Edit>,
+) {
+    if !field.is_editing() {
+        return;
+    }
+    let modifier_pressed = keyboard.pressed(KeyCode::SuperLeft)
+        || keyboard.pressed(KeyCode::SuperRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight);
+
+    for event in keyboard_events.drain() {
+        if !field.is_editing() || event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                if let Some(edit) = field.finish_editing() {
+                    edit_events.write(edit);
+                }
+            }
+            Key::Tab if T::TABS_BETWEEN_FIELDS => {
+                let edit = field.edit_next_field(fontir_state.as_deref());
+                if let Some(edit) = edit {
+                    edit_events.write(edit);
+                }
+            }
+            Key::Escape => field.cancel_editing(),
+            key => {
+                type_key(
+                    field.input_mut(),
+                    key,
+                    modifier_pressed,
+                    T::ALLOWS_SPACES,
+                );
+            }
+        }
+    }
+
+    // Keep the typed keys away from shortcuts and tools
+    keyboard.reset_all();
+}
+
+/// Edit typed text with a key: Backspace, Space or a character
+///
+/// Characters typed with Command/Ctrl held are shortcuts rather than text.
+/// Returns whether the text changed.
+pub fn type_key(
+    input: &mut String,
+    key: &Key,
+    modifier_pressed: bool,
+    allows_spaces: bool,
+) -> bool {
+    match key {
+        Key::Backspace => input.pop().is_some(),
+        Key::Space if allows_spaces => {
+            input.push(' ');
+            true
+        }
+        Key::Character(text) if !modifier_pressed => {
+            input.push_str(text);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_key() {
+        let mut input = String::new();
+        assert!(type_key(
+            &mut input,
+            &Key::Character("a".into()),
+            false,
+            true
+        ));
+        assert!(type_key(&mut input, &Key::Space, false, true));
+        assert!(!type_key(&mut input, &Key::Space, false, false));
+        assert!(!type_key(
+            &mut input,
+            &Key::Character("c".into()),
+            true,
+            true
+        ));
+        assert_eq!(input, "a ");
+        assert!(type_key(&mut input, &Key::Backspace, false, true));
+        assert_eq!(input, "a");
+    }
+}
@@ -232,6 +232,20 @@ pub const FILLED_GLYPH_Z: f32 = 7.0;
 pub const METRICS_GUIDE_COLOR: Color = Color::srgba(0.3, 1.0, 0.5, 0.5);
 pub const SORT_ACTIVE_METRICS_COLOR: Color = Color::srgba(0.3, 1.0, 0.5, 0.5);
 pub const SORT_INACTIVE_METRICS_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.5);
+/// Color for font-level guidelines
+pub const FONT_GUIDELINE_COLOR: Color = Color::srgba(0.3, 0.7, 1.0, 0.7);
+/// Color for glyph-level guidelines
+pub const GLYPH_GUIDELINE_COLOR: Color = Color::srgba(1.0, 0.4, 0.7, 0.7);
+/// Alpha for locked guidelines (drawn fainter)
+pub const LOCKED_GUIDELINE_ALPHA: f32 = 0.3;
+/// Color for highlighting the target a position snapped to
+pub const SNAP_HIGHLIGHT_COLOR: Color = Color::srgba(1.0, 0.5, 0.0, 0.9);
+/// Color for marking problems found by the outline check
+pub const OUTLINE_PROBLEM_COLOR: Color = Color::srgba(1.0, 0.2, 0.2, 0.6);
+/// Color for marking points that keep masters from interpolating
+pub const COMPATIBILITY_ISSUE_COLOR: Color = Color::srgba(0.9, 0.3, 1.0, 0.6);
+/// Color for the background layer shown behind the glyph being edited
+pub const BACKGROUND_LAYER_COLOR: Color = Color::srgba(0.4, 0.6, 1.0, 0.25);
 
 pub const NORMAL_BUTTON_COLOR: Color = Color::srgb(0.1, 0.1, 0.1);
 pub const HOVERED_BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
@@ -90,11 +90,14 @@ pub fn reload_theme_on_keypress(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut current_theme: ResMut<CurrentTheme>,
 ) {
-    // Press Ctrl/Cmd + R to reload the current theme
+    // Press Ctrl/Cmd + R to reload the current theme; with Shift it builds
+    // composites instead
     let ctrl_held = keyboard.pressed(KeyCode::ControlLeft)
         || keyboard.pressed(KeyCode::ControlRight);
+    let shift_held = keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight);
 
-    if ctrl_held && keyboard.just_pressed(KeyCode::KeyR) {
+    if ctrl_held && !shift_held && keyboard.just_pressed(KeyCode::KeyR) {
         let variant = current_theme.variant.clone();
         current_theme.switch_to(variant);
         info!("Theme reloaded!");
@@ -6,6 +6,7 @@
 use crate::core::settings::BezySettings;
 use crate::core::state::{AppState, GlyphNavigation};
 use crate::editing::selection::systems::AppStateChanged;
+use crate::geometry::simplify::simplify_path;
 use crate::ui::toolbars::edit_mode_toolbar::{EditTool, ToolRegistry};
 use bevy::prelude::*;
 
@@ -117,13 +118,17 @@ fn register_hyper_tool(mut tool_registry: ResMut<ToolRegistry>) {
 pub fn handle_hyper_mouse_events(
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
-    camera_query: Query<(&Camera, &GlobalTransform)>,
+    camera_query: Query<
+        (&Camera, &GlobalTransform),
+        With<crate::rendering::cameras::DesignCamera>,
+    >,
     keyboard: Res<ButtonInput<KeyCode>>,
     mut hyper_state: ResMut<HyperToolState>,
     hyper_mode: Option<Res<HyperModeActive>>,
     glyph_navigation: Res<GlyphNavigation>,
     mut app_state: ResMut<AppState>,
     mut app_state_changed: EventWriter<AppStateChanged>,
+    settings: Res<BezySettings>,
 ) {
     // Only handle events when in hyper mode
     if let Some(hyper_mode) = hyper_mode {
@@ -151,7 +156,6 @@ pub fn handle_hyper_mouse_events(
         camera.viewport_to_world_2d(camera_transform, cursor_position)
     {
         // Apply grid snapping
-        let settings = BezySettings::default();
         let snapped_position = settings.apply_grid_snap(world_position);
 
         // Update cursor position for preview
@@ -173,6 +177,7 @@ pub fn handle_hyper_mouse_events(
                         &glyph_navigation,
                         &mut app_state,
                         &mut app_state_changed,
+                        &settings,
                     );
                 }
 
@@ -200,6 +205,7 @@ pub fn handle_hyper_mouse_events(
                     &glyph_navigation,
                     &mut app_state,
                     &mut app_state_changed,
+                    &settings,
                 );
             }
 
@@ -305,6 +311,50 @@ pub fn render_hyper_preview(
     }
 }
 
+/// Build a smooth path through the placed points
+///
+/// Smooth points get Catmull-Rom style tangents; corner points (placed with
+/// Alt held) keep straight handles so the outline breaks there.
+fn hyper_points_to_bezpath(
+    points: &[Vec2],
+    is_smooth: &[bool],
+    closed: bool,
+) -> kurbo::BezPath {
+    let to_point = |v: Vec2| kurbo::Point::new(v.x as f64, v.y as f64);
+    let count = points.len();
+    let neighbor = |i: usize, offset: isize| -> Vec2 {
+        let j = i as isize + offset;
+        if closed {
+            points[j.rem_euclid(count as isize) as usize]
+        } else {
+            points[j.clamp(0, count as isize - 1) as usize]
+        }
+    };
+    let tangent = |i: usize| -> Vec2 {
+        if *is_smooth.get(i).unwrap_or(&true) {
+            (neighbor(i, 1) - neighbor(i, -1)) / 6.0
+        } else {
+            Vec2::ZERO
+        }
+    };
+
+    let mut path = kurbo::BezPath::new();
+    path.move_to(to_point(points[0]));
+    let segment_count = if closed { count } else { count - 1 };
+    for (i, &point) in points.iter().enumerate().take(segment_count) {
+        let next = (i + 1) % count;
+        path.curve_to(
+            to_point(point + tangent(i)),
+            to_point(points[next] - tangent(next)),
+            to_point(points[next]),
+        );
+    }
+    if closed {
+        path.close_path();
+    }
+    path
+}
+
 /// Create a hyperbezier contour from points
 fn create_hyper_contour(
     points: &[Vec2],
@@ -313,6 +363,7 @@ fn create_hyper_contour(
     glyph_navigation: &GlyphNavigation,
     app_state: &mut AppState,
     app_state_changed: &mut EventWriter<AppStateChanged>,
+    settings: &BezySettings,
 ) {
     let Some(glyph_name) = glyph_navigation.find_glyph(app_state) else {
         warn!("No current glyph selected for hyperbezier creation");
@@ -324,28 +375,32 @@ fn create_hyper_contour(
     }
 
     // Create contour points with smooth curves
-    let mut contour_points = Vec::new();
+    let contour_points = if settings.simplify.apply_to_tool_output {
+        let path = hyper_points_to_bezpath(points, is_smooth, closed);
+        let simplified = simplify_path(&path, &settings.simplify.params());
+        crate::core::state::ContourData::from_bezpath(&simplified).points
+    } else {
+        let mut contour_points = Vec::new();
 
-    for (i, &point) in points.iter().enumerate() {
-        let point_type = if i == 0 {
-            crate::core::state::PointTypeData::Move
-        } else if *is_smooth.get(i).unwrap_or(&true) {
-            crate::core::state::PointTypeData::Curve
-        } else {
-            crate::core::state::PointTypeData::Line
-        };
+        for (i, &point) in points.iter().enumerate() {
+            let point_type = if i == 0 {
+                crate::core::state::PointTypeData::Move
+            } else if *is_smooth.get(i).unwrap_or(&true) {
+                crate::core::state::PointTypeData::Curve
+            } else {
+                crate::core::state::PointTypeData::Line
+            };
 
-        contour_points.push(crate::core::state::PointData {
-            x: point.x as f64,
-            y: point.y as f64,
-            point_type,
-        });
-    }
+            contour_points.push(crate::core::state::PointData {
+                x: point.x as f64,
+                y: point.y as f64,
+                point_type,
+            });
+        }
 
-    // If closed, add a line back to start if needed
-    if closed && points.len() > 2 {
-        // The path will automatically close in UFO format
-    }
+        // If closed, the path will automatically close in UFO format
+        contour_points
+    };
 
     // Add the contour to the glyph
     if let Some(glyph_data) =
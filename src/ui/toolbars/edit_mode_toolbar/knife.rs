@@ -147,7 +147,10 @@ fn register_knife_tool(mut tool_registry: ResMut<ToolRegistry>) {
 pub fn handle_knife_mouse_events(
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
-    camera_query: Query<(&Camera, &GlobalTransform)>,
+    camera_query: Query<
+        (&Camera, &GlobalTransform),
+        With<crate::rendering::cameras::DesignCamera>,
+    >,
     keyboard: Res<ButtonInput<KeyCode>>,
     mut knife_state: ResMut<KnifeToolState>,
     knife_mode: Option<Res<KnifeModeActive>>,
@@ -612,19 +615,8 @@ fn perform_fontir_cut(
         let location = fontir_state.current_location.clone();
         let key = (current_glyph.clone(), location);
         
-        // Ensure we have a working copy
-        if !fontir_state.working_copies.contains_key(&key) {
-            if let Some(original_paths) = fontir_state.get_glyph_paths(&current_glyph) {
-                let working_copy = crate::core::state::fontir_app_state::EditableGlyphInstance {
-                    width: fontir_state.get_glyph_advance_width(&current_glyph) as f64,
-                    height: None,
-                    vertical_origin: None,
-                    contours: original_paths,
-                    is_dirty: false,
-                };
-                fontir_state.working_copies.insert(key.clone(), working_copy);
-            }
-        }
+        // Ensure we have a working copy, unless between masters
+        fontir_state.get_or_create_working_copy(current_glyph);
         
         // Perform the cut on the working copy
         if let Some(working_copy) = fontir_state.working_copies.get_mut(&key) {
@@ -32,6 +32,7 @@ Status:
 use crate::core::settings::BezySettings;
 use crate::core::state::{AppState, GlyphNavigation};
 use crate::editing::selection::systems::AppStateChanged;
+use crate::geometry::simplify::simplify_path;
 use crate::ui::theme::{
     METABALL_GIZMO_COLOR, METABALL_OUTLINE_COLOR, METABALL_SELECTED_COLOR,
 };
@@ -164,7 +165,10 @@ pub fn handle_metaballs_mouse_events(
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
     mut metaball_gizmos: ResMut<MetaballGizmos>,
-    camera_query: Query<(&Camera, &GlobalTransform)>,
+    camera_query: Query<
+        (&Camera, &GlobalTransform),
+        With<crate::rendering::cameras::DesignCamera>,
+    >,
     metaballs_mode: Option<Res<MetaballsModeActive>>,
 ) {
     // Only handle input if metaballs tool is active
@@ -246,6 +250,7 @@ pub fn handle_metaballs_keyboard_shortcuts(
     mut app_state_changed: EventWriter<AppStateChanged>,
     glyph_navigation: Res<GlyphNavigation>,
     metaballs_mode: Option<Res<MetaballsModeActive>>,
+    settings: Res<BezySettings>,
 ) {
     // Only handle input if metaballs tool is active
     if let Some(metaballs_mode) = metaballs_mode {
@@ -256,8 +261,11 @@ pub fn handle_metaballs_keyboard_shortcuts(
         return;
     }
 
-    // Ctrl+C: Convert metaballs to curves
+    // Ctrl+C: Convert metaballs to curves; Ctrl+Shift+C adds a component
+    let shift_pressed = keyboard_input.pressed(KeyCode::ShiftLeft)
+        || keyboard_input.pressed(KeyCode::ShiftRight);
     if keyboard_input.pressed(KeyCode::ControlLeft)
+        && !shift_pressed
         && keyboard_input.just_pressed(KeyCode::KeyC)
     {
         debug!("METABALLS TOOL: Converting metaballs to curves");
@@ -266,6 +274,7 @@ pub fn handle_metaballs_keyboard_shortcuts(
             &mut app_state,
             &mut app_state_changed,
             &glyph_navigation,
+            &settings,
         );
     }
 
@@ -461,6 +470,7 @@ fn convert_metaballs_to_curves(
     app_state: &mut AppState,
     app_state_changed: &mut EventWriter<AppStateChanged>,
     glyph_navigation: &GlyphNavigation,
+    bezy_settings: &BezySettings,
 ) {
     let Some(glyph_name) = glyph_navigation.find_glyph(app_state) else {
         warn!("No current glyph selected for metaball conversion");
@@ -483,7 +493,11 @@ fn convert_metaballs_to_curves(
     }
 
     // Convert outline points to cubic curves
-    let curves = outline_points_to_cubic_curves(&outline_points);
+    let curves = if bezy_settings.simplify.apply_to_tool_output {
+        simplify_outline_points(&outline_points, bezy_settings)
+    } else {
+        outline_points_to_cubic_curves(&outline_points)
+    };
     let curves_count = curves.len();
 
     // Add curves to the glyph
@@ -550,6 +564,31 @@ fn outline_points_to_cubic_curves(
     curves
 }
 
+/// Refit the traced outline polygon with as few cubic curves as possible
+fn simplify_outline_points(
+    points: &[Vec2],
+    bezy_settings: &BezySettings,
+) -> Vec<Vec<crate::core::state::PointData>> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut polyline = kurbo::BezPath::new();
+    polyline.move_to((points[0].x as f64, points[0].y as f64));
+    for point in points.iter().skip(1) {
+        polyline.line_to((point.x as f64, point.y as f64));
+    }
+    polyline.close_path();
+
+    let simplified = simplify_path(&polyline, &bezy_settings.simplify.params());
+    debug!(
+        "METABALLS TOOL: Simplified {} outline points to {} segments",
+        points.len(),
+        simplified.segments().count()
+    );
+    vec![crate::core::state::ContourData::from_bezpath(&simplified).points]
+}
+
 // ============================================================================
 // NEW: CUBIC BEZIER METABALL OUTLINE GENERATOR
 // ============================================================================
@@ -8,6 +8,7 @@
 use crate::core::settings::BezySettings;
 use crate::core::state::{AppState, GlyphNavigation};
 use crate::editing::selection::events::AppStateChanged;
+use crate::editing::snapping::SnapState;
 use crate::rendering::camera_responsive::CameraResponsiveScale;
 use crate::ui::toolbars::edit_mode_toolbar::{EditTool, ToolRegistry};
 use crate::ui::themes::{CurrentTheme, ToolbarBorderRadius};
@@ -189,7 +190,10 @@ pub fn handle_shape_mouse_events(
     windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
     current_shape_type: Res<CurrentShapeType>,
     mut active_drawing: ResMut<ActiveShapeDrawing>,
-    camera_query: Query<(&Camera, &GlobalTransform)>,
+    camera_query: Query<
+        (&Camera, &GlobalTransform),
+        With<crate::rendering::cameras::DesignCamera>,
+    >,
     mut app_state_changed: EventWriter<AppStateChanged>,
     mut app_state: Option<ResMut<AppState>>,
     mut fontir_app_state: Option<ResMut<crate::core::state::FontIRAppState>>,
@@ -198,6 +202,7 @@ pub fn handle_shape_mouse_events(
     shapes_mode: Option<Res<ShapesModeActive>>,
     current_tool: Option<Res<crate::ui::toolbars::edit_mode_toolbar::CurrentTool>>,
     settings: Res<BezySettings>,
+    mut snap_state: ResMut<SnapState>,
 ) {
     // Check if shapes mode is active via multiple methods (same as preview system)
     let shapes_is_active = shapes_mode.as_ref().is_some_and(|s| s.0) 
@@ -231,15 +236,16 @@ pub fn handle_shape_mouse_events(
     if let Ok(world_position) =
         camera.viewport_to_world_2d(camera_transform, cursor_position)
     {
-        // Apply grid snapping
-        let mut snapped_position = settings.apply_grid_snap(world_position);
-        
-        // Apply shift-key constraints for squares/circles
-        if keyboard_input.pressed(KeyCode::ShiftLeft) || keyboard_input.pressed(KeyCode::ShiftRight) {
-            if let Some(start_pos) = active_drawing.start_position {
-                snapped_position = apply_shape_constraints(snapped_position, start_pos, current_shape_type.0);
+        // Apply shift-key constraints for squares/circles, otherwise snap
+        // to the grid and nearby targets
+        let shift_pressed = keyboard_input.pressed(KeyCode::ShiftLeft) || keyboard_input.pressed(KeyCode::ShiftRight);
+        let snapped_position = match active_drawing.start_position {
+            Some(start_pos) if shift_pressed => {
+                let grid_position = settings.apply_grid_snap(world_position);
+                apply_shape_constraints(grid_position, start_pos, current_shape_type.0)
             }
-        }
+            _ => snap_state.snap_and_highlight(world_position, &settings),
+        };
 
         // Handle mouse button press
         if mouse_button_input.just_pressed(MouseButton::Left) {
@@ -484,18 +490,8 @@ fn create_shape_fontir(
     let location = fontir_app_state.current_location.clone();
     let key = (current_glyph_name.clone(), location);
 
-    // Get or create a working copy
-    let working_copy_exists = fontir_app_state.working_copies.contains_key(&key);
-    
-    if !working_copy_exists {
-        // Create working copy from original FontIR data
-        if let Some(fontir_glyph) = fontir_app_state.glyph_cache.get(&current_glyph_name) {
-            if let Some((_location, instance)) = fontir_glyph.sources().iter().next() {
-                let working_copy = crate::core::state::fontir_app_state::EditableGlyphInstance::from(instance);
-                fontir_app_state.working_copies.insert(key.clone(), working_copy);
-            }
-        }
-    }
+    // Get or create a working copy, unless between masters
+    fontir_app_state.get_or_create_working_copy(&current_glyph_name);
 
     // Add the new contour to the working copy
     if let Some(working_copy) = fontir_app_state.working_copies.get_mut(&key) {
@@ -53,11 +53,13 @@ mod workspace_tests {
 
         // Verify the workspace state
         assert_eq!(
-            app_state.workspace.info.family_name, "Bezy Grotesk",
+            app_state.workspace.info.family_name.as_deref(),
+            Some("Bezy Grotesk"),
             "Workspace family name should match"
         );
         assert_eq!(
-            app_state.workspace.info.style_name, "Regular",
+            app_state.workspace.info.style_name.as_deref(),
+            Some("Regular"),
             "Workspace style name should match"
         );
 